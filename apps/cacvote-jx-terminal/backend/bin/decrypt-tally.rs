@@ -4,7 +4,7 @@ use clap::Parser;
 use color_eyre::eyre::bail;
 use electionguard_rs::{config, tally};
 use sqlx::postgres::PgPoolOptions;
-use types_rs::cacvote::Payload;
+use types_rs::cacvote::{BlobContent, ObjectView, Payload};
 use url::Url;
 use uuid::Uuid;
 
@@ -21,6 +21,11 @@ struct Opts {
     )]
     cacvote_server_url: Url,
 
+    /// API key of an official of the ballot's jurisdiction, needed to read the
+    /// CastBallot without redaction.
+    #[clap(long, env = "CACVOTE_API_KEY")]
+    cacvote_api_key: Option<String>,
+
     #[clap(long, env = "DATABASE_URL")]
     database_url: String,
 
@@ -33,10 +38,19 @@ async fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
 
     let opts = Opts::parse();
-    let client = Client::new(opts.cacvote_server_url.clone());
+    let mut client = Client::new(opts.cacvote_server_url.clone());
+    if let Some(api_key) = &opts.cacvote_api_key {
+        client = client.with_api_key(api_key);
+    }
 
     let cast_ballot_object = match client.get_object_by_id(opts.object_id).await? {
-        Some(object) => object,
+        Some(ObjectView::Full(object)) => object,
+        Some(ObjectView::Redacted(_)) => {
+            bail!(
+                "not allowed to read CastBallot with ID {}, check CACVOTE_API_KEY",
+                opts.object_id
+            );
+        }
         None => {
             bail!("no CastBallot found with ID {}", opts.object_id);
         }
@@ -49,6 +63,7 @@ async fn main() -> color_eyre::Result<()> {
     let election_object = match client
         .get_object_by_id(cast_ballot.election_object_id)
         .await?
+        .and_then(ObjectView::into_signed_object)
     {
         Some(object) => object,
         None => {
@@ -66,9 +81,20 @@ async fn main() -> color_eyre::Result<()> {
         );
     };
 
+    let election_metadata_blob = match election.electionguard_election_metadata_blob {
+        BlobContent::Inline(data) => data,
+        BlobContent::Blob(reference) => match client.get_blob(&reference).await? {
+            Some(data) => data,
+            None => bail!(
+                "no election metadata blob found with SHA-256 {}",
+                reference.sha256
+            ),
+        },
+    };
+
     let encrypted_tally = tally::accumulate(
         &opts.electionguard_classpath,
-        &election.electionguard_election_metadata_blob,
+        &election_metadata_blob,
         once(cast_ballot.electionguard_encrypted_ballot.as_bytes()),
     )?;
 
@@ -91,7 +117,7 @@ async fn main() -> color_eyre::Result<()> {
     .await?;
 
     let election_config = config::ElectionConfig {
-        public_metadata_blob: election.electionguard_election_metadata_blob,
+        public_metadata_blob: election_metadata_blob,
         private_metadata_blob: record.private_key,
     };
 
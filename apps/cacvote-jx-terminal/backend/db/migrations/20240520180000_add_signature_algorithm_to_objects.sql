@@ -0,0 +1,2 @@
+-- NULL for objects signed before algorithm identifiers were introduced
+ALTER TABLE objects ADD COLUMN signature_algorithm VARCHAR(255);
@@ -0,0 +1,30 @@
+ALTER TABLE objects ADD COLUMN cosignatures JSONB NOT NULL DEFAULT '[]';
+
+-- objects that don't yet have all the signatures their signature policy
+-- requires, e.g. a decrypted tally awaiting a second election manager. once
+-- fully signed they are moved to `objects` and synced to the server.
+CREATE TABLE pending_objects (
+    id UUID PRIMARY KEY,
+
+    election_id UUID REFERENCES objects(id),
+
+    -- which jurisdiction owns the object, e.g. "ca.alameda"
+    jurisdiction varchar(255) NOT NULL,
+
+    -- what type of object is this, e.g. "DecryptedElectionTally"
+    object_type varchar(255) NOT NULL,
+
+    -- raw object data, the same as `objects.payload`
+    payload BYTEA NOT NULL,
+
+    -- the primary signer's certificates, signature, and signature algorithm
+    certificates BYTEA NOT NULL,
+    signature BYTEA NOT NULL,
+    signature_algorithm varchar(255),
+
+    -- signatures collected so far from other signers
+    cosignatures JSONB NOT NULL DEFAULT '[]',
+
+    -- when the object was created
+    created_at timestamptz NOT NULL DEFAULT current_timestamp
+);
@@ -0,0 +1,10 @@
+-- X25519 key pairs that voter PII is encrypted to. the public key of each is
+-- published to the server in a `JurisdictionEncryptionKey` object, and the
+-- private keys never leave this machine.
+CREATE TABLE jurisdiction_encryption_keys (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    jurisdiction varchar(255) NOT NULL,
+    private_key BYTEA NOT NULL,
+    public_key BYTEA NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT current_timestamp
+);
@@ -0,0 +1,8 @@
+-- large artifacts that payloads refer to by digest rather than embedding them.
+-- each is uploaded to the server before the objects that refer to it.
+CREATE TABLE blobs (
+    -- hex-encoded SHA-256 digest of `data`
+    sha256 varchar(64) PRIMARY KEY,
+    data BYTEA NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT current_timestamp
+);
@@ -0,0 +1,11 @@
+-- the certificates table is keyed on the signer's certificate but only kept
+-- the stack of the first object that signer signed, so objects signed with
+-- the same certificate under a different chain were returned with the wrong
+-- one. each object keeps the stack it was signed with, and the certificates
+-- table only indexes signers.
+ALTER TABLE objects ADD COLUMN certificates BYTEA;
+UPDATE objects AS o
+SET certificates = c.certificates
+FROM certificates AS c
+WHERE c.fingerprint = o.signer_fingerprint;
+ALTER TABLE objects ALTER COLUMN certificates SET NOT NULL;
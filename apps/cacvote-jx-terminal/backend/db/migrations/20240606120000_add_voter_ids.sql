@@ -0,0 +1,34 @@
+-- cast ballots were attributed to voters by a pattern match over their signing
+-- certificate's common name. they are now attributed by the binding of their
+-- CAC ID, which the server checks against the signer's chain-validated
+-- certificate before accepting the ballot (see `cacvote::field_binding`).
+-- ballots received before this are indexed by the application on startup, and
+-- any without a binding fall back to their signing certificate.
+ALTER TABLE objects ADD COLUMN voter_id VARCHAR(64);
+
+CREATE INDEX objects_voter_id_idx ON objects (voter_id);
+
+-- ballots are superseded in the order the server recorded them, so ballots
+-- the server hasn't journaled yet don't take part
+DROP VIEW cast_ballot_supersessions;
+CREATE VIEW cast_ballot_supersessions AS
+SELECT
+    o.id AS cast_ballot_id,
+    (convert_from(o.payload, 'UTF8')::jsonb ->> 'electionObjectId')::uuid AS election_object_id,
+    lead(o.id) OVER voter_cast_ballots AS superseded_by_id
+FROM objects AS o
+INNER JOIN journal_entries AS j
+  ON j.object_id = o.id AND j.action = 'create'
+WHERE o.object_type = 'CastBallot'
+  AND o.deleted_at IS NULL
+WINDOW voter_cast_ballots AS (
+    PARTITION BY
+        convert_from(o.payload, 'UTF8')::jsonb ->> 'electionObjectId',
+        coalesce(o.voter_id, o.signer_fingerprint)
+    ORDER BY j.created_at, o.id
+);
+
+-- nothing reads the common name from the database any more, and storing it
+-- invited treating an unverified name as an identity
+DROP INDEX certificates_common_name_idx;
+ALTER TABLE certificates DROP COLUMN common_name;
@@ -0,0 +1,8 @@
+-- the previous version of the object, as named by the `supersedes` field of
+-- its signed payload. versions may arrive from the server in any order, so
+-- this doesn't reference `objects`
+ALTER TABLE objects ADD COLUMN supersedes_object_id UUID;
+UPDATE objects
+SET supersedes_object_id = (convert_from(payload, 'UTF8')::jsonb ->> 'supersedes')::uuid;
+
+CREATE INDEX objects_supersedes_object_id_idx ON objects (supersedes_object_id);
@@ -5,13 +5,16 @@
 
 use std::convert::Infallible;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
 use std::time::Duration;
 
-use auth_rs::card_details::CardDetailsWithAuthInfo;
+use auth_rs::card_details::{CardDetails, CardDetailsWithAuthInfo};
 use axum::extract::Path;
+use axum::http::header;
+use axum::middleware;
 use axum::response::sse::{Event, KeepAlive};
 use axum::response::Sse;
-use axum::routing::post;
+use axum::routing::{delete, post};
 use axum::Json;
 use axum::{extract::DefaultBodyLimit, routing::get, Router};
 use axum::{extract::State, http::StatusCode, response::IntoResponse};
@@ -22,12 +25,19 @@ use tokio_stream::StreamExt;
 use tower_http::services::{ServeDir, ServeFile};
 use tower_http::trace::TraceLayer;
 use tracing::Level;
-use types_rs::cacvote;
+use types_rs::cacvote::{self, hpke, ReadinessCheck, ReadinessReport, SmartcardStatus};
+use utoipa::OpenApi;
 use uuid::Uuid;
 
 use crate::config::{Config, MAX_REQUEST_SIZE};
-use crate::{db, smartcard};
+use crate::metrics::{self, Metrics};
+use crate::openapi::{
+    ApiDoc, CreatedResponse, ElectionsResponse, ErrorResponse, PendingObjectResponse,
+};
+use crate::supervisor::{Supervisor, TaskHealth};
+use crate::{db, log, smartcard};
 use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
 
 #[derive(Clone)]
 struct AppState {
@@ -35,11 +45,19 @@ struct AppState {
     pool: PgPool,
     smartcard: smartcard::DynSmartcard,
     broadcast_tx: broadcast::Sender<cacvote::SessionData>,
+    metrics: Arc<Metrics>,
+    supervisor: Supervisor,
 }
 
 /// Prepares the application with all the routes. Run the application with
 /// `app::run(…)` once you have it.
-pub(crate) fn setup(pool: PgPool, config: Config, smartcard: smartcard::DynSmartcard) -> Router {
+pub(crate) fn setup(
+    pool: PgPool,
+    config: Config,
+    smartcard: smartcard::DynSmartcard,
+    metrics: Arc<Metrics>,
+    supervisor: Supervisor,
+) -> Router {
     let _entered = tracing::span!(Level::DEBUG, "Setting up application").entered();
 
     let router = match &config.public_dir {
@@ -56,54 +74,35 @@ pub(crate) fn setup(pool: PgPool, config: Config, smartcard: smartcard::DynSmart
 
     let (broadcast_tx, _) = broadcast::channel(1);
 
-    tokio::spawn({
+    supervisor.spawn("sessionBroadcast", {
         let jurisdiction_code = config.jurisdiction_code.clone();
         let pool = pool.clone();
         let smartcard = smartcard.clone();
         let broadcast_tx = broadcast_tx.clone();
-        async move {
-            loop {
-                let mut connection = pool.acquire().await.unwrap();
-
-                let session_data = match smartcard.get_card_details() {
-                    Some(CardDetailsWithAuthInfo { card_details, .. })
-                        if card_details.jurisdiction_code() == jurisdiction_code =>
-                    {
-                        let elections = db::get_elections(&mut connection).await.unwrap();
-                        let pending_registration_requests =
-                            db::get_pending_registration_requests(&mut connection)
-                                .await
-                                .unwrap();
-                        let registrations = db::get_registrations(&mut connection).await.unwrap();
-                        let cast_ballots = db::get_cast_ballots(&mut connection).await.unwrap();
-                        cacvote::SessionData::Authenticated {
-                            jurisdiction_code: jurisdiction_code.clone(),
-                            elections,
-                            pending_registration_requests,
-                            registrations,
-                            cast_ballots,
-                        }
-                    }
-                    Some(_) => cacvote::SessionData::Unauthenticated {
-                        has_smartcard: true,
-                    },
-                    None => cacvote::SessionData::Unauthenticated {
-                        has_smartcard: false,
-                    },
-                };
-
-                let _ = broadcast_tx.send(session_data);
-                tokio::time::sleep(Duration::from_millis(100)).await;
-            }
+        move |shutdown| {
+            broadcast_session_data(
+                jurisdiction_code.clone(),
+                pool.clone(),
+                smartcard.clone(),
+                broadcast_tx.clone(),
+                shutdown,
+            )
         }
     });
 
     router
+        .route("/metrics", get(get_metrics))
+        .route("/api/openapi.json", get(get_openapi))
         .route("/api/status", get(get_status))
+        .route("/api/ready", get(get_readiness))
         .route("/api/status-stream", get(get_status_stream))
         .route("/api/elections", get(get_elections))
         .route("/api/elections", post(create_election))
         .route("/api/registrations", post(create_registration))
+        .route(
+            "/api/jurisdiction-encryption-keys",
+            post(rotate_jurisdiction_encryption_key),
+        )
         .route(
             "/api/elections/:election_id/encrypted-tally",
             post(generate_encrypted_election_tally),
@@ -116,30 +115,236 @@ pub(crate) fn setup(pool: PgPool, config: Config, smartcard: smartcard::DynSmart
             "/api/elections/:election_id/mixed-ballots",
             post(mix_encrypted_ballots),
         )
+        .route(
+            "/api/pending-objects/:object_id",
+            delete(delete_pending_object),
+        )
+        .route(
+            "/api/pending-objects/:object_id/cosignatures",
+            post(cosign_pending_object),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            metrics.clone(),
+            metrics::track_request_duration,
+        ))
         .layer(DefaultBodyLimit::max(MAX_REQUEST_SIZE))
-        .layer(TraceLayer::new_for_http())
+        .layer(TraceLayer::new_for_http().make_span_with(log::make_request_span))
         .with_state(AppState {
             config,
             pool,
             smartcard,
             broadcast_tx,
+            metrics,
+            supervisor,
         })
 }
 
-/// Runs an application built by `app::setup(…)`.
-pub(crate) async fn run(app: Router, config: &Config) -> color_eyre::Result<()> {
+/// Sends the session data for the inserted card, if any, to the clients of
+/// [`get_status_stream`] every 100ms until shutdown.
+async fn broadcast_session_data(
+    jurisdiction_code: cacvote::JurisdictionCode,
+    pool: PgPool,
+    smartcard: smartcard::DynSmartcard,
+    broadcast_tx: broadcast::Sender<cacvote::SessionData>,
+    shutdown: CancellationToken,
+) -> color_eyre::Result<()> {
+    loop {
+        let mut connection = pool.acquire().await?;
+
+        let session_data = match smartcard.get_card_details() {
+            Some(CardDetailsWithAuthInfo { card_details, .. })
+                if card_details.jurisdiction_code() == jurisdiction_code =>
+            {
+                let elections = db::get_elections(&mut connection).await?;
+                let pending_registration_requests =
+                    db::get_pending_registration_requests(&mut connection).await?;
+                let registrations = db::get_registrations(&mut connection).await?;
+                let cast_ballots = db::get_cast_ballots(&mut connection).await?;
+                let pending_objects = db::get_pending_objects(&mut connection).await?;
+                cacvote::SessionData::Authenticated {
+                    jurisdiction_code: jurisdiction_code.clone(),
+                    elections,
+                    pending_registration_requests,
+                    registrations,
+                    cast_ballots,
+                    pending_objects,
+                }
+            }
+            Some(_) => cacvote::SessionData::Unauthenticated {
+                has_smartcard: true,
+            },
+            None => cacvote::SessionData::Unauthenticated {
+                has_smartcard: false,
+            },
+        };
+
+        // release the connection while waiting
+        drop(connection);
+
+        let _ = broadcast_tx.send(session_data);
+        tokio::select! {
+            () = shutdown.cancelled() => return Ok(()),
+            () = tokio::time::sleep(Duration::from_millis(100)) => {}
+        }
+    }
+}
+
+/// Runs an application built by `app::setup(…)` until `shutdown` is
+/// cancelled, then returns once the requests in progress have been answered.
+pub(crate) async fn run(
+    app: Router,
+    config: &Config,
+    shutdown: CancellationToken,
+) -> color_eyre::Result<()> {
     let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), config.port);
     tracing::info!("Server listening at http://{addr}/");
     axum::Server::bind(&addr)
         .serve(app.into_make_service())
+        .with_graceful_shutdown(shutdown.cancelled_owned())
         .await?;
     Ok(())
 }
 
+/// Always responds with a successful status. Used to check whether the
+/// backend is running, see [`get_readiness`] for whether it can serve requests.
+#[utoipa::path(
+    get,
+    path = "/api/status",
+    tag = "status",
+    responses(
+        (status = OK, description = "The backend is running")
+    )
+)]
 async fn get_status() -> impl IntoResponse {
     StatusCode::OK
 }
 
+/// Checks that the database is reachable and fully migrated, that the Java
+/// ElectionGuard CLI can be run, that CACVote Server is reachable, and that a
+/// card reader is connected, responding with 503 Service Unavailable if not.
+#[utoipa::path(
+    get,
+    path = "/api/ready",
+    tag = "status",
+    responses(
+        (status = OK, description = "Every check passed", body = ReadinessReport),
+        (status = SERVICE_UNAVAILABLE, description = "A check failed", body = ReadinessReport)
+    )
+)]
+async fn get_readiness(
+    State(AppState {
+        config,
+        pool,
+        smartcard,
+        supervisor,
+        ..
+    }): State<AppState>,
+) -> impl IntoResponse {
+    let (database, migrations) = match pool.acquire().await {
+        Ok(mut connection) => (
+            Ok(()),
+            match db::get_applied_migration_version(&mut connection).await {
+                Ok(version) if version == db::latest_migration_version() => Ok(()),
+                Ok(version) => Err(format!(
+                    "Database is at migration {version:?}, expected {:?}",
+                    db::latest_migration_version()
+                )),
+                Err(e) => Err(e.to_string()),
+            },
+        ),
+        Err(e) => (
+            Err(e.to_string()),
+            Err("Database is unavailable".to_owned()),
+        ),
+    };
+
+    let eg_classpath = config.eg_classpath.clone();
+    let electionguard = match tokio::task::spawn_blocking(move || {
+        electionguard_rs::check_electionguard_cli(&eg_classpath)
+    })
+    .await
+    {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+
+    let cacvote_server = cacvote_server_client::Client::new(config.cacvote_url)
+        .check_status()
+        .await
+        .map_err(|e| e.to_string());
+
+    let card_reader = match smartcard.get_status() {
+        SmartcardStatus::NoReader => Err("No card reader connected".to_owned()),
+        SmartcardStatus::NoCard | SmartcardStatus::Card => Ok(()),
+    };
+
+    let mut checks = vec![
+        ReadinessCheck::new("database", database),
+        ReadinessCheck::new("migrations", migrations),
+        ReadinessCheck::new("electionguard", electionguard),
+        ReadinessCheck::new("cacvoteServer", cacvote_server),
+        ReadinessCheck::new("cardReader", card_reader),
+    ];
+    checks.extend(supervisor.health().into_iter().map(|(name, health)| {
+        ReadinessCheck::new(
+            format!("{name}Task"),
+            match health {
+                TaskHealth::Running => Ok(()),
+                TaskHealth::Restarting { error } => Err(format!("Restarting after: {error}")),
+                TaskHealth::Stopped => Err("Stopped".to_owned()),
+            },
+        )
+    }));
+
+    let report = ReadinessReport::new(checks);
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "status",
+    responses(
+        (status = OK, description = "Metrics in the Prometheus text exposition format", body = String, content_type = "text/plain"),
+        (status = INTERNAL_SERVER_ERROR, description = "An error occurred", body = ErrorResponse)
+    )
+)]
+async fn get_metrics(State(AppState { pool, metrics, .. }): State<AppState>) -> impl IntoResponse {
+    let journal_entries = match pool.acquire().await {
+        Ok(mut connection) => db::count_journal_entries(&mut connection).await,
+        Err(e) => Err(e.into()),
+    };
+
+    match journal_entries.and_then(|journal_entries| Ok(metrics.encode(journal_entries)?)) {
+        Ok(body) => ([(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)], body).into_response(),
+        Err(e) => {
+            tracing::error!("error encoding metrics: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error encoding metrics" })),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/openapi.json",
+    tag = "status",
+    responses(
+        (status = OK, description = "This OpenAPI document", body = Object)
+    )
+)]
+async fn get_openapi() -> impl IntoResponse {
+    Json(ApiDoc::openapi())
+}
+
 fn distinct_until_changed<S: Stream>(stream: S) -> impl Stream<Item = S::Item>
 where
     S::Item: Clone + PartialEq,
@@ -152,8 +357,20 @@ where
     })
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/status-stream",
+    tag = "status",
+    responses(
+        (status = OK, description = "Server-sent events, each the JSON session data whenever it changes", body = cacvote::SessionData, content_type = "text/event-stream")
+    )
+)]
 async fn get_status_stream(
-    State(AppState { broadcast_tx, .. }): State<AppState>,
+    State(AppState {
+        broadcast_tx,
+        supervisor,
+        ..
+    }): State<AppState>,
 ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
     let broadcast_rx = broadcast_tx.subscribe();
 
@@ -163,9 +380,22 @@ async fn get_status_stream(
     .map(|data| Event::default().json_data(data).unwrap())
     .map(Ok);
 
+    // ends the stream on shutdown, which otherwise waits for it to end
+    let stream =
+        futures::StreamExt::take_until(stream, supervisor.shutdown_token().cancelled_owned());
+
     Sse::new(stream).keep_alive(KeepAlive::default())
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/elections",
+    tag = "elections",
+    responses(
+        (status = OK, description = "Every election", body = ElectionsResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "An error occurred", body = ErrorResponse)
+    )
+)]
 async fn get_elections(State(AppState { pool, .. }): State<AppState>) -> impl IntoResponse {
     let mut connection = match pool.acquire().await {
         Ok(connection) => connection,
@@ -192,11 +422,23 @@ async fn get_elections(State(AppState { pool, .. }): State<AppState>) -> impl In
     (StatusCode::OK, Json(json!({ "elections": elections })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/elections",
+    tag = "elections",
+    request_body = cacvote::CreateElectionRequest,
+    responses(
+        (status = CREATED, description = "The election object was created", body = CreatedResponse),
+        (status = BAD_REQUEST, description = "The request is invalid", body = ErrorResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "An error occurred", body = ErrorResponse)
+    )
+)]
 async fn create_election(
     State(AppState {
         config,
         pool,
         smartcard,
+        metrics,
         ..
     }): State<AppState>,
     Json(election): Json<cacvote::CreateElectionRequest>,
@@ -230,10 +472,12 @@ async fn create_election(
         }
     };
 
-    let election_config = match electionguard_rs::config::generate_election_config(
-        &config.eg_classpath,
-        election.election_definition.election.clone(),
-    ) {
+    let election_config = match metrics.time_electionguard_command("create_election_config", || {
+        electionguard_rs::config::generate_election_config(
+            &config.eg_classpath,
+            election.election_definition.election.clone(),
+        )
+    }) {
         Ok(election_config) => election_config,
         Err(e) => {
             tracing::error!("error generating election config: {e}");
@@ -244,14 +488,28 @@ async fn create_election(
         }
     };
 
+    // the metadata grows with the size of the election, so it is stored as a
+    // blob that gets uploaded separately
+    let election_metadata_blob =
+        match db::add_blob(&mut transaction, &election_config.public_metadata_blob).await {
+            Ok(reference) => reference,
+            Err(e) => {
+                tracing::error!("error storing election metadata: {e}");
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({ "error": "error storing election metadata" })),
+                );
+            }
+        };
+
     let payload = cacvote::Payload::Election(cacvote::Election {
         jurisdiction_code: election.jurisdiction_code,
         mailing_address: election.mailing_address,
         election_definition: election.election_definition,
-        electionguard_election_metadata_blob: election_config.public_metadata_blob,
+        electionguard_election_metadata_blob: cacvote::BlobContent::Blob(election_metadata_blob),
     });
 
-    let serialized_payload = match serde_json::to_vec(&payload) {
+    let serialized_payload = match payload.to_canonical_json() {
         Ok(serialized_payload) => serialized_payload,
         Err(e) => {
             tracing::error!("error serializing payload: {e}");
@@ -294,6 +552,8 @@ async fn create_election(
         payload: serialized_payload,
         certificates,
         signature: signed.data,
+        signature_algorithm: Some(signed.algorithm),
+        cosignatures: cacvote::Cosignatures::default(),
     };
 
     if let Err(e) = db::add_object(&mut transaction, &signed_object).await {
@@ -318,6 +578,30 @@ async fn create_election(
         );
     }
 
+    // voters can't register until there's a key to encrypt their PII to
+    match db::get_latest_jurisdiction_encryption_key(&mut transaction, &jurisdiction_code).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            if let Err(e) =
+                create_jurisdiction_encryption_key(&mut transaction, &smartcard, jurisdiction_code)
+                    .await
+            {
+                tracing::error!("error creating jurisdiction encryption key: {e}");
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({ "error": "error creating jurisdiction encryption key" })),
+                );
+            }
+        }
+        Err(e) => {
+            tracing::error!("error getting jurisdiction encryption key from database: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting jurisdiction encryption key from database" })),
+            );
+        }
+    }
+
     if let Err(e) = transaction.commit().await {
         tracing::error!("error committing transaction: {e}");
         return (
@@ -329,6 +613,33 @@ async fn create_election(
     (StatusCode::CREATED, Json(json!({ "id": signed_object.id })))
 }
 
+/// Loads the ElectionGuard metadata of `election`, whether embedded or in a
+/// blob, or the error response to send if it can't be loaded.
+async fn load_election_metadata_blob(
+    connection: &mut sqlx::PgConnection,
+    election: &cacvote::Election,
+) -> Result<Vec<u8>, (StatusCode, Json<serde_json::Value>)> {
+    db::load_blob_content(connection, &election.electionguard_election_metadata_blob)
+        .await
+        .map_err(|e| {
+            tracing::error!("error loading election metadata: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error loading election metadata" })),
+            )
+        })
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/registrations",
+    tag = "registrations",
+    request_body = cacvote::CreateRegistrationRequest,
+    responses(
+        (status = CREATED, description = "The registration object was created", body = CreatedResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "An error occurred", body = ErrorResponse)
+    )
+)]
 async fn create_registration(
     State(AppState {
         pool, smartcard, ..
@@ -375,14 +686,44 @@ async fn create_registration(
         };
 
     let payload = cacvote::Payload::Registration(cacvote::Registration {
-        jurisdiction_code,
+        jurisdiction_code: jurisdiction_code.clone(),
         common_access_card_id: registration_request.common_access_card_id,
         registration_request_object_id: registration_request_id,
         election_object_id: election_id,
         ballot_style_id,
         precinct_id,
     });
-    let serialized_payload = match serde_json::to_vec(&payload) {
+    let payload = match db::get_latest_jurisdiction_encryption_key(
+        &mut connection,
+        &jurisdiction_code,
+    )
+    .await
+    {
+        Ok(Some(key_pair)) => {
+            match payload.encrypt_private_fields(&cacvote::JurisdictionEncryptionKey {
+                jurisdiction_code,
+                public_key: key_pair.public_key,
+            }) {
+                Ok(payload) => payload,
+                Err(e) => {
+                    tracing::error!("error encrypting payload: {e}");
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(json!({ "error": "error encrypting payload" })),
+                    );
+                }
+            }
+        }
+        Ok(None) => payload,
+        Err(e) => {
+            tracing::error!("error getting jurisdiction encryption key from database: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting jurisdiction encryption key from database" })),
+            );
+        }
+    };
+    let serialized_payload = match payload.to_canonical_json() {
         Ok(serialized_payload) => serialized_payload,
         Err(e) => {
             tracing::error!("error serializing payload: {e}");
@@ -424,6 +765,8 @@ async fn create_registration(
         payload: serialized_payload,
         certificates,
         signature: signed.data,
+        signature_algorithm: Some(signed.algorithm),
+        cosignatures: cacvote::Cosignatures::default(),
     };
 
     if let Err(e) = db::add_object(&mut connection, &signed_object).await {
@@ -437,11 +780,125 @@ async fn create_registration(
     (StatusCode::CREATED, Json(json!({ "id": signed_object.id })))
 }
 
+/// Replaces the jurisdiction's encryption key with a new one. Objects
+/// encrypted to earlier keys can still be decrypted.
+#[utoipa::path(
+    post,
+    path = "/api/jurisdiction-encryption-keys",
+    tag = "jurisdiction",
+    responses(
+        (status = CREATED, description = "The encryption key object was created", body = CreatedResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "An error occurred", body = ErrorResponse)
+    )
+)]
+async fn rotate_jurisdiction_encryption_key(
+    State(AppState {
+        pool, smartcard, ..
+    }): State<AppState>,
+) -> impl IntoResponse {
+    let jurisdiction_code = match smartcard.get_card_details() {
+        Some(card_details) => card_details.card_details.jurisdiction_code(),
+        None => {
+            tracing::error!("no card details found");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "no card details found" })),
+            );
+        }
+    };
+
+    let mut transaction = match pool.begin().await {
+        Ok(connection) => connection,
+        Err(e) => {
+            tracing::error!("error getting database connection: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting database connection" })),
+            );
+        }
+    };
+
+    let object_id =
+        match create_jurisdiction_encryption_key(&mut transaction, &smartcard, jurisdiction_code)
+            .await
+        {
+            Ok(object_id) => object_id,
+            Err(e) => {
+                tracing::error!("error creating jurisdiction encryption key: {e}");
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({ "error": "error creating jurisdiction encryption key" })),
+                );
+            }
+        };
+
+    if let Err(e) = transaction.commit().await {
+        tracing::error!("error committing transaction: {e}");
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "error committing transaction" })),
+        );
+    }
+
+    (StatusCode::CREATED, Json(json!({ "id": object_id })))
+}
+
+/// Generates a key pair for encrypting the jurisdiction's voter PII and adds
+/// an object publishing its public key, signed by the current card.
+async fn create_jurisdiction_encryption_key(
+    connection: &mut sqlx::PgConnection,
+    smartcard: &smartcard::DynSmartcard,
+    jurisdiction_code: cacvote::JurisdictionCode,
+) -> color_eyre::Result<Uuid> {
+    let key_pair = hpke::KeyPair::generate()?;
+    db::add_jurisdiction_encryption_key(connection, &jurisdiction_code, &key_pair).await?;
+
+    let payload = cacvote::Payload::JurisdictionEncryptionKey(cacvote::JurisdictionEncryptionKey {
+        jurisdiction_code,
+        public_key: key_pair.public_key,
+    });
+    let serialized_payload = payload.to_canonical_json()?;
+    let signed = smartcard
+        .sign(&serialized_payload, None)
+        .map_err(|e| color_eyre::eyre::eyre!("error signing payload: {e}"))?;
+    let certificates = signed
+        .cert_stack
+        .iter()
+        .map(|cert| cert.to_pem())
+        .collect::<Result<Vec<_>, _>>()?
+        .concat();
+    let signed_object = cacvote::SignedObject {
+        id: Uuid::new_v4(),
+        election_id: None,
+        payload: serialized_payload,
+        certificates,
+        signature: signed.data,
+        signature_algorithm: Some(signed.algorithm),
+        cosignatures: cacvote::Cosignatures::default(),
+    };
+
+    db::add_object(connection, &signed_object).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/elections/{election_id}/encrypted-tally",
+    tag = "elections",
+    params(("election_id" = Uuid, Path, description = "The ID of the election object")),
+    responses(
+        (status = CREATED, description = "The encrypted tally object was created", body = CreatedResponse),
+        (status = BAD_REQUEST, description = "The request is invalid", body = ErrorResponse),
+        (status = NOT_FOUND, description = "Not found", body = ErrorResponse),
+        (status = CONFLICT, description = "The tally already exists", body = ErrorResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "An error occurred", body = ErrorResponse)
+    )
+)]
 async fn generate_encrypted_election_tally(
     State(AppState {
         pool,
         config,
         smartcard,
+        metrics,
         ..
     }): State<AppState>,
     Path(election_id): Path<Uuid>,
@@ -511,13 +968,21 @@ async fn generate_encrypted_election_tally(
         .await
         .unwrap();
 
-    let encrypted_tally = match electionguard_rs::tally::accumulate(
-        &config.eg_classpath,
-        &election.electionguard_election_metadata_blob,
-        cast_ballots
-            .iter()
-            .map(|cast_ballot| cast_ballot.electionguard_encrypted_ballot.as_bytes()),
-    ) {
+    let election_metadata_blob =
+        match load_election_metadata_blob(&mut transaction, &election).await {
+            Ok(election_metadata_blob) => election_metadata_blob,
+            Err(response) => return response,
+        };
+
+    let encrypted_tally = match metrics.time_electionguard_command("accumulate_tally", || {
+        electionguard_rs::tally::accumulate(
+            &config.eg_classpath,
+            &election_metadata_blob,
+            cast_ballots
+                .iter()
+                .map(|cast_ballot| cast_ballot.electionguard_encrypted_ballot.as_bytes()),
+        )
+    }) {
         Ok(encrypted_tally) => encrypted_tally,
         Err(e) => {
             tracing::error!("error accumulating tally: {e}");
@@ -534,7 +999,7 @@ async fn generate_encrypted_election_tally(
         electionguard_encrypted_tally: encrypted_tally,
     });
 
-    let serialized_payload = match serde_json::to_vec(&payload) {
+    let serialized_payload = match payload.to_canonical_json() {
         Ok(serialized_payload) => serialized_payload,
         Err(e) => {
             tracing::error!("error serializing payload: {e}");
@@ -578,6 +1043,8 @@ async fn generate_encrypted_election_tally(
         payload: serialized_payload,
         certificates,
         signature: signed.data,
+        signature_algorithm: Some(signed.algorithm),
+        cosignatures: cacvote::Cosignatures::default(),
     };
 
     if let Err(e) = db::add_object(&mut transaction, &signed_object).await {
@@ -599,11 +1066,25 @@ async fn generate_encrypted_election_tally(
     (StatusCode::CREATED, Json(json!({ "id": signed_object.id })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/elections/{election_id}/decrypted-tally",
+    tag = "elections",
+    params(("election_id" = Uuid, Path, description = "The ID of the election object")),
+    responses(
+        (status = CREATED, description = "The decrypted tally object was created, and may be pending further signatures", body = PendingObjectResponse),
+        (status = BAD_REQUEST, description = "The request is invalid", body = ErrorResponse),
+        (status = NOT_FOUND, description = "Not found", body = ErrorResponse),
+        (status = CONFLICT, description = "The tally already exists or is awaiting approval", body = ErrorResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "An error occurred", body = ErrorResponse)
+    )
+)]
 async fn decrypt_encrypted_election_tally(
     State(AppState {
         pool,
         config,
         smartcard,
+        metrics,
         ..
     }): State<AppState>,
     Path(election_id): Path<Uuid>,
@@ -642,6 +1123,29 @@ async fn decrypt_encrypted_election_tally(
         }
     };
 
+    match db::has_pending_object(
+        &mut transaction,
+        election_id,
+        cacvote::Payload::decrypted_election_tally_object_type(),
+    )
+    .await
+    {
+        Ok(false) => (),
+        Ok(true) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(json!({ "error": "decrypted tally is awaiting approval" })),
+            )
+        }
+        Err(e) => {
+            tracing::error!("error getting pending objects from database: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting pending objects from database" })),
+            );
+        }
+    }
+
     let election_object = match db::get_object(&mut transaction, election_id).await {
         Ok(object) => object,
         Err(e) => {
@@ -686,18 +1190,26 @@ async fn decrypt_encrypted_election_tally(
         }
     };
 
+    let election_metadata_blob =
+        match load_election_metadata_blob(&mut transaction, &election).await {
+            Ok(election_metadata_blob) => election_metadata_blob,
+            Err(response) => return response,
+        };
+
     let election_config = electionguard_rs::config::ElectionConfig {
-        public_metadata_blob: election.electionguard_election_metadata_blob,
+        public_metadata_blob: election_metadata_blob,
         private_metadata_blob: private_key,
     };
 
-    let decrypted_tally = match electionguard_rs::tally::decrypt(
-        &config.eg_classpath,
-        &election_config,
-        &encrypted_tally
-            .encrypted_election_tally
-            .electionguard_encrypted_tally,
-    ) {
+    let decrypted_tally = match metrics.time_electionguard_command("decrypt_tally", || {
+        electionguard_rs::tally::decrypt(
+            &config.eg_classpath,
+            &election_config,
+            &encrypted_tally
+                .encrypted_election_tally
+                .electionguard_encrypted_tally,
+        )
+    }) {
         Ok(decrypted_tally) => decrypted_tally,
         Err(e) => {
             tracing::error!("error decrypting tally: {e}");
@@ -714,7 +1226,7 @@ async fn decrypt_encrypted_election_tally(
         electionguard_decrypted_tally: decrypted_tally,
     });
 
-    let serialized_payload = match serde_json::to_vec(&payload) {
+    let serialized_payload = match payload.to_canonical_json() {
         Ok(serialized_payload) => serialized_payload,
         Err(e) => {
             tracing::error!("error serializing payload: {e}");
@@ -758,9 +1270,30 @@ async fn decrypt_encrypted_election_tally(
         payload: serialized_payload,
         certificates,
         signature: signed.data,
+        signature_algorithm: Some(signed.algorithm),
+        cosignatures: cacvote::Cosignatures::default(),
     };
 
-    if let Err(e) = db::add_object(&mut transaction, &signed_object).await {
+    let signature_policy_status = match signed_object.signature_policy_status() {
+        Ok(signature_policy_status) => signature_policy_status,
+        Err(e) => {
+            tracing::error!("error checking signature policy: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error checking signature policy" })),
+            );
+        }
+    };
+
+    // publishing a decrypted tally needs approval from more than one election
+    // manager, so it usually waits for more signatures (see `cosign_pending_object`)
+    let result = if signature_policy_status.is_satisfied() {
+        db::add_object(&mut transaction, &signed_object).await
+    } else {
+        db::add_pending_object(&mut transaction, &signed_object).await
+    };
+
+    if let Err(e) = result {
         tracing::error!("error adding object to database: {e}");
         return (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -776,14 +1309,34 @@ async fn decrypt_encrypted_election_tally(
         );
     }
 
-    (StatusCode::CREATED, Json(json!({ "id": signed_object.id })))
+    (
+        StatusCode::CREATED,
+        Json(json!({
+            "id": signed_object.id,
+            "signaturePolicyStatus": signature_policy_status,
+        })),
+    )
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/elections/{election_id}/mixed-ballots",
+    tag = "elections",
+    params(("election_id" = Uuid, Path, description = "The ID of the election object")),
+    request_body = cacvote::MixEncryptedBallotsRequest,
+    responses(
+        (status = CREATED, description = "The shuffled ballots object was created", body = CreatedResponse),
+        (status = BAD_REQUEST, description = "The request is invalid", body = ErrorResponse),
+        (status = NOT_FOUND, description = "Not found", body = ErrorResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "An error occurred", body = ErrorResponse)
+    )
+)]
 async fn mix_encrypted_ballots(
     State(AppState {
         pool,
         config,
         smartcard,
+        metrics,
         ..
     }): State<AppState>,
     Path(election_id): Path<Uuid>,
@@ -837,14 +1390,22 @@ async fn mix_encrypted_ballots(
         .await
         .unwrap();
 
-    let shuffled_ballots = match electionguard_rs::mixnet::mix(
-        &config.eg_classpath,
-        &election.electionguard_election_metadata_blob,
-        cast_ballots
-            .iter()
-            .map(|cast_ballot| cast_ballot.electionguard_encrypted_ballot.as_bytes()),
-        phases,
-    ) {
+    let election_metadata_blob =
+        match load_election_metadata_blob(&mut transaction, &election).await {
+            Ok(election_metadata_blob) => election_metadata_blob,
+            Err(response) => return response,
+        };
+
+    let shuffled_ballots = match metrics.time_electionguard_command("mix", || {
+        electionguard_rs::mixnet::mix(
+            &config.eg_classpath,
+            &election_metadata_blob,
+            cast_ballots
+                .iter()
+                .map(|cast_ballot| cast_ballot.electionguard_encrypted_ballot.as_bytes()),
+            phases,
+        )
+    }) {
         Ok(shuffled_ballots) => shuffled_ballots,
         Err(e) => {
             tracing::error!("error mixing ballots: {e}");
@@ -855,14 +1416,27 @@ async fn mix_encrypted_ballots(
         }
     };
 
+    // the shuffled ballots grow with the number of ballots cast, so they are
+    // stored as a blob that gets uploaded separately
+    let shuffled_ballots = match db::add_blob(&mut transaction, &shuffled_ballots).await {
+        Ok(reference) => reference,
+        Err(e) => {
+            tracing::error!("error storing shuffled ballots: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error storing shuffled ballots" })),
+            );
+        }
+    };
+
     let payload =
         cacvote::Payload::ShuffledEncryptedCastBallots(cacvote::ShuffledEncryptedCastBallots {
             election_object_id: election_id,
             jurisdiction_code: election.jurisdiction_code,
-            electionguard_shuffled_ballots: shuffled_ballots,
+            electionguard_shuffled_ballots: cacvote::BlobContent::Blob(shuffled_ballots),
         });
 
-    let serialized_payload = match serde_json::to_vec(&payload) {
+    let serialized_payload = match payload.to_canonical_json() {
         Ok(serialized_payload) => serialized_payload,
         Err(e) => {
             tracing::error!("error serializing payload: {e}");
@@ -906,6 +1480,8 @@ async fn mix_encrypted_ballots(
         payload: serialized_payload,
         certificates,
         signature: signed.data,
+        signature_algorithm: Some(signed.algorithm),
+        cosignatures: cacvote::Cosignatures::default(),
     };
 
     if let Err(e) = db::add_object(&mut transaction, &signed_object).await {
@@ -926,3 +1502,261 @@ async fn mix_encrypted_ballots(
 
     (StatusCode::CREATED, Json(json!({ "id": signed_object.id })))
 }
+
+/// Adds the inserted card's signature to a pending object. Once the object has
+/// all the signatures its policy requires, it's moved out of pending so that it
+/// will be synced to the server.
+#[utoipa::path(
+    post,
+    path = "/api/pending-objects/{object_id}/cosignatures",
+    tag = "pending objects",
+    params(("object_id" = Uuid, Path, description = "The ID of the pending object")),
+    responses(
+        (status = OK, description = "The card's signature was added", body = PendingObjectResponse),
+        (status = FORBIDDEN, description = "The card may not sign this object", body = ErrorResponse),
+        (status = NOT_FOUND, description = "Not found", body = ErrorResponse),
+        (status = CONFLICT, description = "The card has already signed this object", body = ErrorResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "An error occurred", body = ErrorResponse)
+    )
+)]
+async fn cosign_pending_object(
+    State(AppState {
+        config,
+        pool,
+        smartcard,
+        ..
+    }): State<AppState>,
+    Path(object_id): Path<Uuid>,
+) -> impl IntoResponse {
+    match smartcard.get_card_details() {
+        Some(card_details)
+            if card_details.card_details.jurisdiction_code() == config.jurisdiction_code => {}
+        Some(_) => {
+            return (
+                StatusCode::FORBIDDEN,
+                Json(json!({ "error": "card is for a different jurisdiction" })),
+            );
+        }
+        None => {
+            tracing::error!("no card details found");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "no card details found" })),
+            );
+        }
+    }
+
+    let mut transaction = match pool.begin().await {
+        Ok(transaction) => transaction,
+        Err(e) => {
+            tracing::error!("error getting database connection: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting database connection" })),
+            );
+        }
+    };
+
+    let mut pending_object = match db::get_pending_object(&mut transaction, object_id).await {
+        Ok(Some(pending_object)) => pending_object,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(json!({ "error": "pending object not found" })),
+            );
+        }
+        Err(e) => {
+            tracing::error!("error getting pending object from database: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting pending object from database" })),
+            );
+        }
+    };
+
+    let signature_policy = match pending_object.try_to_inner() {
+        Ok(payload) => cacvote::SignaturePolicy::for_object_type(payload.object_type()),
+        Err(e) => {
+            tracing::error!("error deserializing pending object: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error deserializing pending object" })),
+            );
+        }
+    };
+
+    let signed = match smartcard.sign(&pending_object.payload, None) {
+        Ok(signed) => signed,
+        Err(e) => {
+            tracing::error!("error signing payload: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error signing payload" })),
+            );
+        }
+    };
+
+    let Some(signing_certificate) = signed.cert_stack.first() else {
+        tracing::error!("no certificate returned from signing");
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "no certificate returned from signing" })),
+        );
+    };
+
+    if !signature_policy.is_eligible_signer(
+        signing_certificate,
+        pending_object.jurisdiction_code().as_ref(),
+    ) {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({ "error": "card is not eligible to approve this object" })),
+        );
+    }
+
+    let signers_before = signature_policy.evaluate(&pending_object).signers_collected;
+
+    let certificates: Vec<u8> = match signed
+        .cert_stack
+        .iter()
+        .map(|cert| cert.to_pem())
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(certificates) => certificates.concat(),
+        Err(e) => {
+            tracing::error!("error converting certificates to PEM: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error converting certificates to PEM" })),
+            );
+        }
+    };
+
+    pending_object.add_cosignature(cacvote::Cosignature {
+        certificates,
+        signature: signed.data,
+        signature_algorithm: signed.algorithm,
+    });
+
+    let signature_policy_status = signature_policy.evaluate(&pending_object);
+
+    if signature_policy_status.signers_collected == signers_before {
+        return (
+            StatusCode::CONFLICT,
+            Json(json!({ "error": "card has already signed this object" })),
+        );
+    }
+
+    let result = if signature_policy_status.is_satisfied() {
+        match db::delete_pending_object(&mut transaction, object_id).await {
+            Ok(_) => db::add_object(&mut transaction, &pending_object)
+                .await
+                .map(|_| ()),
+            Err(e) => Err(e),
+        }
+    } else {
+        db::update_pending_object_cosignatures(
+            &mut transaction,
+            object_id,
+            &pending_object.cosignatures,
+        )
+        .await
+    };
+
+    if let Err(e) = result {
+        tracing::error!("error updating pending object in database: {e}");
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "error updating pending object in database" })),
+        );
+    }
+
+    if let Err(e) = transaction.commit().await {
+        tracing::error!("error committing transaction: {e}");
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "error committing transaction" })),
+        );
+    }
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "id": object_id,
+            "signaturePolicyStatus": signature_policy_status,
+        })),
+    )
+}
+
+/// Abandons a pending object, e.g. so that a tally can be decrypted again.
+#[utoipa::path(
+    delete,
+    path = "/api/pending-objects/{object_id}",
+    tag = "pending objects",
+    params(("object_id" = Uuid, Path, description = "The ID of the pending object")),
+    responses(
+        (status = OK, description = "The pending object was deleted", body = CreatedResponse),
+        (status = UNAUTHORIZED, description = "No card is inserted", body = ErrorResponse),
+        (status = FORBIDDEN, description = "The card is not an election manager card for this jurisdiction", body = ErrorResponse),
+        (status = NOT_FOUND, description = "Not found", body = ErrorResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "An error occurred", body = ErrorResponse)
+    )
+)]
+async fn delete_pending_object(
+    State(AppState {
+        config,
+        pool,
+        smartcard,
+        ..
+    }): State<AppState>,
+    Path(object_id): Path<Uuid>,
+) -> impl IntoResponse {
+    // abandoning an object discards the signatures collected so far, so only
+    // those who could have signed it may
+    match smartcard.get_card_details() {
+        Some(CardDetailsWithAuthInfo {
+            card_details: card_details @ CardDetails::ElectionManagerCard(_),
+            ..
+        }) if card_details.jurisdiction_code() == config.jurisdiction_code => {}
+        Some(_) => {
+            return (
+                StatusCode::FORBIDDEN,
+                Json(json!({
+                    "error": "card is not an election manager card for this jurisdiction"
+                })),
+            );
+        }
+        None => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({ "error": "no card found" })),
+            );
+        }
+    }
+
+    let mut connection = match pool.acquire().await {
+        Ok(connection) => connection,
+        Err(e) => {
+            tracing::error!("error getting database connection: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting database connection" })),
+            );
+        }
+    };
+
+    match db::delete_pending_object(&mut connection, object_id).await {
+        Ok(true) => (StatusCode::OK, Json(json!({ "id": object_id }))),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "pending object not found" })),
+        ),
+        Err(e) => {
+            tracing::error!("error deleting pending object from database: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error deleting pending object from database" })),
+            )
+        }
+    }
+}
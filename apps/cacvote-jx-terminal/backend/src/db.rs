@@ -14,7 +14,7 @@ use color_eyre::eyre::bail;
 use sqlx::postgres::PgPoolOptions;
 use sqlx::{Connection, PgPool};
 use tracing::Level;
-use types_rs::cacvote;
+use types_rs::cacvote::{self, hpke, Cosignatures, SignatureAlgorithm};
 use uuid::Uuid;
 
 use crate::config::Config;
@@ -32,36 +32,241 @@ pub(crate) async fn setup(config: &Config) -> color_eyre::Result<PgPool> {
         .await?;
     tracing::debug!("Running database migrations");
     sqlx::migrate!("db/migrations").run(&pool).await?;
+    index_signer_certificates(&mut *pool.acquire().await?).await?;
+    index_voter_ids(&mut *pool.acquire().await?).await?;
     Ok(pool)
 }
 
+/// The version of the latest migration in this build, i.e. the version the
+/// database is at once [`setup`] has run.
+pub(crate) fn latest_migration_version() -> Option<i64> {
+    sqlx::migrate!("db/migrations")
+        .iter()
+        .map(|migration| migration.version)
+        .max()
+}
+
+/// The version of the latest migration applied to the database.
+pub(crate) async fn get_applied_migration_version(
+    connection: &mut sqlx::PgConnection,
+) -> color_eyre::Result<Option<i64>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT MAX(version) AS version
+        FROM _sqlx_migrations
+        WHERE success
+        "#
+    )
+    .fetch_one(connection)
+    .await?
+    .version)
+}
+
+/// Fills in the signer attributes of certificates that were stored without
+/// them, i.e. those moved out of the `objects` table by migration.
+async fn index_signer_certificates(connection: &mut sqlx::PgConnection) -> color_eyre::Result<()> {
+    let records = sqlx::query!(
+        r#"
+        SELECT fingerprint, certificates
+        FROM certificates
+        WHERE not_after IS NULL
+        "#
+    )
+    .fetch_all(&mut *connection)
+    .await?;
+
+    for record in records {
+        let signer_certificate =
+            match cacvote::SignerCertificate::from_pem_stack(&record.certificates) {
+                Ok(signer_certificate) => signer_certificate,
+                Err(e) => {
+                    tracing::warn!(
+                        "unable to parse certificates with fingerprint {}: {e}",
+                        record.fingerprint
+                    );
+                    continue;
+                }
+            };
+
+        sqlx::query!(
+            r#"
+            UPDATE certificates
+            SET jurisdiction = $2, card_type = $3, not_after = $4
+            WHERE fingerprint = $1
+            "#,
+            record.fingerprint,
+            signer_certificate.jurisdiction,
+            signer_certificate.card_type,
+            signer_certificate.not_after,
+        )
+        .execute(&mut *connection)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Fills in the voter IDs of cast ballots that were stored without them, i.e.
+/// those received before voter IDs were recorded.
+async fn index_voter_ids(connection: &mut sqlx::PgConnection) -> color_eyre::Result<()> {
+    let records = sqlx::query!(
+        r#"
+        SELECT id, payload
+        FROM objects
+        WHERE voter_id IS NULL
+          AND object_type = $1
+        "#,
+        cacvote::Payload::cast_ballot_object_type(),
+    )
+    .fetch_all(&mut *connection)
+    .await?;
+
+    for record in records {
+        let Ok(cacvote::Payload::CastBallot(cast_ballot)) = serde_json::from_slice(&record.payload)
+        else {
+            tracing::warn!("unable to parse cast ballot with id {}", record.id);
+            continue;
+        };
+
+        sqlx::query!(
+            r#"
+            UPDATE objects
+            SET voter_id = $2
+            WHERE id = $1
+            "#,
+            record.id,
+            cast_ballot_voter_id(&cast_ballot),
+        )
+        .execute(&mut *connection)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Identifies the voter who cast `cast_ballot` by the binding of its CAC ID
+/// (see [`cacvote::field_binding`]), which the server checks against the
+/// signer's chain-validated certificate. The binding is the same whether or not
+/// the ID is encrypted, so this doesn't need the jurisdiction's keys. `None`
+/// for encrypted IDs without a binding.
+fn cast_ballot_voter_id(cast_ballot: &cacvote::CastBallot) -> Option<String> {
+    let common_access_card_id = &cast_ballot.common_access_card_id;
+    if cacvote::is_encrypted_field_value(common_access_card_id) {
+        return cacvote::encrypted_field_binding(common_access_card_id).map(str::to_owned);
+    }
+
+    Some(cacvote::field_binding(
+        cacvote::Payload::cast_ballot_object_type(),
+        cast_ballot.jurisdiction_code.as_str(),
+        cacvote::CastBallot::common_access_card_id_field_name(),
+        common_access_card_id,
+    ))
+}
+
+/// Indexes `object`'s primary signer if it isn't already indexed, returning
+/// the signer's fingerprint. The certificate stack itself is stored with each
+/// object, since the same signer may present different chains over time.
+async fn store_signer_certificate(
+    connection: &mut sqlx::PgConnection,
+    object: &cacvote::SignedObject,
+) -> color_eyre::Result<String> {
+    let signer_certificate = object.signer_certificate()?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO certificates (fingerprint, certificates, jurisdiction, card_type, not_after)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (fingerprint) DO NOTHING
+        "#,
+        signer_certificate.fingerprint,
+        &object.certificates,
+        signer_certificate.jurisdiction,
+        signer_certificate.card_type,
+        signer_certificate.not_after,
+    )
+    .execute(connection)
+    .await?;
+
+    Ok(signer_certificate.fingerprint)
+}
+
+/// Gets the latest version of each election, newest election first. Elections
+/// are identified by their original version's ID, which other objects refer
+/// to.
 pub(crate) async fn get_elections(
     connection: &mut sqlx::PgConnection,
 ) -> color_eyre::eyre::Result<Vec<cacvote::ElectionPresenter>> {
-    let objects = sqlx::query_as!(
-        cacvote::SignedObject,
+    let records = sqlx::query!(
         r#"
+        WITH RECURSIVE
+        versions AS (
+            SELECT id AS original_id, id, 0 AS depth
+            FROM objects
+            WHERE object_type = $1
+              AND supersedes_object_id IS NULL
+            UNION ALL
+            SELECT v.original_id, o.id, v.depth + 1
+            FROM objects AS o
+            INNER JOIN versions AS v
+              ON o.supersedes_object_id = v.id
+        ),
+        latest_versions AS (
+            SELECT DISTINCT ON (original_id) original_id, id
+            FROM versions
+            ORDER BY original_id, depth DESC
+        )
         SELECT
-            id,
-            election_id,
-            payload,
-            certificates,
-            signature
-        FROM objects
-        WHERE object_type = 'Election'
-        ORDER BY created_at DESC
+            l.original_id AS "original_id!",
+            o.id,
+            o.election_id,
+            o.payload,
+            o.certificates,
+            o.signature,
+            o.signature_algorithm AS "signature_algorithm: SignatureAlgorithm",
+            o.cosignatures AS "cosignatures: Cosignatures"
+        FROM latest_versions AS l
+        INNER JOIN objects AS original
+          ON original.id = l.original_id
+        INNER JOIN objects AS o
+          ON o.id = l.id
+        ORDER BY original.created_at DESC
         "#,
+        cacvote::Payload::election_object_type(),
     )
     .fetch_all(&mut *connection)
     .await?;
 
     let mut elections = Vec::new();
 
-    for object in objects {
-        let payload = match object.try_to_inner() {
+    for record in records {
+        let election_id = record.original_id;
+        let object = cacvote::SignedObject {
+            id: record.id,
+            election_id: record.election_id,
+            payload: record.payload,
+            certificates: record.certificates,
+            signature: record.signature,
+            signature_algorithm: record.signature_algorithm,
+            cosignatures: record.cosignatures,
+        };
+        let election = match object.try_to_inner() {
+            Ok(cacvote::Payload::Election(election)) => election,
+            // e.g. an election in a newer payload envelope version, which is
+            // kept so that a newer build can show it
+            Ok(payload @ cacvote::Payload::Unknown { .. }) => {
+                tracing::warn!(
+                    "skipping election {} in a format this build can't interpret: {payload:?}",
+                    object.id
+                );
+                continue;
+            }
             Ok(payload) => {
-                tracing::trace!("got object payload: {payload:?}");
-                payload
+                tracing::error!(
+                    "object {} is not an election: {}",
+                    object.id,
+                    payload.object_type()
+                );
+                continue;
             }
             Err(err) => {
                 tracing::error!("unable to parse object payload: {err:?}");
@@ -71,22 +276,20 @@ pub(crate) async fn get_elections(
 
         // FIXME: this makes `get_elections` run N+1 queries
         let (encrypted_tally, decrypted_tally) =
-            get_tallies_for_election(&mut *connection, object.id)
+            get_tallies_for_election(&mut *connection, election_id)
                 .await?
                 .into();
 
         let shuffled_encrypted_cast_ballots =
-            get_shuffled_encrypted_cast_ballots(&mut *connection, &object.id).await?;
+            get_shuffled_encrypted_cast_ballots(&mut *connection, &election_id).await?;
 
-        if let cacvote::Payload::Election(election) = payload {
-            elections.push(cacvote::ElectionPresenter::new(
-                object.id,
-                election,
-                encrypted_tally,
-                decrypted_tally,
-                shuffled_encrypted_cast_ballots,
-            ));
-        }
+        elections.push(cacvote::ElectionPresenter::new(
+            election_id,
+            election,
+            encrypted_tally,
+            decrypted_tally,
+            shuffled_encrypted_cast_ballots,
+        ));
     }
 
     Ok(elections)
@@ -104,6 +307,8 @@ pub async fn get_pending_registration_requests(
             rr.payload,
             rr.certificates,
             rr.signature,
+            rr.signature_algorithm AS "signature_algorithm: SignatureAlgorithm",
+            rr.cosignatures AS "cosignatures: Cosignatures",
             rr.created_at
         FROM
             objects AS rr
@@ -122,9 +327,10 @@ pub async fn get_pending_registration_requests(
         cacvote::Payload::registration_object_type(),
         cacvote::Registration::registration_request_object_id_field_name(),
     )
-    .fetch_all(connection)
+    .fetch_all(&mut *connection)
     .await?;
 
+    let key_pairs = get_jurisdiction_encryption_key_pairs(connection).await?;
     let mut registration_requests = Vec::new();
 
     for record in records {
@@ -134,10 +340,12 @@ pub async fn get_pending_registration_requests(
             payload: record.payload,
             certificates: record.certificates,
             signature: record.signature,
+            signature_algorithm: record.signature_algorithm,
+            cosignatures: record.cosignatures,
         };
 
         if let cacvote::Payload::RegistrationRequest(registration_request) =
-            object.try_to_inner()?
+            decrypt_payload(&object, &key_pairs)?
         {
             registration_requests.push(cacvote::RegistrationRequestPresenter::new(
                 object.id,
@@ -165,16 +373,22 @@ pub async fn get_registrations(
             r.payload AS registration_payload,
             r.certificates AS registration_certificates,
             r.signature AS registration_signature,
+            r.signature_algorithm AS "registration_signature_algorithm: SignatureAlgorithm",
+            r.cosignatures AS "registration_cosignatures: Cosignatures",
             e.id AS election_id,
             e.election_id AS election_election_id,
             e.payload AS election_payload,
             e.certificates AS election_certificates,
             e.signature AS election_signature,
+            e.signature_algorithm AS "election_signature_algorithm: SignatureAlgorithm",
+            e.cosignatures AS "election_cosignatures: Cosignatures",
             rr.id AS registration_request_id,
             rr.election_id AS registration_request_election_id,
             rr.payload AS registration_request_payload,
             rr.certificates AS registration_request_certificates,
             rr.signature AS registration_request_signature,
+            rr.signature_algorithm AS "registration_request_signature_algorithm: SignatureAlgorithm",
+            rr.cosignatures AS "registration_request_cosignatures: Cosignatures",
             r.created_at AS created_at,
             r.server_synced_at IS NOT NULL AS "is_synced!: bool"
         FROM objects AS r
@@ -191,9 +405,10 @@ pub async fn get_registrations(
         cacvote::Payload::election_object_type(),
         cacvote::Payload::registration_object_type(),
     )
-    .fetch_all(connection)
+    .fetch_all(&mut *connection)
     .await?;
 
+    let key_pairs = get_jurisdiction_encryption_key_pairs(connection).await?;
     let mut registrations = Vec::new();
 
     for record in records {
@@ -203,6 +418,8 @@ pub async fn get_registrations(
             payload: record.registration_payload,
             certificates: record.registration_certificates,
             signature: record.registration_signature,
+            signature_algorithm: record.registration_signature_algorithm,
+            cosignatures: record.registration_cosignatures,
         };
         let election_object = cacvote::SignedObject {
             id: record.election_id,
@@ -210,6 +427,8 @@ pub async fn get_registrations(
             payload: record.election_payload,
             certificates: record.election_certificates,
             signature: record.election_signature,
+            signature_algorithm: record.election_signature_algorithm,
+            cosignatures: record.election_cosignatures,
         };
         let registration_request_object = cacvote::SignedObject {
             id: record.registration_request_id,
@@ -217,12 +436,16 @@ pub async fn get_registrations(
             payload: record.registration_request_payload,
             certificates: record.registration_request_certificates,
             signature: record.registration_request_signature,
+            signature_algorithm: record.registration_request_signature_algorithm,
+            cosignatures: record.registration_request_cosignatures,
         };
 
-        if let cacvote::Payload::Registration(registration) = registration_object.try_to_inner()? {
+        if let cacvote::Payload::Registration(registration) =
+            decrypt_payload(&registration_object, &key_pairs)?
+        {
             if let cacvote::Payload::Election(election_payload) = election_object.try_to_inner()? {
                 if let cacvote::Payload::RegistrationRequest(registration_request) =
-                    registration_request_object.try_to_inner()?
+                    decrypt_payload(&registration_request_object, &key_pairs)?
                 {
                     let display_name = registration_request.display_name();
                     let election_title = election_payload.election.title.clone();
@@ -251,8 +474,11 @@ pub async fn get_registration_request(
     connection: &mut sqlx::PgConnection,
     id: Uuid,
 ) -> color_eyre::Result<cacvote::RegistrationRequest> {
+    let object = get_object(&mut *connection, id).await?;
+    let key_pairs = get_jurisdiction_encryption_key_pairs(connection).await?;
+
     if let cacvote::Payload::RegistrationRequest(registration_request) =
-        get_object(connection, id).await?.try_to_inner()?
+        decrypt_payload(&object, &key_pairs)?
     {
         Ok(registration_request)
     } else {
@@ -269,13 +495,15 @@ pub async fn get_object(
         cacvote::SignedObject,
         r#"
         SELECT
-            id,
-            election_id,
-            payload,
-            certificates,
-            signature
-        FROM objects
-        WHERE id = $1
+            o.id,
+            o.election_id,
+            o.payload,
+            o.certificates,
+            o.signature,
+            o.signature_algorithm AS "signature_algorithm: SignatureAlgorithm",
+            o.cosignatures AS "cosignatures: Cosignatures"
+        FROM objects AS o
+        WHERE o.id = $1
         "#,
         id
     )
@@ -405,12 +633,19 @@ pub async fn add_object_from_server(
         bail!("No jurisdiction found");
     };
 
-    let object_type = object.try_to_inner()?.object_type();
+    let payload = object.try_to_inner()?;
+    let object_type = payload.object_type();
+
+    let signer_fingerprint = store_signer_certificate(&mut *connection, object).await?;
+    let voter_id = match &payload {
+        cacvote::Payload::CastBallot(cast_ballot) => cast_ballot_voter_id(cast_ballot),
+        _ => None,
+    };
 
     sqlx::query!(
         r#"
-        INSERT INTO objects (id, election_id, jurisdiction, object_type, payload, certificates, signature, server_synced_at)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, now())
+        INSERT INTO objects (id, election_id, jurisdiction, object_type, payload, certificates, signer_fingerprint, signature, signature_algorithm, cosignatures, voter_id, supersedes_object_id, server_synced_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, now())
         "#,
         &object.id,
         object.election_id,
@@ -418,7 +653,12 @@ pub async fn add_object_from_server(
         object_type,
         &object.payload,
         &object.certificates,
-        &object.signature
+        signer_fingerprint,
+        &object.signature,
+        object.signature_algorithm as Option<SignatureAlgorithm>,
+        &object.cosignatures as &Cosignatures,
+        voter_id,
+        object.supersedes()?,
     )
     .execute(connection)
     .await?;
@@ -441,12 +681,24 @@ pub async fn add_object(
         bail!("No jurisdiction found");
     };
 
-    let object_type = object.try_to_inner()?.object_type();
+    let payload = object.try_to_inner()?;
+    let object_type = payload.object_type();
+
+    // the server would reject it, so it has to wait in `pending_objects`
+    if !object.signature_policy_status()?.is_satisfied() {
+        bail!("Object does not have the signatures required for its type");
+    }
+
+    let signer_fingerprint = store_signer_certificate(&mut *connection, object).await?;
+    let voter_id = match &payload {
+        cacvote::Payload::CastBallot(cast_ballot) => cast_ballot_voter_id(cast_ballot),
+        _ => None,
+    };
 
     sqlx::query!(
         r#"
-        INSERT INTO objects (id, election_id, jurisdiction, object_type, payload, certificates, signature)
-        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        INSERT INTO objects (id, election_id, jurisdiction, object_type, payload, certificates, signer_fingerprint, signature, signature_algorithm, cosignatures, voter_id, supersedes_object_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
         "#,
         &object.id,
         object.election_id,
@@ -454,7 +706,12 @@ pub async fn add_object(
         object_type,
         &object.payload,
         &object.certificates,
-        &object.signature
+        signer_fingerprint,
+        &object.signature,
+        object.signature_algorithm as Option<SignatureAlgorithm>,
+        &object.cosignatures as &Cosignatures,
+        voter_id,
+        object.supersedes()?,
     )
     .execute(connection)
     .await?;
@@ -464,6 +721,183 @@ pub async fn add_object(
     Ok(object.id)
 }
 
+/// Adds an object that needs more signatures before it can be added to
+/// `objects`, see [`cacvote::SignaturePolicy`].
+#[tracing::instrument(skip(connection, object))]
+pub(crate) async fn add_pending_object(
+    connection: &mut sqlx::PgConnection,
+    object: &cacvote::SignedObject,
+) -> color_eyre::Result<Uuid> {
+    if !object.verify()? {
+        bail!("Unable to verify signature/certificates")
+    }
+
+    let Some(jurisdiction_code) = object.jurisdiction_code() else {
+        bail!("No jurisdiction found");
+    };
+
+    let payload = object.try_to_inner()?;
+    let object_type = payload.object_type();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO pending_objects (id, election_id, jurisdiction, object_type, payload, certificates, signature, signature_algorithm, cosignatures)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#,
+        &object.id,
+        object.election_id,
+        jurisdiction_code.as_str(),
+        object_type,
+        &object.payload,
+        &object.certificates,
+        &object.signature,
+        object.signature_algorithm as Option<SignatureAlgorithm>,
+        &object.cosignatures as &Cosignatures,
+    )
+    .execute(connection)
+    .await?;
+
+    tracing::info!("Created pending object with id {}", object.id);
+
+    Ok(object.id)
+}
+
+#[tracing::instrument(skip(connection))]
+pub(crate) async fn get_pending_object(
+    connection: &mut sqlx::PgConnection,
+    id: Uuid,
+) -> color_eyre::Result<Option<cacvote::SignedObject>> {
+    Ok(sqlx::query_as!(
+        cacvote::SignedObject,
+        r#"
+        SELECT
+            id,
+            election_id,
+            payload,
+            certificates,
+            signature,
+            signature_algorithm AS "signature_algorithm: SignatureAlgorithm",
+            cosignatures AS "cosignatures: Cosignatures"
+        FROM pending_objects
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(connection)
+    .await?)
+}
+
+pub(crate) async fn get_pending_objects(
+    connection: &mut sqlx::PgConnection,
+) -> color_eyre::Result<Vec<cacvote::PendingObjectPresenter>> {
+    let records = sqlx::query!(
+        r#"
+        SELECT
+            id,
+            election_id,
+            payload,
+            certificates,
+            signature,
+            signature_algorithm AS "signature_algorithm: SignatureAlgorithm",
+            cosignatures AS "cosignatures: Cosignatures",
+            object_type,
+            created_at
+        FROM pending_objects
+        ORDER BY created_at DESC
+        "#,
+    )
+    .fetch_all(connection)
+    .await?;
+
+    records
+        .into_iter()
+        .map(|record| {
+            let object = cacvote::SignedObject {
+                id: record.id,
+                election_id: record.election_id,
+                payload: record.payload,
+                certificates: record.certificates,
+                signature: record.signature,
+                signature_algorithm: record.signature_algorithm,
+                cosignatures: record.cosignatures,
+            };
+
+            Ok(cacvote::PendingObjectPresenter::new(
+                object.id,
+                record.object_type,
+                object.election_id,
+                object.signature_policy_status()?,
+                record.created_at,
+            ))
+        })
+        .collect()
+}
+
+/// Determines whether an object of the given type for the given election is
+/// waiting on signatures.
+pub(crate) async fn has_pending_object(
+    connection: &mut sqlx::PgConnection,
+    election_id: Uuid,
+    object_type: &str,
+) -> color_eyre::Result<bool> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1
+            FROM pending_objects
+            WHERE election_id = $1
+              AND object_type = $2
+        ) AS "exists!: bool"
+        "#,
+        election_id,
+        object_type,
+    )
+    .fetch_one(connection)
+    .await?
+    .exists)
+}
+
+/// Records the cosignatures collected so far for a pending object.
+#[tracing::instrument(skip(connection, cosignatures))]
+pub(crate) async fn update_pending_object_cosignatures(
+    connection: &mut sqlx::PgConnection,
+    id: Uuid,
+    cosignatures: &Cosignatures,
+) -> color_eyre::Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE pending_objects
+        SET cosignatures = $2
+        WHERE id = $1
+        "#,
+        id,
+        cosignatures as &Cosignatures,
+    )
+    .execute(connection)
+    .await?;
+
+    Ok(())
+}
+
+/// Deletes a pending object, returning whether it existed.
+#[tracing::instrument(skip(connection))]
+pub(crate) async fn delete_pending_object(
+    connection: &mut sqlx::PgConnection,
+    id: Uuid,
+) -> color_eyre::Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM pending_objects
+        WHERE id = $1
+        "#,
+        id,
+    )
+    .execute(connection)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
 #[tracing::instrument(skip(connection, entries))]
 pub(crate) async fn add_journal_entries(
     connection: &mut sqlx::PgConnection,
@@ -491,6 +925,62 @@ pub(crate) async fn add_journal_entries(
     Ok(())
 }
 
+/// Adds the objects and journal entries of a snapshot from the server,
+/// skipping objects this terminal already has, e.g. ones it created itself.
+#[tracing::instrument(skip(connection, entries))]
+pub(crate) async fn add_snapshot_entries(
+    connection: &mut sqlx::PgConnection,
+    entries: &[cacvote::SnapshotEntry],
+) -> color_eyre::Result<()> {
+    let mut txn = connection.begin().await?;
+
+    for entry in entries {
+        let exists = sqlx::query!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1
+                FROM objects
+                WHERE id = $1
+            ) AS "exists!: bool"
+            "#,
+            entry.object.id,
+        )
+        .fetch_one(&mut *txn)
+        .await?
+        .exists;
+
+        if !exists {
+            add_object_from_server(&mut txn, &entry.object).await?;
+        }
+    }
+
+    add_journal_entries(
+        &mut txn,
+        entries
+            .iter()
+            .map(|entry| entry.journal_entry.clone())
+            .collect(),
+    )
+    .await?;
+
+    txn.commit().await?;
+    Ok(())
+}
+
+pub(crate) async fn count_journal_entries(
+    connection: &mut sqlx::PgConnection,
+) -> color_eyre::eyre::Result<i64> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT COUNT(*) AS "count!"
+        FROM journal_entries
+        "#
+    )
+    .fetch_one(connection)
+    .await?
+    .count)
+}
+
 pub(crate) async fn get_latest_journal_entry(
     connection: &mut sqlx::PgConnection,
 ) -> color_eyre::eyre::Result<Option<cacvote::JournalEntry>> {
@@ -521,13 +1011,15 @@ pub(crate) async fn get_unsynced_objects(
         cacvote::SignedObject,
         r#"
         SELECT
-            id,
-            election_id,
-            payload,
-            certificates,
-            signature
-        FROM objects
-        WHERE server_synced_at IS NULL
+            o.id,
+            o.election_id,
+            o.payload,
+            o.certificates,
+            o.signature,
+            o.signature_algorithm AS "signature_algorithm: SignatureAlgorithm",
+            o.cosignatures AS "cosignatures: Cosignatures"
+        FROM objects AS o
+        WHERE o.server_synced_at IS NULL
         "#,
     )
     .fetch_all(&mut *executor)
@@ -552,6 +1044,9 @@ pub(crate) async fn mark_object_synced(
     Ok(())
 }
 
+/// Gets the journal entries of objects this terminal doesn't have but needs:
+/// those voters create on the server, and new versions of any object, which
+/// may have been signed elsewhere.
 pub(crate) async fn get_journal_entries_for_objects_to_pull(
     executor: &mut sqlx::PgConnection,
 ) -> color_eyre::eyre::Result<Vec<cacvote::JournalEntry>> {
@@ -568,11 +1063,12 @@ pub(crate) async fn get_journal_entries_for_objects_to_pull(
             created_at
         FROM journal_entries
         WHERE object_id IS NOT NULL
-          AND object_type IN ($1, $2)
+          AND (object_type IN ($1, $2) OR action = $3)
           AND object_id NOT IN (SELECT id FROM objects)
         "#,
         cacvote::Payload::registration_request_object_type(),
         cacvote::Payload::cast_ballot_object_type(),
+        cacvote::JournalEntryAction::Update.as_str(),
     )
     .fetch_all(&mut *executor)
     .await?)
@@ -589,16 +1085,22 @@ pub(crate) async fn get_cast_ballots(
             cb.payload AS cast_ballot_payload,
             cb.certificates AS cast_ballot_certificates,
             cb.signature AS cast_ballot_signature,
+            cb.signature_algorithm AS "cast_ballot_signature_algorithm: SignatureAlgorithm",
+            cb.cosignatures AS "cast_ballot_cosignatures: Cosignatures",
             rr.id AS registration_request_id,
             rr.election_id AS registration_request_election_id,
             rr.payload AS registration_request_payload,
             rr.certificates AS registration_request_certificates,
             rr.signature AS registration_request_signature,
+            rr.signature_algorithm AS "registration_request_signature_algorithm: SignatureAlgorithm",
+            rr.cosignatures AS "registration_request_cosignatures: Cosignatures",
             r.id AS registration_id,
             r.election_id AS registration_election_id,
             r.payload AS registration_payload,
             r.certificates AS registration_certificates,
             r.signature AS registration_signature,
+            r.signature_algorithm AS "registration_signature_algorithm: SignatureAlgorithm",
+            r.cosignatures AS "registration_cosignatures: Cosignatures",
             cb.created_at AS created_at
         FROM objects AS cb
         -- join on registration request
@@ -621,6 +1123,7 @@ pub(crate) async fn get_cast_ballots(
     .fetch_all(&mut *executor)
     .await?;
 
+    let key_pairs = get_jurisdiction_encryption_key_pairs(executor).await?;
     let mut cast_ballots = Vec::new();
 
     for record in records {
@@ -630,6 +1133,8 @@ pub(crate) async fn get_cast_ballots(
             payload: record.cast_ballot_payload,
             certificates: record.cast_ballot_certificates,
             signature: record.cast_ballot_signature,
+            signature_algorithm: record.cast_ballot_signature_algorithm,
+            cosignatures: record.cast_ballot_cosignatures,
         };
         let registration_object = cacvote::SignedObject {
             id: record.registration_id,
@@ -637,6 +1142,8 @@ pub(crate) async fn get_cast_ballots(
             payload: record.registration_payload,
             certificates: record.registration_certificates,
             signature: record.registration_signature,
+            signature_algorithm: record.registration_signature_algorithm,
+            cosignatures: record.registration_cosignatures,
         };
         let registration_request_object = cacvote::SignedObject {
             id: record.registration_request_id,
@@ -644,14 +1151,18 @@ pub(crate) async fn get_cast_ballots(
             payload: record.registration_request_payload,
             certificates: record.registration_request_certificates,
             signature: record.registration_request_signature,
+            signature_algorithm: record.registration_request_signature_algorithm,
+            cosignatures: record.registration_request_cosignatures,
         };
 
-        if let cacvote::Payload::CastBallot(cast_ballot) = cast_ballot_object.try_to_inner()? {
+        if let cacvote::Payload::CastBallot(cast_ballot) =
+            decrypt_payload(&cast_ballot_object, &key_pairs)?
+        {
             if let cacvote::Payload::RegistrationRequest(registration_request) =
-                registration_request_object.try_to_inner()?
+                decrypt_payload(&registration_request_object, &key_pairs)?
             {
                 if let cacvote::Payload::Registration(registration) =
-                    registration_object.try_to_inner()?
+                    decrypt_payload(&registration_object, &key_pairs)?
                 {
                     // TODO: remove this or replace with actual verification status
                     // we already verify the signature as part of adding the object to the DB,
@@ -677,6 +1188,9 @@ pub(crate) async fn get_cast_ballots(
     Ok(cast_ballots)
 }
 
+/// Gets the cast ballots that count in the election, i.e. the last one cast by
+/// each voter. Earlier ballots are superseded, see the
+/// `cast_ballot_supersessions` view.
 pub(crate) async fn get_cast_ballots_for_election(
     executor: &mut sqlx::PgConnection,
     election_object_id: &Uuid,
@@ -688,10 +1202,15 @@ pub(crate) async fn get_cast_ballots_for_election(
             cb.election_id as cast_ballot_election_id,
             cb.payload AS cast_ballot_payload,
             cb.certificates AS cast_ballot_certificates,
-            cb.signature AS cast_ballot_signature
+            cb.signature AS cast_ballot_signature,
+            cb.signature_algorithm AS "cast_ballot_signature_algorithm: SignatureAlgorithm",
+            cb.cosignatures AS "cast_ballot_cosignatures: Cosignatures"
         FROM objects AS cb
+        INNER JOIN cast_ballot_supersessions AS cbs
+            ON cbs.cast_ballot_id = cb.id
         WHERE cb.object_type = $1
           AND (convert_from(cb.payload, 'UTF8')::jsonb ->> $2)::uuid = $3
+          AND cbs.superseded_by_id IS NULL
         "#,
         cacvote::Payload::cast_ballot_object_type(),
         cacvote::CastBallot::election_object_id_field_name(),
@@ -709,6 +1228,8 @@ pub(crate) async fn get_cast_ballots_for_election(
             payload: record.cast_ballot_payload,
             certificates: record.cast_ballot_certificates,
             signature: record.cast_ballot_signature,
+            signature_algorithm: record.cast_ballot_signature_algorithm,
+            cosignatures: record.cast_ballot_cosignatures,
         };
 
         if let cacvote::Payload::CastBallot(cast_ballot) = cast_ballot.try_to_inner()? {
@@ -731,6 +1252,8 @@ pub(crate) async fn get_shuffled_encrypted_cast_ballots(
             b.payload AS shuffled_encrypted_cast_ballots_payload,
             b.certificates AS shuffled_encrypted_cast_ballots_certificates,
             b.signature AS shuffled_encrypted_cast_ballots_signature,
+            b.signature_algorithm AS "signature_algorithm: SignatureAlgorithm",
+            b.cosignatures AS "cosignatures: Cosignatures",
             b.created_at AS shuffled_encrypted_cast_ballots_created_at,
             b.server_synced_at AS shuffled_encrypted_cast_ballots_server_synced_at
         FROM objects AS b
@@ -741,7 +1264,7 @@ pub(crate) async fn get_shuffled_encrypted_cast_ballots(
         cacvote::ShuffledEncryptedCastBallots::election_object_id_field_name(),
         election_object_id,
     )
-    .fetch_optional(executor)
+    .fetch_optional(&mut *executor)
     .await?
     else {
         return Ok(None);
@@ -753,14 +1276,24 @@ pub(crate) async fn get_shuffled_encrypted_cast_ballots(
         payload: record.shuffled_encrypted_cast_ballots_payload,
         certificates: record.shuffled_encrypted_cast_ballots_certificates,
         signature: record.shuffled_encrypted_cast_ballots_signature,
+        signature_algorithm: record.signature_algorithm,
+        cosignatures: record.cosignatures,
     };
 
-    let cacvote::Payload::ShuffledEncryptedCastBallots(shuffled_encrypted_cast_ballots) =
+    let cacvote::Payload::ShuffledEncryptedCastBallots(mut shuffled_encrypted_cast_ballots) =
         shuffled_encrypted_cast_ballots.try_to_inner()?
     else {
         bail!("Object is not a shuffled encrypted cast ballots")
     };
 
+    // the presenter carries the shuffled ballots themselves, not a reference
+    shuffled_encrypted_cast_ballots.electionguard_shuffled_ballots = load_blob_content(
+        executor,
+        &shuffled_encrypted_cast_ballots.electionguard_shuffled_ballots,
+    )
+    .await?
+    .into();
+
     let shuffled_encrypted_cast_ballots = cacvote::ShuffledEncryptedCastBallotsPresenter {
         shuffled_encrypted_cast_ballots,
         created_at: record.shuffled_encrypted_cast_ballots_created_at,
@@ -770,6 +1303,59 @@ pub(crate) async fn get_shuffled_encrypted_cast_ballots(
     Ok(Some(shuffled_encrypted_cast_ballots))
 }
 
+/// Stores `data` as a blob, returning the reference to it for use in payloads.
+pub(crate) async fn add_blob(
+    executor: &mut sqlx::PgConnection,
+    data: &[u8],
+) -> color_eyre::Result<cacvote::BlobReference> {
+    let reference = cacvote::BlobReference::for_data(data);
+
+    sqlx::query!(
+        r#"
+        INSERT INTO blobs (sha256, data)
+        VALUES ($1, $2)
+        ON CONFLICT (sha256) DO NOTHING
+        "#,
+        reference.sha256,
+        data,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(reference)
+}
+
+pub(crate) async fn get_blob(
+    executor: &mut sqlx::PgConnection,
+    reference: &cacvote::BlobReference,
+) -> color_eyre::Result<Option<Vec<u8>>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT data
+        FROM blobs
+        WHERE sha256 = $1
+        "#,
+        reference.sha256,
+    )
+    .fetch_optional(executor)
+    .await?
+    .map(|record| record.data))
+}
+
+/// Gets the data of `content`, whether embedded or in a blob.
+pub(crate) async fn load_blob_content(
+    executor: &mut sqlx::PgConnection,
+    content: &cacvote::BlobContent,
+) -> color_eyre::Result<Vec<u8>> {
+    match content {
+        cacvote::BlobContent::Inline(data) => Ok(data.clone()),
+        cacvote::BlobContent::Blob(reference) => match get_blob(executor, reference).await? {
+            Some(data) => Ok(data),
+            None => bail!("Blob {} not found", reference.sha256),
+        },
+    }
+}
+
 pub(crate) async fn add_eg_private_key(
     executor: &mut sqlx::PgConnection,
     election_object_id: &Uuid,
@@ -808,6 +1394,84 @@ pub(crate) async fn get_eg_private_key(
     Ok(record.private_key)
 }
 
+/// Stores a new key pair for encrypting the jurisdiction's voter PII.
+pub(crate) async fn add_jurisdiction_encryption_key(
+    executor: &mut sqlx::PgConnection,
+    jurisdiction_code: &cacvote::JurisdictionCode,
+    key_pair: &hpke::KeyPair,
+) -> color_eyre::Result<Uuid> {
+    let record = sqlx::query!(
+        r#"
+        INSERT INTO jurisdiction_encryption_keys (jurisdiction, private_key, public_key)
+        VALUES ($1, $2, $3)
+        RETURNING id
+        "#,
+        jurisdiction_code.as_str(),
+        key_pair.private_key,
+        key_pair.public_key,
+    )
+    .fetch_one(executor)
+    .await?;
+
+    Ok(record.id)
+}
+
+/// Gets the key pair most recently added for a jurisdiction, i.e. the one new
+/// objects should be encrypted to.
+pub(crate) async fn get_latest_jurisdiction_encryption_key(
+    executor: &mut sqlx::PgConnection,
+    jurisdiction_code: &cacvote::JurisdictionCode,
+) -> color_eyre::Result<Option<hpke::KeyPair>> {
+    let record = sqlx::query!(
+        r#"
+        SELECT private_key, public_key
+        FROM jurisdiction_encryption_keys
+        WHERE jurisdiction = $1
+        ORDER BY created_at DESC
+        LIMIT 1
+        "#,
+        jurisdiction_code.as_str(),
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(record.map(|record| hpke::KeyPair {
+        private_key: record.private_key,
+        public_key: record.public_key,
+    }))
+}
+
+/// Gets every key pair ever added, so that objects encrypted to keys that
+/// have since been rotated can still be decrypted.
+pub(crate) async fn get_jurisdiction_encryption_key_pairs(
+    executor: &mut sqlx::PgConnection,
+) -> color_eyre::Result<Vec<hpke::KeyPair>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT private_key, public_key
+        FROM jurisdiction_encryption_keys
+        ORDER BY created_at DESC
+        "#,
+    )
+    .fetch_all(executor)
+    .await?
+    .into_iter()
+    .map(|record| hpke::KeyPair {
+        private_key: record.private_key,
+        public_key: record.public_key,
+    })
+    .collect())
+}
+
+/// Parses an object's payload, decrypting any private fields encrypted to one
+/// of `key_pairs`.
+fn decrypt_payload(
+    object: &cacvote::SignedObject,
+    key_pairs: &[hpke::KeyPair],
+) -> color_eyre::Result<cacvote::Payload> {
+    object.try_to_inner()?.decrypt_private_fields(key_pairs)
+}
+
 #[cfg(test)]
 mod tests {
     use openssl::{
@@ -831,6 +1495,28 @@ mod tests {
         Ok((x509, public_key, private_key))
     }
 
+    fn load_election_manager_keypairs() -> color_eyre::Result<[(X509, PKey<Private>); 2]> {
+        // two different election manager cards, both for the "jurisdiction" jurisdiction
+        Ok([
+            (
+                X509::from_der(include_bytes!(
+                    "../../../../libs/auth/certs/test/set-1/election-manager/card-vx-admin-cert.der"
+                ))?,
+                PKey::private_key_from_pem(include_bytes!(
+                    "../../../../libs/auth/certs/test/set-1/election-manager/card-vx-admin-private-key.pem"
+                ))?,
+            ),
+            (
+                X509::from_der(include_bytes!(
+                    "../../../../libs/auth/certs/test/set-2/election-manager/card-vx-admin-cert.der"
+                ))?,
+                PKey::private_key_from_pem(include_bytes!(
+                    "../../../../libs/auth/certs/test/set-2/election-manager/card-vx-admin-private-key.pem"
+                ))?,
+            ),
+        ])
+    }
+
     fn load_election_definition() -> color_eyre::Result<ElectionDefinition> {
         Ok(ElectionDefinition::try_from(
             &include_bytes!("../tests/fixtures/electionFamousNames2021.json")[..],
@@ -848,7 +1534,7 @@ mod tests {
             jurisdiction_code: jurisdiction_code.clone(),
             election_definition: election_definition.clone(),
             mailing_address: "123 Main St".to_owned(),
-            electionguard_election_metadata_blob: vec![],
+            electionguard_election_metadata_blob: vec![].into(),
         });
         let election_object = cacvote::SignedObject::from_payload(
             &election_payload,
@@ -920,4 +1606,470 @@ mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_decrypts_private_fields(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let (certificates, _, private_key) = load_keypair()?;
+        let election_definition = load_election_definition()?;
+        let connection = &mut pool.acquire().await?;
+        let jurisdiction_code = JurisdictionCode::try_from("st.test-jurisdiction").unwrap();
+
+        let election_object = cacvote::SignedObject::from_payload(
+            &cacvote::Payload::Election(cacvote::Election {
+                jurisdiction_code: jurisdiction_code.clone(),
+                election_definition: election_definition.clone(),
+                mailing_address: "123 Main St".to_owned(),
+                electionguard_election_metadata_blob: vec![].into(),
+            }),
+            vec![certificates.clone()],
+            &private_key,
+        )?;
+        add_object_from_server(connection, &election_object).await?;
+
+        // rotate once so that decryption has to find the right key
+        let old_key_pair = hpke::KeyPair::generate()?;
+        add_jurisdiction_encryption_key(connection, &jurisdiction_code, &old_key_pair).await?;
+        let key_pair = hpke::KeyPair::generate()?;
+        add_jurisdiction_encryption_key(connection, &jurisdiction_code, &key_pair).await?;
+        assert_eq!(
+            get_latest_jurisdiction_encryption_key(connection, &jurisdiction_code).await?,
+            Some(key_pair.clone())
+        );
+
+        let encryption_key = cacvote::JurisdictionEncryptionKey {
+            jurisdiction_code: jurisdiction_code.clone(),
+            public_key: key_pair.public_key,
+        };
+        let registration_request_object = cacvote::SignedObject::from_payload(
+            &cacvote::Payload::RegistrationRequest(cacvote::RegistrationRequest {
+                jurisdiction_code: jurisdiction_code.clone(),
+                common_access_card_id: "0123456789".to_owned(),
+                family_name: "Smith".to_owned(),
+                given_name: "John".to_owned(),
+            })
+            .encrypt_private_fields(&encryption_key)?,
+            vec![certificates.clone()],
+            &private_key,
+        )?;
+        add_object_from_server(connection, &registration_request_object).await?;
+
+        match get_pending_registration_requests(connection)
+            .await?
+            .as_slice()
+        {
+            [registration_request] => {
+                assert_eq!(registration_request.common_access_card_id, "0123456789");
+                assert_eq!(registration_request.given_name, "John");
+                assert_eq!(registration_request.family_name, "Smith");
+            }
+            pending_registration_requests => {
+                panic!("Expected one registration request, got {pending_registration_requests:?}")
+            }
+        }
+
+        let registration_request =
+            get_registration_request(connection, registration_request_object.id).await?;
+        assert_eq!(registration_request.display_name(), "John Smith");
+
+        let registration_object = cacvote::SignedObject::from_payload(
+            &cacvote::Payload::Registration(cacvote::Registration {
+                jurisdiction_code: jurisdiction_code.clone(),
+                common_access_card_id: "0123456789".to_owned(),
+                registration_request_object_id: registration_request_object.id,
+                election_object_id: election_object.id,
+                ballot_style_id: election_definition.election.ballot_styles[0].id.clone(),
+                precinct_id: election_definition.election.precincts[0].id.clone(),
+            })
+            .encrypt_private_fields(&encryption_key)?,
+            vec![certificates.clone()],
+            &private_key,
+        )?;
+        add_object(connection, &registration_object).await?;
+
+        match get_registrations(connection).await?.as_slice() {
+            [registration] => {
+                assert_eq!(registration.common_access_card_id, "0123456789");
+                assert_eq!(registration.display_name(), "John Smith");
+            }
+            registrations => panic!("Expected one registration, got {registrations:?}"),
+        }
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_cast_ballots_for_election(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let (certificates, _, private_key) = load_keypair()?;
+        let [(other_certificate, other_private_key), _] = load_election_manager_keypairs()?;
+        let connection = &mut pool.acquire().await?;
+        let jurisdiction_code = JurisdictionCode::try_from("st.test-jurisdiction").unwrap();
+
+        let election_object = cacvote::SignedObject::from_payload(
+            &cacvote::Payload::Election(cacvote::Election {
+                jurisdiction_code: jurisdiction_code.clone(),
+                election_definition: load_election_definition()?,
+                mailing_address: "123 Main St".to_owned(),
+                electionguard_election_metadata_blob: vec![].into(),
+            }),
+            vec![certificates.clone()],
+            &private_key,
+        )?;
+        add_object_from_server(connection, &election_object).await?;
+
+        let cast_ballot = |common_access_card_id: &str,
+                           certificate: &X509,
+                           private_key: &PKey<Private>,
+                           encrypted_ballot: &str|
+         -> color_eyre::Result<cacvote::SignedObject> {
+            cacvote::SignedObject::from_payload(
+                &cacvote::Payload::CastBallot(cacvote::CastBallot {
+                    common_access_card_id: common_access_card_id.to_owned(),
+                    jurisdiction_code: jurisdiction_code.clone(),
+                    registration_request_object_id: Uuid::new_v4(),
+                    registration_object_id: Uuid::new_v4(),
+                    election_object_id: election_object.id,
+                    electionguard_encrypted_ballot: encrypted_ballot.to_owned(),
+                }),
+                vec![certificate.clone()],
+                private_key,
+            )
+        };
+
+        // one voter casts twice, the second time with a reissued card, and
+        // another once
+        let first_ballot = cast_ballot("0123456789", &certificates, &private_key, "first")?;
+        let second_ballot = cast_ballot(
+            "0123456789",
+            &other_certificate,
+            &other_private_key,
+            "second",
+        )?;
+        let other_ballot = cast_ballot("9876543210", &certificates, &private_key, "other")?;
+
+        // the server's journal decides the order, not when the JX got them
+        let recorded_at = time::OffsetDateTime::now_utc();
+        let mut journal_entries = vec![];
+        for (object, minutes_ago) in [(&second_ballot, 1), (&other_ballot, 2), (&first_ballot, 3)] {
+            add_object_from_server(connection, object).await?;
+            journal_entries.push(cacvote::JournalEntry {
+                id: Uuid::new_v4(),
+                object_id: object.id,
+                election_id: object.election_id,
+                jurisdiction_code: jurisdiction_code.clone(),
+                object_type: cacvote::Payload::cast_ballot_object_type().to_owned(),
+                action: cacvote::JournalEntryAction::Create,
+                created_at: recorded_at - time::Duration::minutes(minutes_ago),
+            });
+        }
+        add_journal_entries(connection, journal_entries).await?;
+
+        let mut encrypted_ballots = get_cast_ballots_for_election(connection, &election_object.id)
+            .await?
+            .into_iter()
+            .map(|cast_ballot| cast_ballot.electionguard_encrypted_ballot)
+            .collect::<Vec<_>>();
+        encrypted_ballots.sort();
+        assert_eq!(encrypted_ballots, vec!["other", "second"]);
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_add_snapshot_entries(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let (certificates, _, private_key) = load_keypair()?;
+        let election_definition = load_election_definition()?;
+        let connection = &mut pool.acquire().await?;
+        let jurisdiction_code = JurisdictionCode::try_from("st.test-jurisdiction").unwrap();
+
+        let election_payload = cacvote::Payload::Election(cacvote::Election {
+            jurisdiction_code: jurisdiction_code.clone(),
+            election_definition,
+            mailing_address: "123 Main St".to_owned(),
+            electionguard_election_metadata_blob: vec![].into(),
+        });
+        let entries = (0..2)
+            .map(|n| {
+                let object = cacvote::SignedObject::from_payload(
+                    &election_payload,
+                    vec![certificates.clone()],
+                    &private_key,
+                )?;
+                Ok(cacvote::SnapshotEntry {
+                    journal_entry: cacvote::JournalEntry {
+                        id: Uuid::new_v4(),
+                        object_id: object.id,
+                        election_id: None,
+                        jurisdiction_code: jurisdiction_code.clone(),
+                        object_type: cacvote::Payload::election_object_type().to_owned(),
+                        action: cacvote::JournalEntryAction::Create,
+                        created_at: time::OffsetDateTime::now_utc() + time::Duration::seconds(n),
+                    },
+                    object,
+                })
+            })
+            .collect::<color_eyre::Result<Vec<_>>>()?;
+
+        // this terminal created the first object itself
+        add_object(connection, &entries[0].object).await?;
+
+        add_snapshot_entries(connection, &entries).await?;
+
+        assert_eq!(
+            get_latest_journal_entry(connection)
+                .await?
+                .map(|journal_entry| journal_entry.id),
+            Some(entries[1].journal_entry.id)
+        );
+        assert_eq!(get_elections(connection).await?.len(), 2);
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_objects_this_build_cannot_interpret(
+        pool: sqlx::PgPool,
+    ) -> color_eyre::Result<()> {
+        let (certificates, _, private_key) = load_keypair()?;
+        let election_definition = load_election_definition()?;
+        let connection = &mut pool.acquire().await?;
+
+        let election_payload = cacvote::Payload::Election(cacvote::Election {
+            jurisdiction_code: JurisdictionCode::try_from("st.test-jurisdiction").unwrap(),
+            election_definition,
+            mailing_address: "123 Main St".to_owned(),
+            electionguard_election_metadata_blob: vec![].into(),
+        });
+        let election_object = cacvote::SignedObject::from_payload(
+            &election_payload,
+            vec![certificates.clone()],
+            &private_key,
+        )?;
+        add_object_from_server(connection, &election_object).await?;
+
+        // an election in a newer payload envelope version, and an object type
+        // added since this build
+        let mut newer_election = serde_json::to_value(&election_payload)?;
+        newer_election[cacvote::ENVELOPE_VERSION_FIELD_NAME] =
+            (cacvote::PAYLOAD_ENVELOPE_VERSION + 1).into();
+        let newer_object_type = serde_json::json!({
+            "objectType": "PollBook",
+            "jurisdictionCode": "st.test-jurisdiction",
+            "electionObjectId": election_object.id,
+        });
+        for payload in [newer_election, newer_object_type] {
+            let payload: cacvote::Payload = serde_json::from_value(payload)?;
+            assert!(payload.is_unknown());
+            let object = cacvote::SignedObject::from_payload(
+                &payload,
+                vec![certificates.clone()],
+                &private_key,
+            )?;
+            add_object_from_server(connection, &object).await?;
+
+            // kept as signed, e.g. to be read by a newer build
+            let stored_object = get_object(connection, object.id).await?;
+            assert_eq!(stored_object.payload, object.payload);
+            assert!(stored_object.verify()?);
+        }
+
+        let elections = get_elections(connection).await?;
+        assert_eq!(
+            elections
+                .iter()
+                .map(|election| election.id)
+                .collect::<Vec<_>>(),
+            vec![election_object.id]
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_election_versions(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let (certificates, _, private_key) = load_keypair()?;
+        let connection = &mut pool.acquire().await?;
+        let jurisdiction_code = JurisdictionCode::try_from("st.test-jurisdiction").unwrap();
+
+        let election = |mailing_address: &str| {
+            cacvote::Payload::Election(cacvote::Election {
+                jurisdiction_code: jurisdiction_code.clone(),
+                election_definition: load_election_definition().unwrap(),
+                mailing_address: mailing_address.to_owned(),
+                electionguard_election_metadata_blob: vec![].into(),
+            })
+        };
+        let election_object = cacvote::SignedObject::from_payload(
+            &election("123 Main St"),
+            vec![certificates.clone()],
+            &private_key,
+        )?;
+        add_object(connection, &election_object).await?;
+
+        // a new version signed elsewhere is pulled from the server
+        let new_version = cacvote::SignedObject::from_payload_superseding(
+            &election("456 Elm St"),
+            election_object.id,
+            vec![certificates.clone()],
+            &private_key,
+        )?;
+        let journal_entry = cacvote::JournalEntry {
+            id: Uuid::new_v4(),
+            object_id: new_version.id,
+            election_id: None,
+            jurisdiction_code: jurisdiction_code.clone(),
+            object_type: cacvote::Payload::election_object_type().to_owned(),
+            action: cacvote::JournalEntryAction::Update,
+            created_at: time::macros::datetime!(2024-06-07 12:00 UTC),
+        };
+        add_journal_entries(connection, vec![journal_entry.clone()]).await?;
+        assert_eq!(
+            get_journal_entries_for_objects_to_pull(connection).await?,
+            vec![journal_entry]
+        );
+        add_object_from_server(connection, &new_version).await?;
+        assert_eq!(
+            get_journal_entries_for_objects_to_pull(connection).await?,
+            vec![]
+        );
+
+        // and shown in place of the original, under the original's ID
+        let elections = get_elections(connection).await?;
+        let [election] = elections.as_slice() else {
+            panic!("expected one election, got: {elections:?}");
+        };
+        assert_eq!(election.id, election_object.id);
+        assert_eq!(
+            serde_json::to_value(election)?["election"]["mailingAddress"],
+            "456 Elm St"
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_blobs(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let mut db = pool.acquire().await?;
+
+        let reference = add_blob(&mut db, b"shuffled ballots").await?;
+        assert_eq!(
+            reference,
+            cacvote::BlobReference::for_data(b"shuffled ballots")
+        );
+
+        // adding the same data again is a no-op
+        assert_eq!(add_blob(&mut db, b"shuffled ballots").await?, reference);
+
+        assert_eq!(
+            get_blob(&mut db, &reference).await?,
+            Some(b"shuffled ballots".to_vec())
+        );
+        assert_eq!(
+            load_blob_content(&mut db, &cacvote::BlobContent::Blob(reference)).await?,
+            b"shuffled ballots".to_vec()
+        );
+        assert_eq!(
+            load_blob_content(&mut db, &b"inline".to_vec().into()).await?,
+            b"inline".to_vec()
+        );
+
+        let missing = cacvote::BlobContent::Blob(cacvote::BlobReference::for_data(b"missing"));
+        assert!(load_blob_content(&mut db, &missing).await.is_err());
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_pending_objects(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let (certificates, _, private_key) = load_keypair()?;
+        let [(first_certificate, first_private_key), (second_certificate, second_private_key)] =
+            load_election_manager_keypairs()?;
+        let connection = &mut pool.acquire().await?;
+        let jurisdiction_code = JurisdictionCode::try_from("jurisdiction").unwrap();
+
+        let election_object = cacvote::SignedObject::from_payload(
+            &cacvote::Payload::Election(cacvote::Election {
+                jurisdiction_code: jurisdiction_code.clone(),
+                election_definition: load_election_definition()?,
+                mailing_address: "123 Main St".to_owned(),
+                electionguard_election_metadata_blob: vec![].into(),
+            }),
+            vec![certificates],
+            &private_key,
+        )?;
+        add_object_from_server(connection, &election_object).await?;
+
+        let mut decrypted_tally_object = cacvote::SignedObject::from_payload(
+            &cacvote::Payload::DecryptedElectionTally(cacvote::DecryptedElectionTally {
+                jurisdiction_code,
+                election_object_id: election_object.id,
+                electionguard_decrypted_tally: b"decrypted tally".to_vec(),
+            }),
+            vec![first_certificate],
+            &first_private_key,
+        )?;
+
+        // not enough signatures to be synced
+        assert!(add_object(connection, &decrypted_tally_object)
+            .await
+            .is_err());
+        add_pending_object(connection, &decrypted_tally_object).await?;
+        assert!(
+            has_pending_object(
+                connection,
+                election_object.id,
+                cacvote::Payload::decrypted_election_tally_object_type()
+            )
+            .await?
+        );
+
+        match get_pending_objects(connection).await?.as_slice() {
+            [pending_object] => {
+                assert_eq!(pending_object.id, decrypted_tally_object.id);
+                assert_eq!(pending_object.object_type(), "DecryptedElectionTally");
+                assert_eq!(pending_object.election_id(), Some(election_object.id));
+                assert_eq!(
+                    pending_object.signature_policy_status(),
+                    cacvote::SignaturePolicyStatus {
+                        signers_required: 2,
+                        signers_collected: 1,
+                    }
+                );
+            }
+            pending_objects => panic!("Expected one pending object, got {pending_objects:?}"),
+        }
+
+        // a second election manager approves
+        decrypted_tally_object.add_cosignature(cacvote::Cosignature::sign(
+            &decrypted_tally_object.payload,
+            vec![second_certificate],
+            &second_private_key,
+        )?);
+        update_pending_object_cosignatures(
+            connection,
+            decrypted_tally_object.id,
+            &decrypted_tally_object.cosignatures,
+        )
+        .await?;
+
+        let pending_object = get_pending_object(connection, decrypted_tally_object.id)
+            .await?
+            .unwrap();
+        assert_eq!(pending_object.cosignatures.len(), 1);
+        assert!(pending_object.verify()?);
+        assert!(pending_object.signature_policy_status()?.is_satisfied());
+
+        assert!(delete_pending_object(connection, pending_object.id).await?);
+        add_object(connection, &pending_object).await?;
+
+        assert!(get_pending_objects(connection).await?.is_empty());
+        assert_eq!(
+            get_object(connection, pending_object.id)
+                .await?
+                .cosignatures
+                .len(),
+            1
+        );
+
+        Ok(())
+    }
 }
@@ -6,16 +6,46 @@
 //!
 //! You may use the `RUST_LOG` environment variable to configure logging at
 //! runtime (see [`EnvFilter`][`tracing_subscriber::EnvFilter`]).
+//!
+//! When an OTLP endpoint is configured, spans are also exported to it as
+//! OpenTelemetry traces, and incoming requests continue the trace named by
+//! their W3C `traceparent` header, if any.
 
+use axum::http::Request;
+use opentelemetry::{global, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{propagation::TraceContextPropagator, runtime, trace, Resource};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
 use tracing_subscriber::{prelude::*, util::SubscriberInitExt};
 
 use crate::config::Config;
 
+const SERVICE_NAME: &str = "cacvote-jx-terminal";
+
 /// Sets up logging for the application. Call this early in the process
 /// lifecycle to ensure logs are not silently ignored.
 pub(crate) fn setup(config: &Config) -> color_eyre::Result<()> {
     color_eyre::install()?;
     let stdout_log = tracing_subscriber::fmt::layer().pretty();
+    let otlp_trace = match &config.otlp_endpoint {
+        Some(endpoint) => {
+            global::set_text_map_propagator(TraceContextPropagator::new());
+            let tracer =
+                opentelemetry_otlp::new_pipeline()
+                    .tracing()
+                    .with_exporter(
+                        opentelemetry_otlp::new_exporter()
+                            .http()
+                            .with_endpoint(endpoint),
+                    )
+                    .with_trace_config(trace::config().with_resource(Resource::new([
+                        KeyValue::new("service.name", SERVICE_NAME),
+                    ])))
+                    .install_batch(runtime::Tokio)?;
+            Some(tracing_opentelemetry::layer().with_tracer(tracer))
+        }
+        None => None,
+    };
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::builder()
@@ -30,6 +60,30 @@ pub(crate) fn setup(config: &Config) -> color_eyre::Result<()> {
                 .from_env_lossy(),
         )
         .with(stdout_log)
+        .with(otlp_trace)
         .init();
     Ok(())
 }
+
+/// Exports any spans not yet exported. Call this before exiting.
+pub(crate) fn shutdown() {
+    global::shutdown_tracer_provider();
+}
+
+/// Makes the span for an HTTP request handled by [`TraceLayer`], continuing
+/// the trace of the client's request if it sent a `traceparent` header.
+///
+/// [`TraceLayer`]: tower_http::trace::TraceLayer
+pub(crate) fn make_request_span<B>(request: &Request<B>) -> tracing::Span {
+    let span = tracing::info_span!(
+        "request",
+        method = %request.method(),
+        uri = %request.uri(),
+        version = ?request.version(),
+    );
+    let parent_context = global::get_text_map_propagator(|propagator| {
+        propagator.extract(&opentelemetry_http::HeaderExtractor(request.headers()))
+    });
+    span.set_parent(parent_context);
+    span
+}
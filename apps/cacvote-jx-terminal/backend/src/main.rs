@@ -51,10 +51,13 @@ mod cac;
 mod config;
 mod db;
 mod log;
+mod metrics;
+mod openapi;
 mod smartcard;
+mod supervisor;
 mod sync;
 
-use crate::smartcard::Smartcard;
+use crate::{smartcard::Smartcard, supervisor::Supervisor};
 
 #[tokio::main]
 async fn main() -> color_eyre::Result<()> {
@@ -63,9 +66,43 @@ async fn main() -> color_eyre::Result<()> {
     let config = config::Config::parse();
     log::setup(&config)?;
     let pool = db::setup(&config).await?;
-    sync::sync_periodically(&pool, config.clone()).await;
+    let metrics = Arc::new(metrics::Metrics::new()?);
+    let supervisor = Supervisor::new(metrics.clone());
+    sync::sync_periodically(&supervisor, pool.clone(), config.clone(), metrics.clone());
     let smartcard_watcher = Watcher::watch();
     let smartcard = Smartcard::new(smartcard_watcher.readers_with_cards());
     let smartcard = Arc::new(smartcard) as smartcard::DynSmartcard;
-    app::run(app::setup(pool, config.clone(), smartcard), &config).await
+
+    let shutdown = supervisor.shutdown_token();
+    tokio::spawn({
+        let shutdown = shutdown.clone();
+        async move {
+            tokio::select! {
+                () = shutdown_rs::shutdown_signal() => {
+                    tracing::info!("Shutting down, finishing requests and syncs in progress");
+                    shutdown.cancel();
+                }
+                () = shutdown.cancelled() => {}
+            }
+        }
+    });
+
+    let result = app::run(
+        app::setup(
+            pool.clone(),
+            config.clone(),
+            smartcard,
+            metrics,
+            supervisor.clone(),
+        ),
+        &config,
+        shutdown,
+    )
+    .await;
+
+    // also stops the tasks if the server failed
+    supervisor.shutdown(config::SHUTDOWN_TIMEOUT).await;
+    pool.close().await;
+    log::shutdown();
+    result
 }
@@ -0,0 +1,258 @@
+//! Prometheus metrics, served at `/metrics` in the text exposition format. All
+//! metric names are prefixed with `cacvote_jx_`.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use axum::{
+    extract::{MatchedPath, State},
+    http::Request,
+    middleware::Next,
+    response::Response,
+};
+use prometheus::{
+    Gauge, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, Opts, Registry, TextEncoder,
+};
+
+#[derive(Debug)]
+pub(crate) struct Metrics {
+    registry: Registry,
+    http_request_duration: HistogramVec,
+    electionguard_command_duration: HistogramVec,
+    journal_entries: IntGauge,
+    sync_lag: Gauge,
+    task_restarts: IntCounterVec,
+
+    /// When the terminal last synced with CACVote Server successfully.
+    last_synced_at: Mutex<Option<Instant>>,
+}
+
+impl Metrics {
+    pub(crate) fn new() -> prometheus::Result<Self> {
+        let registry = Registry::new_custom(Some("cacvote_jx".to_owned()), None)?;
+
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "Time taken to respond to HTTP requests, by route",
+            ),
+            &["method", "route", "status"],
+        )?;
+        let electionguard_command_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "electionguard_command_duration_seconds",
+                "Time taken by Java ElectionGuard CLI commands",
+            )
+            .buckets(vec![0.1, 0.5, 1.0, 5.0, 10.0, 30.0, 60.0, 300.0, 900.0]),
+            &["command"],
+        )?;
+        let journal_entries = IntGauge::new("journal_entries", "Entries in the local journal")?;
+        let sync_lag = Gauge::new(
+            "sync_lag_seconds",
+            "Time since the last successful sync with CACVote Server",
+        )?;
+
+        let task_restarts = IntCounterVec::new(
+            Opts::new(
+                "task_restarts_total",
+                "Times each background task was restarted after failing",
+            ),
+            &["task"],
+        )?;
+
+        registry.register(Box::new(http_request_duration.clone()))?;
+        registry.register(Box::new(electionguard_command_duration.clone()))?;
+        registry.register(Box::new(journal_entries.clone()))?;
+        registry.register(Box::new(sync_lag.clone()))?;
+        registry.register(Box::new(task_restarts.clone()))?;
+
+        Ok(Self {
+            registry,
+            http_request_duration,
+            electionguard_command_duration,
+            journal_entries,
+            sync_lag,
+            task_restarts,
+            last_synced_at: Mutex::new(None),
+        })
+    }
+
+    pub(crate) fn record_sync(&self) {
+        *self.last_synced_at.lock().unwrap() = Some(Instant::now());
+    }
+
+    pub(crate) fn record_task_restart(&self, task: &str) {
+        self.task_restarts.with_label_values(&[task]).inc();
+    }
+
+    /// Runs `f`, which runs the Java ElectionGuard CLI `command`, in its own
+    /// span, recording how long it took.
+    pub(crate) fn time_electionguard_command<T>(&self, command: &str, f: impl FnOnce() -> T) -> T {
+        let _timer = self
+            .electionguard_command_duration
+            .with_label_values(&[command])
+            .start_timer();
+        tracing::info_span!("electionguard_command", command).in_scope(f)
+    }
+
+    /// Encodes every metric in the text exposition format, with the journal
+    /// size, which is read from the database when scraped, set to
+    /// `journal_entries`. The sync lag is omitted until the first successful
+    /// sync.
+    pub(crate) fn encode(&self, journal_entries: i64) -> prometheus::Result<String> {
+        self.journal_entries.set(journal_entries);
+        let last_synced_at = *self.last_synced_at.lock().unwrap();
+        if let Some(last_synced_at) = last_synced_at {
+            self.sync_lag.set(last_synced_at.elapsed().as_secs_f64());
+        }
+
+        let metric_families = self
+            .registry
+            .gather()
+            .into_iter()
+            .filter(|metric_family| {
+                last_synced_at.is_some()
+                    || metric_family.get_name() != "cacvote_jx_sync_lag_seconds"
+            })
+            .collect::<Vec<_>>();
+        TextEncoder::new().encode_to_string(&metric_families)
+    }
+}
+
+/// Records the time taken to respond to each request by its route, e.g.
+/// `/api/elections/:election_id/encrypted-tally`, rather than its path, so
+/// that the number of series stays bounded. Must be added with
+/// [`axum::Router::route_layer`] so that the route is known.
+pub(crate) async fn track_request_duration<B>(
+    State(metrics): State<Arc<Metrics>>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let started_at = Instant::now();
+    let method = request.method().clone();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| "unknown".to_owned(), |path| path.as_str().to_owned());
+
+    let response = next.run(request).await;
+
+    metrics
+        .http_request_duration
+        .with_label_values(&[method.as_str(), &route, response.status().as_str()])
+        .observe(started_at.elapsed().as_secs_f64());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::TcpListener, path::PathBuf};
+
+    use reqwest::Url;
+    use tracing::Level;
+    use types_rs::cacvote::{JurisdictionCode, ReadinessReport, SmartcardStatus};
+
+    use crate::{app, config::Config, smartcard::MockSmartcardTrait, supervisor::Supervisor};
+
+    use super::*;
+
+    /// Runs the app with no card inserted and without the Java ElectionGuard
+    /// CLI, acting as its own CACVote Server.
+    fn spawn_app(pool: sqlx::PgPool) -> color_eyre::Result<Url> {
+        let listener = TcpListener::bind("0.0.0.0:0")?;
+        let addr = listener.local_addr()?;
+        let base_url: Url = format!("http://{addr}").parse()?;
+        let config = Config {
+            cacvote_url: base_url.clone(),
+            cacvote_api_key: None,
+            cacvote_snapshot_public_key: None,
+            database_url: "".to_owned(),
+            machine_id: "".to_owned(),
+            port: addr.port(),
+            public_dir: None,
+            log_level: Level::DEBUG,
+            otlp_endpoint: None,
+            jurisdiction_code: JurisdictionCode::try_from("st.test-jurisdiction").unwrap(),
+            eg_classpath: PathBuf::from("/not/real/path"),
+        };
+
+        let mut smartcard = MockSmartcardTrait::new();
+        smartcard
+            .expect_get_status()
+            .returning(|| SmartcardStatus::NoCard);
+        smartcard.expect_get_card_details().returning(|| None);
+
+        tokio::spawn(async move {
+            let metrics = Arc::new(Metrics::new().unwrap());
+            let app = app::setup(
+                pool,
+                config,
+                Arc::new(smartcard),
+                metrics.clone(),
+                Supervisor::new(metrics),
+            );
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service())
+                .await
+                .unwrap();
+        });
+
+        Ok(base_url)
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_metrics_and_readiness(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let base_url = spawn_app(pool)?;
+        let http = reqwest::Client::new();
+
+        let response = http.get(base_url.join("/api/ready")?).send().await?;
+        assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+        let report: ReadinessReport = response.json().await?;
+        assert!(!report.ready, "{report:?}");
+        let failing_checks = report
+            .checks
+            .iter()
+            .filter(|check| !check.ok)
+            .map(|check| check.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(failing_checks, vec!["electionguard"], "{report:?}");
+        for name in [
+            "database",
+            "migrations",
+            "cacvoteServer",
+            "cardReader",
+            "sessionBroadcastTask",
+        ] {
+            assert!(
+                report.checks.iter().any(|check| check.name == name),
+                "{name} not in {report:?}"
+            );
+        }
+
+        let metrics = http
+            .get(base_url.join("/metrics")?)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        for expected in [
+            "cacvote_jx_journal_entries 0",
+            r#"cacvote_jx_http_request_duration_seconds_count{method="GET",route="/api/ready",status="503"} 1"#,
+            r#"cacvote_jx_http_request_duration_seconds_count{method="GET",route="/api/status",status="200"} 1"#,
+        ] {
+            assert!(metrics.contains(expected), "{expected} not in {metrics}");
+        }
+
+        // not synced yet
+        assert!(
+            !metrics.contains("cacvote_jx_sync_lag_seconds"),
+            "{metrics}"
+        );
+
+        Ok(())
+    }
+}
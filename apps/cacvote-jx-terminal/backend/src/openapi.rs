@@ -0,0 +1,180 @@
+//! The OpenAPI document describing the HTTP API used by the JX terminal's
+//! frontend, served at `/api/openapi.json`. It is generated from the
+//! `#[utoipa::path]` attribute of each route handler in [`app`] and the schemas
+//! derived for the [`types_rs::cacvote`] types they use.
+//!
+//! The response types here only describe the JSON the handlers build and are
+//! never constructed.
+
+use serde::Serialize;
+use types_rs::cacvote::{ElectionPresenter, SignaturePolicyStatus};
+use utoipa::{OpenApi, ToSchema};
+use uuid::Uuid;
+
+use crate::app;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "CACVote JX Terminal",
+        description = "Manages a jurisdiction's elections, voters, and ballots using the inserted Common Access Card."
+    ),
+    paths(
+        app::get_openapi,
+        app::get_metrics,
+        app::get_status,
+        app::get_readiness,
+        app::get_status_stream,
+        app::get_elections,
+        app::create_election,
+        app::create_registration,
+        app::rotate_jurisdiction_encryption_key,
+        app::generate_encrypted_election_tally,
+        app::decrypt_encrypted_election_tally,
+        app::mix_encrypted_ballots,
+        app::delete_pending_object,
+        app::cosign_pending_object,
+    )
+)]
+pub(crate) struct ApiDoc;
+
+/// The body of every error response.
+#[allow(dead_code)]
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct ErrorResponse {
+    error: String,
+}
+
+/// The body of a response to creating an object.
+#[allow(dead_code)]
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct CreatedResponse {
+    id: Uuid,
+}
+
+/// The body of a response to creating or cosigning an object that needs more
+/// signatures before it will be synced.
+#[allow(dead_code)]
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PendingObjectResponse {
+    id: Uuid,
+    signature_policy_status: SignaturePolicyStatus,
+}
+
+/// The body of a response listing elections.
+#[allow(dead_code)]
+#[derive(Debug, Serialize, ToSchema)]
+pub(crate) struct ElectionsResponse {
+    elections: Vec<ElectionPresenter>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::TcpListener, path::PathBuf, sync::Arc};
+
+    use regex::Regex;
+    use reqwest::Url;
+    use tracing::Level;
+    use types_rs::cacvote::{JurisdictionCode, SmartcardStatus};
+
+    use crate::{
+        config::Config, metrics::Metrics, smartcard::MockSmartcardTrait, supervisor::Supervisor,
+    };
+
+    use super::*;
+
+    /// Runs the app with no card inserted.
+    fn spawn_app(pool: sqlx::PgPool) -> color_eyre::Result<Url> {
+        let listener = TcpListener::bind("0.0.0.0:0")?;
+        let addr = listener.local_addr()?;
+        let base_url: Url = format!("http://{addr}").parse()?;
+        let config = Config {
+            cacvote_url: base_url.clone(),
+            cacvote_api_key: None,
+            cacvote_snapshot_public_key: None,
+            database_url: "".to_owned(),
+            machine_id: "".to_owned(),
+            port: addr.port(),
+            public_dir: None,
+            log_level: Level::DEBUG,
+            otlp_endpoint: None,
+            jurisdiction_code: JurisdictionCode::try_from("st.test-jurisdiction").unwrap(),
+            eg_classpath: PathBuf::from("/not/real/path"),
+        };
+
+        let mut smartcard = MockSmartcardTrait::new();
+        smartcard
+            .expect_get_status()
+            .returning(|| SmartcardStatus::NoCard);
+        smartcard.expect_get_card_details().returning(|| None);
+
+        tokio::spawn(async move {
+            let metrics = Arc::new(Metrics::new().unwrap());
+            let app = app::setup(
+                pool,
+                config,
+                Arc::new(smartcard),
+                metrics.clone(),
+                Supervisor::new(metrics),
+            );
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service())
+                .await
+                .unwrap();
+        });
+
+        Ok(base_url)
+    }
+
+    /// Changes to the API show up as changes to this snapshot.
+    #[test]
+    fn test_openapi_document() {
+        insta::assert_snapshot!(ApiDoc::openapi().to_pretty_json().unwrap());
+    }
+
+    /// Every operation in the OpenAPI document is routed, i.e. requests for it
+    /// don't fall through to the router's empty 404 or get a 405.
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_openapi_routes_exist(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let base_url = spawn_app(pool)?;
+        let http = reqwest::Client::new();
+
+        let openapi: serde_json::Value = http
+            .get(base_url.join("/api/openapi.json")?)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let paths = openapi["paths"].as_object().unwrap();
+        assert!(paths.contains_key("/api/elections"), "{paths:?}");
+
+        let path_param = Regex::new(r"\{[a-z0-9_]+\}")?;
+        for (path, path_item) in paths {
+            let path = path_param.replace_all(path, uuid::Uuid::new_v4().to_string());
+            for method in path_item.as_object().unwrap().keys() {
+                let response = http
+                    .request(method.to_uppercase().parse()?, base_url.join(&path)?)
+                    .send()
+                    .await?;
+                let status = response.status();
+                assert_ne!(
+                    status,
+                    reqwest::StatusCode::METHOD_NOT_ALLOWED,
+                    "{method} {path}"
+                );
+                // the status stream never ends, so only read 404 bodies
+                if status == reqwest::StatusCode::NOT_FOUND {
+                    assert!(
+                        !response.bytes().await?.is_empty(),
+                        "{method} {path} is not routed"
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
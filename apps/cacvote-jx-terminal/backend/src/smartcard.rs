@@ -5,7 +5,7 @@ use auth_rs::card_details::CardDetailsWithAuthInfo;
 use auth_rs::vx_card::{VxCard, CARD_VX_ADMIN_CERT};
 use auth_rs::{CardReader, SharedCardReaders};
 use openssl::x509::X509;
-use types_rs::cacvote::SmartcardStatus;
+use types_rs::cacvote::{SignatureAlgorithm, SmartcardStatus};
 
 pub(crate) type DynSmartcard = Arc<dyn SmartcardTrait + Send + Sync>;
 
@@ -14,6 +14,8 @@ pub(crate) trait SmartcardTrait {
     fn get_status(&self) -> SmartcardStatus;
     fn get_card_details(&self) -> Option<CardDetailsWithAuthInfo>;
 
+    /// Signs `data` as the payload of an object or cosignature, i.e. signs
+    /// its [`SignatureAlgorithm::signing_message`].
     #[allow(clippy::needless_lifetimes)] // automock needs the lifetimes
     fn sign<'a, 'b, 'c>(&'a self, data: &'b [u8], pin: Option<&'c str>) -> Result<Signed, String>;
 }
@@ -22,6 +24,7 @@ pub(crate) trait SmartcardTrait {
 pub(crate) struct Signed {
     pub(crate) data: Vec<u8>,
     pub(crate) cert_stack: Vec<X509>,
+    pub(crate) algorithm: SignatureAlgorithm,
 }
 
 /// Provides access to the current smartcard.
@@ -47,7 +50,6 @@ impl Debug for Smartcard {
 
 #[derive(Clone)]
 struct SmartcardInner {
-    #[allow(dead_code)]
     ctx: pcsc::Context,
     #[allow(dead_code)]
     readers_with_cards: SharedCardReaders,
@@ -126,8 +128,6 @@ impl SmartcardInner {
 
 impl SmartcardTrait for Smartcard {
     /// Gets the current smartcard status.
-    #[allow(dead_code)]
-    #[must_use]
     fn get_status(&self) -> SmartcardStatus {
         let inner = match self.0.lock() {
             Ok(inner) => inner,
@@ -137,12 +137,18 @@ impl SmartcardTrait for Smartcard {
             }
         };
 
-        let readers = inner.readers_with_cards.lock().unwrap();
+        if !inner.readers_with_cards.lock().unwrap().is_empty() {
+            return SmartcardStatus::Card;
+        }
 
-        if readers.is_empty() {
-            SmartcardStatus::NoCard
-        } else {
-            SmartcardStatus::Card
+        // readers without a card aren't among the readers with cards
+        match inner.ctx.list_readers_owned() {
+            Ok(readers) if !readers.is_empty() => SmartcardStatus::NoCard,
+            Ok(_) | Err(pcsc::Error::NoReadersAvailable) => SmartcardStatus::NoReader,
+            Err(e) => {
+                tracing::error!("error listing card readers: {e}");
+                SmartcardStatus::NoReader
+            }
         }
     }
 
@@ -172,13 +178,20 @@ impl SmartcardTrait for Smartcard {
             }
         };
         let card = inner.card.as_ref().ok_or("no card")?;
+        // VxAdmin cards sign with ECDSA P-256 over a SHA-256 digest
+        let algorithm = SignatureAlgorithm::EcdsaP256Sha256;
         let (data, public_key) = card
-            .sign(CARD_VX_ADMIN_CERT, data, pin)
+            .sign(CARD_VX_ADMIN_CERT, &algorithm.signing_message(data), pin)
             .map_err(|e| format!("error signing: {e}"))?;
+        // the server only trusts the card's certificate by way of its issuer
+        let vx_admin_cert_authority_cert = card
+            .vx_admin_cert_authority_cert()
+            .map_err(|e| format!("error reading VxAdmin certificate: {e}"))?;
 
         Ok(Signed {
             data,
-            cert_stack: vec![public_key],
+            cert_stack: vec![public_key, vx_admin_cert_authority_cert],
+            algorithm,
         })
     }
 }
@@ -0,0 +1,327 @@
+//! Supervision of background tasks, e.g. syncing with CACVote Server. A task
+//! that fails or panics is restarted after a delay that doubles with each
+//! consecutive failure, and its health is reported by `/api/ready`.
+//!
+//! Tasks are cancelled cooperatively: each is given a [`CancellationToken`]
+//! that is cancelled on shutdown, and is expected to return once it has
+//! finished whatever it was in the middle of.
+
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::metrics::Metrics;
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TaskHealth {
+    Running,
+
+    /// Failed, and waiting to be restarted.
+    Restarting {
+        error: String,
+    },
+
+    /// Returned after shutdown began.
+    Stopped,
+}
+
+#[derive(Debug)]
+struct SupervisedTask {
+    name: &'static str,
+    health: Arc<Mutex<TaskHealth>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+/// Runs background tasks until shutdown, restarting them if they fail.
+/// Clones share the same tasks.
+#[derive(Debug, Clone)]
+pub(crate) struct Supervisor {
+    shutdown: CancellationToken,
+    metrics: Arc<Metrics>,
+    tasks: Arc<Mutex<Vec<SupervisedTask>>>,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Supervisor {
+    pub(crate) fn new(metrics: Arc<Metrics>) -> Self {
+        Self {
+            shutdown: CancellationToken::new(),
+            metrics,
+            tasks: Arc::default(),
+            initial_backoff: INITIAL_BACKOFF,
+            max_backoff: MAX_BACKOFF,
+        }
+    }
+
+    /// Cancelled once shutdown begins.
+    pub(crate) fn shutdown_token(&self) -> CancellationToken {
+        self.shutdown.clone()
+    }
+
+    /// Runs the task `run` starts until shutdown, starting it again whenever
+    /// it fails, panics or returns early. `run` is given a token that is
+    /// cancelled on shutdown.
+    pub(crate) fn spawn<F, Fut>(&self, name: &'static str, run: F)
+    where
+        F: Fn(CancellationToken) -> Fut + Send + 'static,
+        Fut: Future<Output = color_eyre::Result<()>> + Send + 'static,
+    {
+        let health = Arc::new(Mutex::new(TaskHealth::Running));
+        let handle = tokio::spawn(supervise(
+            name,
+            run,
+            self.shutdown.clone(),
+            health.clone(),
+            self.metrics.clone(),
+            (self.initial_backoff, self.max_backoff),
+        ));
+        self.tasks.lock().unwrap().push(SupervisedTask {
+            name,
+            health,
+            handle: Some(handle),
+        });
+    }
+
+    /// The health of each task, in the order they were spawned.
+    pub(crate) fn health(&self) -> Vec<(&'static str, TaskHealth)> {
+        self.tasks
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|task| (task.name, task.health.lock().unwrap().clone()))
+            .collect()
+    }
+
+    /// Cancels every task and waits up to `timeout` for them to return,
+    /// aborting any that haven't.
+    pub(crate) async fn shutdown(&self, timeout: Duration) {
+        self.shutdown.cancel();
+
+        let tasks = self
+            .tasks
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .filter_map(|task| Some((task.name, task.health.clone(), task.handle.take()?)))
+            .collect::<Vec<_>>();
+
+        let deadline = tokio::time::Instant::now() + timeout;
+        for (name, health, mut handle) in tasks {
+            if tokio::time::timeout_at(deadline, &mut handle)
+                .await
+                .is_err()
+            {
+                tracing::warn!("Task {name} did not stop within {timeout:?}, aborting it");
+                handle.abort();
+                let _ = handle.await;
+                *health.lock().unwrap() = TaskHealth::Stopped;
+            }
+        }
+    }
+}
+
+async fn supervise<F, Fut>(
+    name: &'static str,
+    run: F,
+    shutdown: CancellationToken,
+    health: Arc<Mutex<TaskHealth>>,
+    metrics: Arc<Metrics>,
+    (initial_backoff, max_backoff): (Duration, Duration),
+) where
+    F: Fn(CancellationToken) -> Fut + Send + 'static,
+    Fut: Future<Output = color_eyre::Result<()>> + Send + 'static,
+{
+    let mut backoff = initial_backoff;
+
+    loop {
+        *health.lock().unwrap() = TaskHealth::Running;
+        let started_at = Instant::now();
+
+        // spawned so that a panic is caught rather than ending supervision
+        let mut task = AbortOnDrop(tokio::spawn(run(shutdown.clone())));
+        let result = (&mut task.0).await;
+        if shutdown.is_cancelled() {
+            if let Ok(Err(e)) = &result {
+                tracing::error!("Task {name} failed while shutting down: {e}");
+            }
+            break;
+        }
+
+        let error = match result {
+            Ok(Ok(())) => "returned before shutdown".to_owned(),
+            Ok(Err(e)) => e.to_string(),
+            Err(e) => e.to_string(),
+        };
+
+        // a task that ran for a while before failing starts over
+        if started_at.elapsed() >= max_backoff {
+            backoff = initial_backoff;
+        }
+
+        tracing::error!("Task {name} failed, restarting in {backoff:?}: {error}");
+        metrics.record_task_restart(name);
+        *health.lock().unwrap() = TaskHealth::Restarting { error };
+
+        tokio::select! {
+            () = shutdown.cancelled() => break,
+            () = tokio::time::sleep(backoff) => {}
+        }
+        backoff = (backoff * 2).min(max_backoff);
+    }
+
+    *health.lock().unwrap() = TaskHealth::Stopped;
+}
+
+/// Aborts a task when dropped, i.e. when its supervision is aborted.
+#[derive(Debug)]
+struct AbortOnDrop<T>(JoinHandle<T>);
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use color_eyre::eyre::bail;
+
+    use super::*;
+
+    fn supervisor() -> Supervisor {
+        Supervisor {
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(40),
+            ..Supervisor::new(Arc::new(Metrics::new().unwrap()))
+        }
+    }
+
+    async fn wait_for_health(supervisor: &Supervisor, expected: &TaskHealth) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while supervisor.health()[0].1 != *expected {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap_or_else(|_| panic!("expected {expected:?}, got {:?}", supervisor.health()));
+    }
+
+    #[tokio::test]
+    async fn test_restarts_failed_tasks() {
+        let supervisor = supervisor();
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        supervisor.spawn("flaky", {
+            let runs = runs.clone();
+            move |shutdown| {
+                let run = runs.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    match run {
+                        0 => bail!("database is unavailable"),
+                        1 => panic!("unexpected response"),
+                        _ => {
+                            shutdown.cancelled().await;
+                            Ok(())
+                        }
+                    }
+                }
+            }
+        });
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while runs.load(Ordering::SeqCst) < 3 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+        wait_for_health(&supervisor, &TaskHealth::Running).await;
+        assert_eq!(supervisor.health()[0].0, "flaky");
+
+        supervisor.shutdown(Duration::from_secs(5)).await;
+        assert_eq!(supervisor.health(), vec![("flaky", TaskHealth::Stopped)]);
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+
+        let metrics = supervisor.metrics.encode(0).unwrap();
+        assert!(
+            metrics.contains(r#"cacvote_jx_task_restarts_total{task="flaky"} 2"#),
+            "{metrics}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reports_failing_tasks() {
+        let supervisor = Supervisor {
+            initial_backoff: Duration::from_secs(60),
+            max_backoff: Duration::from_secs(60),
+            ..supervisor()
+        };
+        supervisor.spawn("failing", |_| async { bail!("database is unavailable") });
+
+        wait_for_health(
+            &supervisor,
+            &TaskHealth::Restarting {
+                error: "database is unavailable".to_owned(),
+            },
+        )
+        .await;
+
+        // shutdown doesn't wait out the backoff
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            supervisor.shutdown(Duration::from_secs(5)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(supervisor.health(), vec![("failing", TaskHealth::Stopped)]);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_lets_tasks_finish() {
+        let supervisor = supervisor();
+        let finished = Arc::new(AtomicUsize::new(0));
+
+        supervisor.spawn("sync", {
+            let finished = finished.clone();
+            move |shutdown| {
+                let finished = finished.clone();
+                async move {
+                    shutdown.cancelled().await;
+                    // e.g. the rest of a sync in progress
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    finished.fetch_add(1, Ordering::SeqCst);
+                    Ok(())
+                }
+            }
+        });
+
+        supervisor.shutdown(Duration::from_secs(5)).await;
+        assert_eq!(finished.load(Ordering::SeqCst), 1);
+        assert_eq!(supervisor.health(), vec![("sync", TaskHealth::Stopped)]);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_aborts_stuck_tasks() {
+        let supervisor = supervisor();
+        supervisor.spawn("stuck", |_| std::future::pending());
+
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            supervisor.shutdown(Duration::from_millis(50)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(supervisor.health(), vec![("stuck", TaskHealth::Stopped)]);
+    }
+}
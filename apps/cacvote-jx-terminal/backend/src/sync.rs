@@ -1,54 +1,151 @@
 //! CACVote Server synchronization utilities.
 
+use std::sync::Arc;
+
 use cacvote_server_client::Client;
+use color_eyre::eyre::bail;
+use openssl::pkey::{PKeyRef, Public};
 use tokio::time::sleep;
-use types_rs::cacvote::JurisdictionCode;
+use types_rs::cacvote::{JurisdictionCode, ObjectView};
 
 use crate::{
     config::{Config, SYNC_INTERVAL},
     db,
+    metrics::Metrics,
+    supervisor::Supervisor,
 };
 
-/// Spawns an async loop that synchronizes with the CACVote Server on a fixed
-/// schedule.
-pub(crate) async fn sync_periodically(pool: &sqlx::PgPool, config: Config) {
-    let mut connection = pool
-        .acquire()
-        .await
-        .expect("failed to acquire database connection");
-
-    let client = Client::new(config.cacvote_url);
-
-    tokio::spawn(async move {
-        loop {
-            match sync(&mut connection, &client, &config.jurisdiction_code).await {
-                Ok(_) => {
-                    tracing::info!("Successfully synced with CACVote Server");
+/// Spawns a supervised loop that synchronizes with the CACVote Server on a
+/// fixed schedule. On shutdown, a sync in progress is finished first.
+pub(crate) fn sync_periodically(
+    supervisor: &Supervisor,
+    pool: sqlx::PgPool,
+    config: Config,
+    metrics: Arc<Metrics>,
+) {
+    let mut client = Client::new(config.cacvote_url.clone());
+    if let Some(api_key) = config.cacvote_api_key.clone() {
+        client = client.with_api_key(api_key);
+    }
+    let client = Arc::new(client);
+
+    supervisor.spawn("sync", move |shutdown| {
+        let pool = pool.clone();
+        let client = client.clone();
+        let config = config.clone();
+        let metrics = metrics.clone();
+
+        async move {
+            let mut connection = pool.acquire().await?;
+
+            loop {
+                match sync(
+                    &mut connection,
+                    &client,
+                    &config.jurisdiction_code,
+                    config.cacvote_snapshot_public_key.as_deref(),
+                )
+                .await
+                {
+                    Ok(_) => {
+                        tracing::info!("Successfully synced with CACVote Server");
+                        metrics.record_sync();
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to sync with CACVote Server: {e}");
+                    }
                 }
-                Err(e) => {
-                    tracing::error!("Failed to sync with CACVote Server: {e}");
+
+                tokio::select! {
+                    () = shutdown.cancelled() => return Ok(()),
+                    () = sleep(SYNC_INTERVAL) => {}
                 }
             }
-            sleep(SYNC_INTERVAL).await;
         }
     });
 }
 
-#[tracing::instrument(skip(executor, client), name = "Sync with CACVote Server")]
+#[tracing::instrument(
+    skip(executor, client, snapshot_public_key),
+    name = "Sync with CACVote Server"
+)]
 pub(crate) async fn sync(
     executor: &mut sqlx::PgConnection,
     client: &Client,
     jurisdiction_code: &JurisdictionCode,
+    snapshot_public_key: Option<&PKeyRef<Public>>,
 ) -> color_eyre::eyre::Result<()> {
     client.check_status().await?;
 
     push_objects(executor, client).await?;
+    bootstrap_from_snapshot(executor, client, jurisdiction_code, snapshot_public_key).await?;
     pull_journal_entries(executor, client, jurisdiction_code).await?;
     pull_objects(executor, client).await?;
 
     Ok(())
 }
 
+/// Loads a snapshot of the jurisdiction's objects if this terminal hasn't
+/// synced any journal entries yet, so that a new terminal doesn't have to
+/// replay the whole journal and fetch each object on its own. Incremental
+/// syncing then carries on from the snapshot's cursor. Without the server's
+/// snapshot public key the snapshot can't be trusted, so the journal is
+/// replayed instead.
+async fn bootstrap_from_snapshot(
+    executor: &mut sqlx::PgConnection,
+    client: &Client,
+    jurisdiction_code: &JurisdictionCode,
+    snapshot_public_key: Option<&PKeyRef<Public>>,
+) -> color_eyre::eyre::Result<()> {
+    if db::get_latest_journal_entry(executor).await?.is_some() {
+        return Ok(());
+    }
+
+    let Some(snapshot_public_key) = snapshot_public_key else {
+        tracing::warn!(
+            "Not bootstrapping from a snapshot, which can't be verified without CACVOTE_SNAPSHOT_PUBLIC_KEY"
+        );
+        return Ok(());
+    };
+
+    let Some(tree_head) = client
+        .get_snapshot_tree_head(jurisdiction_code, None)
+        .await?
+    else {
+        tracing::debug!("no snapshot to bootstrap from");
+        return Ok(());
+    };
+
+    if !tree_head.verify_signature(snapshot_public_key)? {
+        bail!(
+            "Snapshot tree head with root hash {} is not signed by CACVote Server",
+            tree_head.root_hash
+        );
+    }
+
+    let entries = match client.get_snapshot(&tree_head).await {
+        Ok(entries) => entries,
+        Err(cacvote_server_client::Error::Reqwest(e))
+            if e.status() == Some(reqwest::StatusCode::UNAUTHORIZED) =>
+        {
+            tracing::warn!(
+                "Unable to bootstrap from a snapshot without an official API key, check CACVOTE_API_KEY"
+            );
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    tracing::info!(
+        "bootstrapping from snapshot of {count} objects as of journal entry {cursor}",
+        count = entries.len(),
+        cursor = tree_head.cursor
+    );
+    db::add_snapshot_entries(executor, &entries).await?;
+
+    Ok(())
+}
+
 async fn pull_journal_entries(
     executor: &mut sqlx::PgConnection,
     client: &Client,
@@ -76,6 +173,18 @@ async fn push_objects(
 ) -> color_eyre::eyre::Result<()> {
     let objects = db::get_unsynced_objects(executor).await?;
     for object in objects {
+        // the server only accepts objects whose blobs it already has
+        for reference in object.try_to_inner()?.blob_references() {
+            let Some(data) = db::get_blob(executor, reference).await? else {
+                bail!(
+                    "Blob {} referred to by object {} not found",
+                    reference.sha256,
+                    object.id
+                );
+            };
+            client.upload_blob(&data).await?;
+        }
+
         let object_id = client.create_object(object).await?;
         db::mark_object_synced(executor, object_id).await?;
     }
@@ -90,9 +199,30 @@ async fn pull_objects(
     let journal_entries = db::get_journal_entries_for_objects_to_pull(executor).await?;
     for journal_entry in journal_entries {
         match client.get_object_by_id(journal_entry.object_id).await? {
-            Some(object) => {
+            Some(ObjectView::Full(object)) => {
+                // e.g. the metadata of an election signed elsewhere
+                for reference in object.try_to_inner()?.blob_references() {
+                    if db::get_blob(executor, reference).await?.is_some() {
+                        continue;
+                    }
+                    let Some(data) = client.get_blob(reference).await? else {
+                        bail!(
+                            "Blob {} referred to by object {} not found on CACVote Server",
+                            reference.sha256,
+                            object.id
+                        );
+                    };
+                    db::add_blob(executor, &data).await?;
+                }
+
                 db::add_object_from_server(executor, &object).await?;
             }
+            Some(ObjectView::Redacted(_)) => {
+                tracing::warn!(
+                    "Object with id {} was redacted by CACVote Server, check CACVOTE_API_KEY",
+                    journal_entry.object_id
+                );
+            }
             None => {
                 tracing::warn!(
                     "Object with id {} not found on CACVote Server",
@@ -128,17 +258,27 @@ mod tests {
         let cacvote_url: Url = format!("http://{addr}").parse()?;
         let config = Config {
             cacvote_url: cacvote_url.clone(),
+            cacvote_api_key: None,
+            cacvote_snapshot_public_key: None,
             database_url: "".to_owned(),
             machine_id: "".to_owned(),
             port: addr.port(),
             public_dir: None,
             log_level: Level::DEBUG,
+            otlp_endpoint: None,
             jurisdiction_code: JurisdictionCode::try_from(JURISDICTION_CODE).unwrap(),
             eg_classpath: PathBuf::from("/not/real/path"),
         };
 
         tokio::spawn(async move {
-            let app = app::setup(pool, config, smartcard_status);
+            let metrics = Arc::new(Metrics::new().unwrap());
+            let app = app::setup(
+                pool,
+                config,
+                smartcard_status,
+                metrics.clone(),
+                Supervisor::new(metrics),
+            );
             axum::Server::from_tcp(listener)
                 .unwrap()
                 .serve(app.into_make_service())
@@ -165,6 +305,7 @@ mod tests {
             &mut connection,
             &client,
             &JurisdictionCode::try_from(JURISDICTION_CODE).unwrap(),
+            None,
         )
         .await;
 
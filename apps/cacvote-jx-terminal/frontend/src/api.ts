@@ -13,7 +13,11 @@ import {
 import { QUERY_CLIENT_DEFAULT_OPTIONS } from '@votingworks/ui';
 import { Buffer } from 'buffer';
 import { useEffect } from 'react';
-import { SessionData, SessionDataSchema } from './cacvote-server/session_data';
+import {
+  SessionData,
+  SessionDataSchema,
+  SignaturePolicyStatus,
+} from './cacvote-server/session_data';
 import { JurisdictionCode, Uuid } from './cacvote-server/types';
 
 export function createQueryClient(): QueryClient {
@@ -162,6 +166,7 @@ export interface DecryptEncryptedElectionTallyRequest {
 
 export interface DecryptEncryptedElectionTallyResponse {
   id: Uuid;
+  signaturePolicyStatus: SignaturePolicyStatus;
 }
 
 export const decryptEncryptedElectionTally = {
@@ -218,3 +223,51 @@ export const shuffleEncryptedBallots = {
     );
   },
 } as const;
+
+export interface CosignPendingObjectRequest {
+  objectId: Uuid;
+}
+
+export interface CosignPendingObjectResponse {
+  id: Uuid;
+  signaturePolicyStatus: SignaturePolicyStatus;
+}
+
+export const cosignPendingObject = {
+  useMutation() {
+    return useMutation(async ({ objectId }: CosignPendingObjectRequest) => {
+      const response = await fetch(
+        `/api/pending-objects/${objectId}/cosignatures`,
+        { method: 'POST' }
+      );
+
+      if (!response.ok) {
+        throw new Error(
+          `Failed to cosign pending object: ${response.statusText}`
+        );
+      }
+
+      return (await response.json()) as CosignPendingObjectResponse;
+    });
+  },
+} as const;
+
+export interface DeletePendingObjectRequest {
+  objectId: Uuid;
+}
+
+export const deletePendingObject = {
+  useMutation() {
+    return useMutation(async ({ objectId }: DeletePendingObjectRequest) => {
+      const response = await fetch(`/api/pending-objects/${objectId}`, {
+        method: 'DELETE',
+      });
+
+      if (!response.ok) {
+        throw new Error(
+          `Failed to delete pending object: ${response.statusText}`
+        );
+      }
+    });
+  },
+} as const;
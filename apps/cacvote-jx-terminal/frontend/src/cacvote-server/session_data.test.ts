@@ -0,0 +1,75 @@
+import { assert } from '@votingworks/basics';
+import { unsafeParse } from '@votingworks/types';
+import { readFileSync } from 'fs';
+import { join } from 'path';
+import { AuthenticatedSessionData, SessionDataSchema } from './session_data';
+
+interface PresenterVector {
+  description: string;
+  schema: string;
+  value: { type: string };
+}
+
+// shared with `types_rs::cacvote::json_schema`, which checks that the backend
+// encodes its presenters exactly this way
+const presenterVectors: PresenterVector[] = JSON.parse(
+  readFileSync(
+    join(
+      __dirname,
+      '../../../../../libs/types-rs/tests/fixtures/presenter-vectors.json'
+    ),
+    'utf-8'
+  )
+);
+
+test.each(presenterVectors)('parses $description', ({ schema, value }) => {
+  expect(schema).toEqual('SessionData');
+  const sessionData = unsafeParse(SessionDataSchema, value);
+  expect(JSON.parse(JSON.stringify(sessionData))).toMatchObject({
+    type: value.type,
+  });
+});
+
+test('parses every kind of presenter', () => {
+  const vector = presenterVectors.find(
+    ({ value }) => value.type === 'authenticated'
+  );
+  assert(vector);
+  const sessionData = unsafeParse(SessionDataSchema, vector.value);
+  assert(sessionData instanceof AuthenticatedSessionData);
+
+  const [election] = sessionData.getElections();
+  assert(election);
+  expect(
+    election.getElection().getElectionDefinition().election.title
+  ).toBeTruthy();
+  expect(election.getEncryptedTally()?.getCreatedAt().isValid).toEqual(true);
+  expect(election.getEncryptedTally()?.getSyncedAt()?.isValid).toEqual(true);
+  expect(election.getDecryptedTally()?.getCreatedAt().isValid).toEqual(true);
+  expect(election.getDecryptedTally()?.getSyncedAt()).toBeUndefined();
+  expect(
+    election.getShuffledEncryptedCastBallots()?.getCreatedAt().isValid
+  ).toEqual(true);
+
+  const [registrationRequest] = sessionData.getPendingRegistrationRequests();
+  expect(registrationRequest?.createdAt.isValid).toEqual(true);
+
+  const [registration] = sessionData.getRegistrations();
+  expect(registration?.getCreatedAt().isValid).toEqual(true);
+  expect(registration?.getIsSynced()).toEqual(true);
+
+  const [castBallot] = sessionData.getCastBallots();
+  expect(castBallot?.getCreatedAt().isValid).toEqual(true);
+  expect(castBallot?.getVerificationStatus()).toEqual({
+    type: 'success',
+    commonAccessCardId: '1234567890',
+    displayName: 'John Doe',
+  });
+
+  const [pendingObject] = sessionData.getPendingObjects();
+  expect(pendingObject?.createdAt.isValid).toEqual(true);
+  expect(pendingObject?.signaturePolicyStatus).toEqual({
+    signersRequired: 2,
+    signersCollected: 1,
+  });
+});
@@ -841,6 +841,34 @@ export const CastBallotPresenterSchema: z.ZodSchema<CastBallotPresenter> =
       )
   ) as unknown as z.ZodSchema<CastBallotPresenter>;
 
+export interface SignaturePolicyStatus {
+  signersRequired: number;
+  signersCollected: number;
+}
+
+export const SignaturePolicyStatusSchema: z.ZodSchema<SignaturePolicyStatus> =
+  z.object({
+    signersRequired: z.number(),
+    signersCollected: z.number(),
+  });
+
+export interface PendingObjectPresenter {
+  id: Uuid;
+  objectType: string;
+  electionId?: Uuid;
+  signaturePolicyStatus: SignaturePolicyStatus;
+  createdAt: DateTime;
+}
+
+export const PendingObjectPresenterSchema: z.ZodSchema<PendingObjectPresenter> =
+  z.object({
+    id: UuidSchema,
+    objectType: z.string(),
+    electionId: UuidSchema.optional(),
+    signaturePolicyStatus: SignaturePolicyStatusSchema,
+    createdAt: Iso8601DateSchema,
+  });
+
 export interface AuthenticatedSessionDataStruct {
   type: 'authenticated';
   jurisdictionCode: JurisdictionCode;
@@ -848,6 +876,7 @@ export interface AuthenticatedSessionDataStruct {
   pendingRegistrationRequests: RegistrationRequestPresenter[];
   registrations: RegistrationPresenterStruct[];
   castBallots: CastBallotPresenterStruct[];
+  pendingObjects: PendingObjectPresenter[];
 }
 
 export const AuthenticatedSessionDataStructSchema: z.ZodSchema<AuthenticatedSessionDataStruct> =
@@ -858,6 +887,7 @@ export const AuthenticatedSessionDataStructSchema: z.ZodSchema<AuthenticatedSess
     pendingRegistrationRequests: z.array(RegistrationRequestPresenterSchema),
     registrations: z.array(RegistrationPresenterStructSchema),
     castBallots: z.array(CastBallotPresenterStructSchema),
+    pendingObjects: z.array(PendingObjectPresenterSchema),
   });
 
 export class AuthenticatedSessionData {
@@ -866,7 +896,8 @@ export class AuthenticatedSessionData {
     private readonly elections: ElectionPresenter[],
     private readonly pendingRegistrationRequests: RegistrationRequestPresenter[],
     private readonly registrations: RegistrationPresenter[],
-    private readonly castBallots: CastBallotPresenter[]
+    private readonly castBallots: CastBallotPresenter[],
+    private readonly pendingObjects: PendingObjectPresenter[]
   ) {}
 
   get type(): 'authenticated' {
@@ -893,6 +924,10 @@ export class AuthenticatedSessionData {
     return this.castBallots;
   }
 
+  getPendingObjects(): PendingObjectPresenter[] {
+    return this.pendingObjects;
+  }
+
   toJSON(): AuthenticatedSessionDataStruct {
     return {
       type: 'authenticated',
@@ -901,6 +936,7 @@ export class AuthenticatedSessionData {
       pendingRegistrationRequests: this.pendingRegistrationRequests,
       registrations: this.registrations.map((r) => r.toJSON()),
       castBallots: this.castBallots.map((c) => c.toJSON()),
+      pendingObjects: this.pendingObjects,
     };
   }
 }
@@ -915,7 +951,10 @@ export const AuthenticatedSessionDataSchema: z.ZodSchema<AuthenticatedSessionDat
         struct.registrations.map((r) =>
           unsafeParse(RegistrationPresenterSchema, r)
         ),
-        struct.castBallots.map((c) => unsafeParse(CastBallotPresenterSchema, c))
+        struct.castBallots.map((c) =>
+          unsafeParse(CastBallotPresenterSchema, c)
+        ),
+        struct.pendingObjects
       )
   ) as unknown as z.ZodSchema<AuthenticatedSessionData>;
 
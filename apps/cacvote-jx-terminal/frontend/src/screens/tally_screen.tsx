@@ -1,7 +1,7 @@
 import { assert, assertDefined, iter } from '@votingworks/basics';
 import { Button, H2, LoadingButton, P } from '@votingworks/ui';
 import { format } from '@votingworks/utils';
-import { useState } from 'react';
+import React, { useState } from 'react';
 import { useParams } from 'react-router-dom';
 import { DateTime } from 'luxon';
 import * as api from '../api';
@@ -48,6 +48,8 @@ export function TallyScreen(): JSX.Element | null {
     api.generateEncryptedElectionTally.useMutation();
   const decryptEncryptedElectionTallyMutation =
     api.decryptEncryptedElectionTally.useMutation();
+  const cosignPendingObjectMutation = api.cosignPendingObject.useMutation();
+  const deletePendingObjectMutation = api.deletePendingObject.useMutation();
   const shuffleEncryptedBallotsMutation =
     api.shuffleEncryptedBallots.useMutation();
 
@@ -73,11 +75,20 @@ export function TallyScreen(): JSX.Element | null {
   const isDecryptedElectionTallyPresent = Boolean(
     electionPresenter.getDecryptedTally()
   );
+  const pendingDecryptedTally = sessionData
+    .getPendingObjects()
+    .find(
+      (o) =>
+        o.objectType === 'DecryptedElectionTally' &&
+        o.electionId === electionId
+    );
   const areShuffledBallotsPresent =
     typeof electionPresenter.getShuffledEncryptedCastBallots() !== 'undefined';
   const isReadyToGenerateEncryptedTally = !isEncryptedElectionTallyPresent;
   const isReadyToDecryptElectionTally =
-    isEncryptedElectionTallyPresent && !isDecryptedElectionTallyPresent;
+    isEncryptedElectionTallyPresent &&
+    !isDecryptedElectionTallyPresent &&
+    !pendingDecryptedTally;
   const isReadyToShuffleBallots =
     castBallotCount > 0 &&
     isDecryptedElectionTallyPresent &&
@@ -106,6 +117,16 @@ export function TallyScreen(): JSX.Element | null {
     decryptEncryptedElectionTallyMutation.mutate({ electionId });
   }
 
+  function onApproveDecryptedTallyPressed() {
+    assert(pendingDecryptedTally);
+    cosignPendingObjectMutation.mutate({ objectId: pendingDecryptedTally.id });
+  }
+
+  function onDiscardDecryptedTallyPressed() {
+    assert(pendingDecryptedTally);
+    deletePendingObjectMutation.mutate({ objectId: pendingDecryptedTally.id });
+  }
+
   function onSaveDecryptedTallyPressed() {
     const decryptedTally = electionPresenter.getDecryptedTally();
     assert(decryptedTally);
@@ -181,10 +202,43 @@ export function TallyScreen(): JSX.Element | null {
       />
       <P>
         This operation uses ElectionGuard to decrypt only the encrypted tally,
-        not any of the encrypted cast ballots. Once the tally is decrypted, the
-        decrypted tally will automatically be posted to the bulletin board. The
-        decrypted tally can be saved below.
+        not any of the encrypted cast ballots. Publishing the decrypted tally
+        requires approval from a second election manager, who must insert their
+        card and approve it. Once approved, the decrypted tally will
+        automatically be posted to the bulletin board. The decrypted tally can
+        be saved below.
       </P>
+      {pendingDecryptedTally && (
+        <React.Fragment>
+          <P>
+            <strong>Awaiting approval:</strong>{' '}
+            {format.count(
+              pendingDecryptedTally.signaturePolicyStatus.signersCollected
+            )}{' '}
+            of{' '}
+            {format.count(
+              pendingDecryptedTally.signaturePolicyStatus.signersRequired
+            )}{' '}
+            approvals
+          </P>
+          <P>
+            {cosignPendingObjectMutation.isLoading ? (
+              <LoadingButton>Approving Decrypted Tally…</LoadingButton>
+            ) : (
+              <Button icon="Checkmark" onPress={onApproveDecryptedTallyPressed}>
+                Approve Decrypted Tally
+              </Button>
+            )}{' '}
+            <Button
+              icon="Delete"
+              onPress={onDiscardDecryptedTallyPressed}
+              disabled={deletePendingObjectMutation.isLoading}
+            >
+              Discard Decrypted Tally
+            </Button>
+          </P>
+        </React.Fragment>
+      )}
       <P>
         {decryptEncryptedElectionTallyMutation.isLoading ? (
           <LoadingButton>Decrypting Election Tally…</LoadingButton>
@@ -27,6 +27,10 @@ create table objects (
   -- signature of `data` using `certificates`
   signature bytea not null,
 
+  -- algorithm named in the signed message, NULL for objects signed before
+  -- algorithms were recorded, e.g. "ES256"
+  signature_algorithm varchar(255),
+
   -- server sync timestamp, NULL if not synced
   server_synced_at timestamptz,
 
@@ -30,6 +30,7 @@ import { DateTime } from 'luxon';
 import { Readable } from 'stream';
 import { isDeepStrictEqual } from 'util';
 import { z } from 'zod';
+import { encryptPrivateFields } from './cacvote-server/field_encryption';
 import {
   Election,
   ElectionObjectType,
@@ -40,7 +41,10 @@ import {
   Uuid,
 } from './cacvote-server/types';
 import { createEncryptedBallotPayload } from './electionguard';
-import { MAILING_LABEL_PRINTER } from './globals';
+import {
+  MAILING_LABEL_PRINTER,
+  VX_CERT_AUTHORITY_CERT_PATH,
+} from './globals';
 import * as mailingLabel from './mailing_label';
 import { Auth, AuthStatus } from './types/auth';
 import { BallotVerificationPayload, SignedBuffer } from './verification';
@@ -81,11 +85,11 @@ function buildApi({
         return undefined;
       }
 
-      const { commonAccessCardId } = authStatus.card;
+      const certificates = await auth.getCertificate();
 
       // TODO: support more than one registration request for a given voter
       const registrationRequest = store
-        .forEachRegistrationRequest({ commonAccessCardId })
+        .forEachRegistrationRequest({ certificates })
         .first();
 
       if (!registrationRequest) {
@@ -94,7 +98,7 @@ function buildApi({
 
       const registration = store
         .forEachRegistration({
-          commonAccessCardId,
+          certificates,
           registrationRequestObjectId: registrationRequest.object.getId(),
         })
         .first();
@@ -147,8 +151,17 @@ function buildApi({
         DateTime.now()
       );
 
-      const payload =
-        Payload.RegistrationRequest(registrationRequest).toBuffer();
+      const registrationRequestPayload =
+        Payload.RegistrationRequest(registrationRequest);
+      const encryptionKey = await store.getJurisdictionEncryptionKey(
+        input.jurisdictionCode,
+        VX_CERT_AUTHORITY_CERT_PATH
+      );
+      const payload = (
+        encryptionKey
+          ? encryptPrivateFields(registrationRequestPayload, encryptionKey)
+          : registrationRequestPayload
+      ).toBuffer();
 
       const generateSignatureResult = await auth.generateSignature(payload, {
         pin: input.pin,
@@ -201,7 +214,7 @@ function buildApi({
 
       const registrationInfo = store
         .forEachRegistration({
-          commonAccessCardId: authStatus.card.commonAccessCardId,
+          certificates: await auth.getCertificate(),
         })
         .first();
 
@@ -257,9 +270,10 @@ function buildApi({
         }
 
         const { commonAccessCardId } = authStatus.card;
+        const commonAccessCardCertificate = await auth.getCertificate();
         // TODO: Handle multiple registrations
         const registration = store
-          .forEachRegistration({ commonAccessCardId })
+          .forEachRegistration({ certificates: commonAccessCardCertificate })
           .first();
 
         if (!registration) {
@@ -304,7 +318,7 @@ function buildApi({
           ballotMarkingMode: 'machine',
         });
 
-        const payload = createEncryptedBallotPayload(
+        const castBallotPayload = createEncryptedBallotPayload(
           commonAccessCardId,
           electionPayload,
           registration.registration.getRegistrationRequestObjectId(),
@@ -313,11 +327,17 @@ function buildApi({
           castVoteRecord,
           input.serialNumber
         );
+        const encryptionKey = await store.getJurisdictionEncryptionKey(
+          election.getJurisdictionCode(),
+          VX_CERT_AUTHORITY_CERT_PATH
+        );
+        const payload = encryptionKey
+          ? encryptPrivateFields(castBallotPayload, encryptionKey)
+          : castBallotPayload;
 
         const signature = (
           await auth.generateSignature(payload.toBuffer(), { pin: input.pin })
         ).okOrElse(bail);
-        const commonAccessCardCertificate = await auth.getCertificate();
         const objectId = Uuid();
         const object = new SignedObject(
           objectId,
@@ -0,0 +1,33 @@
+import { readFileSync } from 'fs';
+import { join } from 'path';
+import { canonicalizeJson } from './canonical_json';
+
+interface Vector {
+  description: string;
+  input: unknown;
+  canonical: string;
+}
+
+// shared with `types_rs::canonical_json`
+const vectors: Vector[] = JSON.parse(
+  readFileSync(
+    join(
+      __dirname,
+      '../../../../../libs/types-rs/tests/fixtures/canonical-json-vectors.json'
+    ),
+    'utf-8'
+  )
+);
+
+test.each(vectors)('$description', ({ input, canonical }) => {
+  expect(canonicalizeJson(input)).toEqual(canonical);
+});
+
+test('respects toJSON', () => {
+  expect(
+    canonicalizeJson({
+      b: { toJSON: () => ({ d: 1, c: 2 }) },
+      a: undefined,
+    })
+  ).toEqual('{"b":{"c":2,"d":1}}');
+});
@@ -0,0 +1,36 @@
+/**
+ * Serializes a plain JSON value per RFC 8785 (JSON Canonicalization Scheme).
+ * `JSON.stringify` already formats numbers and escapes strings as JCS
+ * requires, so all that's left is sorting object keys by UTF-16 code units,
+ * which is how JavaScript compares strings.
+ */
+function serializeCanonicalValue(value: unknown): string {
+  if (value === null || typeof value !== 'object') {
+    return JSON.stringify(value);
+  }
+
+  if (Array.isArray(value)) {
+    return `[${value.map(serializeCanonicalValue).join(',')}]`;
+  }
+
+  const entries = Object.entries(value as Record<string, unknown>)
+    .filter(([, entryValue]) => entryValue !== undefined)
+    // keys are unique, so they never compare equal
+    .sort(([a], [b]) => (a < b ? -1 : 1));
+  return `{${entries
+    .map(
+      ([key, entryValue]) =>
+        `${JSON.stringify(key)}:${serializeCanonicalValue(entryValue)}`
+    )
+    .join(',')}}`;
+}
+
+/**
+ * Encodes `value` as canonical JSON (RFC 8785). This must produce exactly
+ * the same bytes as `types_rs::canonical_json` so that signatures made here
+ * verify on the CACVote server. Values are first passed through
+ * `JSON.stringify` so that `toJSON` methods are respected.
+ */
+export function canonicalizeJson(value: unknown): string {
+  return serializeCanonicalValue(JSON.parse(JSON.stringify(value)));
+}
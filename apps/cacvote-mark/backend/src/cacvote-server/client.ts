@@ -24,7 +24,14 @@ export type ClientError =
 export type ClientResult<T> = Result<T, ClientError>;
 
 export class Client {
-  constructor(private readonly baseUrl: URL) {}
+  /**
+   * @param apiKey a jurisdiction official's API key, which allows reading that
+   * jurisdiction's objects without their private fields redacted
+   */
+  constructor(
+    private readonly baseUrl: URL,
+    private readonly apiKey?: string
+  ) {}
 
   /**
    * Create a new client to connect to the server running on localhost.
@@ -135,7 +142,11 @@ export class Client {
 
   private async get(path: string): Promise<ClientResult<Response>> {
     try {
-      return ok(await fetch(new URL(path, this.baseUrl)));
+      return ok(
+        await fetch(new URL(path, this.baseUrl), {
+          headers: this.authorizationHeaders(),
+        })
+      );
     } catch (error) {
       return err({ type: 'network', message: (error as Error).message });
     }
@@ -148,6 +159,7 @@ export class Client {
     const request = new Request(new URL(path, this.baseUrl), {
       method: 'POST',
       headers: new Headers({
+        ...this.authorizationHeaders(),
         'Content-Type': 'application/json',
       }),
       body,
@@ -159,4 +171,8 @@ export class Client {
       return err({ type: 'network', message: (error as Error).message });
     }
   }
+
+  private authorizationHeaders(): Record<string, string> {
+    return this.apiKey ? { Authorization: `Bearer ${this.apiKey}` } : {};
+  }
 }
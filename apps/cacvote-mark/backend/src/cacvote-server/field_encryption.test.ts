@@ -0,0 +1,119 @@
+import { unsafeParse } from '@votingworks/types';
+import { generateKeyPairSync } from 'crypto';
+import { DateTime } from 'luxon';
+import {
+  ENCRYPTED_FIELD_PREFIX,
+  encryptPrivateFields,
+  fieldBinding,
+  isEncryptedFieldValue,
+} from './field_encryption';
+import {
+  JurisdictionCodeSchema,
+  JurisdictionEncryptionKey,
+  Payload,
+  RegistrationRequest,
+} from './types';
+
+const jurisdictionCode = unsafeParse(
+  JurisdictionCodeSchema,
+  'st.test-jurisdiction'
+);
+
+function generateEncryptionKey(
+  code = jurisdictionCode
+): JurisdictionEncryptionKey {
+  const publicKey = generateKeyPairSync('x25519')
+    .publicKey.export({ format: 'der', type: 'spki' })
+    .subarray(-32);
+  return new JurisdictionEncryptionKey(code, publicKey);
+}
+
+const registrationRequestPayload = Payload.RegistrationRequest(
+  new RegistrationRequest(
+    '1234567890',
+    jurisdictionCode,
+    'John',
+    'Doe',
+    DateTime.now()
+  )
+);
+
+test('encryptPrivateFields', () => {
+  const encrypted = encryptPrivateFields(
+    registrationRequestPayload,
+    generateEncryptionKey()
+  );
+  const registrationRequest = encrypted.getData();
+
+  for (const [fieldName, value, plaintext] of [
+    [
+      'commonAccessCardId',
+      registrationRequest.getCommonAccessCardId(),
+      '1234567890',
+    ],
+    ['givenName', registrationRequest.getGivenName(), 'John'],
+    ['familyName', registrationRequest.getFamilyName(), 'Doe'],
+  ] as const) {
+    expect(isEncryptedFieldValue(value)).toBeTruthy();
+    // bound to the plaintext so the server can check it against the signer
+    expect(value.split(':').at(-1)).toEqual(
+      fieldBinding(
+        'RegistrationRequest',
+        jurisdictionCode,
+        fieldName,
+        plaintext
+      )
+    );
+  }
+  expect(registrationRequest.getJurisdictionCode()).toEqual(jurisdictionCode);
+
+  const json = encrypted.toBuffer().toString();
+  for (const privateValue of ['1234567890', 'John', 'Doe']) {
+    expect(json).not.toContain(privateValue);
+  }
+});
+
+test('encryptPrivateFields leaves encrypted fields alone', () => {
+  const key = generateEncryptionKey();
+  const encrypted = encryptPrivateFields(registrationRequestPayload, key);
+  expect(encryptPrivateFields(encrypted, key).toBuffer()).toEqual(
+    encrypted.toBuffer()
+  );
+});
+
+test('encryptPrivateFields with the wrong jurisdiction', () => {
+  expect(() =>
+    encryptPrivateFields(
+      registrationRequestPayload,
+      generateEncryptionKey(
+        unsafeParse(JurisdictionCodeSchema, 'st.other-jurisdiction')
+      )
+    )
+  ).toThrow();
+});
+
+test('isEncryptedFieldValue', () => {
+  expect(isEncryptedFieldValue(`${ENCRYPTED_FIELD_PREFIX}abc:def`)).toEqual(
+    true
+  );
+  expect(isEncryptedFieldValue('1234567890')).toEqual(false);
+});
+
+test('fieldBinding', () => {
+  // must match `test_field_binding` in `types-rs`
+  expect(
+    fieldBinding('RegistrationRequest', jurisdictionCode, 'givenName', ' john ')
+  ).toEqual(
+    'e2fbd1b7b0502093323419887f8ce83288b1699fd14400c05dc96fe28866b351'
+  );
+  expect(
+    fieldBinding('RegistrationRequest', jurisdictionCode, 'givenName', 'JOHN')
+  ).toEqual(
+    fieldBinding('RegistrationRequest', jurisdictionCode, 'givenName', 'John')
+  );
+  expect(
+    fieldBinding('RegistrationRequest', jurisdictionCode, 'familyName', 'John')
+  ).not.toEqual(
+    fieldBinding('RegistrationRequest', jurisdictionCode, 'givenName', 'John')
+  );
+});
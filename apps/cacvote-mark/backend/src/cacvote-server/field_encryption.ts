@@ -0,0 +1,124 @@
+import { assert } from '@votingworks/basics';
+import { unsafeParse } from '@votingworks/types';
+import { Buffer } from 'buffer';
+import { createHash } from 'crypto';
+import { seal } from './hpke';
+import {
+  CastBallotObjectType,
+  JurisdictionEncryptionKey,
+  Payload,
+  PayloadInner,
+  PayloadSchema,
+  RegistrationObjectType,
+  RegistrationRequestObjectType,
+} from './types';
+
+/**
+ * Encryption of the private payload fields of voter objects to a
+ * jurisdiction's key, matching `types_rs::cacvote::field_encryption`. Fields
+ * are encrypted before signing so that signatures cover the ciphertext.
+ */
+
+/**
+ * The prefix of every encrypted field value. The rest of the value is
+ * `<key id>:<base64 of the encapsulated key followed by the ciphertext>:<binding>`,
+ * see `fieldBinding`.
+ */
+export const ENCRYPTED_FIELD_PREFIX = 'hpke:';
+
+const HPKE_INFO = Buffer.from('cacvote field encryption v1');
+
+const FIELD_BINDING_PREFIX = Buffer.from('cacvote field binding v1\0');
+
+/**
+ * Top-level payload fields that identify a voter, by object type.
+ */
+export const PRIVATE_FIELDS: Readonly<Record<string, readonly string[]>> = {
+  [RegistrationRequestObjectType]: [
+    'commonAccessCardId',
+    'givenName',
+    'familyName',
+  ],
+  [RegistrationObjectType]: ['commonAccessCardId'],
+  [CastBallotObjectType]: ['commonAccessCardId'],
+};
+
+/**
+ * Determines whether a payload field value is encrypted.
+ */
+export function isEncryptedFieldValue(value: string): boolean {
+  return value.startsWith(ENCRYPTED_FIELD_PREFIX);
+}
+
+/**
+ * Commits to the plaintext of an encrypted field so that the server can check
+ * it against the signer's certificate without decrypting it. Must match
+ * `field_binding` in `types-rs`, which compares values trimmed and ignoring
+ * ASCII case.
+ */
+export function fieldBinding(
+  objectType: string,
+  jurisdictionCode: string,
+  fieldName: string,
+  plaintext: string
+): string {
+  return createHash('sha256')
+    .update(FIELD_BINDING_PREFIX)
+    .update([objectType, jurisdictionCode, fieldName].join('\0'))
+    .update(Buffer.of(0))
+    .update(plaintext.trim().replace(/[a-z]+/g, (s) => s.toUpperCase()))
+    .digest('hex');
+}
+
+function keyId(publicKey: Buffer): string {
+  return createHash('sha256')
+    .update(publicKey)
+    .digest()
+    .subarray(0, 8)
+    .toString('hex');
+}
+
+/**
+ * Encrypts the plaintext private fields of `payload` to `key`, which must
+ * belong to the payload's jurisdiction.
+ */
+export function encryptPrivateFields<T extends PayloadInner>(
+  payload: Payload<T>,
+  key: JurisdictionEncryptionKey
+): Payload<T> {
+  const jurisdictionCode = payload.getData().getJurisdictionCode();
+  assert(
+    key.getJurisdictionCode() === jurisdictionCode,
+    `encryption key is for jurisdiction ${key.getJurisdictionCode()}, not ${jurisdictionCode}`
+  );
+
+  const objectType = payload.getObjectType();
+  const fields = JSON.parse(payload.toBuffer().toString()) as Record<
+    string,
+    unknown
+  >;
+
+  for (const fieldName of PRIVATE_FIELDS[objectType] ?? []) {
+    const value = fields[fieldName];
+    if (typeof value !== 'string' || isEncryptedFieldValue(value)) {
+      continue;
+    }
+
+    const { enc, ciphertext } = seal(
+      key.getPublicKey(),
+      HPKE_INFO,
+      Buffer.from([objectType, jurisdictionCode, fieldName].join('\0')),
+      Buffer.from(value)
+    );
+    fields[fieldName] = `${ENCRYPTED_FIELD_PREFIX}${keyId(
+      key.getPublicKey()
+    )}:${Buffer.concat([enc, ciphertext]).toString('base64')}:${fieldBinding(
+      objectType,
+      jurisdictionCode,
+      fieldName,
+      value
+    )}`;
+  }
+
+  return unsafeParse(PayloadSchema, fields) as Payload<T>;
+}
@@ -0,0 +1,42 @@
+import { Buffer } from 'buffer';
+import { seal, x25519PrivateKeyFromRaw } from './hpke';
+
+function fromHex(hex: string): Buffer {
+  return Buffer.from(hex, 'hex');
+}
+
+// RFC 9180, Appendix A.1.1, sequence number 0
+test('RFC 9180 test vector', () => {
+  const { enc, ciphertext } = seal(
+    fromHex('3948cfe0ad1ddb695d780e59077195da6c56506b027329794ab02bca80815c4d'),
+    fromHex('4f6465206f6e2061204772656369616e2055726e'),
+    fromHex('436f756e742d30'),
+    fromHex('4265617574792069732074727574682c20747275746820626561757479'),
+    x25519PrivateKeyFromRaw(
+      fromHex(
+        '52c4a758a802cd8b936eceea314432798d5baf2d7e9235dc084ab1b9cfa2f736'
+      )
+    )
+  );
+
+  expect(enc.toString('hex')).toEqual(
+    '37fda3567bdbd628e88668c3c8d7e97d1d1253b6d4ea6d44c150f741f1bf4431'
+  );
+  expect(ciphertext.toString('hex')).toEqual(
+    'f938558b5d72f1a23810b4be2ab4f84331acc02fc97babc53a52ae8218a355a96d8770ac83d07bea87e13c512a'
+  );
+});
+
+test('uses a fresh ephemeral key each time', () => {
+  const publicKey = fromHex(
+    '3948cfe0ad1ddb695d780e59077195da6c56506b027329794ab02bca80815c4d'
+  );
+  const args = [
+    publicKey,
+    Buffer.from('info'),
+    Buffer.from('aad'),
+    Buffer.from('secret'),
+  ] as const;
+
+  expect(seal(...args).enc).not.toEqual(seal(...args).enc);
+});
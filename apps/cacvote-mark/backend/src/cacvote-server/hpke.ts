@@ -0,0 +1,171 @@
+import { Buffer } from 'buffer';
+import {
+  KeyObject,
+  createCipheriv,
+  createHmac,
+  createPrivateKey,
+  createPublicKey,
+  diffieHellman,
+  generateKeyPairSync,
+} from 'crypto';
+
+/**
+ * Single-shot HPKE (RFC 9180) sealing in base mode with the
+ * DHKEM(X25519, HKDF-SHA256), HKDF-SHA256, AES-128-GCM cipher suite. This
+ * matches `types_rs::cacvote::hpke`, which holds the opening side.
+ */
+
+const KEM_SUITE_ID = Buffer.from('KEM\x00\x20', 'latin1');
+const HPKE_SUITE_ID = Buffer.from('HPKE\x00\x20\x00\x01\x00\x01', 'latin1');
+const HPKE_VERSION_LABEL = Buffer.from('HPKE-v1');
+const MODE_BASE = 0x00;
+const AEAD_KEY_LENGTH = 16;
+const AEAD_NONCE_LENGTH = 12;
+const SHA256_LENGTH = 32;
+
+// DER prefixes that turn raw X25519 keys into SPKI/PKCS#8 keys
+const X25519_SPKI_PREFIX = Buffer.from('302a300506032b656e032100', 'hex');
+const X25519_PKCS8_PREFIX = Buffer.from(
+  '302e020100300506032b656e04220420',
+  'hex'
+);
+
+function x25519PublicKeyFromRaw(publicKey: Buffer): KeyObject {
+  return createPublicKey({
+    key: Buffer.concat([X25519_SPKI_PREFIX, publicKey]),
+    format: 'der',
+    type: 'spki',
+  });
+}
+
+/**
+ * Imports a raw X25519 private key. Only needed to reproduce test vectors,
+ * since ephemeral keys are normally generated.
+ */
+export function x25519PrivateKeyFromRaw(privateKey: Buffer): KeyObject {
+  return createPrivateKey({
+    key: Buffer.concat([X25519_PKCS8_PREFIX, privateKey]),
+    format: 'der',
+    type: 'pkcs8',
+  });
+}
+
+function rawPublicKey(key: KeyObject): Buffer {
+  return createPublicKey(key)
+    .export({ format: 'der', type: 'spki' })
+    .subarray(X25519_SPKI_PREFIX.length);
+}
+
+function hkdfExtract(salt: Buffer, ikm: Buffer): Buffer {
+  return createHmac('sha256', salt).update(ikm).digest();
+}
+
+function hkdfExpand(prk: Buffer, info: Buffer, length: number): Buffer {
+  const blocks: Buffer[] = [];
+  let previous = Buffer.alloc(0);
+  for (let i = 1; blocks.length * SHA256_LENGTH < length; i += 1) {
+    previous = createHmac('sha256', prk)
+      .update(Buffer.concat([previous, info, Buffer.from([i])]))
+      .digest();
+    blocks.push(previous);
+  }
+  return Buffer.concat(blocks).subarray(0, length);
+}
+
+function labeledExtract(
+  suiteId: Buffer,
+  salt: Buffer,
+  label: string,
+  ikm: Buffer
+): Buffer {
+  return hkdfExtract(
+    salt,
+    Buffer.concat([HPKE_VERSION_LABEL, suiteId, Buffer.from(label), ikm])
+  );
+}
+
+function labeledExpand(
+  suiteId: Buffer,
+  prk: Buffer,
+  label: string,
+  info: Buffer,
+  length: number
+): Buffer {
+  const lengthPrefix = Buffer.alloc(2);
+  lengthPrefix.writeUInt16BE(length);
+  return hkdfExpand(
+    prk,
+    Buffer.concat([
+      lengthPrefix,
+      HPKE_VERSION_LABEL,
+      suiteId,
+      Buffer.from(label),
+      info,
+    ]),
+    length
+  );
+}
+
+function keySchedule(
+  sharedSecret: Buffer,
+  info: Buffer
+): { key: Buffer; nonce: Buffer } {
+  const empty = Buffer.alloc(0);
+  const pskIdHash = labeledExtract(HPKE_SUITE_ID, empty, 'psk_id_hash', empty);
+  const infoHash = labeledExtract(HPKE_SUITE_ID, empty, 'info_hash', info);
+  const context = Buffer.concat([
+    Buffer.from([MODE_BASE]),
+    pskIdHash,
+    infoHash,
+  ]);
+  const secret = labeledExtract(HPKE_SUITE_ID, sharedSecret, 'secret', empty);
+
+  return {
+    key: labeledExpand(HPKE_SUITE_ID, secret, 'key', context, AEAD_KEY_LENGTH),
+    nonce: labeledExpand(
+      HPKE_SUITE_ID,
+      secret,
+      'base_nonce',
+      context,
+      AEAD_NONCE_LENGTH
+    ),
+  };
+}
+
+/**
+ * Encrypts `plaintext` to the holder of the private key for the raw X25519
+ * `publicKey`. Returns the encapsulated key and the ciphertext, with the
+ * authentication tag appended.
+ */
+export function seal(
+  publicKey: Buffer,
+  info: Buffer,
+  aad: Buffer,
+  plaintext: Buffer,
+  ephemeralPrivateKey: KeyObject = generateKeyPairSync('x25519').privateKey
+): { enc: Buffer; ciphertext: Buffer } {
+  const enc = rawPublicKey(ephemeralPrivateKey);
+  const dh = diffieHellman({
+    privateKey: ephemeralPrivateKey,
+    publicKey: x25519PublicKeyFromRaw(publicKey),
+  });
+  const eaePrk = labeledExtract(KEM_SUITE_ID, Buffer.alloc(0), 'eae_prk', dh);
+  const sharedSecret = labeledExpand(
+    KEM_SUITE_ID,
+    eaePrk,
+    'shared_secret',
+    Buffer.concat([enc, publicKey]),
+    SHA256_LENGTH
+  );
+  const { key, nonce } = keySchedule(sharedSecret, info);
+
+  const cipher = createCipheriv('aes-128-gcm', key, nonce);
+  cipher.setAAD(aad);
+  const ciphertext = Buffer.concat([
+    cipher.update(plaintext),
+    cipher.final(),
+    cipher.getAuthTag(),
+  ]);
+
+  return { enc, ciphertext };
+}
@@ -1,6 +1,20 @@
 import { safeParseJson } from '@votingworks/types';
 import { Buffer } from 'buffer';
-import { SignedObject, SignedObjectSchema, Uuid } from './types';
+import { createSign } from 'crypto';
+import { readFileSync } from 'fs';
+import { join } from 'path';
+import {
+  CastBallotObjectType,
+  ElectionObjectType,
+  JurisdictionCode,
+  JurisdictionEncryptionKeyObjectType,
+  PayloadSchema,
+  RegistrationObjectType,
+  RegistrationRequestObjectType,
+  SignedObject,
+  SignedObjectSchema,
+  Uuid,
+} from './types';
 
 test('round-trip SignedObject', () => {
   const signedObject = new SignedObject(
@@ -18,3 +32,161 @@ test('round-trip SignedObject', () => {
     ).unsafeUnwrap()
   ).toEqual(signedObject);
 });
+
+const testCertsPath = join(__dirname, '../../../../../libs/auth/certs/test');
+
+function signAsElectionManager(
+  certsPath: string,
+  signatureAlgorithm?: string
+): SignedObject {
+  const unsigned = new SignedObject(
+    Uuid(),
+    undefined,
+    Buffer.from('payload'),
+    Buffer.concat([
+      Buffer.from(
+        `-----BEGIN CERTIFICATE-----\n${readFileSync(
+          join(certsPath, 'election-manager/card-vx-admin-cert.der')
+        ).toString('base64')}\n-----END CERTIFICATE-----\n`
+      ),
+      readFileSync(join(certsPath, 'vx-admin-cert-authority-cert.pem')),
+    ]),
+    Buffer.of(),
+    signatureAlgorithm
+  );
+  const signature = createSign('sha256')
+    .update(unsigned.getSigningMessage())
+    .sign(
+      readFileSync(
+        join(certsPath, 'election-manager/card-vx-admin-private-key.pem')
+      )
+    );
+
+  return new SignedObject(
+    unsigned.getId(),
+    undefined,
+    unsigned.getPayloadRaw(),
+    unsigned.getCertificates(),
+    signature,
+    signatureAlgorithm
+  );
+}
+
+test('isSignedByElectionManager', async () => {
+  // the test certs are issued for a jurisdiction literally named "jurisdiction"
+  const jurisdictionCode = 'jurisdiction' as JurisdictionCode;
+  const caPath = join(testCertsPath, 'set-1/vx-cert-authority-cert.pem');
+
+  for (const signatureAlgorithm of [undefined, 'ES256']) {
+    const object = signAsElectionManager(
+      join(testCertsPath, 'set-1'),
+      signatureAlgorithm
+    );
+    expect(
+      await object.isSignedByElectionManager(jurisdictionCode, caPath)
+    ).toEqual(true);
+    expect(
+      await object.isSignedByElectionManager(
+        'st.other-jurisdiction' as JurisdictionCode,
+        caPath
+      )
+    ).toEqual(false);
+  }
+
+  // a different CA
+  expect(
+    await signAsElectionManager(
+      join(testCertsPath, 'set-2')
+    ).isSignedByElectionManager(jurisdictionCode, caPath)
+  ).toEqual(false);
+
+  // a signature that doesn't match
+  const object = signAsElectionManager(join(testCertsPath, 'set-1'));
+  expect(
+    await new SignedObject(
+      object.getId(),
+      undefined,
+      Buffer.from('other payload'),
+      object.getCertificates(),
+      object.getSignature()
+    ).isSignedByElectionManager(jurisdictionCode, caPath)
+  ).toEqual(false);
+
+  // an algorithm that can't be verified here
+  expect(
+    await signAsElectionManager(
+      join(testCertsPath, 'set-1'),
+      'EdDSA'
+    ).isSignedByElectionManager(jurisdictionCode, caPath)
+  ).toEqual(false);
+});
+
+interface PayloadVector {
+  description: string;
+  payload: { objectType: string };
+}
+
+// shared with `types_rs::cacvote::json_schema`, which checks them against the
+// JSON Schema derived from the Rust types
+const payloadVectors: PayloadVector[] = JSON.parse(
+  readFileSync(
+    join(
+      __dirname,
+      '../../../../../libs/types-rs/tests/fixtures/payload-vectors.json'
+    ),
+    'utf-8'
+  )
+);
+
+// the object types CACVote Mark knows about, the others are only handled by
+// the Rust implementation
+const knownObjectTypes: string[] = [
+  ElectionObjectType,
+  RegistrationRequestObjectType,
+  RegistrationObjectType,
+  CastBallotObjectType,
+  JurisdictionEncryptionKeyObjectType,
+];
+
+const knownPayloadVectors = payloadVectors.filter(({ payload }) =>
+  knownObjectTypes.includes(payload.objectType)
+);
+
+test('payload vectors cover every known object type', () => {
+  expect(
+    new Set(knownPayloadVectors.map(({ payload }) => payload.objectType))
+  ).toEqual(new Set(knownObjectTypes));
+});
+
+test.each(knownPayloadVectors)('parses $description', ({ payload }) => {
+  const parsed = safeParseJson(
+    JSON.stringify(payload),
+    PayloadSchema
+  ).unsafeUnwrap();
+  expect(parsed.getObjectType()).toEqual(payload.objectType);
+});
+
+test.each(
+  payloadVectors.filter(
+    ({ payload }) => !knownObjectTypes.includes(payload.objectType)
+  )
+)('rejects $description', ({ payload }) => {
+  const result = safeParseJson(JSON.stringify(payload), PayloadSchema);
+  expect(result.isErr()).toEqual(true);
+});
+
+// CACVote Mark encodes each payload it knows exactly as the vector does, so
+// that the signatures it makes are over the same bytes the Rust
+// implementation would produce
+test.each(knownPayloadVectors)('round-trip $description', ({ payload }) => {
+  const parsed = safeParseJson(
+    JSON.stringify(payload),
+    PayloadSchema
+  ).unsafeUnwrap();
+  // `createdAt` is encoded again in the local time zone
+  expect(JSON.parse(parsed.toBuffer().toString())).toEqual(
+    'createdAt' in payload
+      ? { ...payload, createdAt: expect.any(String) }
+      : payload
+  );
+});
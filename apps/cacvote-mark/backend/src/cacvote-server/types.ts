@@ -23,16 +23,19 @@ import { Buffer } from 'buffer';
 import { DateTime } from 'luxon';
 import { v4, validate } from 'uuid';
 import { ZodError, z } from 'zod';
+import { canonicalizeJson } from './canonical_json';
 
 export const ElectionObjectType = 'Election';
 export const RegistrationRequestObjectType = 'RegistrationRequest';
 export const RegistrationObjectType = 'Registration';
 export const CastBallotObjectType = 'CastBallot';
+export const JurisdictionEncryptionKeyObjectType = 'JurisdictionEncryptionKey';
 export type PayloadObjectType =
   | typeof ElectionObjectType
   | typeof RegistrationRequestObjectType
   | typeof RegistrationObjectType
-  | typeof CastBallotObjectType;
+  | typeof CastBallotObjectType
+  | typeof JurisdictionEncryptionKeyObjectType;
 export type Uuid = NewType<string, 'Uuid'>;
 
 export function Uuid(): Uuid {
@@ -51,7 +54,7 @@ export const JurisdictionCodeSchema = z
     message: 'Invalid jurisdiction code',
   }) as unknown as z.ZodSchema<JurisdictionCode>;
 
-export type JournalEntryAction = 'create' | 'delete' | string;
+export type JournalEntryAction = 'create' | 'update' | 'delete' | string;
 
 export const DateTimeSchema = z
   .string()
@@ -169,7 +172,9 @@ export class Election {
   toJSON(): unknown {
     return {
       jurisdictionCode: this.jurisdictionCode,
-      electionDefinition: this.electionDefinition,
+      electionDefinition: Buffer.from(
+        this.electionDefinition.electionData
+      ).toString('base64'),
       mailingAddress: this.mailingAddress,
       electionguardElectionMetadataBlob:
         this.electionguardElectionMetadataBlob.toString('base64'),
@@ -374,11 +379,51 @@ export const CastBallotSchema: z.ZodSchema<CastBallot> =
       )
   ) as unknown as z.ZodSchema<CastBallot>;
 
+/**
+ * A public key that voter PII in a jurisdiction's objects is encrypted to. The
+ * latest key published for a jurisdiction is the one to use.
+ */
+export class JurisdictionEncryptionKey {
+  constructor(
+    private readonly jurisdictionCode: JurisdictionCode,
+    private readonly publicKey: Buffer
+  ) {}
+
+  getJurisdictionCode(): JurisdictionCode {
+    return this.jurisdictionCode;
+  }
+
+  /**
+   * A raw X25519 public key.
+   */
+  getPublicKey(): Buffer {
+    return this.publicKey;
+  }
+
+  toJSON(): unknown {
+    return {
+      jurisdictionCode: this.jurisdictionCode,
+      publicKey: this.publicKey.toString('base64'),
+    };
+  }
+}
+
+const JurisdictionEncryptionKeyStructSchema = z.object({
+  jurisdictionCode: JurisdictionCodeSchema,
+  publicKey: z.string().transform((s) => Buffer.from(s, 'base64')),
+});
+
+export const JurisdictionEncryptionKeySchema: z.ZodSchema<JurisdictionEncryptionKey> =
+  JurisdictionEncryptionKeyStructSchema.transform(
+    (o) => new JurisdictionEncryptionKey(o.jurisdictionCode, o.publicKey)
+  ) as unknown as z.ZodSchema<JurisdictionEncryptionKey>;
+
 export type PayloadInner =
   | Election
   | Registration
   | RegistrationRequest
-  | CastBallot;
+  | CastBallot
+  | JurisdictionEncryptionKey;
 
 export class Payload<T extends PayloadInner = PayloadInner> {
   constructor(
@@ -402,8 +447,11 @@ export class Payload<T extends PayloadInner = PayloadInner> {
     };
   }
 
+  /**
+   * Encodes the payload as canonical JSON, which is what gets signed.
+   */
   toBuffer(): Buffer {
-    return Buffer.from(JSON.stringify(this));
+    return Buffer.from(canonicalizeJson(this));
   }
 
   static Election(data: Election): Payload<Election> {
@@ -423,6 +471,12 @@ export class Payload<T extends PayloadInner = PayloadInner> {
   static CastBallot(data: CastBallot): Payload<CastBallot> {
     return new Payload(CastBallotObjectType, data);
   }
+
+  static JurisdictionEncryptionKey(
+    data: JurisdictionEncryptionKey
+  ): Payload<JurisdictionEncryptionKey> {
+    return new Payload(JurisdictionEncryptionKeyObjectType, data);
+  }
 }
 
 export const PayloadSchema: z.ZodSchema<Payload> = z
@@ -439,6 +493,9 @@ export const PayloadSchema: z.ZodSchema<Payload> = z
     z
       .object({ objectType: z.literal(CastBallotObjectType) })
       .merge(CastBallotStructSchema),
+    z
+      .object({ objectType: z.literal(JurisdictionEncryptionKeyObjectType) })
+      .merge(JurisdictionEncryptionKeyStructSchema),
   ])
   .transform((o) => {
     switch (o.objectType) {
@@ -491,11 +548,45 @@ export const PayloadSchema: z.ZodSchema<Payload> = z
         );
       }
 
+      case JurisdictionEncryptionKeyObjectType: {
+        return Payload.JurisdictionEncryptionKey(
+          new JurisdictionEncryptionKey(o.jurisdictionCode, o.publicKey)
+        );
+      }
+
       default:
         throwIllegalValue(o);
     }
   }) as unknown as z.ZodSchema<Payload>;
 
+/**
+ * Separates object signatures from signatures over anything else a key might
+ * sign. Must match `OBJECT_SIGNING_PREFIX` in `types-rs`.
+ */
+const OBJECT_SIGNING_PREFIX = Buffer.from('cacvote signed object\0');
+
+/**
+ * Signature algorithms whose signatures can be verified here, i.e. those that
+ * use SHA-256 digests (see `cryptography.verifySignature`).
+ */
+const VERIFIABLE_SIGNATURE_ALGORITHMS: readonly string[] = ['ES256', 'RS256'];
+
+/**
+ * The card type of election manager cards, as found in the card type field of
+ * the card's certificate.
+ */
+export const ELECTION_MANAGER_CARD_TYPE = 'election-manager';
+
+function splitPemStack(certificates: Buffer): Buffer[] {
+  return (
+    certificates
+      .toString('utf-8')
+      .match(
+        /-----BEGIN CERTIFICATE-----[^-]+-----END CERTIFICATE-----/g
+      ) ?? []
+  ).map((certificate) => Buffer.from(`${certificate}\n`));
+}
+
 export class SignedObject {
   constructor(
     private readonly id: Uuid,
@@ -503,7 +594,13 @@ export class SignedObject {
     private readonly electionId: Optional<Uuid>,
     private readonly payload: Buffer,
     private readonly certificates: Buffer,
-    private readonly signature: Buffer
+    private readonly signature: Buffer,
+    /**
+     * The JOSE name of the algorithm used to produce `signature`, e.g.
+     * `ES256`. Objects signed before algorithms were recorded have none, and
+     * their signature is over the payload alone.
+     */
+    private readonly signatureAlgorithm?: string
   ) {}
 
   getId(): Uuid {
@@ -534,6 +631,9 @@ export class SignedObject {
   getPayloadAsObjectType(
     objectType: typeof CastBallotObjectType
   ): Result<Payload<CastBallot>, ZodError | SyntaxError>;
+  getPayloadAsObjectType(
+    objectType: typeof JurisdictionEncryptionKeyObjectType
+  ): Result<Payload<JurisdictionEncryptionKey>, ZodError | SyntaxError>;
   // eslint-disable-next-line vx/gts-no-return-type-only-generics
   getPayloadAsObjectType<T extends PayloadInner>(
     objectType: PayloadObjectType
@@ -567,6 +667,27 @@ export class SignedObject {
     return this.signature;
   }
 
+  getSignatureAlgorithm(): Optional<string> {
+    return this.signatureAlgorithm;
+  }
+
+  /**
+   * The data that was signed to produce `signature`. Must match
+   * `SignatureAlgorithm::signing_message` in `types-rs`.
+   */
+  getSigningMessage(): Buffer {
+    if (this.signatureAlgorithm === undefined) {
+      return this.payload;
+    }
+
+    return Buffer.concat([
+      OBJECT_SIGNING_PREFIX,
+      Buffer.from(this.signatureAlgorithm),
+      Buffer.of(0),
+      this.payload,
+    ]);
+  }
+
   async getJurisdictionCode(): Promise<
     Result<JurisdictionCode, ZodError | SyntaxError>
   > {
@@ -589,12 +710,19 @@ export class SignedObject {
    * is invalid, or `err(Error)` if there was an error verifying the signature.
    */
   async verify(): Promise<Result<boolean, Error>> {
+    if (
+      this.signatureAlgorithm !== undefined &&
+      !VERIFIABLE_SIGNATURE_ALGORITHMS.includes(this.signatureAlgorithm)
+    ) {
+      return ok(false);
+    }
+
     try {
       const publicKey = await cryptography.extractPublicKeyFromCert(
         this.certificates
       );
       await cryptography.verifySignature({
-        message: this.payload,
+        message: this.getSigningMessage(),
         messageSignature: this.signature,
         publicKey,
       });
@@ -604,6 +732,50 @@ export class SignedObject {
     }
   }
 
+  /**
+   * Determines whether this object was signed by an election manager of
+   * `jurisdictionCode` whose certificate chains to the CA at
+   * `vxCertAuthorityCertPath`. The certificate's fields are only trusted once
+   * the chain is, since anyone can make a certificate with any fields.
+   */
+  async isSignedByElectionManager(
+    jurisdictionCode: JurisdictionCode,
+    vxCertAuthorityCertPath: string
+  ): Promise<boolean> {
+    const verifyResult = await this.verify();
+    if (!verifyResult.isOk() || !verifyResult.ok()) {
+      return false;
+    }
+
+    const [signerCertificate, ...intermediateCertificates] = splitPemStack(
+      this.certificates
+    );
+    if (!signerCertificate) {
+      return false;
+    }
+
+    try {
+      await cryptography.openssl([
+        'verify',
+        '-CAfile',
+        vxCertAuthorityCertPath,
+        ...(intermediateCertificates.length > 0
+          ? ['-untrusted', Buffer.concat(intermediateCertificates)]
+          : []),
+        signerCertificate,
+      ]);
+    } catch {
+      return false;
+    }
+
+    const fields = await certs.getCertSubjectFields(signerCertificate);
+    return (
+      fields.get(certs.VX_CUSTOM_CERT_FIELD.CARD_TYPE) ===
+        ELECTION_MANAGER_CARD_TYPE &&
+      fields.get(certs.VX_CUSTOM_CERT_FIELD.JURISDICTION) === jurisdictionCode
+    );
+  }
+
   toJSON(): unknown {
     return {
       id: this.id.toString(),
@@ -611,6 +783,7 @@ export class SignedObject {
       payload: this.payload.toString('base64'),
       certificates: this.certificates.toString('base64'),
       signature: this.signature.toString('base64'),
+      signatureAlgorithm: this.signatureAlgorithm,
     };
   }
 }
@@ -622,6 +795,7 @@ export const SignedObjectSchema: z.ZodSchema<SignedObject> = z
     payload: z.string(),
     certificates: z.string(),
     signature: z.string(),
+    signatureAlgorithm: z.string().optional(),
   })
   .transform(
     (o) =>
@@ -630,6 +804,7 @@ export const SignedObjectSchema: z.ZodSchema<SignedObject> = z
         o.electionId,
         Buffer.from(o.payload, 'base64'),
         Buffer.from(o.certificates, 'base64'),
-        Buffer.from(o.signature, 'base64')
+        Buffer.from(o.signature, 'base64'),
+        o.signatureAlgorithm
       )
   ) as unknown as z.ZodSchema<SignedObject>;
@@ -10,6 +10,7 @@ declare namespace NodeJS {
     readonly VX_SCREEN_ORIENTATION?: 'portrait' | 'landscape';
     readonly CACVOTE_MARK_WORKSPACE?: string;
     readonly CACVOTE_URL?: string;
+    readonly CACVOTE_API_KEY?: string;
     readonly MAILING_LABEL_PRINTER?: string;
   }
 }
@@ -1,3 +1,7 @@
+import {
+  DEV_VX_CERT_AUTHORITY_CERT_PATH,
+  PROD_VX_CERT_AUTHORITY_CERT_PATH,
+} from '@votingworks/auth';
 import { NODE_ENV } from '@votingworks/backend';
 import { safeParseInt, unsafeParse } from '@votingworks/types';
 import * as dotenv from 'dotenv';
@@ -61,6 +65,22 @@ export const CACVOTE_URL = process.env.CACVOTE_URL
   ? new URL('http://localhost:8000')
   : undefined;
 
+/**
+ * API key identifying this machine to the CACVote Server as an official of its
+ * jurisdiction, so that objects are synced without redaction.
+ */
+export const CACVOTE_API_KEY = process.env.CACVOTE_API_KEY;
+
+/**
+ * The VotingWorks CA that election managers' card certificates must chain to
+ * for this machine to encrypt voter data to the keys they publish.
+ */
+export const VX_CERT_AUTHORITY_CERT_PATH =
+  process.env.VX_CERT_AUTHORITY_CERT_PATH ??
+  (NODE_ENV === 'production'
+    ? PROD_VX_CERT_AUTHORITY_CERT_PATH
+    : DEV_VX_CERT_AUTHORITY_CERT_PATH);
+
 /**
  * Should we mock the CACVote Server?
  */
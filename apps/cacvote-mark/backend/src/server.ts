@@ -5,7 +5,7 @@ import { Server } from 'http';
 import { buildApp } from './app';
 import { Client } from './cacvote-server/client';
 import { syncPeriodically } from './cacvote-server/sync';
-import { CACVOTE_URL } from './globals';
+import { CACVOTE_API_KEY, CACVOTE_URL } from './globals';
 import { Auth } from './types/auth';
 import { Workspace } from './workspace';
 
@@ -76,7 +76,7 @@ function getCacvoteServerClient(): Client {
     throw new Error('CACVOTE_URL not set');
   }
 
-  return new Client(CACVOTE_URL);
+  return new Client(CACVOTE_URL, CACVOTE_API_KEY);
 }
 
 /**
@@ -12,6 +12,7 @@ import { join } from 'path';
 import {
   JurisdictionCode,
   JurisdictionCodeSchema,
+  JurisdictionEncryptionKey,
   Payload,
   RegistrationRequest,
   SignedObject,
@@ -118,10 +119,10 @@ test('forEachObjectOfType', async () => {
 
 test('forEachRegistrationRequest', async () => {
   const store = Store.memoryStore();
-  const commonAccessCardId = '1234567890';
+  const certificates = await getCertificates();
 
   const registrationRequest = new RegistrationRequest(
-    commonAccessCardId,
+    '1234567890',
     unsafeParse(JurisdictionCodeSchema, 'st.test-jurisdiction'),
     'Given Name',
     'Family Name',
@@ -131,22 +132,113 @@ test('forEachRegistrationRequest', async () => {
     Uuid(),
     Uuid(),
     Payload.RegistrationRequest(registrationRequest).toBuffer(),
-    await getCertificates(),
+    certificates,
     Buffer.from('signature')
   );
 
   expect(
-    store.forEachRegistrationRequest({ commonAccessCardId }).isEmpty()
+    store.forEachRegistrationRequest({ certificates }).isEmpty()
   ).toBeTruthy();
 
   (await store.addObject(object)).unsafeUnwrap();
 
+  expect(store.forEachRegistrationRequest({ certificates }).count()).toEqual(
+    1
+  );
+  expect(store.forEachRegistrationRequest({ certificates }).first()).toEqual({
+    object,
+    registrationRequest,
+  });
   expect(
-    store.forEachRegistrationRequest({ commonAccessCardId }).count()
-  ).toEqual(1);
+    store
+      .forEachRegistrationRequest({
+        certificates: Buffer.from('other certificates'),
+      })
+      .isEmpty()
+  ).toBeTruthy();
+});
+
+test('getJurisdictionEncryptionKey', async () => {
+  const store = Store.memoryStore();
+  // must match the jurisdiction of the signing certificate
+  const jurisdictionCode = unsafeParse(
+    JurisdictionCodeSchema,
+    'st.dev-jurisdiction'
+  );
+  const vxCertAuthorityCertPath = 'vx-cert-authority-cert.pem';
+
   expect(
-    store.forEachRegistrationRequest({ commonAccessCardId }).first()
-  ).toEqual({ object, registrationRequest });
+    await store.getJurisdictionEncryptionKey(
+      jurisdictionCode,
+      vxCertAuthorityCertPath
+    )
+  ).toBeUndefined();
+
+  // chain verification is covered by the `SignedObject` tests
+  const trustedObjectIds = new Set<Uuid>();
+  const isSignedByElectionManagerSpy = jest
+    .spyOn(SignedObject.prototype, 'isSignedByElectionManager')
+    .mockImplementation(function isSignedByElectionManager(
+      this: SignedObject
+    ) {
+      return Promise.resolve(trustedObjectIds.has(this.getId()));
+    });
+
+  async function addEncryptionKey(
+    publicKey: Buffer,
+    trusted: boolean
+  ): Promise<JurisdictionEncryptionKey> {
+    const encryptionKey = new JurisdictionEncryptionKey(
+      jurisdictionCode,
+      publicKey
+    );
+    const object = new SignedObject(
+      Uuid(),
+      undefined,
+      Payload.JurisdictionEncryptionKey(encryptionKey).toBuffer(),
+      await getCertificates(),
+      Buffer.from('signature')
+    );
+    if (trusted) {
+      trustedObjectIds.add(object.getId());
+    }
+    (await store.addObject(object)).unsafeUnwrap();
+    return encryptionKey;
+  }
+
+  const trustedEncryptionKey = await addEncryptionKey(
+    Buffer.alloc(32, 1),
+    true
+  );
+  expect(
+    await store.getJurisdictionEncryptionKey(
+      jurisdictionCode,
+      vxCertAuthorityCertPath
+    )
+  ).toEqual(trustedEncryptionKey);
+  expect(isSignedByElectionManagerSpy).toHaveBeenCalledWith(
+    jurisdictionCode,
+    vxCertAuthorityCertPath
+  );
+
+  // a newer key that isn't signed by an election manager is ignored
+  await addEncryptionKey(Buffer.alloc(32, 2), false);
+  expect(
+    await store.getJurisdictionEncryptionKey(
+      jurisdictionCode,
+      vxCertAuthorityCertPath
+    )
+  ).toEqual(trustedEncryptionKey);
+
+  expect(
+    await store.getJurisdictionEncryptionKey(
+      unsafeParse(JurisdictionCodeSchema, 'st.other-jurisdiction'),
+      vxCertAuthorityCertPath
+    )
+  ).toBeUndefined();
+
+  isSignedByElectionManagerSpy.mockRestore();
+});
   expect(
     store
       .forEachRegistrationRequest({
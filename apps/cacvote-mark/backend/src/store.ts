@@ -23,6 +23,8 @@ import {
   JournalEntry,
   JurisdictionCode,
   JurisdictionCodeSchema,
+  JurisdictionEncryptionKey,
+  JurisdictionEncryptionKeyObjectType,
   Registration,
   RegistrationObjectType,
   RegistrationRequest,
@@ -201,15 +203,16 @@ export class Store {
       const payload = object.getPayload().okOrElse(bail);
 
       this.client.run(
-        `insert into objects (id, election_id, jurisdiction, object_type, payload, certificates, signature)
-        values (?, ?, ?, ?, ?, ?, ?)`,
+        `insert into objects (id, election_id, jurisdiction, object_type, payload, certificates, signature, signature_algorithm)
+        values (?, ?, ?, ?, ?, ?, ?, ?)`,
         object.getId(),
         object.getElectionId() ?? null,
         jurisdiction,
         payload.getObjectType(),
         object.getPayloadRaw(),
         object.getCertificates(),
-        object.getSignature()
+        object.getSignature(),
+        object.getSignatureAlgorithm() ?? null
       );
 
       return object.getId();
@@ -227,15 +230,16 @@ export class Store {
       const payload = object.getPayload().okOrElse(bail);
 
       this.client.run(
-        `insert into objects (id, election_id, jurisdiction, object_type, payload, certificates, signature, server_synced_at)
-        values (?, ?, ?, ?, ?, ?, ?, current_timestamp)`,
+        `insert into objects (id, election_id, jurisdiction, object_type, payload, certificates, signature, signature_algorithm, server_synced_at)
+        values (?, ?, ?, ?, ?, ?, ?, ?, current_timestamp)`,
         object.getId(),
         object.getElectionId() ?? null,
         jurisdiction,
         payload.getObjectType(),
         object.getPayloadRaw(),
         object.getCertificates(),
-        object.getSignature()
+        object.getSignature(),
+        object.getSignatureAlgorithm() ?? null
       );
 
       return object.getId();
@@ -247,7 +251,7 @@ export class Store {
    */
   getObjectById(objectId: Uuid): Optional<SignedObject> {
     const row = this.client.one(
-      `select id, election_id as electionId, payload, certificates, signature from objects where id = ?`,
+      `select id, election_id as electionId, payload, certificates, signature, signature_algorithm as signatureAlgorithm from objects where id = ?`,
       objectId
     ) as Optional<{
       id: string;
@@ -255,6 +259,7 @@ export class Store {
       payload: Buffer;
       certificates: Buffer;
       signature: Buffer;
+      signatureAlgorithm: string | null;
     }>;
 
     return row
@@ -263,16 +268,58 @@ export class Store {
           row.electionId ? unsafeParse(UuidSchema, row.electionId) : undefined,
           row.payload,
           row.certificates,
-          row.signature
+          row.signature,
+          row.signatureAlgorithm ?? undefined
         )
       : undefined;
   }
 
+  /**
+   * Gets the latest encryption key published by an election manager of a
+   * jurisdiction, if any. Keys signed by anyone else, or by certificates that
+   * don't chain to the CA at `vxCertAuthorityCertPath`, are ignored: whoever
+   * chooses the key can read the voter data encrypted to it.
+   */
+  async getJurisdictionEncryptionKey(
+    jurisdictionCode: JurisdictionCode,
+    vxCertAuthorityCertPath: string
+  ): Promise<Optional<JurisdictionEncryptionKey>> {
+    const rows = this.client.all(
+      `select id from objects
+        where object_type = ? and jurisdiction = ?
+        order by created_at desc, rowid desc`,
+      JurisdictionEncryptionKeyObjectType,
+      jurisdictionCode
+    ) as Array<{ id: string }>;
+
+    for (const row of rows) {
+      const object = this.getObjectById(unsafeParse(UuidSchema, row.id));
+      const encryptionKey = object
+        ?.getPayloadAsObjectType(JurisdictionEncryptionKeyObjectType)
+        .ok()
+        ?.getData();
+
+      if (
+        object &&
+        encryptionKey?.getJurisdictionCode() === jurisdictionCode &&
+        (await object.isSignedByElectionManager(
+          jurisdictionCode,
+          vxCertAuthorityCertPath
+        ))
+      ) {
+        return encryptionKey;
+      }
+    }
+
+    return undefined;
+  }
+
   getJournalEntriesForObjectsToPull(): JournalEntry[] {
     const objectTypesToPull = [
       RegistrationRequestObjectType,
       RegistrationObjectType,
       ElectionObjectType,
+      JurisdictionEncryptionKeyObjectType,
     ];
     const action = 'create';
 
@@ -316,13 +363,14 @@ export class Store {
    */
   getObjectsToPush(): SignedObject[] {
     const rows = this.client.all(
-      `select id, election_id as electionId, payload, certificates, signature from objects where server_synced_at is null`
+      `select id, election_id as electionId, payload, certificates, signature, signature_algorithm as signatureAlgorithm from objects where server_synced_at is null`
     ) as Array<{
       id: string;
       electionId: string | null;
       payload: Buffer;
       certificates: Buffer;
       signature: Buffer;
+      signatureAlgorithm: string | null;
     }>;
 
     return rows.map(
@@ -332,7 +380,8 @@ export class Store {
           row.electionId ? unsafeParse(UuidSchema, row.electionId) : undefined,
           row.payload,
           row.certificates,
-          row.signature
+          row.signature,
+          row.signatureAlgorithm ?? undefined
         )
     );
   }
@@ -361,10 +410,15 @@ export class Store {
     });
   }
 
+  /**
+   * Iterates over the registration requests signed by the CAC with
+   * `certificates`. Matching on the signer rather than the payload works even
+   * when the payload's private fields are encrypted.
+   */
   forEachRegistrationRequest({
-    commonAccessCardId,
+    certificates,
   }: {
-    commonAccessCardId: string;
+    certificates: Buffer;
   }): IteratorPlus<{
     object: SignedObject;
     registrationRequest: RegistrationRequest;
@@ -379,25 +433,33 @@ export class Store {
           registrationRequest instanceof RegistrationRequest,
           'payload matches object type because we used forEachObjectType'
         );
-        if (
-          registrationRequest.getCommonAccessCardId() === commonAccessCardId
-        ) {
+        if (object.getCertificates().equals(certificates)) {
           return { object, registrationRequest };
         }
       }
     );
   }
 
+  /**
+   * Iterates over the registrations of the registration requests signed by
+   * the CAC with `certificates`.
+   */
   forEachRegistration({
-    commonAccessCardId,
+    certificates,
     registrationRequestObjectId,
   }: {
-    commonAccessCardId: string;
+    certificates: Buffer;
     registrationRequestObjectId?: Uuid;
   }): IteratorPlus<{
     object: SignedObject;
     registration: Registration;
   }> {
+    const registrationRequestObjectIds = new Set(
+      this.forEachRegistrationRequest({ certificates })
+        .map(({ object }) => object.getId())
+        .toArray()
+    );
+
     return this.forEachObjectOfType(RegistrationObjectType).filterMap(
       (object) => {
         const registration = object.getPayload().unsafeUnwrap().getData();
@@ -406,7 +468,9 @@ export class Store {
           'payload matches object type because we used forEachObjectType'
         );
         if (
-          registration.getCommonAccessCardId() === commonAccessCardId &&
+          registrationRequestObjectIds.has(
+            registration.getRegistrationRequestObjectId()
+          ) &&
           (!registrationRequestObjectId ||
             registrationRequestObjectId ===
               registration.getRegistrationRequestObjectId())
@@ -421,7 +485,7 @@ export class Store {
     // FIXME: this should be using `this.client.each`, but there seems to be a race condition
     // that results in errors with "This database connection is busy executing a query"
     const rows = this.client.all(
-      `select id, election_id as electionId, payload, certificates, signature from objects
+      `select id, election_id as electionId, payload, certificates, signature, signature_algorithm as signatureAlgorithm from objects
         where json_extract(payload, '$.objectType') = ?`,
       objectType
     ) as Array<{
@@ -430,6 +494,7 @@ export class Store {
       payload: Buffer;
       certificates: Buffer;
       signature: Buffer;
+      signatureAlgorithm: string | null;
     }>;
     return iter(rows).map(
       (row) =>
@@ -438,7 +503,8 @@ export class Store {
           row.electionId ? unsafeParse(UuidSchema, row.electionId) : undefined,
           row.payload,
           row.certificates,
-          row.signature
+          row.signature,
+          row.signatureAlgorithm ?? undefined
         )
     );
   }
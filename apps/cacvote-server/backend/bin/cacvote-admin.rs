@@ -0,0 +1,681 @@
+//! `cacvote-admin` is the operator's tool for a `cacvote-server` instance. It
+//! talks to the server's API for anything an API client could do, and to the
+//! server's database directly for the rest, i.e. auditing objects and managing
+//! machines.
+//!
+//! Every command prints human-readable text by default, or JSON with
+//! `--output json`.
+
+use std::path::{Path, PathBuf};
+
+use cacvote_server::{audit, cac, client::Client, db};
+use clap::{Parser, Subcommand, ValueEnum};
+use color_eyre::eyre::{bail, Context};
+use openssl::{pkey::PKey, x509::X509};
+use serde::Serialize;
+use serde_json::json;
+use types_rs::cacvote::{
+    JournalEntry, JournalEntryAction, JurisdictionCode, ObjectView, Payload, SignatureAlgorithm,
+    SignedObject,
+};
+use url::Url;
+use uuid::Uuid;
+
+#[derive(Debug, Parser)]
+struct Opts {
+    #[clap(
+        long,
+        env = "CACVOTE_SERVER_URL",
+        default_value = "http://localhost:8000"
+    )]
+    cacvote_server_url: Url,
+
+    /// API key of an official, needed to see private fields and restricted
+    /// objects.
+    #[clap(long, env = "CACVOTE_API_KEY")]
+    cacvote_api_key: Option<String>,
+
+    /// URL of the server's database, needed by `audit` and `machines`.
+    #[clap(long, env = "DATABASE_URL")]
+    database_url: Option<String>,
+
+    #[clap(long, value_enum, default_value_t = Output::Text)]
+    output: Output,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Output {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Signs a payload and uploads it as a new object.
+    Sign {
+        /// JSON file with the payload, e.g. `{"objectType": "Election", ...}`.
+        #[clap(long)]
+        payload: PathBuf,
+
+        /// PEM file with the signer's certificate, followed by any
+        /// intermediate certificates.
+        #[clap(long)]
+        certificates: PathBuf,
+
+        /// PEM file with the signer's private key.
+        #[clap(long)]
+        private_key: PathBuf,
+
+        /// Signature algorithm, e.g. `ES256`. Defaults to the preferred
+        /// algorithm for the key's type.
+        #[clap(long)]
+        algorithm: Option<SignatureAlgorithm>,
+
+        /// ID of the object the new object is a new version of.
+        #[clap(long)]
+        supersedes: Option<Uuid>,
+    },
+
+    /// Lists journal entries.
+    Journal {
+        /// Only list entries after this one.
+        #[clap(long)]
+        since: Option<Uuid>,
+
+        #[clap(long)]
+        jurisdiction: Option<JurisdictionCode>,
+
+        #[clap(long)]
+        object_type: Option<String>,
+
+        /// `create`, `update` or `delete`.
+        #[clap(long)]
+        action: Option<JournalEntryAction>,
+
+        #[clap(long)]
+        election: Option<Uuid>,
+    },
+
+    /// Fetches an object and prints it with its decoded payload.
+    Object { object_id: Uuid },
+
+    /// Fetches an object and checks its signatures, its signature policy, and
+    /// optionally that its signer's certificate chains to a CA.
+    Verify {
+        object_id: Uuid,
+
+        /// PEM or DER file with a CA certificate the signer's certificate
+        /// should chain to. May be given more than once.
+        #[clap(long = "ca-certificate", value_parser = cac::load_ca_certificate)]
+        ca_certificates: Vec<X509>,
+    },
+
+    /// Checks every stored object for consistency, exiting with an error if
+    /// any problems are found.
+    Audit {
+        /// PEM or DER file with a CA certificate election officials' cards
+        /// chain to, as configured for the server. May be given more than
+        /// once.
+        #[clap(
+            long = "vx-ca-certificate",
+            env = "VX_CA_CERTIFICATES",
+            value_delimiter = ',',
+            value_parser = cac::load_ca_certificate
+        )]
+        vx_ca_certificates: Vec<X509>,
+    },
+
+    /// Manages the machines whose mailing labels the server accepts.
+    Machines {
+        #[clap(subcommand)]
+        command: MachinesCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum MachinesCommand {
+    /// Lists the machines.
+    List,
+
+    /// Adds a machine.
+    Add {
+        machine_identifier: String,
+
+        /// PEM file with the machine's certificates.
+        #[clap(long)]
+        certificates: PathBuf,
+    },
+
+    /// Removes a machine whose mailing labels haven't been scanned.
+    Remove { machine_identifier: String },
+}
+
+#[tokio::main]
+async fn main() -> color_eyre::Result<()> {
+    color_eyre::install()?;
+    let _ = dotenvy::from_filename(".env.local");
+    let _ = dotenvy::dotenv();
+
+    run(Opts::parse()).await
+}
+
+async fn run(opts: Opts) -> color_eyre::Result<()> {
+    let output = opts.output;
+    let mut client = Client::new(opts.cacvote_server_url.clone());
+    if let Some(api_key) = &opts.cacvote_api_key {
+        client = client.with_api_key(api_key);
+    }
+
+    match opts.command {
+        Command::Sign {
+            payload,
+            certificates,
+            private_key,
+            algorithm,
+            supersedes,
+        } => {
+            let signed_object = sign(&payload, &certificates, &private_key, algorithm, supersedes)?;
+            let object_id = client.create_object(signed_object).await?;
+            print(output, &json!({ "objectId": object_id }), || {
+                format!("Created object {object_id}")
+            })?;
+        }
+
+        Command::Journal {
+            since,
+            jurisdiction,
+            object_type,
+            action,
+            election,
+        } => {
+            let entries = client
+                .get_journal_entries(since.as_ref(), jurisdiction.as_ref())
+                .await?
+                .into_iter()
+                .filter(|entry| {
+                    object_type
+                        .as_ref()
+                        .is_none_or(|object_type| entry.object_type == *object_type)
+                        && action.as_ref().is_none_or(|action| entry.action == *action)
+                        && election.is_none_or(|election| entry.election_id == Some(election))
+                })
+                .collect::<Vec<_>>();
+            print(output, &entries, || format_journal_entries(&entries))?;
+        }
+
+        Command::Object { object_id } => {
+            let object_view = get_object(&client, object_id).await?;
+            match &object_view {
+                ObjectView::Full(signed_object) => {
+                    let payload = signed_object.try_to_inner()?;
+                    print(
+                        output,
+                        &json!({ "object": signed_object, "payload": payload }),
+                        || format!("{signed_object:#?}\n{payload:#?}"),
+                    )?;
+                }
+                ObjectView::Redacted(redacted_object) => {
+                    print(output, &json!({ "object": redacted_object }), || {
+                        format!("{redacted_object:#?}")
+                    })?;
+                }
+            }
+        }
+
+        Command::Verify {
+            object_id,
+            ca_certificates,
+        } => {
+            let ObjectView::Full(signed_object) = get_object(&client, object_id).await? else {
+                bail!(
+                    "Object {object_id} is redacted, pass the API key of an official to verify it"
+                );
+            };
+            let verification = verify(&signed_object, &ca_certificates)?;
+            print(output, &verification, || verification.to_string())?;
+
+            if !verification.is_valid() {
+                bail!("Object {object_id} failed verification");
+            }
+        }
+
+        Command::Audit { vx_ca_certificates } => {
+            let mut conn = connect(opts.database_url.as_deref()).await?;
+            let report = audit::run(&mut conn, &vx_ca_certificates).await?;
+            print(output, &report, || format_audit_report(&report))?;
+
+            if !report.is_clean() {
+                bail!("Audit found {} problems", report.findings.len());
+            }
+        }
+
+        Command::Machines { command } => {
+            let mut conn = connect(opts.database_url.as_deref()).await?;
+            match command {
+                MachinesCommand::List => {
+                    let machines = db::get_machines(&mut conn)
+                        .await?
+                        .iter()
+                        .map(MachineOutput::from)
+                        .collect::<Vec<_>>();
+                    print(output, &machines, || {
+                        machines
+                            .iter()
+                            .map(|machine| {
+                                format!(
+                                    "{}  {}  {}",
+                                    machine.machine_identifier, machine.id, machine.created_at
+                                )
+                            })
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    })?;
+                }
+                MachinesCommand::Add {
+                    machine_identifier,
+                    certificates,
+                } => {
+                    let certificates = std::fs::read(&certificates)
+                        .with_context(|| format!("Unable to read {}", certificates.display()))?;
+                    if X509::stack_from_pem(&certificates)
+                        .context("Machine certificates must be PEM-encoded")?
+                        .is_empty()
+                    {
+                        bail!("No PEM-encoded certificates found in the machine's certificates");
+                    }
+                    let id =
+                        db::create_machine(&mut conn, &machine_identifier, &certificates).await?;
+                    print(output, &json!({ "id": id }), || {
+                        format!("Added machine {machine_identifier} ({id})")
+                    })?;
+                }
+                MachinesCommand::Remove { machine_identifier } => {
+                    if !db::delete_machine(&mut conn, &machine_identifier).await? {
+                        bail!("No machine found with identifier {machine_identifier}");
+                    }
+                    print(output, &json!({ "removed": machine_identifier }), || {
+                        format!("Removed machine {machine_identifier}")
+                    })?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints `value` as JSON, or the result of `text` as text.
+fn print<T: Serialize>(
+    output: Output,
+    value: &T,
+    text: impl FnOnce() -> String,
+) -> color_eyre::Result<()> {
+    match output {
+        Output::Text => println!("{}", text()),
+        Output::Json => println!("{}", serde_json::to_string_pretty(value)?),
+    }
+    Ok(())
+}
+
+async fn connect(database_url: Option<&str>) -> color_eyre::Result<sqlx::PgConnection> {
+    use sqlx::Connection;
+
+    let Some(database_url) = database_url else {
+        bail!("--database-url or DATABASE_URL is required for this command");
+    };
+    Ok(sqlx::PgConnection::connect(database_url).await?)
+}
+
+fn sign(
+    payload: &Path,
+    certificates: &Path,
+    private_key: &Path,
+    algorithm: Option<SignatureAlgorithm>,
+    supersedes: Option<Uuid>,
+) -> color_eyre::Result<SignedObject> {
+    let payload: Payload = serde_json::from_slice(
+        &std::fs::read(payload).with_context(|| format!("Unable to read {}", payload.display()))?,
+    )
+    .with_context(|| format!("Invalid payload in {}", payload.display()))?;
+    let certificates = X509::stack_from_pem(
+        &std::fs::read(certificates)
+            .with_context(|| format!("Unable to read {}", certificates.display()))?,
+    )?;
+    let private_key = PKey::private_key_from_pem(
+        &std::fs::read(private_key)
+            .with_context(|| format!("Unable to read {}", private_key.display()))?,
+    )?;
+
+    let signed_object = match (algorithm, supersedes) {
+        (None, None) => SignedObject::from_payload(&payload, certificates, &private_key)?,
+        (Some(algorithm), None) => SignedObject::from_payload_with_algorithm(
+            &payload,
+            certificates,
+            &private_key,
+            algorithm,
+        )?,
+        (None, Some(supersedes)) => SignedObject::from_payload_superseding(
+            &payload,
+            supersedes,
+            certificates,
+            &private_key,
+        )?,
+        (Some(algorithm), Some(supersedes)) => {
+            SignedObject::from_payload_superseding_with_algorithm(
+                &payload,
+                supersedes,
+                certificates,
+                &private_key,
+                algorithm,
+            )?
+        }
+    };
+
+    if !signed_object.verify()? {
+        bail!("The private key does not match the signer's certificate");
+    }
+
+    Ok(signed_object)
+}
+
+async fn get_object(client: &Client, object_id: Uuid) -> color_eyre::Result<ObjectView> {
+    match client.get_object_by_id(object_id).await? {
+        Some(object_view) => Ok(object_view),
+        None => bail!("No object found with ID {object_id}"),
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Verification {
+    object_id: Uuid,
+    signatures_valid: bool,
+    signers_required: usize,
+    signers_collected: usize,
+
+    /// `None` if no CA certificates were given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    certificate_chain_error: Option<Option<String>>,
+}
+
+impl Verification {
+    fn is_valid(&self) -> bool {
+        self.signatures_valid
+            && self.signers_collected >= self.signers_required
+            && !matches!(self.certificate_chain_error, Some(Some(_)))
+    }
+}
+
+impl std::fmt::Display for Verification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Object:            {}", self.object_id)?;
+        writeln!(
+            f,
+            "Signatures:        {}",
+            if self.signatures_valid {
+                "valid"
+            } else {
+                "INVALID"
+            }
+        )?;
+        write!(
+            f,
+            "Signature policy:  {} of {} eligible signers",
+            self.signers_collected, self.signers_required
+        )?;
+        match &self.certificate_chain_error {
+            None => Ok(()),
+            Some(None) => write!(f, "\nCertificate chain: valid"),
+            Some(Some(error)) => write!(f, "\nCertificate chain: INVALID ({error})"),
+        }
+    }
+}
+
+fn verify(
+    signed_object: &SignedObject,
+    ca_certificates: &[X509],
+) -> color_eyre::Result<Verification> {
+    let signature_policy_status = if ca_certificates.is_empty() {
+        signed_object.signature_policy_status()?
+    } else {
+        signed_object.trusted_signature_policy_status(ca_certificates)?
+    };
+    let certificate_chain_error = (!ca_certificates.is_empty()).then(|| {
+        cac::verify_certificate_chain(&signed_object.certificates, ca_certificates)
+            .err()
+            .map(|error| error.to_string())
+    });
+
+    Ok(Verification {
+        object_id: signed_object.id,
+        signatures_valid: signed_object.verify()?,
+        signers_required: signature_policy_status.signers_required,
+        signers_collected: signature_policy_status.signers_collected,
+        certificate_chain_error,
+    })
+}
+
+fn format_journal_entries(entries: &[JournalEntry]) -> String {
+    entries
+        .iter()
+        .map(|entry| {
+            format!(
+                "{}  {}  {:<6}  {:<36}  {}  {}",
+                entry.created_at,
+                entry.id,
+                entry.action.as_str(),
+                entry.object_type,
+                entry.object_id,
+                entry.jurisdiction_code,
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn format_audit_report(report: &audit::AuditReport) -> String {
+    let mut text = format!(
+        "Checked {} objects, found {} problems",
+        report.objects_checked,
+        report.findings.len()
+    );
+    for finding in &report.findings {
+        text.push_str(&format!("\n{}: {}", finding.object_id, finding.problem));
+    }
+    text
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MachineOutput {
+    id: Uuid,
+    machine_identifier: String,
+    #[serde(with = "time::serde::iso8601")]
+    created_at: time::OffsetDateTime,
+}
+
+impl From<&db::Machine> for MachineOutput {
+    fn from(machine: &db::Machine) -> Self {
+        Self {
+            id: machine.id,
+            machine_identifier: machine.machine_identifier.clone(),
+            created_at: machine.created_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+    use types_rs::cacvote::RegistrationRequest;
+
+    use super::*;
+
+    const DEV_CERTS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../../libs/auth/certs/dev");
+
+    /// Writes `contents` to a new file in the temporary directory.
+    fn temp_file(contents: &[u8]) -> color_eyre::Result<PathBuf> {
+        let path = std::env::temp_dir().join(format!("cacvote-admin-{}", Uuid::new_v4()));
+        std::fs::write(&path, contents)?;
+        Ok(path)
+    }
+
+    fn payload_file() -> color_eyre::Result<PathBuf> {
+        temp_file(&serde_json::to_vec(&Payload::RegistrationRequest(
+            RegistrationRequest {
+                common_access_card_id: "1234567890".to_owned(),
+                jurisdiction_code: JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
+                given_name: "John".to_owned(),
+                family_name: "Doe".to_owned(),
+            },
+        ))?)
+    }
+
+    fn opts(args: &[&str]) -> Opts {
+        Opts::try_parse_from(std::iter::once("cacvote-admin").chain(args.iter().copied())).unwrap()
+    }
+
+    #[test]
+    fn test_opts() {
+        Opts::command().debug_assert();
+
+        let object_id = Uuid::new_v4().to_string();
+        let Command::Sign {
+            algorithm,
+            supersedes,
+            ..
+        } = opts(&[
+            "sign",
+            "--payload",
+            "payload.json",
+            "--certificates",
+            "cert.pem",
+            "--private-key",
+            "key.pem",
+            "--algorithm",
+            "ES256",
+            "--supersedes",
+            &object_id,
+        ])
+        .command
+        else {
+            panic!("expected sign");
+        };
+        assert_eq!(algorithm, Some(SignatureAlgorithm::EcdsaP256Sha256));
+        assert_eq!(supersedes.map(|id| id.to_string()), Some(object_id));
+
+        let Command::Journal { action, .. } = opts(&["journal", "--action", "update"]).command
+        else {
+            panic!("expected journal");
+        };
+        assert_eq!(action, Some(JournalEntryAction::Update));
+        assert_eq!(
+            opts(&["--output", "json", "machines", "list"]).output,
+            Output::Json
+        );
+    }
+
+    #[test]
+    fn test_sign() -> color_eyre::Result<()> {
+        let payload = payload_file()?;
+        let certificates = PathBuf::from(DEV_CERTS).join("vx-admin-cert-authority-cert.pem");
+        let private_key = PathBuf::from(DEV_CERTS).join("vx-admin-private-key.pem");
+        let supersedes = Uuid::new_v4();
+
+        let signed_object = sign(&payload, &certificates, &private_key, None, None)?;
+        assert_eq!(signed_object.supersedes()?, None);
+        assert!(verify(&signed_object, &[])?.signatures_valid);
+
+        let signed_object = sign(
+            &payload,
+            &certificates,
+            &private_key,
+            Some(SignatureAlgorithm::EcdsaP256Sha256),
+            Some(supersedes),
+        )?;
+        assert_eq!(signed_object.supersedes()?, Some(supersedes));
+        assert_eq!(
+            signed_object.signature_algorithm,
+            Some(SignatureAlgorithm::EcdsaP256Sha256)
+        );
+        assert!(signed_object.verify()?);
+
+        // the algorithm must suit the key
+        assert!(sign(
+            &payload,
+            &certificates,
+            &private_key,
+            Some(SignatureAlgorithm::RsaPkcs1Sha256),
+            Some(supersedes),
+        )
+        .is_err());
+
+        // the key must match the certificate
+        let error = sign(
+            &payload,
+            &PathBuf::from(DEV_CERTS).join("vx-mark-cert.pem"),
+            &private_key,
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("does not match"), "{error}");
+
+        std::fs::remove_file(payload)?;
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_machines(
+        _: sqlx::postgres::PgPoolOptions,
+        connect_options: sqlx::postgres::PgConnectOptions,
+    ) -> color_eyre::Result<()> {
+        // the test database is on the same server as `DATABASE_URL`
+        let mut database_url = Url::parse(&std::env::var("DATABASE_URL")?)?;
+        database_url.set_path(connect_options.get_database().unwrap_or_default());
+        let database_url = database_url.to_string();
+        let certificates = PathBuf::from(DEV_CERTS).join("vx-mark-cert.pem");
+        let machines = |args: &[&str]| {
+            opts(&[&["--database-url", database_url.as_str(), "machines"], args].concat())
+        };
+
+        run(machines(&[
+            "add",
+            "mark-1",
+            "--certificates",
+            certificates.to_str().unwrap(),
+        ]))
+        .await?;
+        run(machines(&["list"])).await?;
+
+        // machines must be unique and have PEM certificates
+        assert!(run(machines(&[
+            "add",
+            "mark-1",
+            "--certificates",
+            certificates.to_str().unwrap(),
+        ]))
+        .await
+        .is_err());
+        let not_pem = temp_file(b"not a certificate")?;
+        let error = run(machines(&[
+            "add",
+            "mark-2",
+            "--certificates",
+            not_pem.to_str().unwrap(),
+        ]))
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("No PEM-encoded"), "{error}");
+        std::fs::remove_file(not_pem)?;
+
+        run(machines(&["remove", "mark-1"])).await?;
+        let error = run(machines(&["remove", "mark-1"])).await.unwrap_err();
+        assert!(error.to_string().contains("No machine found"), "{error}");
+
+        Ok(())
+    }
+}
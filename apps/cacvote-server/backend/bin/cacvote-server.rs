@@ -58,5 +58,8 @@ async fn main() -> color_eyre::Result<()> {
     log::setup(&config)?;
     let pool = db::setup(&config).await?;
 
-    app::run(app::setup(pool).await?, &config).await
+    let result = app::run(app::setup(pool.clone(), config.clone()).await?, &config).await;
+    pool.close().await;
+    log::shutdown();
+    result
 }
@@ -0,0 +1 @@
+ALTER TABLE objects ADD COLUMN cosignatures JSONB NOT NULL DEFAULT '[]';
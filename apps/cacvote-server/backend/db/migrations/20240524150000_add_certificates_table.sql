@@ -0,0 +1,57 @@
+CREATE TABLE certificates (
+    -- hex-encoded SHA-256 digest of the signer's (first) certificate in DER form
+    fingerprint VARCHAR(64) PRIMARY KEY,
+
+    -- the PEM-encoded certificate stack, signer's certificate first
+    certificates BYTEA NOT NULL,
+
+    -- signer attributes parsed out of the signer's certificate. these are
+    -- NULL until the application has parsed the certificate, which it does
+    -- when storing new certificates and on startup for any that are missing
+    common_name VARCHAR(255),
+    jurisdiction VARCHAR(255),
+    card_type VARCHAR(255),
+    not_after TIMESTAMPTZ,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT current_timestamp
+);
+
+CREATE INDEX certificates_common_name_idx ON certificates (common_name);
+CREATE INDEX certificates_jurisdiction_idx ON certificates (jurisdiction);
+CREATE INDEX certificates_card_type_idx ON certificates (card_type);
+CREATE INDEX certificates_not_after_idx ON certificates (not_after);
+
+-- computes the fingerprint of the first certificate in a PEM-encoded stack
+CREATE FUNCTION pem_stack_fingerprint(certificates BYTEA) RETURNS VARCHAR(64) AS $$
+    SELECT encode(
+        sha256(
+            decode(
+                regexp_replace(
+                    substring(
+                        convert_from(certificates, 'UTF8')
+                        FROM '-----BEGIN CERTIFICATE-----([^-]+)-----END CERTIFICATE-----'
+                    ),
+                    '\s', '', 'g'
+                ),
+                'base64'
+            )
+        ),
+        'hex'
+    )
+$$ LANGUAGE SQL IMMUTABLE;
+
+INSERT INTO certificates (fingerprint, certificates)
+SELECT DISTINCT ON (pem_stack_fingerprint(certificates))
+    pem_stack_fingerprint(certificates),
+    certificates
+FROM objects
+ORDER BY pem_stack_fingerprint(certificates), created_at;
+
+ALTER TABLE objects ADD COLUMN signer_fingerprint VARCHAR(64) REFERENCES certificates (fingerprint);
+UPDATE objects SET signer_fingerprint = pem_stack_fingerprint(certificates);
+ALTER TABLE objects ALTER COLUMN signer_fingerprint SET NOT NULL;
+ALTER TABLE objects DROP COLUMN certificates;
+
+CREATE INDEX objects_signer_fingerprint_idx ON objects (signer_fingerprint);
+
+DROP FUNCTION pem_stack_fingerprint(BYTEA);
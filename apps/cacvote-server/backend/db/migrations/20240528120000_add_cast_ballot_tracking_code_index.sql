@@ -0,0 +1,3 @@
+-- voters look up their cast ballot by tracking code, the SHA-256 digest of its
+-- signature (see `types_rs::cacvote::tracking_code`)
+CREATE INDEX objects_signature_sha256_idx ON objects (sha256(signature));
@@ -0,0 +1,22 @@
+CREATE TABLE voter_session_challenges (
+    -- random bytes to be signed by the voter's CAC, usable once
+    nonce BYTEA PRIMARY KEY,
+
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT current_timestamp
+);
+
+CREATE TABLE voter_sessions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- SHA-256 digest of the session token, so that the tokens themselves
+    -- aren't stored
+    token_hash BYTEA NOT NULL UNIQUE,
+
+    -- fingerprint of the CAC certificate that answered the challenge, which
+    -- identifies the voter's objects by their signer
+    signer_fingerprint VARCHAR(64) NOT NULL,
+
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT current_timestamp
+);
@@ -0,0 +1,28 @@
+-- voters may cast more than one ballot in an election, and only the last one
+-- counts. each cast ballot is superseded by the next one cast by the same
+-- voter in the same election, in journal order. voters are identified by the
+-- CAC ID in their signing certificate's common name, falling back to the
+-- signing certificate itself for certificates that don't have one.
+CREATE VIEW cast_ballot_supersessions AS
+SELECT
+    o.id AS cast_ballot_id,
+    o.election_id,
+    j.created_at AS recorded_at,
+    lead(o.id) OVER voter_cast_ballots AS superseded_by_id,
+    lead(j.created_at) OVER voter_cast_ballots AS superseded_at
+FROM objects AS o
+INNER JOIN journal_entries AS j
+  ON j.object_id = o.id AND j.action = 'create'
+INNER JOIN certificates AS c
+  ON c.fingerprint = o.signer_fingerprint
+WHERE o.object_type = 'CastBallot'
+  AND o.deleted_at IS NULL
+WINDOW voter_cast_ballots AS (
+    PARTITION BY
+        o.election_id,
+        coalesce(
+            substring(c.common_name FROM '^[^.]*\.[^.]*\.[^.]*\.([0-9]+)$'),
+            c.fingerprint
+        )
+    ORDER BY j.created_at, o.id
+);
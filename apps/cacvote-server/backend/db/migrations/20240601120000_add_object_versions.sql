@@ -0,0 +1,4 @@
+-- the previous version of the object, as named by the `supersedes` field of
+-- its signed payload. each version can only be superseded once, so an object's
+-- versions form a single chain
+ALTER TABLE objects ADD COLUMN supersedes_object_id UUID UNIQUE REFERENCES objects(id);
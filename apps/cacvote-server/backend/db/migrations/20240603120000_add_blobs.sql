@@ -0,0 +1,31 @@
+CREATE TABLE blobs (
+    -- hex-encoded SHA-256 digest of `data`, by which objects refer to the blob
+    sha256 VARCHAR(64) PRIMARY KEY,
+
+    data BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT current_timestamp
+);
+
+CREATE TABLE blob_uploads (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- the blob being uploaded. there is at most one upload per blob, so that
+    -- starting an upload again resumes it
+    sha256 VARCHAR(64) NOT NULL UNIQUE,
+    size BIGINT NOT NULL,
+
+    -- set once all chunks have been received and the blob stored, at which
+    -- point the chunks are deleted
+    completed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT current_timestamp
+);
+
+CREATE TABLE blob_upload_chunks (
+    upload_id UUID NOT NULL REFERENCES blob_uploads(id) ON DELETE CASCADE,
+
+    -- where `data` starts within the blob
+    "offset" BIGINT NOT NULL,
+    data BYTEA NOT NULL,
+
+    PRIMARY KEY (upload_id, "offset")
+);
@@ -0,0 +1,63 @@
+-- the certificates table is keyed on the signer's certificate but only kept
+-- the stack of the first object that signer signed, so objects signed with
+-- the same certificate under a different chain were returned with the wrong
+-- one. each object keeps the stack it was signed with, and the certificates
+-- table only indexes signers.
+ALTER TABLE objects ADD COLUMN certificates BYTEA;
+UPDATE objects AS o
+SET certificates = c.certificates
+FROM certificates AS c
+WHERE c.fingerprint = o.signer_fingerprint;
+ALTER TABLE objects ALTER COLUMN certificates SET NOT NULL;
+
+-- signers of an object's cosignatures, so that objects can be found by any of
+-- their signers and not only the primary one
+CREATE TABLE object_cosigners (
+    object_id UUID NOT NULL REFERENCES objects (id),
+    signer_fingerprint VARCHAR(64) NOT NULL REFERENCES certificates (fingerprint),
+    PRIMARY KEY (object_id, signer_fingerprint)
+);
+
+CREATE INDEX object_cosigners_signer_fingerprint_idx ON object_cosigners (signer_fingerprint);
+
+-- computes the fingerprint of the first certificate in a PEM-encoded stack
+CREATE FUNCTION pem_stack_fingerprint(certificates BYTEA) RETURNS VARCHAR(64) AS $$
+    SELECT encode(
+        sha256(
+            decode(
+                regexp_replace(
+                    substring(
+                        convert_from(certificates, 'UTF8')
+                        FROM '-----BEGIN CERTIFICATE-----([^-]+)-----END CERTIFICATE-----'
+                    ),
+                    '\s', '', 'g'
+                ),
+                'base64'
+            )
+        ),
+        'hex'
+    )
+$$ LANGUAGE SQL IMMUTABLE;
+
+CREATE TEMPORARY TABLE existing_cosignatures AS
+SELECT
+    o.id AS object_id,
+    o.created_at,
+    decode(cosignature->>'certificates', 'base64') AS certificates
+FROM objects AS o, jsonb_array_elements(o.cosignatures) AS cosignature;
+
+-- signer attributes are filled in by the application on startup
+INSERT INTO certificates (fingerprint, certificates)
+SELECT DISTINCT ON (pem_stack_fingerprint(certificates))
+    pem_stack_fingerprint(certificates),
+    certificates
+FROM existing_cosignatures
+ORDER BY pem_stack_fingerprint(certificates), created_at
+ON CONFLICT (fingerprint) DO NOTHING;
+
+INSERT INTO object_cosigners (object_id, signer_fingerprint)
+SELECT DISTINCT object_id, pem_stack_fingerprint(certificates)
+FROM existing_cosignatures;
+
+DROP TABLE existing_cosignatures;
+DROP FUNCTION pem_stack_fingerprint(BYTEA);
@@ -0,0 +1,14 @@
+-- voters are identified by the CAC ID in the chain-validated certificate that
+-- signed their objects, committed to with the redaction key (see
+-- `cac::voter_id`) so that the ID itself isn't stored. the certificate alone
+-- doesn't identify a voter, since reissued cards have new certificates.
+-- voters' objects created before this are indexed by the application on
+-- startup.
+ALTER TABLE objects ADD COLUMN voter_id VARCHAR(64);
+
+CREATE INDEX objects_voter_id_idx ON objects (voter_id);
+
+-- sessions last minutes, so existing ones are ended rather than converted
+DELETE FROM voter_sessions;
+ALTER TABLE voter_sessions DROP COLUMN signer_fingerprint;
+ALTER TABLE voter_sessions ADD COLUMN voter_id VARCHAR(64) NOT NULL;
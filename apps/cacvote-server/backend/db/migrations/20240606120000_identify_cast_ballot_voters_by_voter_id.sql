@@ -0,0 +1,28 @@
+-- cast ballots were attributed to voters by a pattern match over their signing
+-- certificate's common name, which is only as trustworthy as the certificate.
+-- voters are now identified by their voter ID, which is only recorded for
+-- chain-validated certificates. ballots without one, i.e. those whose
+-- certificates no longer verify, fall back to their signing certificate.
+DROP VIEW cast_ballot_supersessions;
+CREATE VIEW cast_ballot_supersessions AS
+SELECT
+    o.id AS cast_ballot_id,
+    o.election_id,
+    j.created_at AS recorded_at,
+    lead(o.id) OVER voter_cast_ballots AS superseded_by_id,
+    lead(j.created_at) OVER voter_cast_ballots AS superseded_at
+FROM objects AS o
+INNER JOIN journal_entries AS j
+  ON j.object_id = o.id AND j.action = 'create'
+WHERE o.object_type = 'CastBallot'
+  AND o.deleted_at IS NULL
+WINDOW voter_cast_ballots AS (
+    PARTITION BY o.election_id, coalesce(o.voter_id, o.signer_fingerprint)
+    ORDER BY j.created_at, o.id
+);
+
+-- nothing reads the common name from the database any more, and storing it
+-- invited treating an unverified name as an identity. it is read from the
+-- certificate when needed.
+DROP INDEX certificates_common_name_idx;
+ALTER TABLE certificates DROP COLUMN common_name;
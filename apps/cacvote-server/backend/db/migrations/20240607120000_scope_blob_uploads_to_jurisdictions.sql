@@ -0,0 +1,14 @@
+-- uploads used to be shared by everyone uploading the same digest, so anyone
+-- could start one with the wrong size or bogus chunks and block the real blob.
+-- each jurisdiction now has its own uploads. uploads in progress can't be
+-- attributed to one, and completed ones are no longer needed, so drop them
+DELETE FROM blob_uploads;
+
+ALTER TABLE blob_uploads
+    ADD COLUMN jurisdiction VARCHAR(255) NOT NULL;
+
+ALTER TABLE blob_uploads
+    DROP CONSTRAINT blob_uploads_sha256_key;
+
+ALTER TABLE blob_uploads
+    ADD CONSTRAINT blob_uploads_jurisdiction_sha256_key UNIQUE (jurisdiction, sha256);
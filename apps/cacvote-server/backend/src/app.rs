@@ -3,45 +3,222 @@
 //! Route handlers are bundled via [`setup`] into an [`axum::Router`], which can then be run
 //! using [`run`] at the configured port (see [`config`][`super::config`]).
 
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::{
+    future::Future,
+    net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener},
+    sync::Arc,
+};
 
+use askama::Template;
 use axum::{
+    async_trait,
     body::Bytes,
-    extract::{DefaultBodyLimit, Path, Query, State},
-    http::StatusCode,
-    response::{IntoResponse, Response},
+    extract::{DefaultBodyLimit, FromRef, FromRequestParts, Path, Query, State},
+    http::{header, request::Parts, HeaderMap, StatusCode},
+    middleware,
+    response::{Html, IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use openssl::{sha::sha256, x509::X509};
 use serde::Deserialize;
 use serde_json::json;
 use sqlx::PgPool;
-use tower_http::trace::TraceLayer;
+use time::OffsetDateTime;
+use tower_http::{compression::CompressionLayer, trace::TraceLayer};
 use tracing::Level;
-use types_rs::cacvote::{self, JournalEntry, JurisdictionCode, SignedObject};
+use types_rs::cacvote::{
+    self, BallotTrackingStatus, BlobReference, BlobUpload, CreateVoterSessionRequest, JournalEntry,
+    JurisdictionCode, ObjectSearchPage, ObjectSearchQuery, ObjectVersion, ObjectView,
+    ReadinessCheck, ReadinessReport, RedactionPolicy, RegistrationConflict, SignatureAlgorithm,
+    SignedObject, SignerCertificate, SnapshotPage, SnapshotQuery, SnapshotTreeHead,
+    VoterRegistration, VoterSession, VoterSessionChallenge,
+};
+use utoipa::{IntoParams, OpenApi};
 use uuid::Uuid;
 
 use crate::{
-    bulletin_board,
-    config::{Config, MAX_REQUEST_SIZE},
-    db,
+    bulletin_board, cac,
+    config::{
+        Config, MAX_BLOB_SIZE, MAX_REQUEST_SIZE, VOTER_SESSION_CHALLENGE_NONCE_LENGTH,
+        VOTER_SESSION_CHALLENGE_TTL, VOTER_SESSION_TOKEN_LENGTH, VOTER_SESSION_TTL,
+    },
+    db, log,
+    metrics::{self, Metrics, SignatureVerificationFailure},
+    negotiation::{Accept, Encoded, Negotiated},
+    openapi::{ApiDoc, Binary, CreatedResponse, ErrorResponse},
+    registration_conflicts,
 };
 
+/// State shared by all route handlers.
+#[derive(Debug, Clone)]
+pub struct AppState {
+    pool: PgPool,
+    config: Config,
+    metrics: Arc<Metrics>,
+}
+
+impl FromRef<AppState> for PgPool {
+    fn from_ref(state: &AppState) -> Self {
+        state.pool.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<Metrics> {
+    fn from_ref(state: &AppState) -> Self {
+        state.metrics.clone()
+    }
+}
+
+/// Who is reading objects, which determines whether their private fields are
+/// redacted (see [`RedactionPolicy`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Viewer {
+    Public,
+
+    /// A jurisdiction official, identified by one of the configured official
+    /// API keys sent as a bearer token.
+    Official(JurisdictionCode),
+}
+
+impl Viewer {
+    /// Shows `object` in full if this viewer may see all of it, otherwise
+    /// redacted.
+    fn view(&self, object: SignedObject, redaction_key: &str) -> Result<ObjectView, Error> {
+        let policy = RedactionPolicy::for_object_type(object.try_to_inner()?.object_type());
+
+        let is_own_jurisdiction = match self {
+            Self::Public => false,
+            Self::Official(jurisdiction_code) => {
+                object.jurisdiction_code().as_ref() == Some(jurisdiction_code)
+            }
+        };
+
+        if policy.is_public() || is_own_jurisdiction {
+            Ok(ObjectView::Full(object))
+        } else {
+            Ok(ObjectView::Redacted(
+                object.redact(&policy, redaction_key.as_bytes())?,
+            ))
+        }
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for Viewer {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Error> {
+        let Some(authorization) = parts.headers.get(header::AUTHORIZATION) else {
+            return Ok(Self::Public);
+        };
+
+        let api_key = authorization
+            .to_str()
+            .ok()
+            .and_then(|authorization| authorization.strip_prefix("Bearer "))
+            .ok_or(Error::Unauthorized)?;
+
+        state
+            .config
+            .official_api_keys
+            .iter()
+            .find(|official_api_key| {
+                official_api_key.key.len() == api_key.len()
+                    && openssl::memcmp::eq(official_api_key.key.as_bytes(), api_key.as_bytes())
+            })
+            .map(|official_api_key| Self::Official(official_api_key.jurisdiction_code.clone()))
+            .ok_or(Error::Unauthorized)
+    }
+}
+
+/// A voter authenticated by a voter session token sent as a bearer token (see
+/// [`create_voter_session`]). Voters may read the objects they signed and the
+/// registrations that approved them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Voter {
+    /// Identifies the voter by their CAC ID rather than by the certificate
+    /// they signed in with, see [`cac::voter_id`].
+    voter_id: String,
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for Voter {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Error> {
+        let token = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|authorization| authorization.to_str().ok())
+            .and_then(|authorization| authorization.strip_prefix("Bearer "))
+            .ok_or(Error::Unauthorized)?;
+
+        let mut conn = state.pool.acquire().await?;
+        db::get_voter_session_voter_id(&mut conn, &sha256(token.as_bytes()))
+            .await?
+            .map(|voter_id| Self { voter_id })
+            .ok_or(Error::Unauthorized)
+    }
+}
+
 /// Prepares the application to be run within an HTTP server.
 ///
 /// Requires a [`PgPool`] from [`db::setup`]. Run the application with [`run`]
 /// with the result of this function.
-pub async fn setup(pool: PgPool) -> color_eyre::Result<Router> {
+pub async fn setup(pool: PgPool, config: Config) -> color_eyre::Result<Router> {
     let _entered = tracing::span!(Level::DEBUG, "Setting up application").entered();
+    let metrics = Arc::new(Metrics::new()?);
     Ok(Router::new()
+        .route("/metrics", get(get_metrics))
+        .route("/api/openapi.json", get(get_openapi))
         .route("/api/status", get(get_status))
-        .route("/api/objects", post(create_object))
+        .route("/api/ready", get(get_readiness))
+        .route("/api/objects", get(search_objects).post(create_object))
         .route("/api/objects/:object_id", get(get_object_by_id))
+        .route("/api/objects/:object_id/versions", get(get_object_versions))
         .route("/api/journal-entries", get(get_journal_entries))
+        .route("/api/snapshot", get(get_snapshot_tree_head))
+        .route("/api/snapshot/entries", get(get_snapshot_entries))
+        .route("/api/blobs/uploads", post(start_blob_upload))
+        .route(
+            "/api/blobs/uploads/:upload_id",
+            get(get_blob_upload).patch(append_blob_upload_chunk),
+        )
+        .route("/api/blobs/:sha256", get(get_blob))
+        .route(
+            "/api/certificates/:fingerprint",
+            get(get_signer_certificate),
+        )
+        .route(
+            "/api/certificates/:fingerprint/objects",
+            get(list_object_ids_by_signer),
+        )
         .route(
             "/api/scanned-mailing-label-code",
             post(scanned_create_mailing_label_code),
         )
+        .route(
+            "/api/jurisdictions/:jurisdiction_code/encryption-key",
+            get(get_jurisdiction_encryption_key),
+        )
+        .route(
+            "/api/ballot-tracking/:tracking_code",
+            get(get_ballot_tracking_status),
+        )
+        .route(
+            "/api/registration-conflicts",
+            get(list_registration_conflicts),
+        )
+        .route(
+            "/api/voter-sessions/challenges",
+            post(create_voter_session_challenge),
+        )
+        .route("/api/voter-sessions", post(create_voter_session))
+        .route("/api/voter/registrations", get(list_voter_registrations))
+        .route("/api/voter/elections", get(list_voter_elections))
+        .route("/api/voter/cast-ballots", get(list_voter_cast_ballots))
         .route("/api/elections", get(list_elections))
         .route(
             "/api/elections/:election_id/cast-ballots",
@@ -63,38 +240,417 @@ pub async fn setup(pool: PgPool) -> color_eyre::Result<Router> {
             "/api/elections/:election_id/shuffled-ballots",
             get(list_shuffled_ballots_by_election),
         )
+        .route("/bulletin-board", get(bulletin_board_elections))
+        .route(
+            "/bulletin-board/elections/:election_id",
+            get(bulletin_board_election),
+        )
+        .route(
+            "/bulletin-board/elections/:election_id/results",
+            get(bulletin_board_results),
+        )
+        .route(
+            "/bulletin-board/elections/:election_id/ballots/:cast_ballot_id",
+            get(bulletin_board_ballot),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            metrics.clone(),
+            metrics::track_request_duration,
+        ))
         .layer(DefaultBodyLimit::max(MAX_REQUEST_SIZE))
-        .layer(TraceLayer::new_for_http())
-        .with_state(pool))
+        .layer(CompressionLayer::new())
+        .layer(TraceLayer::new_for_http().make_span_with(log::make_request_span))
+        .with_state(AppState {
+            pool,
+            config,
+            metrics,
+        }))
 }
 
 /// Create and run an HTTP server using the provided application at the port
-/// from [`config`][`super::config`].
+/// from [`config`][`super::config`]. On SIGINT or SIGTERM it stops accepting
+/// connections and returns once the requests in progress have been answered.
 pub async fn run(app: Router, config: &Config) -> color_eyre::Result<()> {
     let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), config.port);
     tracing::info!("Server listening at http://{addr}/");
-    axum::Server::bind(&addr)
+    serve(
+        TcpListener::bind(addr)?,
+        app,
+        shutdown_rs::shutdown_signal(),
+    )
+    .await
+}
+
+/// Serves `app` on `listener` until `shutdown` completes, then stops accepting
+/// connections and returns once the requests in progress have been answered.
+pub(crate) async fn serve(
+    listener: TcpListener,
+    app: Router,
+    shutdown: impl Future<Output = ()>,
+) -> color_eyre::Result<()> {
+    axum::Server::from_tcp(listener)?
         .serve(app.into_make_service())
+        .with_graceful_shutdown(shutdown)
         .await?;
     Ok(())
 }
 
 /// Always responds with a successful status. Used to check whether the server
-/// is running.
+/// is running, see [`get_readiness`] for whether it can serve requests.
+#[utoipa::path(
+    get,
+    path = "/api/status",
+    tag = "status",
+    responses(
+        (status = OK, description = "The server is running")
+    )
+)]
 async fn get_status() -> impl IntoResponse {
     StatusCode::OK
 }
 
+/// Checks that the database is reachable and fully migrated, responding with
+/// 503 Service Unavailable if not.
+#[utoipa::path(
+    get,
+    path = "/api/ready",
+    tag = "status",
+    responses(
+        (status = OK, description = "Every check passed", body = ReadinessReport),
+        (status = SERVICE_UNAVAILABLE, description = "A check failed", body = ReadinessReport)
+    )
+)]
+async fn get_readiness(State(pool): State<PgPool>) -> impl IntoResponse {
+    let (database, migrations) = match pool.acquire().await {
+        Ok(mut conn) => (
+            Ok(()),
+            match db::get_applied_migration_version(&mut conn).await {
+                Ok(version) if version == db::latest_migration_version() => Ok(()),
+                Ok(version) => Err(format!(
+                    "Database is at migration {version:?}, expected {:?}",
+                    db::latest_migration_version()
+                )),
+                Err(e) => Err(e.to_string()),
+            },
+        ),
+        Err(e) => (
+            Err(e.to_string()),
+            Err("Database is unavailable".to_owned()),
+        ),
+    };
+
+    let report = ReadinessReport::new(vec![
+        ReadinessCheck::new("database", database),
+        ReadinessCheck::new("migrations", migrations),
+    ]);
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/openapi.json",
+    tag = "status",
+    responses(
+        (status = OK, description = "This OpenAPI document", body = Object)
+    )
+)]
+async fn get_openapi() -> impl IntoResponse {
+    Json(ApiDoc::openapi())
+}
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "status",
+    responses(
+        (status = OK, description = "Metrics in the Prometheus text exposition format", body = String, content_type = "text/plain")
+    )
+)]
+async fn get_metrics(
+    State(AppState { pool, metrics, .. }): State<AppState>,
+) -> Result<impl IntoResponse, Error> {
+    let mut conn = pool.acquire().await?;
+    let journal_entries = db::count_journal_entries(&mut conn).await?;
+    let body = metrics
+        .encode(journal_entries)
+        .map_err(color_eyre::Report::from)?;
+    Ok(([(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)], body))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/objects",
+    tag = "objects",
+    request_body = SignedObject,
+    responses(
+        (status = CREATED, description = "The ID of the new object", body = String, content_type = "text/plain"),
+        (status = BAD_REQUEST, description = "The request is invalid", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip_all, fields(object_id = %object.id))]
 async fn create_object(
-    State(pool): State<PgPool>,
-    object: Json<SignedObject>,
+    State(AppState {
+        pool,
+        config,
+        metrics,
+    }): State<AppState>,
+    Negotiated(object): Negotiated<SignedObject>,
 ) -> Result<impl IntoResponse, Error> {
+    // a malformed signature is an error rather than a failed verification
+    if !matches!(object.verify(), Ok(true)) {
+        metrics.record_signature_verification_failure(SignatureVerificationFailure::Object);
+        return Err(Error::BadRequest(
+            "Unable to verify signature/certificates".to_owned(),
+        ));
+    }
+
+    // legacy objects verify as stored, but new ones must be re-encodable
+    if !object.has_canonical_payload() {
+        return Err(Error::BadRequest(
+            "Payload must be canonically encoded".to_owned(),
+        ));
+    }
+
+    match object.effective_signature_algorithm() {
+        Some(algorithm) if config.allowed_signature_algorithms.contains(&algorithm) => {}
+        Some(algorithm) => {
+            return Err(Error::BadRequest(format!(
+                "Signature algorithm not allowed: {algorithm}"
+            )))
+        }
+        None => {
+            return Err(Error::BadRequest(
+                "Unable to determine signature algorithm".to_owned(),
+            ))
+        }
+    }
+
+    if let Some(cosignature) = object.cosignatures.iter().find(|cosignature| {
+        !config
+            .allowed_signature_algorithms
+            .contains(&cosignature.signature_algorithm)
+    }) {
+        return Err(Error::BadRequest(format!(
+            "Signature algorithm not allowed: {}",
+            cosignature.signature_algorithm
+        )));
+    }
+
+    let payload = object.try_to_inner()?;
+    if payload.is_unknown() {
+        // a known object type can't be checked the way its type requires, e.g.
+        // that a cast ballot is signed by the voter, unless it's interpreted
+        if cacvote::Payload::OBJECT_TYPES.contains(&payload.object_type()) {
+            return Err(Error::BadRequest(format!(
+                "Unsupported payload envelope or schema version for object type: {}",
+                payload.object_type()
+            )));
+        }
+
+        if !config.allow_unknown_object_types {
+            return Err(Error::BadRequest(format!(
+                "Unknown object type: {}",
+                payload.object_type()
+            )));
+        }
+    }
+
+    // the stored election ID is used to look objects up, so must be the one
+    // that was signed
+    if payload.election_id() != object.election_id {
+        return Err(Error::BadRequest(format!(
+            "Election ID {:?} does not match payload election ID {:?}",
+            object.election_id,
+            payload.election_id()
+        )));
+    }
+
+    let signature_policy_status =
+        object.trusted_signature_policy_status(&config.vx_ca_certificates)?;
+    if !signature_policy_status.is_satisfied() {
+        return Err(Error::BadRequest(format!(
+            "Object requires {} eligible signers, but has {}",
+            signature_policy_status.signers_required, signature_policy_status.signers_collected
+        )));
+    }
+
+    // voters sign with their CAC, which must be the one their objects name
+    let voter_id = match cac::verify_voter_object_signer(&object, &config.cac_ca_certificates) {
+        Ok(common_access_card_id) => common_access_card_id
+            .map(|common_access_card_id| {
+                cac::voter_id(config.redaction_key.as_bytes(), &common_access_card_id)
+            })
+            .transpose()?,
+        Err(error) => {
+            metrics.record_signature_verification_failure(
+                SignatureVerificationFailure::VoterCertificate,
+            );
+            return Err(Error::BadRequest(format!(
+                "Object must be signed by the voter's Common Access Card: {error}"
+            )));
+        }
+    };
+
     let mut conn = pool.acquire().await?;
-    let object_id = db::create_object(&mut conn, &object).await?;
+
+    // once a jurisdiction has published an encryption key, voter PII must be
+    // encrypted to it so that the server never sees it
+    if object.try_to_inner()?.has_plaintext_private_fields()? {
+        if let Some(jurisdiction_code) = object.jurisdiction_code() {
+            if get_trusted_jurisdiction_encryption_key(
+                &mut conn,
+                &config.vx_ca_certificates,
+                &jurisdiction_code,
+            )
+            .await?
+            .is_some()
+            {
+                return Err(Error::BadRequest(format!(
+                    "Private fields must be encrypted to the encryption key of {jurisdiction_code}"
+                )));
+            }
+        }
+    }
+
+    for reference in object.try_to_inner()?.blob_references() {
+        if !db::has_blob(&mut conn, reference).await? {
+            return Err(Error::BadRequest(format!(
+                "Blob {} must be uploaded before objects that refer to it",
+                reference.sha256
+            )));
+        }
+    }
+
+    if let Some(superseded_object_id) = object.supersedes()? {
+        check_supersession(&mut conn, &object, superseded_object_id).await?;
+    }
+
+    let object_id = match db::create_object(&mut conn, &object, voter_id.as_deref()).await {
+        Ok(object_id) => object_id,
+        // another version was stored since `check_supersession`
+        Err(e) => match e.downcast::<db::AlreadySuperseded>() {
+            Ok(already_superseded) => {
+                return Err(Error::BadRequest(already_superseded.to_string()))
+            }
+            Err(e) => return Err(e.into()),
+        },
+    };
+    if let Some(jurisdiction_code) = object.jurisdiction_code() {
+        metrics.record_object_created(
+            object.try_to_inner()?.object_type(),
+            jurisdiction_code.as_str(),
+        );
+    }
     Ok((StatusCode::CREATED, object_id.to_string()))
 }
 
-#[derive(Debug, Deserialize)]
+/// Checks that `object` may be posted as a new version of the object with
+/// `superseded_object_id`, see [`cacvote::ObjectVersion`].
+async fn check_supersession(
+    conn: &mut sqlx::PgConnection,
+    object: &SignedObject,
+    superseded_object_id: Uuid,
+) -> Result<(), Error> {
+    let payload = object.try_to_inner()?;
+
+    // voters' objects are tied to their CAC, and a voter replaces a cast
+    // ballot by casting another one
+    if matches!(
+        payload,
+        cacvote::Payload::RegistrationRequest(_) | cacvote::Payload::CastBallot(_)
+    ) {
+        return Err(Error::BadRequest(format!(
+            "{} objects cannot be superseded",
+            payload.object_type()
+        )));
+    }
+
+    let Some(superseded_object) = db::get_object_by_id(conn, superseded_object_id).await? else {
+        return Err(Error::BadRequest(format!(
+            "Superseded object not found: {superseded_object_id}"
+        )));
+    };
+    let superseded_payload = superseded_object.try_to_inner()?;
+
+    if superseded_payload.object_type() != payload.object_type()
+        || superseded_object.jurisdiction_code() != object.jurisdiction_code()
+    {
+        return Err(Error::BadRequest(
+            "An object can only be superseded by one of the same type and jurisdiction".to_owned(),
+        ));
+    }
+
+    // only whoever could post the original may replace it: its signer, or
+    // anyone its type's signature policy trusts, which was checked already
+    if object.signer_certificate()?.fingerprint
+        != superseded_object.signer_certificate()?.fingerprint
+        && !cacvote::SignaturePolicy::for_object_type(payload.object_type()).restricts_signers()
+    {
+        return Err(Error::BadRequest(format!(
+            "Object {superseded_object_id} can only be superseded by its signer"
+        )));
+    }
+
+    if let Some(superseding_object_id) =
+        db::get_superseding_object_id(conn, superseded_object_id).await?
+    {
+        return Err(Error::BadRequest(format!(
+            "Object {superseded_object_id} has already been superseded by {superseding_object_id}"
+        )));
+    }
+
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/objects",
+    tag = "objects",
+    params(ObjectSearchQuery),
+    responses(
+        (status = OK, description = "A page of matching objects", body = ObjectSearchPage),
+        (status = BAD_REQUEST, description = "The request is invalid", body = ErrorResponse),
+        (status = UNAUTHORIZED, description = "The bearer token is missing or unknown", body = ErrorResponse)
+    ),
+    security((), ("officialApiKey" = []))
+)]
+async fn search_objects(
+    State(AppState { pool, config, .. }): State<AppState>,
+    viewer: Viewer,
+    Query(query): Query<ObjectSearchQuery>,
+    accept: Accept,
+) -> Result<Encoded<ObjectSearchPage>, Error> {
+    let limit = query.limit.unwrap_or(ObjectSearchQuery::DEFAULT_LIMIT);
+    if !(1..=ObjectSearchQuery::MAX_LIMIT).contains(&limit) {
+        return Err(Error::BadRequest(format!(
+            "limit must be between 1 and {}",
+            ObjectSearchQuery::MAX_LIMIT
+        )));
+    }
+
+    let mut conn = pool.acquire().await?;
+    let Some(mut page) = db::search_objects(&mut conn, &query, limit).await? else {
+        return Err(Error::BadRequest(
+            "after must be the cursor of a previous page".to_owned(),
+        ));
+    };
+
+    for result in &mut page.objects {
+        if let Some(ObjectView::Full(object)) = result.object.take() {
+            result.object = Some(viewer.view(object, &config.redaction_key)?);
+        }
+    }
+
+    Ok(accept.respond(page))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct GetJournalEntriesQuery {
     #[serde(rename = "since")]
     since_journal_entry_id: Option<Uuid>,
@@ -103,10 +659,20 @@ struct GetJournalEntriesQuery {
     jurisdiction_code: Option<JurisdictionCode>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/journal-entries",
+    tag = "objects",
+    params(GetJournalEntriesQuery),
+    responses(
+        (status = OK, description = "Journal entries, oldest first", body = Vec<JournalEntry>)
+    )
+)]
 async fn get_journal_entries(
     State(pool): State<PgPool>,
     Query(query): Query<GetJournalEntriesQuery>,
-) -> Result<Json<Vec<JournalEntry>>, Error> {
+    accept: Accept,
+) -> Result<Encoded<Vec<JournalEntry>>, Error> {
     let mut conn = pool.acquire().await?;
 
     Ok(db::get_journal_entries(
@@ -115,24 +681,368 @@ async fn get_journal_entries(
         query.jurisdiction_code,
     )
     .await
-    .map(Json)?)
+    .map(|entries| accept.respond(entries))?)
 }
 
-async fn get_object_by_id(
+/// Gets the tree head of the snapshot of a jurisdiction's objects as of a
+/// journal entry, by default the jurisdiction's latest, signed if the server
+/// has a snapshot signing key.
+#[utoipa::path(
+    get,
+    path = "/api/snapshot",
+    tag = "snapshots",
+    params(SnapshotQuery),
+    responses(
+        (status = OK, description = "The snapshot's tree head", body = SnapshotTreeHead),
+        (status = NOT_FOUND, description = "Not found", body = ErrorResponse)
+    )
+)]
+async fn get_snapshot_tree_head(
+    State(AppState { pool, config, .. }): State<AppState>,
+    Query(query): Query<SnapshotQuery>,
+    accept: Accept,
+) -> Result<Encoded<SnapshotTreeHead>, Error> {
+    let mut conn = pool.acquire().await?;
+
+    let cursor = match query.cursor {
+        Some(cursor) => cursor,
+        None => db::get_latest_journal_entry_id(&mut conn, &query.jurisdiction_code)
+            .await?
+            .ok_or(Error::NotFound)?,
+    };
+
+    let Some(leaves) = db::get_snapshot_leaves(&mut conn, &query.jurisdiction_code, cursor).await?
+    else {
+        return Err(Error::NotFound);
+    };
+
+    let mut tree_head = SnapshotTreeHead::new(query.jurisdiction_code, cursor, &leaves);
+    if let Some(signing_key) = &config.snapshot_signing_key {
+        tree_head.sign(signing_key)?;
+    }
+
+    Ok(accept.respond(tree_head))
+}
+
+/// Gets a page of the entries of the snapshot of a jurisdiction's objects as
+/// of a journal entry. Only the jurisdiction's officials may get them, since
+/// they hold the objects unredacted.
+#[utoipa::path(
+    get,
+    path = "/api/snapshot/entries",
+    tag = "snapshots",
+    params(SnapshotQuery),
+    responses(
+        (status = OK, description = "A page of the snapshot's entries", body = SnapshotPage),
+        (status = BAD_REQUEST, description = "The request is invalid", body = ErrorResponse),
+        (status = UNAUTHORIZED, description = "The bearer token is missing or unknown", body = ErrorResponse),
+        (status = NOT_FOUND, description = "Not found", body = ErrorResponse)
+    ),
+    security(("officialApiKey" = []))
+)]
+async fn get_snapshot_entries(
     State(pool): State<PgPool>,
+    viewer: Viewer,
+    Query(query): Query<SnapshotQuery>,
+    accept: Accept,
+) -> Result<Encoded<SnapshotPage>, Error> {
+    if viewer != Viewer::Official(query.jurisdiction_code.clone()) {
+        return Err(Error::Unauthorized);
+    }
+
+    let Some(cursor) = query.cursor else {
+        return Err(Error::BadRequest("cursor is required".to_owned()));
+    };
+
+    let limit = query.limit.unwrap_or(SnapshotQuery::DEFAULT_LIMIT);
+    if !(1..=SnapshotQuery::MAX_LIMIT).contains(&limit) {
+        return Err(Error::BadRequest(format!(
+            "limit must be between 1 and {}",
+            SnapshotQuery::MAX_LIMIT
+        )));
+    }
+
+    let mut conn = pool.acquire().await?;
+    match db::get_snapshot_entries(
+        &mut conn,
+        &query.jurisdiction_code,
+        cursor,
+        query.offset.unwrap_or_default(),
+        limit,
+    )
+    .await?
+    {
+        Some(page) => Ok(accept.respond(page)),
+        None => Err(Error::NotFound),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/objects/{object_id}",
+    tag = "objects",
+    params(("object_id" = Uuid, Path, description = "ID of the object")),
+    responses(
+        (status = OK, description = "The object, redacted unless the reader may see it in full", body = ObjectView),
+        (status = UNAUTHORIZED, description = "The bearer token is missing or unknown", body = ErrorResponse),
+        (status = NOT_FOUND, description = "Not found", body = ErrorResponse)
+    ),
+    security((), ("officialApiKey" = []))
+)]
+async fn get_object_by_id(
+    State(AppState { pool, config, .. }): State<AppState>,
+    viewer: Viewer,
     Path(object_id): Path<Uuid>,
-) -> Result<Json<SignedObject>, Error> {
+    accept: Accept,
+) -> Result<Encoded<ObjectView>, Error> {
     let mut conn = pool.acquire().await?;
 
     match db::get_object_by_id(&mut conn, object_id).await? {
-        Some(object) => {
-            tracing::info!("PAYLOAD: {}", std::str::from_utf8(&object.payload).unwrap());
-            Ok(Json(object))
+        Some(object) => Ok(accept.respond(viewer.view(object, &config.redaction_key)?)),
+        None => Err(Error::NotFound),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/objects/{object_id}/versions",
+    tag = "objects",
+    params(("object_id" = Uuid, Path, description = "ID of any version of the object")),
+    responses(
+        (status = OK, description = "Every version of the object, oldest first", body = Vec<ObjectVersion>),
+        (status = NOT_FOUND, description = "Not found", body = ErrorResponse)
+    )
+)]
+async fn get_object_versions(
+    State(pool): State<PgPool>,
+    Path(object_id): Path<Uuid>,
+    accept: Accept,
+) -> Result<Encoded<Vec<ObjectVersion>>, Error> {
+    let mut conn = pool.acquire().await?;
+
+    let versions = db::get_object_versions(&mut conn, object_id).await?;
+    if versions.is_empty() {
+        return Err(Error::NotFound);
+    }
+
+    Ok(accept.respond(versions))
+}
+
+/// Starts a chunked upload of a blob, or returns the upload the official's
+/// jurisdiction already started for it so that it can be resumed (see
+/// [`cacvote::BlobUpload`]). Only officials may upload blobs.
+#[utoipa::path(
+    post,
+    path = "/api/blobs/uploads",
+    tag = "blobs",
+    request_body = BlobReference,
+    responses(
+        (status = OK, description = "The new or resumable upload, which is already complete if the server has the blob", body = BlobUpload),
+        (status = BAD_REQUEST, description = "The request is invalid", body = ErrorResponse),
+        (status = UNAUTHORIZED, description = "The bearer token is missing or unknown", body = ErrorResponse)
+    ),
+    security(("officialApiKey" = []))
+)]
+async fn start_blob_upload(
+    State(pool): State<PgPool>,
+    viewer: Viewer,
+    accept: Accept,
+    Negotiated(reference): Negotiated<BlobReference>,
+) -> Result<Encoded<BlobUpload>, Error> {
+    let Viewer::Official(jurisdiction_code) = viewer else {
+        return Err(Error::Unauthorized);
+    };
+
+    if !BlobReference::is_valid_sha256(&reference.sha256) {
+        return Err(Error::BadRequest(
+            "sha256 must be 64 lowercase hex digits".to_owned(),
+        ));
+    }
+
+    if reference.size > MAX_BLOB_SIZE {
+        return Err(Error::BadRequest(format!(
+            "Blobs may be at most {MAX_BLOB_SIZE} bytes"
+        )));
+    }
+
+    let mut conn = pool.acquire().await?;
+    let upload = db::start_blob_upload(&mut conn, &jurisdiction_code, &reference).await?;
+
+    Ok(accept.respond(upload))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/blobs/uploads/{upload_id}",
+    tag = "blobs",
+    params(("upload_id" = Uuid, Path, description = "ID of the upload")),
+    responses(
+        (status = OK, description = "The upload's progress", body = BlobUpload),
+        (status = UNAUTHORIZED, description = "The bearer token is missing or unknown", body = ErrorResponse),
+        (status = NOT_FOUND, description = "Not found, or started by another jurisdiction", body = ErrorResponse)
+    ),
+    security(("officialApiKey" = []))
+)]
+async fn get_blob_upload(
+    State(pool): State<PgPool>,
+    viewer: Viewer,
+    Path(upload_id): Path<Uuid>,
+    accept: Accept,
+) -> Result<Encoded<BlobUpload>, Error> {
+    let Viewer::Official(jurisdiction_code) = viewer else {
+        return Err(Error::Unauthorized);
+    };
+
+    let mut conn = pool.acquire().await?;
+
+    match db::get_blob_upload(&mut conn, &jurisdiction_code, upload_id).await? {
+        Some(upload) => Ok(accept.respond(upload)),
+        None => Err(Error::NotFound),
+    }
+}
+
+/// Appends a chunk to a blob upload. The chunk must start at the upload's
+/// offset, given in the `Upload-Offset` header. Otherwise it is rejected with
+/// the upload's progress so that the client can resume from there.
+#[utoipa::path(
+    patch,
+    path = "/api/blobs/uploads/{upload_id}",
+    tag = "blobs",
+    params(("upload_id" = Uuid, Path, description = "ID of the upload"),
+        ("Upload-Offset" = u64, Header, description = "Where the chunk starts in the blob")),
+    request_body(content = Binary, content_type = "application/octet-stream"),
+    responses(
+        (status = OK, description = "The chunk was appended", body = BlobUpload),
+        (status = CONFLICT, description = "The chunk does not start at the upload's offset", body = BlobUpload),
+        (status = BAD_REQUEST, description = "The request is invalid", body = ErrorResponse),
+        (status = UNAUTHORIZED, description = "The bearer token is missing or unknown", body = ErrorResponse),
+        (status = NOT_FOUND, description = "Not found, or started by another jurisdiction", body = ErrorResponse)
+    ),
+    security(("officialApiKey" = []))
+)]
+async fn append_blob_upload_chunk(
+    State(pool): State<PgPool>,
+    viewer: Viewer,
+    Path(upload_id): Path<Uuid>,
+    accept: Accept,
+    headers: HeaderMap,
+    chunk: Bytes,
+) -> Result<impl IntoResponse, Error> {
+    let Viewer::Official(jurisdiction_code) = viewer else {
+        return Err(Error::Unauthorized);
+    };
+
+    let Some(offset) = headers
+        .get(BlobUpload::OFFSET_HEADER)
+        .and_then(|offset| offset.to_str().ok())
+        .and_then(|offset| offset.parse::<u64>().ok())
+    else {
+        return Err(Error::BadRequest(format!(
+            "{} header is required",
+            BlobUpload::OFFSET_HEADER
+        )));
+    };
+
+    let mut conn = pool.acquire().await?;
+
+    match db::append_blob_upload_chunk(&mut conn, &jurisdiction_code, upload_id, offset, &chunk)
+        .await?
+    {
+        Some(db::BlobUploadChunkOutcome::Appended(upload)) => {
+            Ok((StatusCode::OK, accept.respond(upload)))
+        }
+        Some(db::BlobUploadChunkOutcome::Rejected(upload)) => {
+            Ok((StatusCode::CONFLICT, accept.respond(upload)))
         }
+        Some(db::BlobUploadChunkOutcome::DigestMismatch) => Err(Error::BadRequest(
+            "Uploaded data does not match the blob's digest".to_owned(),
+        )),
         None => Err(Error::NotFound),
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/blobs/{sha256}",
+    tag = "blobs",
+    params(("sha256" = String, Path, description = "Hex-encoded SHA-256 digest of the blob")),
+    responses(
+        (status = OK, description = "The blob", body = Binary, content_type = "application/octet-stream"),
+        (status = NOT_FOUND, description = "Not found", body = ErrorResponse)
+    )
+)]
+async fn get_blob(
+    State(pool): State<PgPool>,
+    Path(sha256): Path<String>,
+) -> Result<impl IntoResponse, Error> {
+    let mut conn = pool.acquire().await?;
+
+    match db::get_blob(&mut conn, &sha256.to_ascii_lowercase()).await? {
+        Some(data) => Ok(([(header::CONTENT_TYPE, "application/octet-stream")], data)),
+        None => Err(Error::NotFound),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/certificates/{fingerprint}",
+    tag = "certificates",
+    params(("fingerprint" = String, Path, description = "Hex-encoded SHA-256 digest of the certificate")),
+    responses(
+        (status = OK, description = "The certificate's attributes. The common name, which identifies voters by their CAC ID, is only included for officials", body = SignerCertificate),
+        (status = NOT_FOUND, description = "Not found", body = ErrorResponse)
+    ),
+    security((), ("officialApiKey" = []))
+)]
+async fn get_signer_certificate(
+    State(pool): State<PgPool>,
+    Path(fingerprint): Path<String>,
+    viewer: Viewer,
+    accept: Accept,
+) -> Result<Encoded<SignerCertificate>, Error> {
+    let mut conn = pool.acquire().await?;
+
+    match db::get_signer_certificate(&mut conn, &fingerprint.to_ascii_lowercase()).await? {
+        Some(mut signer_certificate) => {
+            if viewer == Viewer::Public {
+                signer_certificate.common_name = None;
+            }
+            Ok(accept.respond(signer_certificate))
+        }
+        None => Err(Error::NotFound),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/certificates/{fingerprint}/objects",
+    tag = "certificates",
+    params(("fingerprint" = String, Path, description = "Hex-encoded SHA-256 digest of the certificate")),
+    responses(
+        (status = OK, description = "IDs of the objects the certificate signed or cosigned", body = Vec<Uuid>)
+    )
+)]
+async fn list_object_ids_by_signer(
+    State(pool): State<PgPool>,
+    Path(fingerprint): Path<String>,
+    accept: Accept,
+) -> Result<Encoded<Vec<Uuid>>, Error> {
+    let mut conn = pool.acquire().await?;
+
+    Ok(accept
+        .respond(db::get_object_ids_by_signer(&mut conn, &fingerprint.to_ascii_lowercase()).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/scanned-mailing-label-code",
+    tag = "elections",
+    request_body(content = Binary, description = "A TLV-encoded signed ballot verification payload", content_type = "application/octet-stream"),
+    responses(
+        (status = CREATED, description = "The scanned code was recorded", body = CreatedResponse),
+        (status = BAD_REQUEST, description = "The request is invalid", body = ErrorResponse)
+    )
+)]
 async fn scanned_create_mailing_label_code(
     State(pool): State<PgPool>,
     scanned_mailing_label_code: Bytes,
@@ -144,12 +1054,377 @@ async fn scanned_create_mailing_label_code(
     Ok((StatusCode::CREATED, Json(json!({ "id": id }))))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/jurisdictions/{jurisdiction_code}/encryption-key",
+    tag = "objects",
+    params(("jurisdiction_code" = String, Path, description = "The jurisdiction")),
+    responses(
+        (status = OK, description = "The jurisdiction's latest encryption key object", body = SignedObject),
+        (status = NOT_FOUND, description = "Not found", body = ErrorResponse)
+    )
+)]
+async fn get_jurisdiction_encryption_key(
+    State(AppState { pool, config, .. }): State<AppState>,
+    Path(jurisdiction_code): Path<JurisdictionCode>,
+    accept: Accept,
+) -> Result<Encoded<SignedObject>, Error> {
+    let mut conn = pool.acquire().await?;
+
+    match get_trusted_jurisdiction_encryption_key(
+        &mut conn,
+        &config.vx_ca_certificates,
+        &jurisdiction_code,
+    )
+    .await?
+    {
+        Some(object) => Ok(accept.respond(object)),
+        None => Err(Error::NotFound),
+    }
+}
+
+/// Gets the latest encryption key object of a jurisdiction whose signers chain
+/// to `vx_ca_certificates`. Whoever chooses the key can read the voter data
+/// encrypted to it, so keys stored before the CAs were configured, or signed
+/// under CAs that are no longer trusted, are skipped.
+async fn get_trusted_jurisdiction_encryption_key(
+    conn: &mut sqlx::PgConnection,
+    vx_ca_certificates: &[X509],
+    jurisdiction_code: &JurisdictionCode,
+) -> color_eyre::Result<Option<SignedObject>> {
+    for object in db::get_objects_by_jurisdiction_and_type(
+        conn,
+        jurisdiction_code,
+        cacvote::Payload::jurisdiction_encryption_key_object_type(),
+    )
+    .await?
+    {
+        if object
+            .trusted_signature_policy_status(vx_ca_certificates)?
+            .is_satisfied()
+        {
+            return Ok(Some(object));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Lists the registration conflicts involving the requesting official's
+/// jurisdiction. Only officials may list them.
+#[utoipa::path(
+    get,
+    path = "/api/registration-conflicts",
+    tag = "voters",
+    responses(
+        (status = OK, description = "Conflicts involving the official's jurisdiction", body = Vec<RegistrationConflict>),
+        (status = UNAUTHORIZED, description = "The bearer token is missing or unknown", body = ErrorResponse)
+    ),
+    security(("officialApiKey" = []))
+)]
+async fn list_registration_conflicts(
+    State(AppState { pool, config, .. }): State<AppState>,
+    viewer: Viewer,
+    accept: Accept,
+) -> Result<Encoded<Vec<RegistrationConflict>>, Error> {
+    let Viewer::Official(jurisdiction_code) = viewer else {
+        return Err(Error::Unauthorized);
+    };
+
+    let mut conn = pool.acquire().await?;
+    let conflicts = registration_conflicts::load_registration_conflicts(
+        &mut conn,
+        &jurisdiction_code,
+        config.redaction_key.as_bytes(),
+    )
+    .await?;
+
+    Ok(accept.respond(conflicts))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/ballot-tracking/{tracking_code}",
+    tag = "elections",
+    params(("tracking_code" = String, Path, description = "The ballot's hex-encoded tracking code")),
+    responses(
+        (status = OK, description = "The ballot's status history", body = BallotTrackingStatus),
+        (status = BAD_REQUEST, description = "The request is invalid", body = ErrorResponse),
+        (status = NOT_FOUND, description = "Not found", body = ErrorResponse)
+    )
+)]
+async fn get_ballot_tracking_status(
+    State(pool): State<PgPool>,
+    Path(tracking_code): Path<String>,
+    accept: Accept,
+) -> Result<Encoded<BallotTrackingStatus>, Error> {
+    let Some(tracking_code) = cacvote::decode_tracking_code(&tracking_code) else {
+        return Err(Error::BadRequest("Invalid tracking code".to_owned()));
+    };
+
+    let mut conn = pool.acquire().await?;
+
+    match bulletin_board::load_ballot_tracking_status(&mut conn, &tracking_code).await? {
+        Some(status) => Ok(accept.respond(status)),
+        None => Err(Error::NotFound),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/voter-sessions/challenges",
+    tag = "voters",
+    responses(
+        (status = CREATED, description = "A challenge for the voter's CAC to sign", body = VoterSessionChallenge)
+    )
+)]
+async fn create_voter_session_challenge(
+    State(pool): State<PgPool>,
+    accept: Accept,
+) -> Result<impl IntoResponse, Error> {
+    let mut nonce = vec![0; VOTER_SESSION_CHALLENGE_NONCE_LENGTH];
+    openssl::rand::rand_bytes(&mut nonce).map_err(color_eyre::Report::from)?;
+    let expires_at = OffsetDateTime::now_utc() + VOTER_SESSION_CHALLENGE_TTL;
+
+    let mut conn = pool.acquire().await?;
+    db::create_voter_session_challenge(&mut conn, &nonce, expires_at).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        accept.respond(VoterSessionChallenge { nonce, expires_at }),
+    ))
+}
+
+/// Starts a voter session for the holder of a CAC that signed a challenge
+/// from [`create_voter_session_challenge`].
+#[utoipa::path(
+    post,
+    path = "/api/voter-sessions",
+    tag = "voters",
+    request_body = CreateVoterSessionRequest,
+    responses(
+        (status = CREATED, description = "A new voter session", body = VoterSession),
+        (status = BAD_REQUEST, description = "The request is invalid", body = ErrorResponse),
+        (status = UNAUTHORIZED, description = "The bearer token is missing or unknown", body = ErrorResponse)
+    )
+)]
+async fn create_voter_session(
+    State(AppState {
+        pool,
+        config,
+        metrics,
+    }): State<AppState>,
+    accept: Accept,
+    Negotiated(request): Negotiated<CreateVoterSessionRequest>,
+) -> Result<impl IntoResponse, Error> {
+    let mut conn = pool.acquire().await?;
+
+    if !db::consume_voter_session_challenge(&mut conn, &request.nonce).await? {
+        return Err(Error::Unauthorized);
+    }
+
+    let certificate = cac::verify_common_access_card_certificate(
+        &request.certificates,
+        &config.cac_ca_certificates,
+    )
+    .map_err(|e| {
+        tracing::warn!("rejecting voter session certificate: {e}");
+        metrics.record_signature_verification_failure(SignatureVerificationFailure::VoterSession);
+        Error::Unauthorized
+    })?;
+    let public_key = certificate.public_key().map_err(color_eyre::Report::from)?;
+
+    let Some(signature_algorithm) = request
+        .signature_algorithm
+        .or_else(|| SignatureAlgorithm::infer_legacy(&public_key))
+    else {
+        return Err(Error::BadRequest(
+            "Unable to determine the signature algorithm".to_owned(),
+        ));
+    };
+    if !config
+        .allowed_signature_algorithms
+        .contains(&signature_algorithm)
+    {
+        return Err(Error::BadRequest(format!(
+            "Signature algorithm {signature_algorithm} is not allowed"
+        )));
+    }
+    if !signature_algorithm
+        .verify(
+            &public_key,
+            &VoterSessionChallenge::signing_message(&request.nonce),
+            &request.signature,
+        )
+        .map_err(color_eyre::Report::from)?
+    {
+        metrics.record_signature_verification_failure(SignatureVerificationFailure::VoterSession);
+        return Err(Error::Unauthorized);
+    }
+
+    let Some(holder) = cac::CommonAccessCardHolder::from_certificate(&certificate) else {
+        return Err(Error::Unauthorized);
+    };
+    let voter_id = cac::voter_id(
+        config.redaction_key.as_bytes(),
+        &holder.common_access_card_id,
+    )?;
+    let mut token = [0; VOTER_SESSION_TOKEN_LENGTH];
+    openssl::rand::rand_bytes(&mut token).map_err(color_eyre::Report::from)?;
+    let token = URL_SAFE_NO_PAD.encode(token);
+    let expires_at = OffsetDateTime::now_utc() + VOTER_SESSION_TTL;
+    db::create_voter_session(&mut conn, &sha256(token.as_bytes()), &voter_id, expires_at).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        accept.respond(VoterSession { token, expires_at }),
+    ))
+}
+
+/// Lists the voter's registration requests, each with its registration if it
+/// has been approved.
+#[utoipa::path(
+    get,
+    path = "/api/voter/registrations",
+    tag = "voters",
+    responses(
+        (status = OK, description = "The voter's registration requests", body = Vec<VoterRegistration>),
+        (status = UNAUTHORIZED, description = "The bearer token is missing or unknown", body = ErrorResponse)
+    ),
+    security(("voterSession" = []))
+)]
+async fn list_voter_registrations(
+    State(pool): State<PgPool>,
+    voter: Voter,
+    accept: Accept,
+) -> Result<Encoded<Vec<VoterRegistration>>, Error> {
+    let mut conn = pool.acquire().await?;
+    Ok(accept.respond(load_voter_registrations(&mut conn, &voter).await?))
+}
+
+/// Lists the elections the voter is registered for.
+#[utoipa::path(
+    get,
+    path = "/api/voter/elections",
+    tag = "voters",
+    responses(
+        (status = OK, description = "The elections the voter is registered for", body = Vec<SignedObject>),
+        (status = UNAUTHORIZED, description = "The bearer token is missing or unknown", body = ErrorResponse)
+    ),
+    security(("voterSession" = []))
+)]
+async fn list_voter_elections(
+    State(pool): State<PgPool>,
+    voter: Voter,
+    accept: Accept,
+) -> Result<Encoded<Vec<SignedObject>>, Error> {
+    let mut conn = pool.acquire().await?;
+
+    let mut election_ids = vec![];
+    for voter_registration in load_voter_registrations(&mut conn, &voter).await? {
+        let election_id = voter_registration
+            .registration
+            .and_then(|registration| registration.election_id);
+        if let Some(election_id) = election_id {
+            if !election_ids.contains(&election_id) {
+                election_ids.push(election_id);
+            }
+        }
+    }
+
+    let mut elections = vec![];
+    for election_id in election_ids {
+        if let Some(election) = db::get_latest_object_version(&mut conn, election_id).await? {
+            elections.push(election);
+        }
+    }
+
+    Ok(accept.respond(elections))
+}
+
+/// Lists the ballots the voter has cast.
+#[utoipa::path(
+    get,
+    path = "/api/voter/cast-ballots",
+    tag = "voters",
+    responses(
+        (status = OK, description = "The ballots the voter has cast", body = Vec<SignedObject>),
+        (status = UNAUTHORIZED, description = "The bearer token is missing or unknown", body = ErrorResponse)
+    ),
+    security(("voterSession" = []))
+)]
+async fn list_voter_cast_ballots(
+    State(pool): State<PgPool>,
+    voter: Voter,
+    accept: Accept,
+) -> Result<Encoded<Vec<SignedObject>>, Error> {
+    let mut conn = pool.acquire().await?;
+
+    Ok(accept.respond(
+        db::get_objects_by_voter_and_type(
+            &mut conn,
+            &voter.voter_id,
+            cacvote::Payload::cast_ballot_object_type(),
+        )
+        .await?,
+    ))
+}
+
+async fn load_voter_registrations(
+    conn: &mut sqlx::PgConnection,
+    voter: &Voter,
+) -> Result<Vec<VoterRegistration>, Error> {
+    let registration_requests = db::get_objects_by_voter_and_type(
+        conn,
+        &voter.voter_id,
+        cacvote::Payload::registration_request_object_type(),
+    )
+    .await?;
+    let registration_request_ids = registration_requests
+        .iter()
+        .map(|registration_request| registration_request.id)
+        .collect::<Vec<_>>();
+
+    let mut registrations = vec![];
+    for registration in
+        db::get_registrations_by_registration_request_ids(conn, &registration_request_ids).await?
+    {
+        if let cacvote::Payload::Registration(payload) = registration.try_to_inner()? {
+            registrations.push((payload.registration_request_object_id, registration));
+        }
+    }
+
+    Ok(registration_requests
+        .into_iter()
+        .map(|registration_request| {
+            // the first registration approving a request is the one that counts
+            let registration = registrations
+                .iter()
+                .position(|(id, _)| *id == registration_request.id)
+                .map(|i| registrations.remove(i).1);
+            VoterRegistration {
+                registration_request,
+                registration,
+            }
+        })
+        .collect())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/elections",
+    tag = "elections",
+    responses(
+        (status = OK, description = "Every election", body = Vec<bulletin_board::Election>)
+    )
+)]
 async fn list_elections(
     State(pool): State<PgPool>,
-) -> Result<Json<Vec<bulletin_board::Election>>, Error> {
+    accept: Accept,
+) -> Result<Encoded<Vec<bulletin_board::Election>>, Error> {
     let mut conn = pool.acquire().await?;
 
-    Ok(Json(
+    Ok(accept.respond(
         db::get_election_ids(&mut conn)
             .await?
             .into_iter()
@@ -158,31 +1433,62 @@ async fn list_elections(
     ))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/elections/{election_id}/cast-ballots",
+    tag = "elections",
+    params(("election_id" = Uuid, Path, description = "ID of the election object")),
+    responses(
+        (status = OK, description = "The ballots cast in the election", body = Vec<bulletin_board::CastBallot>)
+    )
+)]
 async fn list_cast_ballots_by_election(
     State(pool): State<PgPool>,
     Path(election_id): Path<Uuid>,
-) -> Result<Json<Vec<bulletin_board::CastBallot>>, Error> {
+    accept: Accept,
+) -> Result<Encoded<Vec<bulletin_board::CastBallot>>, Error> {
     let mut conn = pool.acquire().await?;
 
-    Ok(Json(
-        db::get_cast_ballot_ids_by_election(&mut conn, election_id)
+    Ok(accept.respond(
+        db::get_cast_ballot_supersessions_by_election(&mut conn, election_id)
             .await?
             .into_iter()
-            .map(|id| bulletin_board::CastBallot::new(id, election_id))
+            .map(|supersession| {
+                bulletin_board::CastBallot::new(
+                    supersession.cast_ballot_id,
+                    election_id,
+                    supersession.superseded_by_id,
+                )
+            })
             .collect(),
     ))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/elections/{election_id}/cast-ballots/{cast_ballot_id}",
+    tag = "elections",
+    params(("election_id" = Uuid, Path, description = "ID of the election object"),
+        ("cast_ballot_id" = Uuid, Path, description = "ID of the cast ballot object")),
+    responses(
+        (status = OK, description = "The cast ballot, redacted unless the reader may see it in full", body = ObjectView),
+        (status = UNAUTHORIZED, description = "The bearer token is missing or unknown", body = ErrorResponse),
+        (status = NOT_FOUND, description = "Not found", body = ErrorResponse)
+    ),
+    security((), ("officialApiKey" = []))
+)]
 async fn get_cast_ballot_by_id(
-    State(pool): State<PgPool>,
+    State(AppState { pool, config, .. }): State<AppState>,
+    viewer: Viewer,
     Path((election_id, cast_ballot_id)): Path<(Uuid, Uuid)>,
-) -> Result<Json<SignedObject>, Error> {
+    accept: Accept,
+) -> Result<Encoded<ObjectView>, Error> {
     let mut conn = pool.acquire().await?;
 
     match db::get_object_by_id(&mut conn, cast_ballot_id).await? {
         Some(cast_ballot) => match cast_ballot.try_to_inner()? {
             cacvote::Payload::CastBallot(payload) if payload.election_object_id == election_id => {
-                Ok(Json(cast_ballot))
+                Ok(accept.respond(viewer.view(cast_ballot, &config.redaction_key)?))
             }
             _ => Err(Error::NotFound),
         },
@@ -190,10 +1496,21 @@ async fn get_cast_ballot_by_id(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/elections/{election_id}/encrypted-tally",
+    tag = "elections",
+    params(("election_id" = Uuid, Path, description = "ID of the election object")),
+    responses(
+        (status = OK, description = "The election's encrypted tally object", body = SignedObject),
+        (status = NOT_FOUND, description = "Not found", body = ErrorResponse)
+    )
+)]
 async fn get_encrypted_tally_by_election(
     State(pool): State<PgPool>,
     Path(election_id): Path<Uuid>,
-) -> Result<Json<SignedObject>, Error> {
+    accept: Accept,
+) -> Result<Encoded<SignedObject>, Error> {
     let mut conn = pool.acquire().await?;
 
     match db::get_object_by_election_id_and_type(
@@ -203,15 +1520,26 @@ async fn get_encrypted_tally_by_election(
     )
     .await?
     {
-        Some(object) => Ok(Json(object)),
+        Some(object) => Ok(accept.respond(object)),
         None => Err(Error::NotFound),
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/elections/{election_id}/decrypted-tally",
+    tag = "elections",
+    params(("election_id" = Uuid, Path, description = "ID of the election object")),
+    responses(
+        (status = OK, description = "The election's decrypted tally object", body = SignedObject),
+        (status = NOT_FOUND, description = "Not found", body = ErrorResponse)
+    )
+)]
 async fn get_decrypted_tally_by_election(
     State(pool): State<PgPool>,
     Path(election_id): Path<Uuid>,
-) -> Result<Json<SignedObject>, Error> {
+    accept: Accept,
+) -> Result<Encoded<SignedObject>, Error> {
     let mut conn = pool.acquire().await?;
 
     match db::get_object_by_election_id_and_type(
@@ -221,15 +1549,26 @@ async fn get_decrypted_tally_by_election(
     )
     .await?
     {
-        Some(object) => Ok(Json(object)),
+        Some(object) => Ok(accept.respond(object)),
         None => Err(Error::NotFound),
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/elections/{election_id}/shuffled-ballots",
+    tag = "elections",
+    params(("election_id" = Uuid, Path, description = "ID of the election object")),
+    responses(
+        (status = OK, description = "The election's shuffled ballots object", body = SignedObject),
+        (status = NOT_FOUND, description = "Not found", body = ErrorResponse)
+    )
+)]
 async fn list_shuffled_ballots_by_election(
     State(pool): State<PgPool>,
     Path(election_id): Path<Uuid>,
-) -> Result<Json<SignedObject>, Error> {
+    accept: Accept,
+) -> Result<Encoded<SignedObject>, Error> {
     let mut conn = pool.acquire().await?;
 
     match db::get_object_by_election_id_and_type(
@@ -239,11 +1578,92 @@ async fn list_shuffled_ballots_by_election(
     )
     .await?
     {
-        Some(object) => Ok(Json(object)),
+        Some(object) => Ok(accept.respond(object)),
         None => Err(Error::NotFound),
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/bulletin-board",
+    tag = "bulletin board",
+    responses(
+        (status = OK, description = "The bulletin board's list of elections", body = String, content_type = "text/html")
+    )
+)]
+async fn bulletin_board_elections(State(pool): State<PgPool>) -> Result<Html<String>, HtmlError> {
+    let mut conn = pool.acquire().await?;
+    let page = bulletin_board::html::ElectionsPage::load(&mut conn).await?;
+    Ok(Html(page.render()?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/bulletin-board/elections/{election_id}",
+    tag = "bulletin board",
+    params(("election_id" = Uuid, Path, description = "ID of the election object")),
+    responses(
+        (status = OK, description = "The election's bulletin board page", body = String, content_type = "text/html"),
+        (status = NOT_FOUND, description = "Not found", body = String, content_type = "text/html")
+    )
+)]
+async fn bulletin_board_election(
+    State(pool): State<PgPool>,
+    Path(election_id): Path<Uuid>,
+) -> Result<Html<String>, HtmlError> {
+    let mut conn = pool.acquire().await?;
+
+    match bulletin_board::html::ElectionPage::load(&mut conn, election_id).await? {
+        Some(page) => Ok(Html(page.render()?)),
+        None => Err(Error::NotFound.into()),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/bulletin-board/elections/{election_id}/results",
+    tag = "bulletin board",
+    params(("election_id" = Uuid, Path, description = "ID of the election object")),
+    responses(
+        (status = OK, description = "The election's results page", body = String, content_type = "text/html"),
+        (status = NOT_FOUND, description = "Not found", body = String, content_type = "text/html")
+    )
+)]
+async fn bulletin_board_results(
+    State(pool): State<PgPool>,
+    Path(election_id): Path<Uuid>,
+) -> Result<Html<String>, HtmlError> {
+    let mut conn = pool.acquire().await?;
+
+    match bulletin_board::html::ResultsPage::load(&mut conn, election_id).await? {
+        Some(page) => Ok(Html(page.render()?)),
+        None => Err(Error::NotFound.into()),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/bulletin-board/elections/{election_id}/ballots/{cast_ballot_id}",
+    tag = "bulletin board",
+    params(("election_id" = Uuid, Path, description = "ID of the election object"),
+        ("cast_ballot_id" = Uuid, Path, description = "ID of the cast ballot object")),
+    responses(
+        (status = OK, description = "The ballot's bulletin board page", body = String, content_type = "text/html"),
+        (status = NOT_FOUND, description = "Not found", body = String, content_type = "text/html")
+    )
+)]
+async fn bulletin_board_ballot(
+    State(pool): State<PgPool>,
+    Path((election_id, cast_ballot_id)): Path<(Uuid, Uuid)>,
+) -> Result<Html<String>, HtmlError> {
+    let mut conn = pool.acquire().await?;
+
+    match bulletin_board::html::BallotPage::load(&mut conn, election_id, cast_ballot_id).await? {
+        Some(page) => Ok(Html(page.render()?)),
+        None => Err(Error::NotFound.into()),
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 enum Error {
     #[error("Database error: {0}")]
@@ -255,31 +1675,137 @@ enum Error {
     #[error("Not found")]
     NotFound,
 
+    #[error("Unauthorized")]
+    Unauthorized,
+
+    #[error("{0}")]
+    BadRequest(String),
+
+    #[error("Template error: {0}")]
+    Template(#[from] askama::Error),
+
     #[error("{0}")]
     Other(#[from] color_eyre::Report),
 }
 
+impl Error {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Error::Database(_) | Error::Template(_) | Error::Other(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            Error::Serde(_) | Error::BadRequest(_) => StatusCode::BAD_REQUEST,
+            Error::Unauthorized => StatusCode::UNAUTHORIZED,
+            Error::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
 impl IntoResponse for Error {
     fn into_response(self) -> Response {
-        let (status, json) = match self {
-            Error::Database(e) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": e.to_string() })),
-            ),
-            Error::Serde(e) => (
-                StatusCode::BAD_REQUEST,
-                Json(json!({ "error": e.to_string() })),
-            ),
-            error @ Error::NotFound => (
-                StatusCode::NOT_FOUND,
-                Json(json!({ "error": error.to_string() })),
-            ),
-            Error::Other(e) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": e.to_string() })),
-            ),
+        let status = self.status_code();
+        let json = match self {
+            Error::Database(e) => Json(json!({ "error": e.to_string() })),
+            Error::Serde(e) => Json(json!({ "error": e.to_string() })),
+            Error::BadRequest(message) => Json(json!({ "error": message })),
+            Error::Template(e) => Json(json!({ "error": e.to_string() })),
+            error @ (Error::Unauthorized | Error::NotFound) => {
+                Json(json!({ "error": error.to_string() }))
+            }
+            Error::Other(e) => Json(json!({ "error": e.to_string() })),
         };
         tracing::error!("Responding with error: {status} {json:?}");
         (status, json).into_response()
     }
 }
+
+/// An error from one of the bulletin board's HTML pages, which is shown as an
+/// HTML page rather than the API's JSON.
+#[derive(Debug)]
+struct HtmlError(Error);
+
+impl<E: Into<Error>> From<E> for HtmlError {
+    fn from(error: E) -> Self {
+        Self(error.into())
+    }
+}
+
+impl IntoResponse for HtmlError {
+    fn into_response(self) -> Response {
+        let status = self.0.status_code();
+        tracing::error!("Responding with error page: {status} {}", self.0);
+
+        // only say what went wrong when it's the reader's doing
+        let page = bulletin_board::html::ErrorPage {
+            message: match self.0 {
+                Error::NotFound => "This page could not be found.".to_owned(),
+                Error::BadRequest(message) => message,
+                _ => "Something went wrong. Please try again later.".to_owned(),
+            },
+        };
+
+        match page.render() {
+            Ok(html) => (status, Html(html)).into_response(),
+            Err(e) => {
+                tracing::error!("unable to render error page: {e}");
+                status.into_response()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::sync::{oneshot, Notify};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_serve_drains_requests_in_progress() -> color_eyre::Result<()> {
+        let started = Arc::new(Notify::new());
+        let release = Arc::new(Notify::new());
+        let app = Router::new().route(
+            "/slow",
+            get({
+                let started = started.clone();
+                let release = release.clone();
+                move || async move {
+                    started.notify_one();
+                    release.notified().await;
+                    "done"
+                }
+            }),
+        );
+
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}/slow", listener.local_addr()?);
+        let (shutdown, shutdown_requested) = oneshot::channel::<()>();
+        let server = tokio::spawn(serve(listener, app, async {
+            shutdown_requested.await.ok();
+        }));
+
+        let request = tokio::spawn(reqwest::get(url.clone()));
+        started.notified().await;
+        shutdown.send(()).unwrap();
+
+        // no new connections are accepted, but the server waits for the
+        // request in progress
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while reqwest::Client::new().get(&url).send().await.is_ok() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await?;
+        assert!(!server.is_finished());
+
+        release.notify_one();
+        let response = request.await??;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.text().await?, "done");
+        tokio::time::timeout(Duration::from_secs(5), server).await???;
+
+        Ok(())
+    }
+}
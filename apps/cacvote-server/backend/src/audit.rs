@@ -0,0 +1,236 @@
+//! Consistency checks of the stored objects, run with `cacvote-admin audit`.
+//! Objects are checked when they are created, so problems found here point to
+//! objects changed in the database since, or created by an older server with
+//! fewer checks.
+
+use openssl::x509::X509;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::db::{self, StoredObject};
+
+/// The outcome of [`run`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditReport {
+    pub objects_checked: usize,
+    pub findings: Vec<AuditFinding>,
+}
+
+impl AuditReport {
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+}
+
+/// A problem with a stored object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditFinding {
+    pub object_id: Uuid,
+    pub problem: String,
+}
+
+/// Checks every stored object: that its signatures verify and satisfy its
+/// type's signature policy, that the fields denormalized out of its payload
+/// match it, that it has exactly one journal entry, and that the blobs it
+/// refers to are stored. Signers count towards signature policies only if their
+/// certificates chain to one of `vx_ca_certificates`, as when objects are
+/// created.
+pub async fn run(
+    conn: &mut sqlx::PgConnection,
+    vx_ca_certificates: &[X509],
+) -> color_eyre::Result<AuditReport> {
+    let stored_objects = db::get_stored_objects(conn).await?;
+    let mut findings = Vec::new();
+
+    for stored_object in &stored_objects {
+        for problem in check_object(conn, stored_object, vx_ca_certificates).await? {
+            findings.push(AuditFinding {
+                object_id: stored_object.object.id,
+                problem,
+            });
+        }
+    }
+
+    Ok(AuditReport {
+        objects_checked: stored_objects.len(),
+        findings,
+    })
+}
+
+async fn check_object(
+    conn: &mut sqlx::PgConnection,
+    stored_object: &StoredObject,
+    vx_ca_certificates: &[X509],
+) -> color_eyre::Result<Vec<String>> {
+    let object = &stored_object.object;
+    let mut problems = Vec::new();
+
+    // a malformed signature is an error rather than a failed verification
+    if !matches!(object.verify(), Ok(true)) {
+        problems.push("Signature does not verify".to_owned());
+    }
+
+    if stored_object.journal_entry_count != 1 {
+        problems.push(format!(
+            "Expected 1 journal entry, found {}",
+            stored_object.journal_entry_count
+        ));
+    }
+
+    let payload = match object.try_to_inner() {
+        Ok(payload) => payload,
+        Err(error) => {
+            problems.push(format!("Payload does not decode: {error}"));
+            return Ok(problems);
+        }
+    };
+
+    if payload.object_type() != stored_object.object_type {
+        problems.push(format!(
+            "Stored object type {} does not match payload object type {}",
+            stored_object.object_type,
+            payload.object_type()
+        ));
+    }
+
+    match object.jurisdiction_code() {
+        Some(jurisdiction_code) if jurisdiction_code.as_str() == stored_object.jurisdiction => {}
+        jurisdiction_code => problems.push(format!(
+            "Stored jurisdiction {} does not match payload jurisdiction {jurisdiction_code:?}",
+            stored_object.jurisdiction
+        )),
+    }
+
+    if payload.election_id() != object.election_id {
+        problems.push(format!(
+            "Stored election ID {:?} does not match payload election ID {:?}",
+            object.election_id,
+            payload.election_id()
+        ));
+    }
+
+    if object.supersedes()? != stored_object.supersedes_object_id {
+        problems.push(format!(
+            "Stored superseded object {:?} does not match payload {:?}",
+            stored_object.supersedes_object_id,
+            object.supersedes()?
+        ));
+    }
+
+    let signature_policy_status = object.trusted_signature_policy_status(vx_ca_certificates)?;
+    if !signature_policy_status.is_satisfied() {
+        problems.push(format!(
+            "Requires {} eligible signers, but has {}",
+            signature_policy_status.signers_required, signature_policy_status.signers_collected
+        ));
+    }
+
+    for reference in payload.blob_references() {
+        if !db::has_blob(conn, reference).await? {
+            problems.push(format!("Blob {} is missing", reference.sha256));
+        }
+    }
+
+    Ok(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::{pkey::PKey, x509::X509};
+    use types_rs::{
+        cacvote::{BlobContent, BlobReference, Election, JurisdictionCode, Payload, SignedObject},
+        election::ElectionDefinition,
+    };
+
+    use super::*;
+
+    fn election_object(
+        electionguard_election_metadata_blob: BlobContent,
+    ) -> color_eyre::Result<SignedObject> {
+        // uses the dev VxAdmin keypair because it has the Jurisdiction field
+        let private_key = PKey::private_key_from_pem(include_bytes!(
+            "../../../../libs/auth/certs/dev/vx-admin-private-key.pem"
+        ))?;
+        let certificates = X509::stack_from_pem(include_bytes!(
+            "../../../../libs/auth/certs/dev/vx-admin-cert-authority-cert.pem"
+        ))?;
+
+        SignedObject::from_payload(
+            &Payload::Election(Election {
+                jurisdiction_code: JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
+                election_definition: ElectionDefinition::try_from(
+                    &include_bytes!("../tests/fixtures/electionFamousNames2021.json")[..],
+                )?,
+                mailing_address: "123 Main St".to_owned(),
+                electionguard_election_metadata_blob,
+            }),
+            certificates,
+            &private_key,
+        )
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_audit(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let mut conn = pool.acquire().await?;
+
+        let report = run(&mut conn, &[]).await?;
+        assert_eq!(report.objects_checked, 0);
+        assert!(report.is_clean());
+
+        let object_id =
+            db::create_object(&mut conn, &election_object(vec![].into())?, None).await?;
+        let report = run(&mut conn, &[]).await?;
+        assert_eq!(report.objects_checked, 1);
+        assert!(report.is_clean(), "{report:?}");
+
+        // tamper with the stored object
+        sqlx::query(
+            "UPDATE objects SET object_type = 'CastBallot', signature = '\\x00' WHERE id = $1",
+        )
+        .bind(object_id)
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query("DELETE FROM journal_entries WHERE object_id = $1")
+            .bind(object_id)
+            .execute(&mut *conn)
+            .await?;
+
+        // objects can only refer to stored blobs when they're created
+        let blob_object = election_object(BlobContent::Blob(BlobReference::for_data(b"metadata")))?;
+        let blob_object_id = db::create_object(&mut conn, &blob_object, None).await?;
+
+        let report = run(&mut conn, &[]).await?;
+        assert_eq!(report.objects_checked, 2);
+        assert_eq!(
+            report.findings,
+            vec![
+                AuditFinding {
+                    object_id,
+                    problem: "Signature does not verify".to_owned(),
+                },
+                AuditFinding {
+                    object_id,
+                    problem: "Expected 1 journal entry, found 0".to_owned(),
+                },
+                AuditFinding {
+                    object_id,
+                    problem:
+                        "Stored object type CastBallot does not match payload object type Election"
+                            .to_owned(),
+                },
+                AuditFinding {
+                    object_id: blob_object_id,
+                    problem: format!(
+                        "Blob {} is missing",
+                        BlobReference::for_data(b"metadata").sha256
+                    ),
+                },
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -1,6 +1,20 @@
+pub mod html;
+
 use serde::{ser::SerializeStruct, Serialize};
+use types_rs::cacvote::{
+    self, BallotTrackingEvent, BallotTrackingStage, BallotTrackingStatus, Payload,
+};
+use utoipa::{
+    openapi::{
+        schema::{KnownFormat, SchemaFormat, SchemaType, Type},
+        ObjectBuilder, RefOr, Schema,
+    },
+    PartialSchema, ToSchema,
+};
 use uuid::Uuid;
 
+use crate::db;
+
 pub struct Election {
     id: Uuid,
 }
@@ -38,14 +52,45 @@ impl Serialize for Election {
     }
 }
 
+impl PartialSchema for Election {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .property("id", uuid_schema([Type::String]))
+            .property("castBallotsUrl", String::schema())
+            .property("encryptedTallyUrl", String::schema())
+            .property("decryptedTallyUrl", String::schema())
+            .property("shuffledBallotsUrl", String::schema())
+            .required("id")
+            .required("castBallotsUrl")
+            .required("encryptedTallyUrl")
+            .required("decryptedTallyUrl")
+            .required("shuffledBallotsUrl")
+            .into()
+    }
+}
+
+impl ToSchema for Election {
+    fn name() -> std::borrow::Cow<'static, str> {
+        "BulletinBoardElection".into()
+    }
+}
+
 pub struct CastBallot {
     id: Uuid,
     election_id: Uuid,
+
+    /// The later ballot from the same voter that replaced this one, if any.
+    /// Superseded ballots aren't counted.
+    superseded_by: Option<Uuid>,
 }
 
 impl CastBallot {
-    pub fn new(id: Uuid, election_id: Uuid) -> Self {
-        Self { id, election_id }
+    pub fn new(id: Uuid, election_id: Uuid, superseded_by: Option<Uuid>) -> Self {
+        Self {
+            id,
+            election_id,
+            superseded_by,
+        }
     }
 }
 
@@ -54,9 +99,10 @@ impl Serialize for CastBallot {
     where
         S: serde::Serializer,
     {
-        let mut cast_ballot = serializer.serialize_struct("CastBallot", 2)?;
+        let mut cast_ballot = serializer.serialize_struct("CastBallot", 4)?;
         cast_ballot.serialize_field("id", &self.id)?;
         cast_ballot.serialize_field("electionId", &self.election_id)?;
+        cast_ballot.serialize_field("supersededBy", &self.superseded_by)?;
         cast_ballot.serialize_field(
             "url",
             &format!(
@@ -67,3 +113,108 @@ impl Serialize for CastBallot {
         cast_ballot.end()
     }
 }
+
+impl PartialSchema for CastBallot {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .property("id", uuid_schema([Type::String]))
+            .property("electionId", uuid_schema([Type::String]))
+            .property("supersededBy", uuid_schema([Type::String, Type::Null]))
+            .property("url", String::schema())
+            .required("id")
+            .required("electionId")
+            .required("supersededBy")
+            .required("url")
+            .into()
+    }
+}
+
+impl ToSchema for CastBallot {
+    fn name() -> std::borrow::Cow<'static, str> {
+        "BulletinBoardCastBallot".into()
+    }
+}
+
+fn uuid_schema<const N: usize>(types: [Type; N]) -> ObjectBuilder {
+    ObjectBuilder::new()
+        .schema_type(SchemaType::from_iter(types))
+        .format(Some(SchemaFormat::KnownFormat(KnownFormat::Uuid)))
+}
+
+/// Loads the status history of the cast ballot with `tracking_code`, see
+/// [`cacvote::tracking_code`]. Returns `None` if there is no such ballot.
+pub(crate) async fn load_ballot_tracking_status(
+    conn: &mut sqlx::PgConnection,
+    tracking_code: &[u8],
+) -> color_eyre::Result<Option<BallotTrackingStatus>> {
+    let Some(cast_ballot_object) =
+        db::get_cast_ballot_by_tracking_code(conn, tracking_code).await?
+    else {
+        return Ok(None);
+    };
+    let Payload::CastBallot(cast_ballot) = cast_ballot_object.try_to_inner()? else {
+        return Ok(None);
+    };
+    let Some(recorded_at) = db::get_object_created_at(conn, cast_ballot_object.id).await? else {
+        return Ok(None);
+    };
+
+    let mut events = vec![BallotTrackingEvent {
+        stage: BallotTrackingStage::BallotRecorded,
+        occurred_at: recorded_at,
+    }];
+
+    if let Some(approved_at) =
+        db::get_object_created_at(conn, cast_ballot.registration_object_id).await?
+    {
+        events.push(BallotTrackingEvent {
+            stage: BallotTrackingStage::RegistrationApproved,
+            occurred_at: approved_at,
+        });
+    }
+
+    if let Some(scanned_at) = db::get_mailing_label_scanned_at(conn, tracking_code).await? {
+        events.push(BallotTrackingEvent {
+            stage: BallotTrackingStage::MailingLabelScanned,
+            occurred_at: scanned_at,
+        });
+    }
+
+    let superseded_at = db::get_cast_ballot_supersession(conn, cast_ballot_object.id)
+        .await?
+        .and_then(|supersession| supersession.superseded_at);
+    if let Some(superseded_at) = superseded_at {
+        events.push(BallotTrackingEvent {
+            stage: BallotTrackingStage::Superseded,
+            occurred_at: superseded_at,
+        });
+    }
+
+    if let Some(shuffled_at) = db::get_first_object_created_at_by_election_and_type(
+        conn,
+        cast_ballot.election_object_id,
+        Payload::shuffled_encrypted_cast_ballots_object_type(),
+        recorded_at,
+    )
+    .await?
+    {
+        // a shuffle posted after the ballot was superseded left it out. its
+        // inputs are opaque, so one posted before could still have left it
+        // out, which is why the stage only claims the shuffle was posted
+        if superseded_at.is_none_or(|superseded_at| shuffled_at < superseded_at) {
+            events.push(BallotTrackingEvent {
+                stage: BallotTrackingStage::ShufflePosted,
+                occurred_at: shuffled_at,
+            });
+        }
+    }
+
+    events.sort_by_key(|event| (event.occurred_at, event.stage));
+
+    Ok(Some(BallotTrackingStatus {
+        tracking_code: cacvote::tracking_code(&cast_ballot_object.signature),
+        election_object_id: cast_ballot.election_object_id,
+        cast_ballot_object_id: cast_ballot_object.id,
+        events,
+    }))
+}
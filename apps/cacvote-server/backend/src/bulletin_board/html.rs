@@ -0,0 +1,792 @@
+//! Server-rendered HTML views of the bulletin board, for voters and observers
+//! who want to read what has been posted without any client-side code.
+//!
+//! Each page is an [`askama`] template (see `templates/bulletin_board`) backed
+//! by a plain struct, which is loaded from the database by its `load` function.
+
+use std::collections::HashSet;
+
+use askama::Template;
+use color_eyre::eyre::eyre;
+use openssl::sha::sha256;
+use serde::Deserialize;
+use time::OffsetDateTime;
+use types_rs::{
+    cacvote::{self, ObjectSearchQuery, Payload, SignedObject},
+    election as vx_election,
+};
+use uuid::Uuid;
+
+use crate::db;
+
+/// The code a voter uses to find their ballot, see
+/// [`cacvote::tracking_code`].
+pub(crate) fn tracking_code(cast_ballot_object: &SignedObject) -> String {
+    cacvote::tracking_code(&cast_ballot_object.signature)
+}
+
+mod filters {
+    use time::{macros::format_description, OffsetDateTime, UtcOffset};
+
+    pub(crate) fn timestamp(timestamp: &OffsetDateTime) -> askama::Result<String> {
+        timestamp
+            .to_offset(UtcOffset::UTC)
+            .format(format_description!(
+                "[year]-[month]-[day] [hour]:[minute] UTC"
+            ))
+            .map_err(|e| askama::Error::Custom(Box::new(e)))
+    }
+}
+
+/// How far along the tally of an election is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TallyStatus {
+    NotTallied,
+    EncryptedTallyPosted,
+    ResultsPosted,
+}
+
+impl std::fmt::Display for TallyStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::NotTallied => "Not yet tallied",
+            Self::EncryptedTallyPosted => "Encrypted tally posted",
+            Self::ResultsPosted => "Results posted",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ElectionSummary {
+    pub(crate) id: Uuid,
+    pub(crate) title: String,
+    pub(crate) jurisdiction_code: String,
+    pub(crate) cast_ballot_count: i64,
+    pub(crate) tally_status: TallyStatus,
+    pub(crate) encrypted_tally_posted_at: Option<OffsetDateTime>,
+    pub(crate) shuffled_ballots_posted_at: Option<OffsetDateTime>,
+}
+
+impl ElectionSummary {
+    /// Summarizes an election from its overview, returning `None` if its
+    /// latest version isn't an election this build can read.
+    fn from_overview(
+        overview: db::ElectionOverview,
+    ) -> color_eyre::Result<Option<(Self, cacvote::Election)>> {
+        let Payload::Election(election) = overview.election_object.try_to_inner()? else {
+            return Ok(None);
+        };
+
+        let tally_status = match (
+            overview.encrypted_tally_posted_at,
+            overview.decrypted_tally_posted_at,
+        ) {
+            (_, Some(_)) => TallyStatus::ResultsPosted,
+            (Some(_), None) => TallyStatus::EncryptedTallyPosted,
+            (None, None) => TallyStatus::NotTallied,
+        };
+
+        Ok(Some((
+            Self {
+                id: overview.election_id,
+                title: election.election_definition.election.title.clone(),
+                jurisdiction_code: election.jurisdiction_code.to_string(),
+                cast_ballot_count: overview.cast_ballot_count,
+                tally_status,
+                encrypted_tally_posted_at: overview.encrypted_tally_posted_at,
+                shuffled_ballots_posted_at: overview.shuffled_ballots_posted_at,
+            },
+            election,
+        )))
+    }
+
+    /// Loads the election with `election_id`, the ID of its original election
+    /// object, which other objects refer to.
+    async fn load_by_id(
+        conn: &mut sqlx::PgConnection,
+        election_id: Uuid,
+    ) -> color_eyre::Result<Option<(Self, cacvote::Election)>> {
+        match db::get_election_overviews(conn, Some(election_id))
+            .await?
+            .into_iter()
+            .next()
+        {
+            Some(overview) => Self::from_overview(overview),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Lists every election on the bulletin board.
+#[derive(Debug, Template)]
+#[template(path = "bulletin_board/elections.html")]
+pub(crate) struct ElectionsPage {
+    pub(crate) elections: Vec<ElectionSummary>,
+}
+
+impl ElectionsPage {
+    pub(crate) async fn load(conn: &mut sqlx::PgConnection) -> color_eyre::Result<Self> {
+        let mut elections = Vec::new();
+
+        for overview in db::get_election_overviews(conn, None).await? {
+            if let Some((election, _)) = ElectionSummary::from_overview(overview)? {
+                elections.push(election);
+            }
+        }
+
+        Ok(Self { elections })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CastBallotSummary {
+    pub(crate) id: Uuid,
+    pub(crate) tracking_code: String,
+    pub(crate) cast_at: OffsetDateTime,
+
+    /// Whether the voter cast a later ballot, so this one won't be counted.
+    pub(crate) is_superseded: bool,
+}
+
+/// An election's status along with the tracking code of every ballot cast in
+/// it.
+#[derive(Debug, Template)]
+#[template(path = "bulletin_board/election.html")]
+pub(crate) struct ElectionPage {
+    pub(crate) election: ElectionSummary,
+    pub(crate) cast_ballots: Vec<CastBallotSummary>,
+}
+
+impl ElectionPage {
+    pub(crate) async fn load(
+        conn: &mut sqlx::PgConnection,
+        election_id: Uuid,
+    ) -> color_eyre::Result<Option<Self>> {
+        let Some((election, _)) = ElectionSummary::load_by_id(conn, election_id).await? else {
+            return Ok(None);
+        };
+
+        let superseded_ids = db::get_cast_ballot_supersessions_by_election(conn, election_id)
+            .await?
+            .into_iter()
+            .filter(|supersession| supersession.superseded_by_id.is_some())
+            .map(|supersession| supersession.cast_ballot_id)
+            .collect::<HashSet<_>>();
+
+        let cast_ballots = search_all(
+            conn,
+            ObjectSearchQuery {
+                election_id: Some(election_id),
+                object_type: Some(Payload::cast_ballot_object_type().to_owned()),
+                ..Default::default()
+            },
+        )
+        .await?
+        .into_iter()
+        .map(|(cast_ballot_object, cast_at)| CastBallotSummary {
+            id: cast_ballot_object.id,
+            tracking_code: tracking_code(&cast_ballot_object),
+            cast_at,
+            is_superseded: superseded_ids.contains(&cast_ballot_object.id),
+        })
+        .collect();
+
+        Ok(Some(Self {
+            election,
+            cast_ballots,
+        }))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SelectionResult {
+    pub(crate) name: String,
+    pub(crate) votes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ContestResult {
+    pub(crate) title: String,
+    pub(crate) selections: Vec<SelectionResult>,
+}
+
+/// The plaintext tally as written by ElectionGuard. Only the fields needed to
+/// show the results are read.
+#[derive(Debug, Deserialize)]
+struct PlaintextTally {
+    contests: Vec<PlaintextTallyContest>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlaintextTallyContest {
+    contest_id: String,
+    selections: Vec<PlaintextTallySelection>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlaintextTallySelection {
+    selection_id: String,
+    tally: u64,
+}
+
+/// Reads the results out of an ElectionGuard plaintext tally, naming contests
+/// and candidates from the election definition. ElectionGuard object IDs are
+/// the VotingWorks IDs prefixed with `contest-` and `cand-`, see
+/// `electionguard_rs::manifest`.
+pub(crate) fn parse_decrypted_tally(
+    election: &vx_election::Election,
+    electionguard_decrypted_tally: &[u8],
+) -> Result<Vec<ContestResult>, serde_json::Error> {
+    let tally: PlaintextTally = serde_json::from_slice(electionguard_decrypted_tally)?;
+
+    Ok(tally
+        .contests
+        .into_iter()
+        .map(|tally_contest| {
+            let contest = election
+                .contests
+                .iter()
+                .find(|contest| tally_contest.contest_id == format!("contest-{}", contest.id()));
+            let candidates = match contest {
+                Some(vx_election::Contest::Candidate(contest)) => contest.candidates.as_slice(),
+                _ => &[],
+            };
+
+            ContestResult {
+                title: match contest {
+                    Some(vx_election::Contest::Candidate(contest)) => contest.title.clone(),
+                    Some(vx_election::Contest::YesNo(contest)) => contest.title.clone(),
+                    None => tally_contest.contest_id,
+                },
+                selections: tally_contest
+                    .selections
+                    .into_iter()
+                    .map(|selection| SelectionResult {
+                        name: candidates
+                            .iter()
+                            .find(|candidate| {
+                                selection.selection_id == format!("cand-{}", candidate.id)
+                            })
+                            .map_or(selection.selection_id, |candidate| candidate.name.clone()),
+                        votes: selection.tally,
+                    })
+                    .collect(),
+            }
+        })
+        .collect())
+}
+
+/// The decrypted results of an election.
+#[derive(Debug, Template)]
+#[template(path = "bulletin_board/results.html")]
+pub(crate) struct ResultsPage {
+    pub(crate) election: ElectionSummary,
+
+    /// `None` if the results haven't been posted.
+    pub(crate) results: Option<Result<Vec<ContestResult>, String>>,
+}
+
+impl ResultsPage {
+    pub(crate) async fn load(
+        conn: &mut sqlx::PgConnection,
+        election_id: Uuid,
+    ) -> color_eyre::Result<Option<Self>> {
+        let Some((election_summary, election)) =
+            ElectionSummary::load_by_id(conn, election_id).await?
+        else {
+            return Ok(None);
+        };
+
+        let decrypted_tally_object = db::get_object_by_election_id_and_type(
+            conn,
+            election_id,
+            Payload::decrypted_election_tally_object_type(),
+        )
+        .await?;
+
+        let results = match decrypted_tally_object {
+            Some(object) => match object.try_to_inner()? {
+                Payload::DecryptedElectionTally(decrypted_tally) => Some(
+                    parse_decrypted_tally(
+                        &election.election_definition.election,
+                        &decrypted_tally.electionguard_decrypted_tally,
+                    )
+                    .map_err(|e| {
+                        tracing::error!("unable to parse decrypted tally: {e}");
+                        "The decrypted tally could not be read.".to_owned()
+                    }),
+                ),
+                _ => None,
+            },
+            None => None,
+        };
+
+        Ok(Some(Self {
+            election: election_summary,
+            results,
+        }))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BallotStatus {
+    pub(crate) label: &'static str,
+    pub(crate) is_complete: bool,
+}
+
+/// The status of a single cast ballot. Shows only what is public about the
+/// ballot, never the voter or their selections.
+#[derive(Debug, Template)]
+#[template(path = "bulletin_board/ballot.html")]
+pub(crate) struct BallotPage {
+    pub(crate) election: ElectionSummary,
+    pub(crate) tracking_code: String,
+    pub(crate) cast_at: OffsetDateTime,
+
+    /// When the voter cast a later ballot that replaced this one, if they did.
+    pub(crate) superseded_at: Option<OffsetDateTime>,
+    pub(crate) statuses: Vec<BallotStatus>,
+}
+
+impl BallotPage {
+    pub(crate) async fn load(
+        conn: &mut sqlx::PgConnection,
+        election_id: Uuid,
+        cast_ballot_id: Uuid,
+    ) -> color_eyre::Result<Option<Self>> {
+        let Some(cast_ballot) = db::get_object_by_id(conn, cast_ballot_id).await? else {
+            return Ok(None);
+        };
+        if cast_ballot.election_id != Some(election_id)
+            || !matches!(cast_ballot.try_to_inner()?, Payload::CastBallot(_))
+        {
+            return Ok(None);
+        }
+        let Some(cast_at) = db::get_object_created_at(conn, cast_ballot_id).await? else {
+            return Ok(None);
+        };
+
+        let Some((election, _)) = ElectionSummary::load_by_id(conn, election_id).await? else {
+            return Ok(None);
+        };
+
+        let is_mailing_label_scanned =
+            db::has_scanned_mailing_label_code(conn, &sha256(&cast_ballot.signature)).await?;
+        let superseded_at = db::get_cast_ballot_supersession(conn, cast_ballot_id)
+            .await?
+            .and_then(|supersession| supersession.superseded_at);
+        let posted_after_cast = |posted_at: Option<OffsetDateTime>| {
+            posted_at.is_some_and(|posted_at| {
+                posted_at >= cast_at
+                    && superseded_at.is_none_or(|superseded_at| posted_at < superseded_at)
+            })
+        };
+
+        Ok(Some(Self {
+            statuses: vec![
+                BallotStatus {
+                    label: "Recorded on the bulletin board",
+                    is_complete: true,
+                },
+                BallotStatus {
+                    label: "Mailing label scanned",
+                    is_complete: is_mailing_label_scanned,
+                },
+                BallotStatus {
+                    label: "Included in the encrypted tally",
+                    is_complete: posted_after_cast(election.encrypted_tally_posted_at),
+                },
+                BallotStatus {
+                    label: "Included in the shuffled ballots",
+                    is_complete: posted_after_cast(election.shuffled_ballots_posted_at),
+                },
+            ],
+            election,
+            tracking_code: tracking_code(&cast_ballot),
+            cast_at,
+            superseded_at,
+        }))
+    }
+}
+
+/// Shown in place of a page that couldn't be loaded.
+#[derive(Debug, Template)]
+#[template(path = "bulletin_board/error.html")]
+pub(crate) struct ErrorPage {
+    pub(crate) message: String,
+}
+
+/// Gets every object matching `query` along with when it was created,
+/// following the pagination cursor.
+async fn search_all(
+    conn: &mut sqlx::PgConnection,
+    mut query: ObjectSearchQuery,
+) -> color_eyre::Result<Vec<(SignedObject, OffsetDateTime)>> {
+    let mut objects = Vec::new();
+
+    loop {
+        let page = db::search_objects(conn, &query, ObjectSearchQuery::MAX_LIMIT)
+            .await?
+            .ok_or_else(|| eyre!("unknown search cursor {:?}", query.cursor))?;
+
+        objects.extend(page.objects.into_iter().filter_map(|result| {
+            Some((
+                result.object?.into_signed_object()?,
+                result.metadata.created_at,
+            ))
+        }));
+
+        match page.next_cursor {
+            Some(cursor) => query.cursor = Some(cursor),
+            None => return Ok(objects),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::{pkey::PKey, x509::X509};
+    use time::macros::datetime;
+    use types_rs::{
+        cacvote::{CastBallot, Election, EncryptedElectionTally, JurisdictionCode},
+        election::ElectionDefinition,
+    };
+
+    use super::*;
+    use crate::cac::{self, test_utils::TestCertificateAuthority};
+
+    fn load_election_definition() -> ElectionDefinition {
+        ElectionDefinition::try_from(
+            &include_bytes!("../../tests/fixtures/electionFamousNames2021.json")[..],
+        )
+        .unwrap()
+    }
+
+    fn election_summary() -> ElectionSummary {
+        ElectionSummary {
+            id: Uuid::from_u128(1),
+            title: "Lincoln Municipal General Election".to_owned(),
+            jurisdiction_code: "st.dev-jurisdiction".to_owned(),
+            cast_ballot_count: 2,
+            tally_status: TallyStatus::ResultsPosted,
+            encrypted_tally_posted_at: Some(datetime!(2024-06-01 18:00:00 UTC)),
+            shuffled_ballots_posted_at: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_decrypted_tally() {
+        let election = load_election_definition().election;
+        let tally = br#"{
+            "tally_id": "tally",
+            "contests": [
+                {
+                    "contest_id": "contest-mayor",
+                    "selections": [
+                        { "selection_id": "cand-sherlock-holmes", "tally": 3 },
+                        { "selection_id": "cand-thomas-edison", "tally": 1 }
+                    ]
+                },
+                {
+                    "contest_id": "contest-unknown",
+                    "selections": [{ "selection_id": "cand-unknown", "tally": 0 }]
+                }
+            ]
+        }"#;
+
+        assert_eq!(
+            parse_decrypted_tally(&election, tally).unwrap(),
+            vec![
+                ContestResult {
+                    title: "Mayor".to_owned(),
+                    selections: vec![
+                        SelectionResult {
+                            name: "Sherlock Holmes".to_owned(),
+                            votes: 3,
+                        },
+                        SelectionResult {
+                            name: "Thomas Edison".to_owned(),
+                            votes: 1,
+                        },
+                    ],
+                },
+                ContestResult {
+                    title: "contest-unknown".to_owned(),
+                    selections: vec![SelectionResult {
+                        name: "cand-unknown".to_owned(),
+                        votes: 0,
+                    }],
+                },
+            ]
+        );
+
+        assert!(parse_decrypted_tally(&election, b"decrypted tally").is_err());
+    }
+
+    #[test]
+    fn test_render_elections_page() {
+        insta::assert_snapshot!(ElectionsPage {
+            elections: vec![election_summary()],
+        }
+        .render()
+        .unwrap());
+        insta::assert_snapshot!(
+            "empty_elections_page",
+            ElectionsPage { elections: vec![] }.render().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_render_election_page() {
+        insta::assert_snapshot!(ElectionPage {
+            election: election_summary(),
+            cast_ballots: vec![
+                CastBallotSummary {
+                    id: Uuid::from_u128(2),
+                    tracking_code:
+                        "7b5c3e09c0f3d4bd1e1d0c5b1f8b2a3c4d5e6f708192a3b4c5d6e7f8091a2b3c"
+                            .to_owned(),
+                    cast_at: datetime!(2024-06-01 12:34:56 UTC),
+                    is_superseded: false,
+                },
+                CastBallotSummary {
+                    id: Uuid::from_u128(3),
+                    tracking_code:
+                        "0f1e2d3c4b5a69788796a5b4c3d2e1f00f1e2d3c4b5a69788796a5b4c3d2e1f0"
+                            .to_owned(),
+                    cast_at: datetime!(2024-06-01 11:22:33 UTC),
+                    is_superseded: true,
+                }
+            ],
+        }
+        .render()
+        .unwrap());
+    }
+
+    #[test]
+    fn test_render_results_page() {
+        insta::assert_snapshot!(ResultsPage {
+            election: election_summary(),
+            results: Some(Ok(vec![ContestResult {
+                title: "Mayor".to_owned(),
+                selections: vec![
+                    SelectionResult {
+                        name: "Sherlock Holmes".to_owned(),
+                        votes: 3,
+                    },
+                    SelectionResult {
+                        name: "Thomas Edison".to_owned(),
+                        votes: 1,
+                    },
+                ],
+            }])),
+        }
+        .render()
+        .unwrap());
+    }
+
+    #[test]
+    fn test_render_ballot_page() {
+        insta::assert_snapshot!(BallotPage {
+            election: election_summary(),
+            tracking_code: "7b5c3e09c0f3d4bd1e1d0c5b1f8b2a3c4d5e6f708192a3b4c5d6e7f8091a2b3c"
+                .to_owned(),
+            cast_at: datetime!(2024-06-01 12:34:56 UTC),
+            superseded_at: None,
+            statuses: vec![
+                BallotStatus {
+                    label: "Recorded on the bulletin board",
+                    is_complete: true,
+                },
+                BallotStatus {
+                    label: "Mailing label scanned",
+                    is_complete: false,
+                },
+            ],
+        }
+        .render()
+        .unwrap());
+    }
+
+    #[test]
+    fn test_render_superseded_ballot_page() {
+        insta::assert_snapshot!(BallotPage {
+            election: election_summary(),
+            tracking_code: "0f1e2d3c4b5a69788796a5b4c3d2e1f00f1e2d3c4b5a69788796a5b4c3d2e1f0"
+                .to_owned(),
+            cast_at: datetime!(2024-06-01 11:22:33 UTC),
+            superseded_at: Some(datetime!(2024-06-01 12:34:56 UTC)),
+            statuses: vec![BallotStatus {
+                label: "Recorded on the bulletin board",
+                is_complete: true,
+            }],
+        }
+        .render()
+        .unwrap());
+    }
+
+    #[test]
+    fn test_render_error_page() {
+        insta::assert_snapshot!(ErrorPage {
+            message: "This page could not be found.".to_owned(),
+        }
+        .render()
+        .unwrap());
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_load_pages(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let mut conn = pool.acquire().await?;
+
+        let private_key = PKey::private_key_from_pem(include_bytes!(
+            "../../../../../libs/auth/certs/dev/vx-admin-private-key.pem"
+        ))?;
+        let certificates = X509::stack_from_pem(include_bytes!(
+            "../../../../../libs/auth/certs/dev/vx-admin-cert-authority-cert.pem"
+        ))?;
+        let jurisdiction_code = JurisdictionCode::try_from("st.dev-jurisdiction").unwrap();
+        let sign = |payload: Payload| {
+            SignedObject::from_payload(&payload, certificates.clone(), &private_key)
+        };
+
+        let election_object = sign(Payload::Election(Election {
+            jurisdiction_code: jurisdiction_code.clone(),
+            election_definition: load_election_definition(),
+            mailing_address: "123 Main St".to_owned(),
+            electionguard_election_metadata_blob: vec![].into(),
+        }))?;
+        let election_id = db::create_object(&mut conn, &election_object, None).await?;
+
+        // the first voter casts again after the second, superseding their
+        // first ballot
+        let certificate_authority = TestCertificateAuthority::new();
+        let voters = ["1234567890", "0987654321"].map(|common_access_card_id| {
+            let (certificates, private_key) =
+                certificate_authority.issue(&format!("DOE.JOHN.EDWARD.{common_access_card_id}"));
+            (common_access_card_id, certificates, private_key)
+        });
+        let mut cast_ballot_objects = vec![];
+        for (common_access_card_id, certificates, private_key) in
+            [&voters[0], &voters[1], &voters[0]]
+        {
+            let cast_ballot_object = SignedObject::from_payload(
+                &Payload::CastBallot(CastBallot {
+                    common_access_card_id: (*common_access_card_id).to_owned(),
+                    jurisdiction_code: jurisdiction_code.clone(),
+                    registration_request_object_id: Uuid::new_v4(),
+                    registration_object_id: Uuid::new_v4(),
+                    election_object_id: election_id,
+                    electionguard_encrypted_ballot: "encrypted ballot".to_owned(),
+                }),
+                X509::stack_from_pem(certificates)?,
+                private_key,
+            )?;
+            db::create_object(
+                &mut conn,
+                &cast_ballot_object,
+                Some(&cac::voter_id(b"key", common_access_card_id)?),
+            )
+            .await?;
+            cast_ballot_objects.push(cast_ballot_object);
+        }
+
+        let elections_page = ElectionsPage::load(&mut conn).await?;
+        let [election] = elections_page.elections.as_slice() else {
+            panic!("expected one election, got: {elections_page:?}");
+        };
+        assert_eq!(election.id, election_id);
+        assert_eq!(election.title, "Lincoln Municipal General Election");
+        assert_eq!(election.cast_ballot_count, 2);
+        assert_eq!(election.tally_status, TallyStatus::NotTallied);
+
+        let election_page = ElectionPage::load(&mut conn, election_id).await?.unwrap();
+        assert_eq!(
+            election_page
+                .cast_ballots
+                .iter()
+                .map(|cast_ballot| (
+                    cast_ballot.id,
+                    cast_ballot.tracking_code.clone(),
+                    cast_ballot.is_superseded
+                ))
+                .collect::<Vec<_>>(),
+            cast_ballot_objects
+                .iter()
+                .zip([true, false, false])
+                .map(|(object, is_superseded)| (object.id, tracking_code(object), is_superseded))
+                .collect::<Vec<_>>()
+        );
+        // the voter's CAC ID is never shown
+        assert!(!election_page.render()?.contains("1234567890"));
+        assert!(ElectionPage::load(&mut conn, Uuid::new_v4())
+            .await?
+            .is_none());
+
+        let cast_ballot_id = cast_ballot_objects[1].id;
+        let ballot_page = BallotPage::load(&mut conn, election_id, cast_ballot_id)
+            .await?
+            .unwrap();
+        assert_eq!(ballot_page.superseded_at, None);
+        assert_eq!(
+            ballot_page
+                .statuses
+                .iter()
+                .map(|status| status.is_complete)
+                .collect::<Vec<_>>(),
+            vec![true, false, false, false]
+        );
+        assert!(BallotPage::load(&mut conn, Uuid::new_v4(), cast_ballot_id)
+            .await?
+            .is_none());
+        assert!(BallotPage::load(&mut conn, election_id, election_id)
+            .await?
+            .is_none());
+
+        db::create_object(
+            &mut conn,
+            &sign(Payload::EncryptedElectionTally(EncryptedElectionTally {
+                jurisdiction_code,
+                election_object_id: election_id,
+                electionguard_encrypted_tally: vec![],
+            }))?,
+            None,
+        )
+        .await?;
+
+        let ballot_page = BallotPage::load(&mut conn, election_id, cast_ballot_id)
+            .await?
+            .unwrap();
+        assert_eq!(
+            ballot_page.election.tally_status,
+            TallyStatus::EncryptedTallyPosted
+        );
+        assert_eq!(
+            ballot_page
+                .statuses
+                .iter()
+                .map(|status| status.is_complete)
+                .collect::<Vec<_>>(),
+            vec![true, false, true, false]
+        );
+
+        // superseded ballots aren't included in anything posted afterwards
+        let superseded_ballot_page =
+            BallotPage::load(&mut conn, election_id, cast_ballot_objects[0].id)
+                .await?
+                .unwrap();
+        assert!(superseded_ballot_page.superseded_at.is_some());
+        assert_eq!(
+            superseded_ballot_page
+                .statuses
+                .iter()
+                .map(|status| status.is_complete)
+                .collect::<Vec<_>>(),
+            vec![true, false, false, false]
+        );
+
+        let results_page = ResultsPage::load(&mut conn, election_id).await?.unwrap();
+        assert_eq!(results_page.results, None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,465 @@
+//! Verification of Common Access Card certificates, following the checks the
+//! JX terminal makes on the certificates of cast ballots.
+
+use color_eyre::eyre::bail;
+use openssl::x509::X509;
+pub use types_rs::cacvote::verify_certificate_chain;
+use types_rs::cacvote::{
+    commitment, encrypted_field_binding, field_binding, is_encrypted_field_value, Payload,
+    SignedObject,
+};
+
+/// Reads a CA certificate from a file in PEM or DER format.
+pub fn load_ca_certificate(path: &str) -> Result<X509, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Unable to read {path}: {e}"))?;
+    X509::from_pem(&bytes)
+        .or_else(|_| X509::from_der(&bytes))
+        .map_err(|e| format!("Unable to parse certificate {path}: {e}"))
+}
+
+/// Verifies that the first certificate of a PEM-encoded stack is a currently
+/// valid CAC certificate issued by one of `ca_certificates`, using the rest of
+/// the stack as intermediates. Returns the CAC certificate.
+pub(crate) fn verify_common_access_card_certificate(
+    certificates: &[u8],
+    ca_certificates: &[X509],
+) -> color_eyre::Result<X509> {
+    let common_access_card_certificate = verify_certificate_chain(certificates, ca_certificates)?;
+
+    if CommonAccessCardHolder::from_certificate(&common_access_card_certificate).is_none() {
+        bail!("Certificate is not a Common Access Card certificate");
+    }
+
+    Ok(common_access_card_certificate)
+}
+
+/// The holder of a Common Access Card, as named by the common name of its
+/// certificate, which is of the form `SURNAME.GIVEN.MIDDLE.ID`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CommonAccessCardHolder {
+    pub(crate) common_access_card_id: String,
+    pub(crate) surname: String,
+    pub(crate) given_name: String,
+    pub(crate) middle_name: String,
+}
+
+impl CommonAccessCardHolder {
+    pub(crate) fn from_certificate(certificate: &X509) -> Option<Self> {
+        let common_name = certificate
+            .subject_name()
+            .entries_by_nid(openssl::nid::Nid::COMMONNAME)
+            .next()?
+            .data()
+            .as_utf8()
+            .ok()?
+            .to_string();
+
+        match common_name.split('.').collect::<Vec<_>>().as_slice() {
+            [surname, given_name, middle_name, id]
+                if !id.is_empty() && id.bytes().all(|byte| byte.is_ascii_digit()) =>
+            {
+                Some(Self {
+                    common_access_card_id: (*id).to_owned(),
+                    surname: surname.trim().to_owned(),
+                    given_name: given_name.trim().to_owned(),
+                    middle_name: middle_name.trim().to_owned(),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Identifies the holder of the CAC with `common_access_card_id` without
+/// storing the ID itself: the same keyed commitment redacted objects show in
+/// place of the ID. Only pass IDs read from certificates verified with
+/// [`verify_common_access_card_certificate`].
+pub(crate) fn voter_id(
+    redaction_key: &[u8],
+    common_access_card_id: &str,
+) -> color_eyre::Result<String> {
+    Ok(commitment(
+        redaction_key,
+        "commonAccessCardId",
+        &serde_json::Value::from(common_access_card_id),
+    )?)
+}
+
+/// Verifies that a voter-originated object, i.e. a registration request or a
+/// cast ballot, is signed by a CAC issued by one of `ca_certificates` and that
+/// the CAC holder is the voter the payload names. Private fields encrypted to
+/// the jurisdiction are compared by their binding (see
+/// [`types_rs::cacvote::field_binding`]) and rejected without one. Returns the
+/// CAC ID of the verified certificate for voter objects, and `None` for other
+/// objects, which pass.
+pub(crate) fn verify_voter_object_signer(
+    object: &SignedObject,
+    ca_certificates: &[X509],
+) -> color_eyre::Result<Option<String>> {
+    let payload = object.try_to_inner()?;
+    let (jurisdiction_code, common_access_card_id, names) = match &payload {
+        Payload::RegistrationRequest(registration_request) => (
+            &registration_request.jurisdiction_code,
+            &registration_request.common_access_card_id,
+            Some((
+                &registration_request.given_name,
+                &registration_request.family_name,
+            )),
+        ),
+        Payload::CastBallot(cast_ballot) => (
+            &cast_ballot.jurisdiction_code,
+            &cast_ballot.common_access_card_id,
+            None,
+        ),
+        _ => return Ok(None),
+    };
+
+    let certificate = verify_common_access_card_certificate(&object.certificates, ca_certificates)?;
+    let Some(holder) = CommonAccessCardHolder::from_certificate(&certificate) else {
+        bail!("Certificate is not a Common Access Card certificate");
+    };
+
+    // bindings are always of trimmed values ignoring case, but plaintext CAC
+    // IDs must match exactly
+    let matches_holder = |field_name: &str, value: &str, expected: &str| {
+        if is_encrypted_field_value(value) {
+            let Some(binding) = encrypted_field_binding(value) else {
+                bail!("Encrypted field {field_name} must be bound to the signer");
+            };
+            Ok(binding
+                == field_binding(
+                    payload.object_type(),
+                    jurisdiction_code.as_str(),
+                    field_name,
+                    expected,
+                ))
+        } else if field_name == "commonAccessCardId" {
+            Ok(value == expected)
+        } else {
+            Ok(value.trim().eq_ignore_ascii_case(expected))
+        }
+    };
+
+    if !matches_holder(
+        "commonAccessCardId",
+        common_access_card_id,
+        &holder.common_access_card_id,
+    )? {
+        bail!("Common access card ID does not match the signing certificate");
+    }
+
+    if let Some((given_name, family_name)) = names {
+        if !matches_holder("givenName", given_name, &holder.given_name)?
+            || !matches_holder("familyName", family_name, &holder.surname)?
+        {
+            bail!("Name does not match the signing certificate");
+        }
+    }
+
+    Ok(Some(holder.common_access_card_id))
+}
+
+#[cfg(test)]
+pub(crate) mod test_utils {
+    use openssl::{
+        asn1::Asn1Time,
+        bn::BigNum,
+        ec::{EcGroup, EcKey},
+        hash::MessageDigest,
+        nid::Nid,
+        pkey::{PKey, Private},
+        x509::{X509Builder, X509NameBuilder},
+    };
+
+    use super::*;
+
+    fn generate_key() -> PKey<Private> {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap()
+    }
+
+    fn build_certificate(
+        subject: &[(&str, &str)],
+        key: &PKey<Private>,
+        issuer: Option<(&X509, &PKey<Private>)>,
+        days_valid: u32,
+    ) -> X509 {
+        let mut name = X509NameBuilder::new().unwrap();
+        for (field, value) in subject {
+            name.append_entry_by_text(field, value).unwrap();
+        }
+        let name = name.build();
+
+        let mut builder = X509Builder::new().unwrap();
+        builder.set_version(2).unwrap();
+        builder
+            .set_serial_number(&BigNum::from_u32(1).unwrap().to_asn1_integer().unwrap())
+            .unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_pubkey(key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(days_valid).unwrap())
+            .unwrap();
+
+        match issuer {
+            Some((issuer_certificate, issuer_key)) => {
+                builder
+                    .set_issuer_name(issuer_certificate.subject_name())
+                    .unwrap();
+                builder.sign(issuer_key, MessageDigest::sha256()).unwrap();
+            }
+            None => {
+                builder.set_issuer_name(&name).unwrap();
+                builder
+                    .append_extension(
+                        openssl::x509::extension::BasicConstraints::new()
+                            .critical()
+                            .ca()
+                            .build()
+                            .unwrap(),
+                    )
+                    .unwrap();
+                builder.sign(key, MessageDigest::sha256()).unwrap();
+            }
+        }
+
+        builder.build()
+    }
+
+    /// A test CA and a function to issue CAC certificates from it.
+    pub(crate) struct TestCertificateAuthority {
+        pub(crate) certificate: X509,
+        key: PKey<Private>,
+    }
+
+    impl TestCertificateAuthority {
+        pub(crate) fn new() -> Self {
+            let key = generate_key();
+            Self {
+                certificate: build_certificate(&[("CN", "Test CAC CA")], &key, None, 30),
+                key,
+            }
+        }
+
+        /// Issues a certificate with `common_name`, returning it as a PEM
+        /// stack along with its private key.
+        pub(crate) fn issue(&self, common_name: &str) -> (Vec<u8>, PKey<Private>) {
+            self.issue_with_subject(&[("CN", common_name)])
+        }
+
+        /// Issues a certificate whose subject has the given fields, named by
+        /// short name or OID, e.g. the VotingWorks card type field.
+        pub(crate) fn issue_with_subject(
+            &self,
+            subject: &[(&str, &str)],
+        ) -> (Vec<u8>, PKey<Private>) {
+            let key = generate_key();
+            let certificate =
+                build_certificate(subject, &key, Some((&self.certificate, &self.key)), 30);
+            (certificate.to_pem().unwrap(), key)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::pkey::{PKey, Private};
+    use types_rs::cacvote::{
+        CastBallot, JurisdictionCode, JurisdictionEncryptionKey, RegistrationRequest,
+    };
+    use uuid::Uuid;
+
+    use super::{test_utils::TestCertificateAuthority, *};
+
+    #[test]
+    fn test_verify_common_access_card_certificate() {
+        let ca = TestCertificateAuthority::new();
+        let other_ca = TestCertificateAuthority::new();
+
+        let (certificates, _) = ca.issue("DOE.JOHN.EDWARD.1234567890");
+        let certificate = verify_common_access_card_certificate(
+            &certificates,
+            std::slice::from_ref(&ca.certificate),
+        )
+        .unwrap();
+        assert_eq!(
+            CommonAccessCardHolder::from_certificate(&certificate),
+            Some(CommonAccessCardHolder {
+                common_access_card_id: "1234567890".to_owned(),
+                surname: "DOE".to_owned(),
+                given_name: "JOHN".to_owned(),
+                middle_name: "EDWARD".to_owned(),
+            })
+        );
+
+        // issued by an untrusted CA
+        assert!(verify_common_access_card_certificate(
+            &certificates,
+            std::slice::from_ref(&other_ca.certificate)
+        )
+        .is_err());
+        assert!(verify_common_access_card_certificate(&certificates, &[]).is_err());
+
+        // not a CAC certificate
+        let (certificates, _) = ca.issue("vx-admin");
+        assert!(verify_common_access_card_certificate(
+            &certificates,
+            std::slice::from_ref(&ca.certificate)
+        )
+        .is_err());
+
+        assert!(verify_common_access_card_certificate(b"", &[ca.certificate]).is_err());
+    }
+
+    #[test]
+    fn test_common_access_card_holder_id() {
+        let ca = TestCertificateAuthority::new();
+        for (common_name, expected) in [
+            ("DOE.JOHN.EDWARD.1234567890", Some("1234567890")),
+            ("DOE.JOHN..1234567890", Some("1234567890")),
+            ("DOE.JOHN.EDWARD.", None),
+            ("DOE.JOHN.EDWARD.12345X", None),
+            ("DOE.JOHN.1234567890", None),
+        ] {
+            let (certificates, _) = ca.issue(common_name);
+            let certificate = X509::from_pem(&certificates).unwrap();
+            assert_eq!(
+                CommonAccessCardHolder::from_certificate(&certificate)
+                    .map(|holder| holder.common_access_card_id)
+                    .as_deref(),
+                expected,
+                "{common_name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify_voter_object_signer() {
+        let ca = TestCertificateAuthority::new();
+        let ca_certificates = std::slice::from_ref(&ca.certificate);
+        let (certificates, private_key) = ca.issue("DOE.JOHN.EDWARD.1234567890");
+        let jurisdiction_code = JurisdictionCode::try_from("st.dev-jurisdiction").unwrap();
+
+        let sign = |payload: Payload, certificates: &[u8], private_key: &PKey<Private>| {
+            SignedObject::from_payload(
+                &payload,
+                X509::stack_from_pem(certificates).unwrap(),
+                private_key,
+            )
+            .unwrap()
+        };
+        let registration_request = |common_access_card_id: &str, given_name: &str| {
+            Payload::RegistrationRequest(RegistrationRequest {
+                common_access_card_id: common_access_card_id.to_owned(),
+                jurisdiction_code: jurisdiction_code.clone(),
+                given_name: given_name.to_owned(),
+                family_name: "Doe".to_owned(),
+            })
+        };
+        // an encrypted value bound to `plaintext`, whose ciphertext the server
+        // can't check
+        let bound = |object_type: &str, field_name: &str, plaintext: &str| {
+            format!(
+                "hpke:0123456789abcdef:AAAA:{}",
+                field_binding(
+                    object_type,
+                    jurisdiction_code.as_str(),
+                    field_name,
+                    plaintext
+                )
+            )
+        };
+        let cast_ballot = |common_access_card_id: &str| {
+            Payload::CastBallot(CastBallot {
+                common_access_card_id: common_access_card_id.to_owned(),
+                jurisdiction_code: jurisdiction_code.clone(),
+                registration_request_object_id: Uuid::new_v4(),
+                registration_object_id: Uuid::new_v4(),
+                election_object_id: Uuid::new_v4(),
+                electionguard_encrypted_ballot: "encrypted ballot".to_owned(),
+            })
+        };
+
+        for payload in [
+            registration_request("1234567890", "John"),
+            registration_request("1234567890", " JOHN "),
+            cast_ballot("1234567890"),
+            // encrypted fields are compared by their bindings
+            registration_request(
+                &bound("RegistrationRequest", "commonAccessCardId", "1234567890"),
+                &bound("RegistrationRequest", "givenName", "John"),
+            ),
+            cast_ballot(&bound("CastBallot", "commonAccessCardId", "1234567890")),
+        ] {
+            // the CAC ID comes from the certificate
+            assert_eq!(
+                verify_voter_object_signer(
+                    &sign(payload, &certificates, &private_key),
+                    ca_certificates,
+                )
+                .unwrap(),
+                Some("1234567890".to_owned())
+            );
+        }
+
+        for payload in [
+            registration_request("0987654321", "John"),
+            registration_request("1234567890", "Jane"),
+            cast_ballot("0987654321"),
+            registration_request(
+                &bound("RegistrationRequest", "commonAccessCardId", "0987654321"),
+                "John",
+            ),
+            registration_request(
+                "1234567890",
+                &bound("RegistrationRequest", "givenName", "Jane"),
+            ),
+            cast_ballot(&bound("CastBallot", "commonAccessCardId", "0987654321")),
+            // bound to another field
+            cast_ballot(&bound("CastBallot", "givenName", "1234567890")),
+            // encrypted fields can't be compared without a binding
+            registration_request("hpke:0123456789abcdef:AAAA", "John"),
+            cast_ballot("hpke:0123456789abcdef:AAAA"),
+        ] {
+            assert!(verify_voter_object_signer(
+                &sign(payload, &certificates, &private_key),
+                ca_certificates,
+            )
+            .is_err());
+        }
+
+        // signed by a CAC from an untrusted CA
+        let (untrusted_certificates, untrusted_private_key) =
+            TestCertificateAuthority::new().issue("DOE.JOHN.EDWARD.1234567890");
+        assert!(verify_voter_object_signer(
+            &sign(
+                cast_ballot("1234567890"),
+                &untrusted_certificates,
+                &untrusted_private_key
+            ),
+            ca_certificates,
+        )
+        .is_err());
+
+        // objects from officials are not checked
+        let (official_certificates, official_private_key) = ca.issue("vx-admin");
+        assert_eq!(
+            verify_voter_object_signer(
+                &sign(
+                    Payload::JurisdictionEncryptionKey(JurisdictionEncryptionKey {
+                        jurisdiction_code: jurisdiction_code.clone(),
+                        public_key: vec![],
+                    }),
+                    &official_certificates,
+                    &official_private_key,
+                ),
+                &[],
+            )
+            .unwrap(),
+            None
+        );
+    }
+}
@@ -2,28 +2,119 @@ pub use cacvote_server_client::{Client, Error, Result};
 
 #[cfg(test)]
 mod tests {
-    use std::net::TcpListener;
+    use std::{net::TcpListener, sync::OnceLock};
 
+    use cacvote_server_client::encoding::{BodyEncoding, ContentEncoding};
     use openssl::{
+        ec::{EcGroup, EcKey},
         hash::MessageDigest,
+        nid::Nid,
         pkey::{PKey, Private, Public},
         sign::{Signer, Verifier},
         x509::X509,
     };
-    use types_rs::cacvote::{
-        JournalEntryAction, JurisdictionCode, Payload, RegistrationRequest, SignedObject,
+    use types_rs::{
+        cacvote::{
+            current_schema_version, hpke, tracking_code, BallotTrackingStage,
+            BallotVerificationPayload, BlobContent, BlobReference, BlobUpload, CastBallot,
+            Cosignature, Cosignatures, CreateVoterSessionRequest, DecryptedElectionTally, Election,
+            EncryptedElectionTally, JournalEntryAction, JurisdictionCode,
+            JurisdictionEncryptionKey, ObjectSearchPage, ObjectSearchQuery, ObjectView, Payload,
+            ReadinessReport, Registration, RegistrationConflictKind, RegistrationRequest,
+            ShuffledEncryptedCastBallots, SignatureAlgorithm, SignedBuffer, SignedObject,
+            SignerCertificate, SnapshotQuery, VoterSessionChallenge, ENVELOPE_VERSION_FIELD_NAME,
+            PAYLOAD_ENVELOPE_VERSION, SCHEMA_VERSION_FIELD_NAME, TRACKING_CODE_LENGTH,
+        },
+        election::{BallotStyleId, ElectionDefinition, PrecinctId},
     };
     use uuid::Uuid;
 
     use super::*;
-    use crate::app;
+    use crate::{
+        app,
+        cac::test_utils::TestCertificateAuthority,
+        config::{Config, OfficialApiKey},
+        db,
+    };
+
+    const OFFICIAL_API_KEY: &str = "st.dev-jurisdiction-api-key";
 
+    /// An official of `jurisdiction`, the jurisdiction of
+    /// [`load_election_manager_keypairs`].
+    const OTHER_OFFICIAL_API_KEY: &str = "jurisdiction-api-key";
+
+    /// Sets up a server and a client authenticated as an official of
+    /// `st.dev-jurisdiction`, the jurisdiction of [`load_keypair`]. The server
+    /// trusts the CA of [`load_voter_keypair`].
     fn setup(pool: sqlx::PgPool) -> color_eyre::Result<Client> {
+        Ok(Client::new(spawn_server(
+            pool,
+            SignatureAlgorithm::ALL.to_vec(),
+            vec![voter_certificate_authority().certificate.clone()],
+        )?)
+        .with_api_key(OFFICIAL_API_KEY))
+    }
+
+    fn setup_with_allowed_signature_algorithms(
+        pool: sqlx::PgPool,
+        allowed_signature_algorithms: Vec<SignatureAlgorithm>,
+    ) -> color_eyre::Result<Client> {
+        Ok(Client::new(spawn_server(
+            pool,
+            allowed_signature_algorithms,
+            vec![voter_certificate_authority().certificate.clone()],
+        )?))
+    }
+
+    fn spawn_server(
+        pool: sqlx::PgPool,
+        allowed_signature_algorithms: Vec<SignatureAlgorithm>,
+        cac_ca_certificates: Vec<X509>,
+    ) -> color_eyre::Result<reqwest::Url> {
+        spawn_configured_server(
+            pool,
+            allowed_signature_algorithms,
+            cac_ca_certificates,
+            |_| {},
+        )
+    }
+
+    /// Like [`spawn_server`], letting `configure` change the rest of the
+    /// server's configuration.
+    fn spawn_configured_server(
+        pool: sqlx::PgPool,
+        allowed_signature_algorithms: Vec<SignatureAlgorithm>,
+        cac_ca_certificates: Vec<X509>,
+        configure: impl FnOnce(&mut Config),
+    ) -> color_eyre::Result<reqwest::Url> {
         let listener = TcpListener::bind("0.0.0.0:0")?;
         let addr = listener.local_addr()?;
+        let mut config = Config {
+            database_url: String::new(),
+            port: addr.port(),
+            log_level: tracing::Level::INFO,
+            otlp_endpoint: None,
+            allowed_signature_algorithms,
+            allow_unknown_object_types: false,
+            redaction_key: "redaction-key".to_owned(),
+            official_api_keys: vec![
+                OfficialApiKey {
+                    jurisdiction_code: JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
+                    key: OFFICIAL_API_KEY.to_owned(),
+                },
+                OfficialApiKey {
+                    jurisdiction_code: JurisdictionCode::try_from("jurisdiction").unwrap(),
+                    key: OTHER_OFFICIAL_API_KEY.to_owned(),
+                },
+            ],
+            cac_ca_certificates,
+            vx_ca_certificates: vx_ca_certificates(),
+            snapshot_signing_key: Some(snapshot_signing_key().clone()),
+        };
+        configure(&mut config);
 
         tokio::spawn(async move {
-            let app = app::setup(pool).await.unwrap();
+            let app = app::setup(pool, config).await.unwrap();
             axum::Server::from_tcp(listener)
                 .unwrap()
                 .serve(app.into_make_service())
@@ -31,7 +122,15 @@ mod tests {
                 .unwrap();
         });
 
-        Ok(Client::new(format!("http://{addr}").parse()?))
+        Ok(format!("http://{addr}").parse()?)
+    }
+
+    fn snapshot_signing_key() -> &'static PKey<Private> {
+        static KEY: OnceLock<PKey<Private>> = OnceLock::new();
+        KEY.get_or_init(|| {
+            let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+            PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap()
+        })
     }
 
     fn load_keypair() -> color_eyre::Result<(Vec<u8>, PKey<Public>, PKey<Private>)> {
@@ -47,6 +146,104 @@ mod tests {
         Ok((certificates, public_key, private_key))
     }
 
+    fn voter_certificate_authority() -> &'static TestCertificateAuthority {
+        static CA: OnceLock<TestCertificateAuthority> = OnceLock::new();
+        CA.get_or_init(TestCertificateAuthority::new)
+    }
+
+    /// Issues a CAC certificate for John Doe, CAC ID 1234567890, who signs
+    /// voter objects.
+    fn load_voter_keypair() -> color_eyre::Result<(Vec<u8>, PKey<Public>, PKey<Private>)> {
+        let (certificates, private_key) =
+            voter_certificate_authority().issue("DOE.JOHN.EDWARD.1234567890");
+        let public_key = X509::from_pem(&certificates)?.public_key()?;
+        Ok((certificates, public_key, private_key))
+    }
+
+    /// Loads two different election manager cards, both for the
+    /// "jurisdiction" jurisdiction, each with the certificate of the VxAdmin
+    /// that programmed it. They chain to [`vx_ca_certificates`].
+    fn load_election_manager_keypairs() -> color_eyre::Result<[(Vec<X509>, PKey<Private>); 2]> {
+        Ok([
+            (
+                vec![
+                    X509::from_der(include_bytes!(
+                        "../../../../libs/auth/certs/test/set-1/election-manager/card-vx-admin-cert.der"
+                    ))?,
+                    X509::from_pem(include_bytes!(
+                        "../../../../libs/auth/certs/test/set-1/vx-admin-cert-authority-cert.pem"
+                    ))?,
+                ],
+                PKey::private_key_from_pem(include_bytes!(
+                    "../../../../libs/auth/certs/test/set-1/election-manager/card-vx-admin-private-key.pem"
+                ))?,
+            ),
+            (
+                vec![
+                    X509::from_der(include_bytes!(
+                        "../../../../libs/auth/certs/test/set-2/election-manager/card-vx-admin-cert.der"
+                    ))?,
+                    X509::from_pem(include_bytes!(
+                        "../../../../libs/auth/certs/test/set-2/vx-admin-cert-authority-cert.pem"
+                    ))?,
+                ],
+                PKey::private_key_from_pem(include_bytes!(
+                    "../../../../libs/auth/certs/test/set-2/election-manager/card-vx-admin-private-key.pem"
+                ))?,
+            ),
+        ])
+    }
+
+    /// The VotingWorks root CAs of the dev and test certificates.
+    fn vx_ca_certificates() -> Vec<X509> {
+        [
+            &include_bytes!("../../../../libs/auth/certs/dev/vx-cert-authority-cert.pem")[..],
+            &include_bytes!("../../../../libs/auth/certs/test/set-1/vx-cert-authority-cert.pem")[..],
+            &include_bytes!("../../../../libs/auth/certs/test/set-2/vx-cert-authority-cert.pem")[..],
+        ]
+        .into_iter()
+        .map(|pem| X509::from_pem(pem).unwrap())
+        .collect()
+    }
+
+    /// Creates an election for `jurisdiction_code`, returning its object ID.
+    async fn create_election(
+        client: &Client,
+        jurisdiction_code: &str,
+        certificates: Vec<X509>,
+        private_key: &PKey<Private>,
+    ) -> color_eyre::Result<Uuid> {
+        Ok(client
+            .create_object(SignedObject::from_payload(
+                &Payload::Election(Election {
+                    jurisdiction_code: JurisdictionCode::try_from(jurisdiction_code).unwrap(),
+                    election_definition: ElectionDefinition::try_from(
+                        &include_bytes!("../tests/fixtures/electionFamousNames2021.json")[..],
+                    )?,
+                    mailing_address: "123 Main St".to_owned(),
+                    electionguard_election_metadata_blob: vec![].into(),
+                }),
+                certificates,
+                private_key,
+            )?)
+            .await?)
+    }
+
+    fn registration(
+        common_access_card_id: &str,
+        jurisdiction_code: &str,
+        election_object_id: Uuid,
+    ) -> Payload {
+        Payload::Registration(Registration {
+            common_access_card_id: common_access_card_id.to_owned(),
+            jurisdiction_code: JurisdictionCode::try_from(jurisdiction_code).unwrap(),
+            registration_request_object_id: Uuid::new_v4(),
+            election_object_id,
+            ballot_style_id: BallotStyleId::from("1".to_owned()),
+            precinct_id: PrecinctId::from("1".to_owned()),
+        })
+    }
+
     fn sign_and_verify(
         payload: &[u8],
         private_key: &PKey<Private>,
@@ -75,8 +272,8 @@ mod tests {
             family_name: "Doe".to_owned(),
             jurisdiction_code: JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
         });
-        let payload = serde_json::to_vec(&payload)?;
-        let (certificates, public_key, private_key) = load_keypair()?;
+        let payload = payload.to_canonical_json()?;
+        let (certificates, public_key, private_key) = load_voter_keypair()?;
         let signature = sign_and_verify(&payload, &private_key, &public_key)?;
 
         // create the object
@@ -87,6 +284,9 @@ mod tests {
                 payload,
                 certificates: certificates.clone(),
                 signature: signature.clone(),
+                // predates algorithm identifiers, so ES256 is inferred
+                signature_algorithm: None,
+                cosignatures: Cosignatures::default(),
             })
             .await?;
 
@@ -130,7 +330,11 @@ mod tests {
         );
 
         // get the object
-        let signed_object = client.get_object_by_id(object_id).await?.unwrap();
+        let signed_object = client
+            .get_object_by_id(object_id)
+            .await?
+            .and_then(ObjectView::into_signed_object)
+            .unwrap();
 
         let round_trip_registration_request = match signed_object.try_to_inner()? {
             Payload::RegistrationRequest(registration_request) => registration_request,
@@ -152,6 +356,121 @@ mod tests {
         Ok(())
     }
 
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_binary_encodings(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let base_url = spawn_server(
+            pool,
+            SignatureAlgorithm::ALL.to_vec(),
+            vec![voter_certificate_authority().certificate.clone()],
+        )?;
+        let (certificates, _, private_key) = load_voter_keypair()?;
+        let payload = Payload::RegistrationRequest(RegistrationRequest {
+            common_access_card_id: "1234567890".to_owned(),
+            given_name: "John".to_owned(),
+            family_name: "Doe".to_owned(),
+            jurisdiction_code: JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
+        });
+
+        for body_encoding in BodyEncoding::ALL {
+            for request_compression in [
+                None,
+                Some(ContentEncoding::Gzip),
+                Some(ContentEncoding::Zstd),
+            ] {
+                let mut client = Client::new(base_url.clone())
+                    .with_api_key(OFFICIAL_API_KEY)
+                    .with_body_encoding(body_encoding);
+                if let Some(content_encoding) = request_compression {
+                    client = client.with_request_compression(content_encoding);
+                }
+
+                let object = SignedObject::from_payload(
+                    &payload,
+                    X509::stack_from_pem(&certificates)?,
+                    &private_key,
+                )?;
+                let signature = object.signature.clone();
+                let object_id = client.create_object(object).await?;
+
+                let round_trip = client
+                    .get_object_by_id(object_id)
+                    .await?
+                    .and_then(ObjectView::into_signed_object)
+                    .unwrap();
+                assert_eq!(round_trip.payload, payload.to_canonical_json()?);
+                assert_eq!(round_trip.certificates, certificates);
+                assert_eq!(round_trip.signature, signature);
+                assert!(round_trip.verify()?);
+
+                let page = client
+                    .search_objects(&ObjectSearchQuery {
+                        signer_fingerprint: Some(
+                            SignerCertificate::from_pem_stack(&certificates)?.fingerprint,
+                        ),
+                        ..Default::default()
+                    })
+                    .await?;
+                assert!(page
+                    .objects
+                    .iter()
+                    .any(|result| result.metadata.id == object_id));
+            }
+        }
+
+        // responses are encoded and compressed as asked
+        let object_id = Client::new(base_url.clone())
+            .create_object(SignedObject::from_payload(
+                &payload,
+                X509::stack_from_pem(&certificates)?,
+                &private_key,
+            )?)
+            .await?;
+        let response = reqwest::Client::new()
+            .get(base_url.join(&format!("/api/objects/{object_id}"))?)
+            .header(reqwest::header::ACCEPT, "application/cbor")
+            .header(reqwest::header::ACCEPT_ENCODING, "zstd")
+            .send()
+            .await?
+            .error_for_status()?;
+        assert_eq!(
+            response.headers()[reqwest::header::CONTENT_TYPE],
+            "application/cbor"
+        );
+        assert_eq!(
+            response.headers()[reqwest::header::CONTENT_ENCODING],
+            "zstd"
+        );
+        let body = ContentEncoding::Zstd.decompress(&response.bytes().await?, usize::MAX)?;
+        let view: ObjectView = BodyEncoding::Cbor.decode(&body)?;
+        assert_eq!(view.id(), object_id);
+
+        // JSON is the default
+        let response = reqwest::Client::new()
+            .get(base_url.join(&format!("/api/objects/{object_id}"))?)
+            .header(reqwest::header::ACCEPT, "text/html")
+            .send()
+            .await?
+            .error_for_status()?;
+        assert_eq!(
+            response.headers()[reqwest::header::CONTENT_TYPE],
+            "application/json"
+        );
+
+        // unsupported request bodies are rejected
+        let response = reqwest::Client::new()
+            .post(base_url.join("/api/objects")?)
+            .header(reqwest::header::CONTENT_TYPE, "text/plain")
+            .body("object")
+            .send()
+            .await?;
+        assert_eq!(
+            response.status(),
+            reqwest::StatusCode::UNSUPPORTED_MEDIA_TYPE
+        );
+
+        Ok(())
+    }
+
     #[sqlx::test(migrations = "db/migrations")]
     async fn test_invalid_certificate(pool: sqlx::PgPool) -> color_eyre::Result<()> {
         let client = setup(pool)?;
@@ -162,7 +481,7 @@ mod tests {
             family_name: "Doe".to_owned(),
             jurisdiction_code: JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
         });
-        let payload = serde_json::to_vec(&payload)?;
+        let payload = payload.to_canonical_json()?;
 
         client
             .create_object(SignedObject {
@@ -172,6 +491,8 @@ mod tests {
                 // invalid certificates and signature
                 certificates: vec![],
                 signature: vec![],
+                signature_algorithm: Some(SignatureAlgorithm::EcdsaP256Sha256),
+                cosignatures: Cosignatures::default(),
             })
             .await
             .unwrap_err();
@@ -181,4 +502,2243 @@ mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_non_canonical_payload(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let client = setup(pool)?;
+
+        let payload = Payload::RegistrationRequest(RegistrationRequest {
+            common_access_card_id: "1234567890".to_owned(),
+            given_name: "John".to_owned(),
+            family_name: "Doe".to_owned(),
+            jurisdiction_code: JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
+        });
+        // field order follows the struct, not the canonical ordering
+        let payload = serde_json::to_vec(&payload)?;
+        let (certificates, public_key, private_key) = load_voter_keypair()?;
+        let signature = sign_and_verify(&payload, &private_key, &public_key)?;
+
+        // rejected even without an algorithm identifier, though such legacy
+        // objects verify as stored
+        let legacy_object = SignedObject {
+            id: Uuid::new_v4(),
+            election_id: None,
+            payload,
+            certificates,
+            signature,
+            signature_algorithm: None,
+            cosignatures: Cosignatures::default(),
+        };
+        assert!(legacy_object.verify()?);
+        client.create_object(legacy_object).await.unwrap_err();
+
+        // check that there are no journal entries
+        assert_eq!(client.get_journal_entries(None, None).await?, vec![]);
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_unknown_object_type(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        // e.g. from a newer client
+        let payload: Payload = serde_json::from_value(serde_json::json!({
+            "objectType": "PollWorkerCheckIn",
+            "jurisdictionCode": "st.dev-jurisdiction",
+            "pollWorkerName": "Jane Doe",
+        }))?;
+        assert!(payload.is_unknown());
+        let (certificates, _, private_key) = load_keypair()?;
+        let sign = || {
+            SignedObject::from_payload(&payload, X509::stack_from_pem(&certificates)?, &private_key)
+        };
+
+        // rejected by default
+        let client = setup(pool.clone())?;
+        client.create_object(sign()?).await.unwrap_err();
+        assert_eq!(client.get_journal_entries(None, None).await?, vec![]);
+
+        let client = Client::new(spawn_configured_server(
+            pool,
+            SignatureAlgorithm::ALL.to_vec(),
+            vec![voter_certificate_authority().certificate.clone()],
+            |config| config.allow_unknown_object_types = true,
+        )?);
+        let signed_object = sign()?;
+        let payload_bytes = signed_object.payload.clone();
+        let object_id = client.create_object(signed_object).await?;
+
+        let entries = client.get_journal_entries(None, None).await?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].object_id, object_id);
+        assert_eq!(entries[0].object_type, "PollWorkerCheckIn");
+
+        // relayed exactly as signed
+        let round_trip_object = client
+            .get_object_by_id(object_id)
+            .await?
+            .and_then(ObjectView::into_signed_object)
+            .unwrap();
+        assert_eq!(round_trip_object.payload, payload_bytes);
+        assert!(round_trip_object.verify()?);
+        let round_trip_payload = round_trip_object.try_to_inner()?;
+        assert_eq!(round_trip_payload.object_type(), "PollWorkerCheckIn");
+        assert!(round_trip_payload.is_unknown());
+
+        // a known object type in a newer envelope would otherwise skip the
+        // checks of its type, here that a cast ballot is signed by the voter
+        let payload: Payload = serde_json::from_value(serde_json::json!({
+            "objectType": "CastBallot",
+            "envelopeVersion": PAYLOAD_ENVELOPE_VERSION + 1,
+            "commonAccessCardId": "1234567890",
+            "jurisdictionCode": "st.dev-jurisdiction",
+            "registrationRequestObjectId": Uuid::new_v4(),
+            "registrationObjectId": Uuid::new_v4(),
+            "electionObjectId": Uuid::new_v4(),
+            "electionguardEncryptedBallot": "{}",
+        }))?;
+        assert!(payload.is_unknown());
+        let error = client
+            .create_object(SignedObject::from_payload(
+                &payload,
+                X509::stack_from_pem(&certificates)?,
+                &private_key,
+            )?)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            cacvote_server_client::Error::Reqwest(ref error)
+                if error.status() == Some(reqwest::StatusCode::BAD_REQUEST)
+        ));
+
+        // and likewise in a newer schema version of its type
+        let mut fields = serde_json::to_value(&payload)?;
+        fields[ENVELOPE_VERSION_FIELD_NAME] = PAYLOAD_ENVELOPE_VERSION.into();
+        fields[SCHEMA_VERSION_FIELD_NAME] = (current_schema_version("CastBallot") + 1).into();
+        let payload: Payload = serde_json::from_value(fields)?;
+        assert!(payload.is_unknown());
+        let error = client
+            .create_object(SignedObject::from_payload(
+                &payload,
+                X509::stack_from_pem(&certificates)?,
+                &private_key,
+            )?)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            cacvote_server_client::Error::Reqwest(ref error)
+                if error.status() == Some(reqwest::StatusCode::BAD_REQUEST)
+        ));
+        assert_eq!(client.get_journal_entries(None, None).await?.len(), 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_explicit_signature_algorithm(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let client = setup(pool)?;
+
+        let payload = Payload::RegistrationRequest(RegistrationRequest {
+            common_access_card_id: "1234567890".to_owned(),
+            given_name: "John".to_owned(),
+            family_name: "Doe".to_owned(),
+            jurisdiction_code: JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
+        });
+        let (certificates, _, private_key) = load_voter_keypair()?;
+        let signed_object = SignedObject::from_payload(
+            &payload,
+            X509::stack_from_pem(&certificates)?,
+            &private_key,
+        )?;
+        assert_eq!(
+            signed_object.signature_algorithm,
+            Some(SignatureAlgorithm::EcdsaP256Sha256)
+        );
+
+        let object_id = client.create_object(signed_object).await?;
+        let round_trip_object = client
+            .get_object_by_id(object_id)
+            .await?
+            .and_then(ObjectView::into_signed_object)
+            .unwrap();
+        assert_eq!(
+            round_trip_object.signature_algorithm,
+            Some(SignatureAlgorithm::EcdsaP256Sha256)
+        );
+        assert!(round_trip_object.verify()?);
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_disallowed_signature_algorithm(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let client =
+            setup_with_allowed_signature_algorithms(pool, vec![SignatureAlgorithm::RsaPssSha256])?;
+
+        let payload = Payload::RegistrationRequest(RegistrationRequest {
+            common_access_card_id: "1234567890".to_owned(),
+            given_name: "John".to_owned(),
+            family_name: "Doe".to_owned(),
+            jurisdiction_code: JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
+        });
+        let (certificates, _, private_key) = load_voter_keypair()?;
+        let signed_object = SignedObject::from_payload(
+            &payload,
+            X509::stack_from_pem(&certificates)?,
+            &private_key,
+        )?;
+
+        let legacy_object = SignedObject {
+            id: Uuid::new_v4(),
+            election_id: None,
+            payload: signed_object.payload.clone(),
+            certificates: signed_object.certificates.clone(),
+            signature: signed_object.signature.clone(),
+            signature_algorithm: None,
+            cosignatures: Cosignatures::default(),
+        };
+
+        // explicit algorithm
+        client.create_object(signed_object).await.unwrap_err();
+
+        // inferred algorithm
+        client.create_object(legacy_object).await.unwrap_err();
+
+        // check that there are no journal entries
+        assert_eq!(client.get_journal_entries(None, None).await?, vec![]);
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_multi_signature_policy(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let client = setup(pool)?;
+
+        let jurisdiction_code = JurisdictionCode::try_from("jurisdiction").unwrap();
+        let (certificates, _, private_key) = load_keypair()?;
+        let election_object = SignedObject::from_payload(
+            &Payload::Election(Election {
+                jurisdiction_code: jurisdiction_code.clone(),
+                election_definition: ElectionDefinition::try_from(
+                    &include_bytes!("../tests/fixtures/electionFamousNames2021.json")[..],
+                )?,
+                mailing_address: "123 Main St".to_owned(),
+                electionguard_election_metadata_blob: vec![].into(),
+            }),
+            X509::stack_from_pem(&certificates)?,
+            &private_key,
+        )?;
+        let election_object_id = client.create_object(election_object).await?;
+
+        let payload = Payload::DecryptedElectionTally(DecryptedElectionTally {
+            jurisdiction_code,
+            election_object_id,
+            electionguard_decrypted_tally: b"decrypted tally".to_vec(),
+        });
+        let [(first_certificates, first_private_key), (second_certificates, second_private_key)] =
+            load_election_manager_keypairs()?;
+        let mut signed_object =
+            SignedObject::from_payload(&payload, first_certificates.clone(), &first_private_key)?;
+
+        // one election manager is not enough
+        assert_eq!(
+            signed_object.signature_policy_status()?.signers_collected,
+            1
+        );
+        let single_signature_object = SignedObject {
+            id: Uuid::new_v4(),
+            election_id: signed_object.election_id,
+            payload: signed_object.payload.clone(),
+            certificates: signed_object.certificates.clone(),
+            signature: signed_object.signature.clone(),
+            signature_algorithm: signed_object.signature_algorithm,
+            cosignatures: Cosignatures::default(),
+        };
+        client
+            .create_object(single_signature_object)
+            .await
+            .unwrap_err();
+
+        // signing again with the same card doesn't count as a second signer
+        signed_object.add_cosignature(Cosignature::sign(
+            &signed_object.payload,
+            first_certificates,
+            &first_private_key,
+        )?);
+        assert_eq!(
+            signed_object.signature_policy_status()?.signers_collected,
+            1
+        );
+
+        // cards from a CA the server doesn't trust look like election managers
+        // but don't count
+        let forger = TestCertificateAuthority::new();
+        let forged_signers = ["1", "2"].map(|serial| {
+            forger.issue_with_subject(&[
+                ("CN", serial),
+                ("1.3.6.1.4.1.59817.2", "jurisdiction"),
+                ("1.3.6.1.4.1.59817.3", "election-manager"),
+            ])
+        });
+        let mut forged_object = SignedObject::from_payload(
+            &payload,
+            X509::stack_from_pem(&forged_signers[0].0)?,
+            &forged_signers[0].1,
+        )?;
+        forged_object.add_cosignature(Cosignature::sign(
+            &forged_object.payload,
+            X509::stack_from_pem(&forged_signers[1].0)?,
+            &forged_signers[1].1,
+        )?);
+        assert!(forged_object.signature_policy_status()?.is_satisfied());
+        assert_eq!(
+            forged_object
+                .trusted_signature_policy_status(&vx_ca_certificates())?
+                .signers_collected,
+            0
+        );
+        client.create_object(forged_object).await.unwrap_err();
+
+        // a second election manager satisfies the policy
+        signed_object.add_cosignature(Cosignature::sign(
+            &signed_object.payload,
+            second_certificates,
+            &second_private_key,
+        )?);
+        assert!(signed_object.signature_policy_status()?.is_satisfied());
+
+        let object_id = client.create_object(signed_object).await?;
+        let round_trip_object = client
+            .get_object_by_id(object_id)
+            .await?
+            .and_then(ObjectView::into_signed_object)
+            .unwrap();
+        assert_eq!(round_trip_object.cosignatures.len(), 2);
+        assert!(round_trip_object.verify()?);
+
+        // the object is listed by its cosigners as well as its signer
+        let cosigner_certificate =
+            SignerCertificate::from_pem_stack(&round_trip_object.cosignatures[1].certificates)?;
+        assert_eq!(
+            client
+                .get_object_ids_by_signer(&cosigner_certificate.fingerprint)
+                .await?,
+            vec![object_id]
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_objects_by_signer(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let base_url = spawn_server(
+            pool,
+            SignatureAlgorithm::ALL.to_vec(),
+            vec![voter_certificate_authority().certificate.clone()],
+        )?;
+        let client = Client::new(base_url.clone()).with_api_key(OFFICIAL_API_KEY);
+        let public_client = Client::new(base_url);
+
+        let (certificates, _, private_key) = load_keypair()?;
+        let [(election_manager_certificates, election_manager_private_key), (other_election_manager_certificates, other_election_manager_private_key)] =
+            load_election_manager_keypairs()?;
+        // signed by neither signer below
+        let election_object_id = create_election(
+            &client,
+            "jurisdiction",
+            other_election_manager_certificates,
+            &other_election_manager_private_key,
+        )
+        .await?;
+        let registration = |common_access_card_id: &str| {
+            registration(
+                common_access_card_id,
+                "st.dev-jurisdiction",
+                election_object_id,
+            )
+        };
+
+        let first_object = SignedObject::from_payload(
+            &registration("1234567890"),
+            X509::stack_from_pem(&certificates)?,
+            &private_key,
+        )?;
+        let signer_certificate = first_object.signer_certificate()?;
+        let first_object_id = client.create_object(first_object).await?;
+        let second_object_id = client
+            .create_object(SignedObject::from_payload(
+                &registration("0987654321"),
+                X509::stack_from_pem(&certificates)?,
+                &private_key,
+            )?)
+            .await?;
+        let election_manager_object = SignedObject::from_payload(
+            &registration("1122334455"),
+            election_manager_certificates.clone(),
+            &election_manager_private_key,
+        )?;
+        let election_manager_signer_certificate = election_manager_object.signer_certificate()?;
+        let election_manager_object_certificates = election_manager_object.certificates.clone();
+        let election_manager_object_id = client.create_object(election_manager_object).await?;
+
+        // the same signer with a different chain
+        let short_chain_object = SignedObject::from_payload(
+            &registration("5544332211"),
+            election_manager_certificates[..1].to_vec(),
+            &election_manager_private_key,
+        )?;
+        let short_chain_certificates = short_chain_object.certificates.clone();
+        let short_chain_object_id = client.create_object(short_chain_object).await?;
+
+        assert_eq!(
+            client
+                .get_signer_certificate(&signer_certificate.fingerprint)
+                .await?,
+            Some(signer_certificate.clone())
+        );
+
+        // a voter's common name has their CAC ID, so the public doesn't get it
+        let (voter_certificates, _, voter_private_key) = load_voter_keypair()?;
+        let voter_object = SignedObject::from_payload(
+            &registration("1234567890"),
+            X509::stack_from_pem(&voter_certificates)?,
+            &voter_private_key,
+        )?;
+        let voter_certificate = voter_object.signer_certificate()?;
+        client.create_object(voter_object).await?;
+        assert_eq!(
+            client
+                .get_signer_certificate(&voter_certificate.fingerprint)
+                .await?
+                .and_then(|certificate| certificate.common_name),
+            Some("DOE.JOHN.EDWARD.1234567890".to_owned())
+        );
+        assert_eq!(
+            public_client
+                .get_signer_certificate(&voter_certificate.fingerprint)
+                .await?,
+            Some(SignerCertificate {
+                common_name: None,
+                ..voter_certificate
+            })
+        );
+        assert_eq!(
+            signer_certificate.jurisdiction.as_deref(),
+            Some("st.dev-jurisdiction")
+        );
+        assert_eq!(
+            client
+                .get_object_ids_by_signer(&signer_certificate.fingerprint)
+                .await?,
+            vec![first_object_id, second_object_id]
+        );
+        assert_eq!(
+            client
+                .get_object_ids_by_signer(&election_manager_signer_certificate.fingerprint)
+                .await?,
+            vec![election_manager_object_id, short_chain_object_id]
+        );
+
+        // each object keeps the chain it was signed with
+        for (object_id, certificates) in [
+            (
+                election_manager_object_id,
+                election_manager_object_certificates,
+            ),
+            (short_chain_object_id, short_chain_certificates),
+        ] {
+            assert_eq!(
+                client
+                    .get_object_by_id(object_id)
+                    .await?
+                    .and_then(ObjectView::into_signed_object)
+                    .unwrap()
+                    .certificates,
+                certificates
+            );
+        }
+
+        // objects sharing a certificate still round trip their certificates
+        assert_eq!(
+            client
+                .get_object_by_id(second_object_id)
+                .await?
+                .and_then(ObjectView::into_signed_object)
+                .unwrap()
+                .certificates,
+            certificates
+        );
+
+        assert_eq!(client.get_signer_certificate(&"0".repeat(64)).await?, None);
+        assert_eq!(
+            client.get_object_ids_by_signer(&"0".repeat(64)).await?,
+            vec![]
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_search_objects(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let client = setup(pool)?;
+
+        let (certificates, _, private_key) = load_keypair()?;
+        let [(election_manager_certificates, election_manager_private_key), _] =
+            load_election_manager_keypairs()?;
+
+        let election_object_id = create_election(
+            &client,
+            "st.dev-jurisdiction",
+            X509::stack_from_pem(&certificates)?,
+            &private_key,
+        )
+        .await?;
+        let registration = |common_access_card_id: &str, jurisdiction_code: &str| {
+            registration(common_access_card_id, jurisdiction_code, election_object_id)
+        };
+
+        let mut object_ids = vec![election_object_id];
+        for common_access_card_id in ["1111111111", "2222222222", "3333333333"] {
+            object_ids.push(
+                client
+                    .create_object(SignedObject::from_payload(
+                        &registration(common_access_card_id, "st.dev-jurisdiction"),
+                        X509::stack_from_pem(&certificates)?,
+                        &private_key,
+                    )?)
+                    .await?,
+            );
+        }
+        let election_manager_object = SignedObject::from_payload(
+            &registration("4444444444", "jurisdiction"),
+            election_manager_certificates,
+            &election_manager_private_key,
+        )?;
+        let election_manager_fingerprint =
+            election_manager_object.signer_certificate()?.fingerprint;
+        object_ids.push(client.create_object(election_manager_object).await?);
+
+        let search_ids = |query: ObjectSearchQuery| {
+            let client = &client;
+            async move {
+                let page = client.search_objects(&query).await?;
+                color_eyre::Result::<_>::Ok((
+                    page.objects
+                        .iter()
+                        .map(|result| result.metadata.id)
+                        .collect::<Vec<_>>(),
+                    page.next_cursor,
+                ))
+            }
+        };
+
+        // everything, oldest first
+        let page = client.search_objects(&ObjectSearchQuery::default()).await?;
+        assert_eq!(
+            page.objects
+                .iter()
+                .map(|result| result.metadata.id)
+                .collect::<Vec<_>>(),
+            object_ids
+        );
+        assert_eq!(page.next_cursor, None);
+        let result = &page.objects[4];
+        assert_eq!(result.metadata.object_type, "Registration");
+        assert_eq!(result.metadata.jurisdiction_code.as_str(), "jurisdiction");
+        assert_eq!(
+            result.metadata.signer_fingerprint,
+            election_manager_fingerprint
+        );
+        // objects from other jurisdictions are redacted
+        assert!(matches!(result.object, Some(ObjectView::Redacted(_))));
+        match &page.objects[0].object {
+            Some(ObjectView::Full(object)) => assert!(object.verify()?),
+            other => panic!("expected a full object, got: {other:?}"),
+        }
+
+        // paginated
+        let (first_page, cursor) = search_ids(ObjectSearchQuery {
+            limit: Some(3),
+            ..Default::default()
+        })
+        .await?;
+        assert_eq!(first_page, object_ids[..3]);
+        assert_eq!(cursor, Some(object_ids[2]));
+        assert_eq!(
+            search_ids(ObjectSearchQuery {
+                limit: Some(3),
+                cursor,
+                ..Default::default()
+            })
+            .await?,
+            (object_ids[3..].to_vec(), None)
+        );
+
+        // filtered
+        assert_eq!(
+            search_ids(ObjectSearchQuery {
+                jurisdiction_code: Some(JurisdictionCode::try_from("st.dev-jurisdiction").unwrap()),
+                ..Default::default()
+            })
+            .await?,
+            (object_ids[..4].to_vec(), None)
+        );
+        assert_eq!(
+            search_ids(ObjectSearchQuery {
+                signer_fingerprint: Some(election_manager_fingerprint),
+                ..Default::default()
+            })
+            .await?,
+            (object_ids[4..].to_vec(), None)
+        );
+        assert_eq!(
+            search_ids(ObjectSearchQuery {
+                object_type: Some(Payload::election_object_type().to_owned()),
+                ..Default::default()
+            })
+            .await?,
+            (vec![election_object_id], None)
+        );
+        assert_eq!(
+            search_ids(ObjectSearchQuery {
+                election_id: Some(Uuid::new_v4()),
+                ..Default::default()
+            })
+            .await?,
+            (vec![], None)
+        );
+        let now = time::OffsetDateTime::now_utc();
+        assert_eq!(
+            search_ids(ObjectSearchQuery {
+                created_before: Some(now + time::Duration::hours(1)),
+                ..Default::default()
+            })
+            .await?,
+            (object_ids.clone(), None)
+        );
+        assert_eq!(
+            search_ids(ObjectSearchQuery {
+                created_after: Some(now + time::Duration::hours(1)),
+                ..Default::default()
+            })
+            .await?,
+            (vec![], None)
+        );
+
+        // metadata only
+        let page = client
+            .search_objects(&ObjectSearchQuery {
+                metadata_only: true,
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(page.objects.len(), 5);
+        assert!(page.objects.iter().all(|result| result.object.is_none()));
+
+        // out of range limits
+        for limit in [0, ObjectSearchQuery::MAX_LIMIT + 1] {
+            assert!(client
+                .search_objects(&ObjectSearchQuery {
+                    limit: Some(limit),
+                    ..Default::default()
+                })
+                .await
+                .is_err());
+        }
+
+        // cursors that aren't objects are rejected rather than ending the
+        // search early
+        let error = client
+            .search_objects(&ObjectSearchQuery {
+                cursor: Some(Uuid::new_v4()),
+                ..Default::default()
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            cacvote_server_client::Error::Reqwest(ref error)
+                if error.status() == Some(reqwest::StatusCode::BAD_REQUEST)
+        ));
+
+        // query parameters are camelCase
+        let request = reqwest::Client::new()
+            .get("http://localhost/api/objects")
+            .query(&ObjectSearchQuery {
+                object_type: Some("RegistrationRequest".to_owned()),
+                election_id: Some(election_object_id),
+                created_after: Some(now),
+                metadata_only: true,
+                include_superseded: true,
+                ..Default::default()
+            })
+            .build()?;
+        let query = request.url().query().unwrap_or_default();
+        for name in [
+            "objectType",
+            "electionId",
+            "createdAfter",
+            "metadataOnly",
+            "includeSuperseded",
+        ] {
+            assert!(query.contains(&format!("{name}=")), "{query}");
+        }
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_redaction(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let base_url = spawn_server(
+            pool,
+            SignatureAlgorithm::ALL.to_vec(),
+            vec![voter_certificate_authority().certificate.clone()],
+        )?;
+        let public_client = Client::new(base_url.clone());
+        let official_client = Client::new(base_url.clone()).with_api_key(OFFICIAL_API_KEY);
+
+        let (certificates, _, private_key) = load_voter_keypair()?;
+        let object_id = official_client
+            .create_object(SignedObject::from_payload(
+                &Payload::RegistrationRequest(RegistrationRequest {
+                    common_access_card_id: "1234567890".to_owned(),
+                    given_name: "John".to_owned(),
+                    family_name: "Doe".to_owned(),
+                    jurisdiction_code: JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
+                }),
+                X509::stack_from_pem(&certificates)?,
+                &private_key,
+            )?)
+            .await?;
+
+        // officials of the object's jurisdiction see all of it
+        let object = official_client
+            .get_object_by_id(object_id)
+            .await?
+            .and_then(ObjectView::into_signed_object)
+            .unwrap();
+        assert!(object.verify()?);
+
+        // everyone else sees commitments in place of the voter's identity
+        let Some(ObjectView::Redacted(redacted_object)) =
+            public_client.get_object_by_id(object_id).await?
+        else {
+            panic!("expected a redacted object");
+        };
+        assert!(redacted_object.is_redaction_of(&object));
+        assert_eq!(
+            redacted_object.redacted_fields,
+            vec!["commonAccessCardId", "givenName", "familyName"]
+        );
+        let redacted_payload = redacted_object.payload.to_string();
+        for private_value in ["1234567890", "John", "Doe"] {
+            assert!(
+                !redacted_payload.contains(private_value),
+                "{redacted_payload}"
+            );
+        }
+        // the voter's certificate names them, so it's left out too
+        assert!(redacted_object.certificates.is_empty());
+        assert!(redacted_object.certificates_commitment.is_some());
+        assert_eq!(
+            redacted_object.payload["commonAccessCardId"],
+            types_rs::cacvote::commitment(
+                b"redaction-key",
+                "commonAccessCardId",
+                &"1234567890".into()
+            )?
+        );
+
+        let page = public_client
+            .search_objects(&ObjectSearchQuery::default())
+            .await?;
+        assert!(matches!(
+            page.objects[0].object,
+            Some(ObjectView::Redacted(_))
+        ));
+
+        // unknown API keys are rejected rather than treated as public
+        Client::new(base_url)
+            .with_api_key("not-an-api-key")
+            .get_object_by_id(object_id)
+            .await
+            .unwrap_err();
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_jurisdiction_encryption_key(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let client = setup(pool)?;
+
+        let jurisdiction_code = JurisdictionCode::try_from("jurisdiction").unwrap();
+        let [(certificates, private_key), _] = load_election_manager_keypairs()?;
+        let (voter_certificates, _, voter_private_key) = load_voter_keypair()?;
+        let registration_request = Payload::RegistrationRequest(RegistrationRequest {
+            common_access_card_id: "1234567890".to_owned(),
+            given_name: "John".to_owned(),
+            family_name: "Doe".to_owned(),
+            jurisdiction_code: jurisdiction_code.clone(),
+        });
+
+        // plaintext is accepted until the jurisdiction publishes a key
+        assert!(client
+            .get_jurisdiction_encryption_key(&jurisdiction_code)
+            .await?
+            .is_none());
+        client
+            .create_object(SignedObject::from_payload(
+                &registration_request,
+                X509::stack_from_pem(&voter_certificates)?,
+                &voter_private_key,
+            )?)
+            .await?;
+
+        let key_pair = hpke::KeyPair::generate()?;
+        let encryption_key = JurisdictionEncryptionKey {
+            jurisdiction_code: jurisdiction_code.clone(),
+            public_key: key_pair.public_key.clone(),
+        };
+
+        // only the jurisdiction's election managers may publish its key
+        let (other_certificates, _, other_private_key) = load_keypair()?;
+        client
+            .create_object(SignedObject::from_payload(
+                &Payload::JurisdictionEncryptionKey(encryption_key.clone()),
+                X509::stack_from_pem(&other_certificates)?,
+                &other_private_key,
+            )?)
+            .await
+            .unwrap_err();
+
+        let key_object_id = client
+            .create_object(SignedObject::from_payload(
+                &Payload::JurisdictionEncryptionKey(encryption_key.clone()),
+                certificates.clone(),
+                &private_key,
+            )?)
+            .await?;
+        let key_object = client
+            .get_jurisdiction_encryption_key(&jurisdiction_code)
+            .await?
+            .unwrap();
+        assert_eq!(key_object.id, key_object_id);
+        assert!(matches!(
+            key_object.try_to_inner()?,
+            Payload::JurisdictionEncryptionKey(key) if key == encryption_key
+        ));
+
+        // now PII must be encrypted
+        client
+            .create_object(SignedObject::from_payload(
+                &registration_request,
+                X509::stack_from_pem(&voter_certificates)?,
+                &voter_private_key,
+            )?)
+            .await
+            .unwrap_err();
+
+        let encrypted_object = SignedObject::from_payload(
+            &registration_request.encrypt_private_fields(&encryption_key)?,
+            X509::stack_from_pem(&voter_certificates)?,
+            &voter_private_key,
+        )?;
+        let payload = String::from_utf8(encrypted_object.payload.clone())?;
+        for private_value in ["1234567890", "John", "Doe"] {
+            assert!(!payload.contains(private_value), "{payload}");
+        }
+        assert_eq!(
+            encrypted_object
+                .try_to_inner()?
+                .decrypt_private_fields(&[key_pair])?
+                .to_canonical_json()?,
+            registration_request.to_canonical_json()?
+        );
+
+        // the signature covers the ciphertext
+        let payload_digest = openssl::sha::sha256(&encrypted_object.payload).to_vec();
+        let object_id = client.create_object(encrypted_object).await?;
+        let Some(ObjectView::Redacted(redacted_object)) =
+            client.get_object_by_id(object_id).await?
+        else {
+            panic!("expected a redacted object");
+        };
+        assert_eq!(redacted_object.payload_digest, payload_digest);
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_jurisdiction_encryption_key_requires_trusted_signer(
+        pool: sqlx::PgPool,
+    ) -> color_eyre::Result<()> {
+        let client = setup(pool.clone())?;
+
+        let jurisdiction_code = JurisdictionCode::try_from("jurisdiction").unwrap();
+        let [(certificates, private_key), _] = load_election_manager_keypairs()?;
+        let (voter_certificates, _, voter_private_key) = load_voter_keypair()?;
+        let encryption_key = JurisdictionEncryptionKey {
+            jurisdiction_code: jurisdiction_code.clone(),
+            public_key: hpke::KeyPair::generate()?.public_key,
+        };
+        client
+            .create_object(SignedObject::from_payload(
+                &Payload::JurisdictionEncryptionKey(encryption_key),
+                certificates,
+                &private_key,
+            )?)
+            .await?;
+        assert!(client
+            .get_jurisdiction_encryption_key(&jurisdiction_code)
+            .await?
+            .is_some());
+
+        // the same key, served by a server that no longer trusts the CA its
+        // signer's card chains to
+        let untrusting_client = Client::new(spawn_configured_server(
+            pool,
+            SignatureAlgorithm::ALL.to_vec(),
+            vec![voter_certificate_authority().certificate.clone()],
+            |config| {
+                config.vx_ca_certificates = vec![X509::from_pem(include_bytes!(
+                    "../../../../libs/auth/certs/dev/vx-cert-authority-cert.pem"
+                ))
+                .unwrap()]
+            },
+        )?)
+        .with_api_key(OFFICIAL_API_KEY);
+        assert!(untrusting_client
+            .get_jurisdiction_encryption_key(&jurisdiction_code)
+            .await?
+            .is_none());
+
+        // nor does it require voter data to be encrypted to that key
+        untrusting_client
+            .create_object(SignedObject::from_payload(
+                &Payload::RegistrationRequest(RegistrationRequest {
+                    common_access_card_id: "1234567890".to_owned(),
+                    given_name: "John".to_owned(),
+                    family_name: "Doe".to_owned(),
+                    jurisdiction_code,
+                }),
+                X509::stack_from_pem(&voter_certificates)?,
+                &voter_private_key,
+            )?)
+            .await?;
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_object_versions(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let base_url = spawn_server(
+            pool.clone(),
+            SignatureAlgorithm::ALL.to_vec(),
+            vec![voter_certificate_authority().certificate.clone()],
+        )?;
+        let client = Client::new(base_url.clone());
+
+        let jurisdiction_code = JurisdictionCode::try_from("st.dev-jurisdiction").unwrap();
+        let (certificates, _, private_key) = load_keypair()?;
+        let certificates = X509::stack_from_pem(&certificates)?;
+        let election_object_id = create_election(
+            &client,
+            jurisdiction_code.as_str(),
+            certificates.clone(),
+            &private_key,
+        )
+        .await?;
+
+        // correct the election's mailing address
+        let updated_election = Payload::Election(Election {
+            jurisdiction_code: jurisdiction_code.clone(),
+            election_definition: ElectionDefinition::try_from(
+                &include_bytes!("../tests/fixtures/electionFamousNames2021.json")[..],
+            )?,
+            mailing_address: "456 Elm St".to_owned(),
+            electionguard_election_metadata_blob: vec![].into(),
+        });
+        let updated_election_object_id = client
+            .create_object(SignedObject::from_payload_superseding(
+                &updated_election,
+                election_object_id,
+                certificates.clone(),
+                &private_key,
+            )?)
+            .await?;
+
+        let entries = client.get_journal_entries(None, None).await?;
+        let entry = entries.last().unwrap();
+        assert_eq!(entry.object_id, updated_election_object_id);
+        assert_eq!(entry.action, JournalEntryAction::Update);
+
+        // the same history from either version
+        let versions = client
+            .get_object_versions(election_object_id)
+            .await?
+            .unwrap();
+        assert_eq!(
+            versions
+                .iter()
+                .map(|version| (version.object_id, version.supersedes))
+                .collect::<Vec<_>>(),
+            vec![
+                (election_object_id, None),
+                (updated_election_object_id, Some(election_object_id))
+            ]
+        );
+        assert_eq!(
+            client
+                .get_object_versions(updated_election_object_id)
+                .await?,
+            Some(versions)
+        );
+        assert_eq!(client.get_object_versions(Uuid::new_v4()).await?, None);
+
+        // each version can only be superseded once, and only by the same type
+        assert!(client
+            .create_object(SignedObject::from_payload_superseding(
+                &updated_election,
+                election_object_id,
+                certificates.clone(),
+                &private_key,
+            )?)
+            .await
+            .is_err());
+        assert!(client
+            .create_object(SignedObject::from_payload_superseding(
+                &registration("1234567890", jurisdiction_code.as_str(), election_object_id),
+                updated_election_object_id,
+                certificates.clone(),
+                &private_key,
+            )?)
+            .await
+            .is_err());
+
+        // and only by its signer, since the election's signature policy
+        // doesn't say who else may sign it
+        let (other_certificates, other_private_key) =
+            TestCertificateAuthority::new().issue("ROE.JANE.ANN.0987654321");
+        client
+            .create_object(SignedObject::from_payload_superseding(
+                &updated_election,
+                updated_election_object_id,
+                X509::stack_from_pem(&other_certificates)?,
+                &other_private_key,
+            )?)
+            .await
+            .unwrap_err();
+
+        // searches only find the latest version unless asked for all of them
+        let query = ObjectSearchQuery {
+            object_type: Some(Payload::election_object_type().to_owned()),
+            metadata_only: true,
+            ..Default::default()
+        };
+        let object_ids = |page: ObjectSearchPage| {
+            page.objects
+                .into_iter()
+                .map(|result| result.metadata.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            object_ids(client.search_objects(&query).await?),
+            vec![updated_election_object_id]
+        );
+        assert_eq!(
+            object_ids(
+                client
+                    .search_objects(&ObjectSearchQuery {
+                        include_superseded: true,
+                        ..query
+                    })
+                    .await?
+            ),
+            vec![election_object_id, updated_election_object_id]
+        );
+
+        // and the election's tally is its latest version
+        let encrypted_tally = |electionguard_encrypted_tally: &[u8]| {
+            Payload::EncryptedElectionTally(EncryptedElectionTally {
+                jurisdiction_code: jurisdiction_code.clone(),
+                election_object_id,
+                electionguard_encrypted_tally: electionguard_encrypted_tally.to_vec(),
+            })
+        };
+        let tally_object_id = client
+            .create_object(SignedObject::from_payload(
+                &encrypted_tally(b"tally"),
+                certificates.clone(),
+                &private_key,
+            )?)
+            .await?;
+        let corrected_tally_object_id = client
+            .create_object(SignedObject::from_payload_superseding(
+                &encrypted_tally(b"corrected tally"),
+                tally_object_id,
+                certificates.clone(),
+                &private_key,
+            )?)
+            .await?;
+        let tally_object: SignedObject = reqwest::get(base_url.join(&format!(
+            "/api/elections/{election_object_id}/encrypted-tally"
+        ))?)
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+        assert_eq!(tally_object.id, corrected_tally_object_id);
+
+        // a version stored between the check and the insert is rejected too
+        let mut conn = pool.acquire().await?;
+        let [first_version, second_version] = [(), ()].map(|()| {
+            SignedObject::from_payload_superseding(
+                &updated_election,
+                updated_election_object_id,
+                certificates.clone(),
+                &private_key,
+            )
+            .unwrap()
+        });
+        db::create_object(&mut conn, &first_version, None).await?;
+        let error = db::create_object(&mut conn, &second_version, None)
+            .await
+            .unwrap_err();
+        assert!(error.downcast_ref::<db::AlreadySuperseded>().is_some());
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_blobs(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let base_url = spawn_server(
+            pool,
+            SignatureAlgorithm::ALL.to_vec(),
+            vec![voter_certificate_authority().certificate.clone()],
+        )?;
+        let client = Client::new(base_url.clone()).with_api_key(OFFICIAL_API_KEY);
+
+        // spans several chunks
+        let data = (0..cacvote_server_client::BLOB_CHUNK_SIZE * 2 + 1000)
+            .map(|i| (i % 251) as u8)
+            .collect::<Vec<_>>();
+        let reference = BlobReference::for_data(&data);
+        assert_eq!(client.get_blob(&reference).await?, None);
+
+        // only officials may upload
+        let http = reqwest::Client::new();
+        let response = http
+            .post(base_url.join("/api/blobs/uploads")?)
+            .json(&reference)
+            .send()
+            .await?;
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        // another jurisdiction's bogus upload of the same digest doesn't get
+        // in the way
+        let bogus_upload: BlobUpload = http
+            .post(base_url.join("/api/blobs/uploads")?)
+            .bearer_auth(OTHER_OFFICIAL_API_KEY)
+            .json(&BlobReference {
+                sha256: reference.sha256.clone(),
+                size: 4,
+            })
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let response = http
+            .patch(base_url.join(&format!("/api/blobs/uploads/{}", bogus_upload.id))?)
+            .bearer_auth(OTHER_OFFICIAL_API_KEY)
+            .header(BlobUpload::OFFSET_HEADER, 0)
+            .body(b"zzz".to_vec())
+            .send()
+            .await?;
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+        // start an upload and send part of it
+        let upload: BlobUpload = http
+            .post(base_url.join("/api/blobs/uploads")?)
+            .bearer_auth(OFFICIAL_API_KEY)
+            .json(&reference)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        assert_eq!(upload.offset, 0);
+        assert_ne!(upload.id, bogus_upload.id);
+        let chunk_url = base_url.join(&format!("/api/blobs/uploads/{}", upload.id))?;
+        let upload: BlobUpload = http
+            .patch(chunk_url.clone())
+            .bearer_auth(OFFICIAL_API_KEY)
+            .header(BlobUpload::OFFSET_HEADER, 0)
+            .body(data[..1000].to_vec())
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        assert_eq!(upload.offset, 1000);
+
+        // other jurisdictions can't see or add to it
+        for response in [
+            http.get(chunk_url.clone())
+                .bearer_auth(OTHER_OFFICIAL_API_KEY)
+                .send()
+                .await?,
+            http.patch(chunk_url.clone())
+                .bearer_auth(OTHER_OFFICIAL_API_KEY)
+                .header(BlobUpload::OFFSET_HEADER, 1000)
+                .body(data[1000..2000].to_vec())
+                .send()
+                .await?,
+        ] {
+            assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+        }
+
+        // chunks must start at the upload's offset
+        let response = http
+            .patch(chunk_url.clone())
+            .bearer_auth(OFFICIAL_API_KEY)
+            .header(BlobUpload::OFFSET_HEADER, 0)
+            .body(data[..1000].to_vec())
+            .send()
+            .await?;
+        assert_eq!(response.status(), reqwest::StatusCode::CONFLICT);
+        assert_eq!(response.json::<BlobUpload>().await?, upload);
+
+        // objects can't refer to blobs the server doesn't have
+        let (certificates, _, private_key) = load_keypair()?;
+        let election_object_id = create_election(
+            &client,
+            "st.dev-jurisdiction",
+            X509::stack_from_pem(&certificates)?,
+            &private_key,
+        )
+        .await?;
+        let shuffled_ballots = || -> color_eyre::Result<SignedObject> {
+            SignedObject::from_payload(
+                &Payload::ShuffledEncryptedCastBallots(ShuffledEncryptedCastBallots {
+                    jurisdiction_code: JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
+                    election_object_id,
+                    electionguard_shuffled_ballots: BlobContent::Blob(reference.clone()),
+                }),
+                X509::stack_from_pem(&certificates)?,
+                &private_key,
+            )
+        };
+        assert!(client.create_object(shuffled_ballots()?).await.is_err());
+
+        // the client resumes the upload
+        assert_eq!(client.upload_blob(&data).await?, reference);
+        assert_eq!(
+            http.get(chunk_url)
+                .bearer_auth(OFFICIAL_API_KEY)
+                .send()
+                .await?
+                .json::<BlobUpload>()
+                .await?,
+            BlobUpload {
+                id: upload.id,
+                blob: reference.clone(),
+                offset: reference.size,
+            }
+        );
+        assert_eq!(client.get_blob(&reference).await?, Some(data.clone()));
+
+        // uploading it again is a no-op, even for another jurisdiction, whose
+        // stale upload is replaced
+        assert_eq!(client.upload_blob(&data).await?, reference);
+        let upload: BlobUpload = http
+            .post(base_url.join("/api/blobs/uploads")?)
+            .bearer_auth(OTHER_OFFICIAL_API_KEY)
+            .json(&BlobReference::for_data(&data))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        assert!(upload.is_complete());
+
+        let object_id = client.create_object(shuffled_ballots()?).await?;
+        let object = client
+            .get_object_by_id(object_id)
+            .await?
+            .and_then(ObjectView::into_signed_object)
+            .unwrap();
+        let Payload::ShuffledEncryptedCastBallots(payload) = object.try_to_inner()? else {
+            panic!("expected ShuffledEncryptedCastBallots");
+        };
+        assert_eq!(
+            payload.electionguard_shuffled_ballots.blob_reference(),
+            Some(&reference)
+        );
+
+        // data that doesn't match the digest is discarded
+        let bogus_reference = BlobReference::for_data(b"expected");
+        let upload: BlobUpload = http
+            .post(base_url.join("/api/blobs/uploads")?)
+            .bearer_auth(OFFICIAL_API_KEY)
+            .json(&bogus_reference)
+            .send()
+            .await?
+            .json()
+            .await?;
+        let response = http
+            .patch(base_url.join(&format!("/api/blobs/uploads/{}", upload.id))?)
+            .bearer_auth(OFFICIAL_API_KEY)
+            .header(BlobUpload::OFFSET_HEADER, 0)
+            .body(b"actual!!".to_vec())
+            .send()
+            .await?;
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+        assert_eq!(client.get_blob(&bogus_reference).await?, None);
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_snapshot(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let base_url = spawn_server(
+            pool,
+            SignatureAlgorithm::ALL.to_vec(),
+            vec![voter_certificate_authority().certificate.clone()],
+        )?;
+        let client = Client::new(base_url.clone()).with_api_key(OFFICIAL_API_KEY);
+        let jurisdiction_code = JurisdictionCode::try_from("st.dev-jurisdiction").unwrap();
+        let snapshot_public_key =
+            PKey::public_key_from_pem(&snapshot_signing_key().public_key_to_pem()?)?;
+
+        assert_eq!(
+            client
+                .get_snapshot_tree_head(&jurisdiction_code, None)
+                .await?,
+            None
+        );
+
+        let (certificates, _, private_key) = load_keypair()?;
+        let mut object_ids = vec![];
+        for _ in 0..3 {
+            object_ids.push(
+                create_election(
+                    &client,
+                    "st.dev-jurisdiction",
+                    X509::stack_from_pem(&certificates)?,
+                    &private_key,
+                )
+                .await?,
+            );
+        }
+
+        let tree_head = client
+            .get_snapshot_tree_head(&jurisdiction_code, None)
+            .await?
+            .unwrap();
+        assert_eq!(tree_head.tree_size, 3);
+        assert!(tree_head.verify_signature(&snapshot_public_key)?);
+
+        // entries come in pages, in journal order
+        let page = client
+            .get_snapshot_entries(&SnapshotQuery {
+                jurisdiction_code: jurisdiction_code.clone(),
+                cursor: Some(tree_head.cursor),
+                offset: None,
+                limit: Some(2),
+            })
+            .await?;
+        assert_eq!(
+            page.entries
+                .iter()
+                .map(|entry| entry.object.id)
+                .collect::<Vec<_>>(),
+            object_ids[..2]
+        );
+        assert_eq!(page.next_offset, Some(2));
+
+        let entries = client.get_snapshot(&tree_head).await?;
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| entry.object.id)
+                .collect::<Vec<_>>(),
+            object_ids
+        );
+        assert_eq!(entries[2].journal_entry.id, tree_head.cursor);
+        assert_eq!(entries[2].journal_entry.action, JournalEntryAction::Create);
+
+        // later objects don't change the snapshot as of an earlier cursor
+        create_election(
+            &client,
+            "st.dev-jurisdiction",
+            X509::stack_from_pem(&certificates)?,
+            &private_key,
+        )
+        .await?;
+        let earlier_tree_head = client
+            .get_snapshot_tree_head(&jurisdiction_code, Some(tree_head.cursor))
+            .await?
+            .unwrap();
+        assert_eq!(
+            (earlier_tree_head.tree_size, earlier_tree_head.root_hash),
+            (tree_head.tree_size, tree_head.root_hash.clone())
+        );
+        assert_eq!(
+            client
+                .get_snapshot_tree_head(&jurisdiction_code, None)
+                .await?
+                .unwrap()
+                .tree_size,
+            4
+        );
+
+        // entries that don't match the tree head are rejected
+        let mut wrong_tree_head = tree_head.clone();
+        wrong_tree_head.tree_size = 2;
+        assert!(matches!(
+            client.get_snapshot(&wrong_tree_head).await,
+            Err(Error::SnapshotMismatch(_))
+        ));
+
+        // cursors must be journal entries of the jurisdiction
+        assert_eq!(
+            client
+                .get_snapshot_tree_head(
+                    &JurisdictionCode::try_from("jurisdiction").unwrap(),
+                    Some(tree_head.cursor)
+                )
+                .await?,
+            None
+        );
+
+        // only the jurisdiction's officials may get the entries
+        let public_client = Client::new(base_url);
+        assert!(public_client.get_snapshot(&tree_head).await.is_err());
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_ballot_tracking(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let mut conn = pool.acquire().await?;
+        let client = Client::new(spawn_server(
+            pool,
+            SignatureAlgorithm::ALL.to_vec(),
+            vec![voter_certificate_authority().certificate.clone()],
+        )?);
+
+        let jurisdiction_code = JurisdictionCode::try_from("st.dev-jurisdiction").unwrap();
+        let (certificates, _, private_key) = load_keypair()?;
+        let sign = |payload: Payload| {
+            SignedObject::from_payload(&payload, X509::stack_from_pem(&certificates)?, &private_key)
+        };
+
+        let election_definition = ElectionDefinition::try_from(
+            &include_bytes!("../tests/fixtures/electionFamousNames2021.json")[..],
+        )?;
+        let election_object_id = client
+            .create_object(sign(Payload::Election(Election {
+                jurisdiction_code: jurisdiction_code.clone(),
+                election_definition: election_definition.clone(),
+                mailing_address: "123 Main St".to_owned(),
+                electionguard_election_metadata_blob: vec![].into(),
+            }))?)
+            .await?;
+        let registration_request_object_id = Uuid::new_v4();
+        let registration_object_id = client
+            .create_object(sign(Payload::Registration(Registration {
+                common_access_card_id: "1234567890".to_owned(),
+                jurisdiction_code: jurisdiction_code.clone(),
+                registration_request_object_id,
+                election_object_id,
+                ballot_style_id: election_definition.election.ballot_styles[0].id.clone(),
+                precinct_id: election_definition.election.precincts[0].id.clone(),
+            }))?)
+            .await?;
+        let (voter_certificates, _, voter_private_key) = load_voter_keypair()?;
+        let cast_ballot_object = SignedObject::from_payload(
+            &Payload::CastBallot(CastBallot {
+                common_access_card_id: "1234567890".to_owned(),
+                jurisdiction_code: jurisdiction_code.clone(),
+                registration_request_object_id,
+                registration_object_id,
+                election_object_id,
+                electionguard_encrypted_ballot: "encrypted ballot".to_owned(),
+            }),
+            X509::stack_from_pem(&voter_certificates)?,
+            &voter_private_key,
+        )?;
+        let tracking_code = tracking_code(&cast_ballot_object.signature);
+        let signature_hash = openssl::sha::sha256(&cast_ballot_object.signature);
+        let cast_ballot_object_id = client.create_object(cast_ballot_object).await?;
+
+        let status = client
+            .get_ballot_tracking_status(&tracking_code)
+            .await?
+            .unwrap();
+        assert_eq!(status.tracking_code, tracking_code);
+        assert_eq!(status.election_object_id, election_object_id);
+        assert_eq!(status.cast_ballot_object_id, cast_ballot_object_id);
+        assert_eq!(
+            status
+                .events
+                .iter()
+                .map(|event| event.stage)
+                .collect::<Vec<_>>(),
+            vec![
+                BallotTrackingStage::RegistrationApproved,
+                BallotTrackingStage::BallotRecorded
+            ]
+        );
+
+        // neither the voter nor their ballot is revealed
+        let status_json = serde_json::to_string(&status)?;
+        for private_value in ["1234567890", "encrypted ballot"] {
+            assert!(!status_json.contains(private_value), "{status_json}");
+        }
+
+        // codes are case-insensitive
+        assert_eq!(
+            client
+                .get_ballot_tracking_status(&tracking_code.to_ascii_uppercase())
+                .await?,
+            Some(status)
+        );
+
+        sqlx::query("INSERT INTO machines (machine_identifier, certificates) VALUES ($1, $2)")
+            .bind("mark-1")
+            .bind(&certificates)
+            .execute(&mut *conn)
+            .await?;
+        let ballot_verification_payload = tlv::to_vec(BallotVerificationPayload::new(
+            "mark-1".to_owned(),
+            "1234567890".to_owned(),
+            election_object_id,
+            signature_hash,
+        ))?;
+        db::create_scanned_mailing_label_code(
+            &mut conn,
+            &tlv::to_vec(SignedBuffer::new(
+                ballot_verification_payload,
+                b"signature".to_vec(),
+            ))?,
+        )
+        .await?;
+        client
+            .create_object(sign(Payload::ShuffledEncryptedCastBallots(
+                ShuffledEncryptedCastBallots {
+                    jurisdiction_code,
+                    election_object_id,
+                    electionguard_shuffled_ballots: vec![].into(),
+                },
+            ))?)
+            .await?;
+
+        // the machine is needed to look up scanned labels
+        let error = db::delete_machine(&mut conn, "mark-1").await.unwrap_err();
+        assert!(
+            error.to_string().contains("1 of its mailing labels"),
+            "{error}"
+        );
+
+        let status = client
+            .get_ballot_tracking_status(&tracking_code)
+            .await?
+            .unwrap();
+        for stage in [
+            BallotTrackingStage::RegistrationApproved,
+            BallotTrackingStage::BallotRecorded,
+            BallotTrackingStage::MailingLabelScanned,
+            BallotTrackingStage::ShufflePosted,
+        ] {
+            assert!(status.has_reached(stage), "{status:?}");
+        }
+
+        // unknown and malformed codes
+        assert_eq!(
+            client
+                .get_ballot_tracking_status(&"00".repeat(TRACKING_CODE_LENGTH))
+                .await?,
+            None
+        );
+        client
+            .get_ballot_tracking_status("not-a-tracking-code")
+            .await
+            .unwrap_err();
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_revoting(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let base_url = spawn_server(
+            pool,
+            SignatureAlgorithm::ALL.to_vec(),
+            vec![voter_certificate_authority().certificate.clone()],
+        )?;
+        let client = Client::new(base_url.clone());
+
+        let jurisdiction_code = JurisdictionCode::try_from("st.dev-jurisdiction").unwrap();
+        let (certificates, _, private_key) = load_keypair()?;
+        let election_object_id = create_election(
+            &client,
+            jurisdiction_code.as_str(),
+            X509::stack_from_pem(&certificates)?,
+            &private_key,
+        )
+        .await?;
+
+        let (voter_certificates, _, voter_private_key) = load_voter_keypair()?;
+        let mut cast_ballot_object_ids = vec![];
+        let mut tracking_codes = vec![];
+        for encrypted_ballot in ["first ballot", "second ballot"] {
+            let cast_ballot_object = SignedObject::from_payload(
+                &Payload::CastBallot(CastBallot {
+                    common_access_card_id: "1234567890".to_owned(),
+                    jurisdiction_code: jurisdiction_code.clone(),
+                    registration_request_object_id: Uuid::new_v4(),
+                    registration_object_id: Uuid::new_v4(),
+                    election_object_id,
+                    electionguard_encrypted_ballot: encrypted_ballot.to_owned(),
+                }),
+                X509::stack_from_pem(&voter_certificates)?,
+                &voter_private_key,
+            )?;
+            tracking_codes.push(tracking_code(&cast_ballot_object.signature));
+            cast_ballot_object_ids.push(client.create_object(cast_ballot_object).await?);
+        }
+
+        // the bulletin board shows which ballot replaced the first
+        let cast_ballots: Vec<serde_json::Value> = reqwest::get(
+            base_url.join(&format!("/api/elections/{election_object_id}/cast-ballots"))?,
+        )
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+        assert_eq!(
+            cast_ballots
+                .iter()
+                .map(|cast_ballot| (
+                    cast_ballot["id"].clone(),
+                    cast_ballot["supersededBy"].clone()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    serde_json::json!(cast_ballot_object_ids[0]),
+                    serde_json::json!(cast_ballot_object_ids[1])
+                ),
+                (
+                    serde_json::json!(cast_ballot_object_ids[1]),
+                    serde_json::Value::Null
+                ),
+            ]
+        );
+
+        client
+            .create_object(SignedObject::from_payload(
+                &Payload::ShuffledEncryptedCastBallots(ShuffledEncryptedCastBallots {
+                    jurisdiction_code,
+                    election_object_id,
+                    electionguard_shuffled_ballots: vec![].into(),
+                }),
+                X509::stack_from_pem(&certificates)?,
+                &private_key,
+            )?)
+            .await?;
+
+        // a shuffle was only posted while the last ballot was current
+        let first_status = client
+            .get_ballot_tracking_status(&tracking_codes[0])
+            .await?
+            .unwrap();
+        assert!(
+            first_status.has_reached(BallotTrackingStage::Superseded),
+            "{first_status:?}"
+        );
+        assert!(
+            !first_status.has_reached(BallotTrackingStage::ShufflePosted),
+            "{first_status:?}"
+        );
+        let second_status = client
+            .get_ballot_tracking_status(&tracking_codes[1])
+            .await?
+            .unwrap();
+        assert!(
+            !second_status.has_reached(BallotTrackingStage::Superseded),
+            "{second_status:?}"
+        );
+        assert!(
+            second_status.has_reached(BallotTrackingStage::ShufflePosted),
+            "{second_status:?}"
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_voter_session(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let ca = TestCertificateAuthority::new();
+        let base_url = spawn_server(
+            pool,
+            SignatureAlgorithm::ALL.to_vec(),
+            vec![ca.certificate.clone()],
+        )?;
+        let client = Client::new(base_url.clone());
+
+        let jurisdiction_code = JurisdictionCode::try_from("st.dev-jurisdiction").unwrap();
+        let (certificates, _, private_key) = load_keypair()?;
+        let (voter_certificates, voter_private_key) = ca.issue("DOE.JOHN.EDWARD.1234567890");
+        let (other_voter_certificates, other_voter_private_key) =
+            ca.issue("ROE.JANE.MARIE.0987654321");
+
+        let start_session = |certificates: Vec<u8>, private_key: PKey<Private>| {
+            let base_url = base_url.clone();
+            async move {
+                let client = Client::new(base_url.clone());
+                let challenge = client.create_voter_session_challenge().await?;
+                let signature = SignatureAlgorithm::EcdsaP256Sha256.sign(
+                    &private_key,
+                    &VoterSessionChallenge::signing_message(&challenge.nonce),
+                )?;
+                let request = CreateVoterSessionRequest {
+                    nonce: challenge.nonce,
+                    certificates,
+                    signature,
+                    signature_algorithm: Some(SignatureAlgorithm::EcdsaP256Sha256),
+                };
+                let session = client.create_voter_session(&request).await?;
+
+                // challenges can only be used once
+                client.create_voter_session(&request).await.unwrap_err();
+
+                color_eyre::Result::<_>::Ok(
+                    Client::new(base_url.clone()).with_voter_session(&session),
+                )
+            }
+        };
+
+        let election_object_id = client
+            .create_object(SignedObject::from_payload(
+                &Payload::Election(Election {
+                    jurisdiction_code: jurisdiction_code.clone(),
+                    election_definition: ElectionDefinition::try_from(
+                        &include_bytes!("../tests/fixtures/electionFamousNames2021.json")[..],
+                    )?,
+                    mailing_address: "123 Main St".to_owned(),
+                    electionguard_election_metadata_blob: vec![].into(),
+                }),
+                X509::stack_from_pem(&certificates)?,
+                &private_key,
+            )?)
+            .await?;
+
+        let mut registration_request_object_ids = vec![];
+        for (certificates, private_key, common_access_card_id, given_name, family_name) in [
+            (
+                &voter_certificates,
+                &voter_private_key,
+                "1234567890",
+                "John",
+                "Doe",
+            ),
+            (
+                &other_voter_certificates,
+                &other_voter_private_key,
+                "0987654321",
+                "Jane",
+                "Roe",
+            ),
+        ] {
+            registration_request_object_ids.push(
+                client
+                    .create_object(SignedObject::from_payload(
+                        &Payload::RegistrationRequest(RegistrationRequest {
+                            common_access_card_id: common_access_card_id.to_owned(),
+                            jurisdiction_code: jurisdiction_code.clone(),
+                            given_name: given_name.to_owned(),
+                            family_name: family_name.to_owned(),
+                        }),
+                        X509::stack_from_pem(certificates)?,
+                        private_key,
+                    )?)
+                    .await?,
+            );
+        }
+
+        let voter_client =
+            start_session(voter_certificates.clone(), voter_private_key.clone()).await?;
+        let [voter_registration] = voter_client
+            .get_voter_registrations()
+            .await?
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            voter_registration.registration_request.id,
+            registration_request_object_ids[0]
+        );
+        assert!(voter_registration.registration.is_none());
+        assert!(voter_client.get_voter_elections().await?.is_empty());
+
+        let election_definition = ElectionDefinition::try_from(
+            &include_bytes!("../tests/fixtures/electionFamousNames2021.json")[..],
+        )?;
+        let registration_object_id = client
+            .create_object(SignedObject::from_payload(
+                &Payload::Registration(Registration {
+                    common_access_card_id: "1234567890".to_owned(),
+                    jurisdiction_code: jurisdiction_code.clone(),
+                    registration_request_object_id: registration_request_object_ids[0],
+                    election_object_id,
+                    ballot_style_id: election_definition.election.ballot_styles[0].id.clone(),
+                    precinct_id: election_definition.election.precincts[0].id.clone(),
+                }),
+                X509::stack_from_pem(&certificates)?,
+                &private_key,
+            )?)
+            .await?;
+        let cast_ballot_object_id = client
+            .create_object(SignedObject::from_payload(
+                &Payload::CastBallot(CastBallot {
+                    common_access_card_id: "1234567890".to_owned(),
+                    jurisdiction_code,
+                    registration_request_object_id: registration_request_object_ids[0],
+                    registration_object_id,
+                    election_object_id,
+                    electionguard_encrypted_ballot: "encrypted ballot".to_owned(),
+                }),
+                X509::stack_from_pem(&voter_certificates)?,
+                &voter_private_key,
+            )?)
+            .await?;
+
+        let [voter_registration] = voter_client
+            .get_voter_registrations()
+            .await?
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            voter_registration
+                .registration
+                .map(|registration| registration.id),
+            Some(registration_object_id)
+        );
+        assert_eq!(
+            voter_client
+                .get_voter_elections()
+                .await?
+                .into_iter()
+                .map(|election| election.id)
+                .collect::<Vec<_>>(),
+            vec![election_object_id]
+        );
+        assert_eq!(
+            voter_client
+                .get_voter_cast_ballots()
+                .await?
+                .into_iter()
+                .map(|cast_ballot| cast_ballot.id)
+                .collect::<Vec<_>>(),
+            vec![cast_ballot_object_id]
+        );
+
+        // other voters see only their own objects
+        let other_voter_client =
+            start_session(other_voter_certificates, other_voter_private_key).await?;
+        let [other_voter_registration] = other_voter_client
+            .get_voter_registrations()
+            .await?
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            other_voter_registration.registration_request.id,
+            registration_request_object_ids[1]
+        );
+        assert!(other_voter_client.get_voter_elections().await?.is_empty());
+        assert!(other_voter_client
+            .get_voter_cast_ballots()
+            .await?
+            .is_empty());
+
+        // voters are identified by their CAC ID, so their objects follow them
+        // to a reissued card with a new certificate
+        let (reissued_certificates, reissued_private_key) = ca.issue("DOE.JOHN.EDWARD.1234567890");
+        let reissued_voter_client =
+            start_session(reissued_certificates, reissued_private_key).await?;
+        assert_eq!(
+            reissued_voter_client
+                .get_voter_cast_ballots()
+                .await?
+                .into_iter()
+                .map(|cast_ballot| cast_ballot.id)
+                .collect::<Vec<_>>(),
+            vec![cast_ballot_object_id]
+        );
+
+        // sessions need a valid token
+        client.get_voter_registrations().await.unwrap_err();
+        Client::new(base_url.clone())
+            .with_api_key("not-a-session-token")
+            .get_voter_cast_ballots()
+            .await
+            .unwrap_err();
+
+        // challenges must be signed by the certificate's key, and certificates
+        // must be issued by a trusted CA
+        let challenge = client.create_voter_session_challenge().await?;
+        let signature = SignatureAlgorithm::EcdsaP256Sha256.sign(
+            &ca.issue("DOE.JOHN.EDWARD.1234567890").1,
+            &VoterSessionChallenge::signing_message(&challenge.nonce),
+        )?;
+        client
+            .create_voter_session(&CreateVoterSessionRequest {
+                nonce: challenge.nonce,
+                certificates: voter_certificates,
+                signature,
+                signature_algorithm: Some(SignatureAlgorithm::EcdsaP256Sha256),
+            })
+            .await
+            .unwrap_err();
+
+        let (untrusted_certificates, untrusted_private_key) =
+            TestCertificateAuthority::new().issue("DOE.JOHN.EDWARD.1234567890");
+        assert!(start_session(untrusted_certificates, untrusted_private_key)
+            .await
+            .is_err());
+
+        // unknown challenges are rejected
+        client
+            .create_voter_session(&CreateVoterSessionRequest {
+                nonce: b"not a challenge".to_vec(),
+                certificates: ca.issue("DOE.JOHN.EDWARD.1234567890").0,
+                signature: vec![],
+                signature_algorithm: Some(SignatureAlgorithm::EcdsaP256Sha256),
+            })
+            .await
+            .unwrap_err();
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_registration_conflicts(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let base_url = spawn_server(
+            pool,
+            SignatureAlgorithm::ALL.to_vec(),
+            vec![voter_certificate_authority().certificate.clone()],
+        )?;
+        let public_client = Client::new(base_url.clone());
+        let official_client = Client::new(base_url.clone()).with_api_key(OFFICIAL_API_KEY);
+        let other_official_client =
+            Client::new(base_url.clone()).with_api_key(OTHER_OFFICIAL_API_KEY);
+
+        let (certificates, _, private_key) = load_keypair()?;
+        let [(election_manager_certificates, election_manager_private_key), _] =
+            load_election_manager_keypairs()?;
+        let (voter_certificates, _, voter_private_key) = load_voter_keypair()?;
+        let (other_voter_certificates, other_voter_private_key) =
+            voter_certificate_authority().issue("ROE.JANE.MARIE.0987654321");
+
+        let registration_request =
+            |jurisdiction_code: &str,
+             (common_access_card_id, given_name, family_name): (&str, &str, &str),
+             certificates: &[u8],
+             private_key: &PKey<Private>| {
+                SignedObject::from_payload(
+                    &Payload::RegistrationRequest(RegistrationRequest {
+                        common_access_card_id: common_access_card_id.to_owned(),
+                        jurisdiction_code: JurisdictionCode::try_from(jurisdiction_code).unwrap(),
+                        given_name: given_name.to_owned(),
+                        family_name: family_name.to_owned(),
+                    }),
+                    X509::stack_from_pem(certificates)?,
+                    private_key,
+                )
+            };
+
+        // the same voter asks to register in two jurisdictions
+        let registration_request_object_id = public_client
+            .create_object(registration_request(
+                "st.dev-jurisdiction",
+                ("1234567890", "John", "Doe"),
+                &voter_certificates,
+                &voter_private_key,
+            )?)
+            .await?;
+        let other_registration_request_object_id = public_client
+            .create_object(registration_request(
+                "jurisdiction",
+                ("1234567890", "John", "Doe"),
+                &voter_certificates,
+                &voter_private_key,
+            )?)
+            .await?;
+        // while another voter registers in just one
+        public_client
+            .create_object(registration_request(
+                "st.dev-jurisdiction",
+                ("0987654321", "Jane", "Roe"),
+                &other_voter_certificates,
+                &other_voter_private_key,
+            )?)
+            .await?;
+
+        // both jurisdictions see the conflict
+        let conflicts = official_client.get_registration_conflicts().await?;
+        let [conflict] = conflicts.as_slice() else {
+            panic!("expected one conflict, got: {conflicts:?}");
+        };
+        assert_eq!(conflict.kind, RegistrationConflictKind::Overlapping);
+        assert_eq!(
+            conflict.jurisdiction_codes,
+            vec![
+                JurisdictionCode::try_from("jurisdiction").unwrap(),
+                JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
+            ]
+        );
+        assert_eq!(
+            conflict
+                .objects
+                .iter()
+                .map(|object| object.object_id)
+                .collect::<Vec<_>>(),
+            vec![
+                registration_request_object_id,
+                other_registration_request_object_id
+            ]
+        );
+        assert_eq!(
+            other_official_client.get_registration_conflicts().await?,
+            conflicts
+        );
+
+        // without revealing the voter
+        let conflicts_json = serde_json::to_string(&conflicts)?;
+        for private_value in ["1234567890", "John", "Doe"] {
+            assert!(!conflicts_json.contains(private_value), "{conflicts_json}");
+        }
+
+        // only officials may list conflicts
+        public_client
+            .get_registration_conflicts()
+            .await
+            .unwrap_err();
+
+        // registrations for elections on different dates don't conflict
+        let election_object_id = create_election(
+            &public_client,
+            "st.dev-jurisdiction",
+            X509::stack_from_pem(&certificates)?,
+            &private_key,
+        )
+        .await?;
+        let mut election_data: serde_json::Value = serde_json::from_slice(include_bytes!(
+            "../tests/fixtures/electionFamousNames2021.json"
+        ))?;
+        election_data["date"] = "2021-11-02".into();
+        let other_election_object_id = public_client
+            .create_object(SignedObject::from_payload(
+                &Payload::Election(Election {
+                    jurisdiction_code: JurisdictionCode::try_from("jurisdiction").unwrap(),
+                    election_definition: ElectionDefinition::try_from(
+                        &serde_json::to_vec(&election_data)?[..],
+                    )?,
+                    mailing_address: "123 Main St".to_owned(),
+                    electionguard_election_metadata_blob: vec![].into(),
+                }),
+                election_manager_certificates.clone(),
+                &election_manager_private_key,
+            )?)
+            .await?;
+
+        for (
+            registration_request_object_id,
+            election_object_id,
+            jurisdiction_code,
+            certificates,
+            private_key,
+        ) in [
+            (
+                registration_request_object_id,
+                election_object_id,
+                "st.dev-jurisdiction",
+                X509::stack_from_pem(&certificates)?,
+                &private_key,
+            ),
+            (
+                other_registration_request_object_id,
+                other_election_object_id,
+                "jurisdiction",
+                election_manager_certificates.clone(),
+                &election_manager_private_key,
+            ),
+        ] {
+            public_client
+                .create_object(SignedObject::from_payload(
+                    &Payload::Registration(Registration {
+                        common_access_card_id: "1234567890".to_owned(),
+                        jurisdiction_code: JurisdictionCode::try_from(jurisdiction_code).unwrap(),
+                        registration_request_object_id,
+                        election_object_id,
+                        ballot_style_id: BallotStyleId::from("1".to_owned()),
+                        precinct_id: PrecinctId::from("23".to_owned()),
+                    }),
+                    certificates,
+                    private_key,
+                )?)
+                .await?;
+        }
+
+        assert_eq!(official_client.get_registration_conflicts().await?, vec![]);
+        assert_eq!(
+            other_official_client.get_registration_conflicts().await?,
+            vec![]
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_metrics_and_readiness(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let base_url = spawn_server(
+            pool,
+            SignatureAlgorithm::ALL.to_vec(),
+            vec![voter_certificate_authority().certificate.clone()],
+        )?;
+        let client = Client::new(base_url.clone());
+        let http = reqwest::Client::new();
+
+        let response = http.get(base_url.join("/api/ready")?).send().await?;
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let report: ReadinessReport = response.json().await?;
+        assert!(report.ready, "{report:?}");
+        assert_eq!(
+            report
+                .checks
+                .iter()
+                .map(|check| check.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["database", "migrations"]
+        );
+
+        let (certificates, _, private_key) = load_keypair()?;
+        let election_object_id = create_election(
+            &client,
+            "st.dev-jurisdiction",
+            X509::stack_from_pem(&certificates)?,
+            &private_key,
+        )
+        .await?;
+        client.get_object_by_id(election_object_id).await?;
+
+        // a tampered signature is rejected and counted
+        let mut tampered_object = SignedObject::from_payload(
+            &registration("1234567890", "st.dev-jurisdiction", election_object_id),
+            X509::stack_from_pem(&certificates)?,
+            &private_key,
+        )?;
+        tampered_object.signature[10] ^= 0xff;
+        assert!(client.create_object(tampered_object).await.is_err());
+
+        let metrics = http
+            .get(base_url.join("/metrics")?)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        for expected in [
+            r#"cacvote_server_objects_created_total{jurisdiction="st.dev-jurisdiction",object_type="Election"} 1"#,
+            r#"cacvote_server_signature_verification_failures_total{kind="object"} 1"#,
+            "cacvote_server_journal_entries 1",
+            r#"cacvote_server_http_request_duration_seconds_count{method="GET",route="/api/objects/:object_id",status="200"} 1"#,
+            r#"cacvote_server_http_request_duration_seconds_count{method="POST",route="/api/objects",status="400"} 1"#,
+        ] {
+            assert!(metrics.contains(expected), "{expected} not in {metrics}");
+        }
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_bulletin_board_error_pages(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let base_url = spawn_server(
+            pool,
+            SignatureAlgorithm::ALL.to_vec(),
+            vec![voter_certificate_authority().certificate.clone()],
+        )?;
+
+        // readers of the HTML pages get HTML errors, not the API's JSON
+        let response =
+            reqwest::get(base_url.join(&format!("/bulletin-board/elections/{}", Uuid::new_v4()))?)
+                .await?;
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+        assert!(response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .is_some_and(|content_type| content_type.starts_with("text/html")));
+        assert!(response
+            .text()
+            .await?
+            .contains("This page could not be found."));
+
+        Ok(())
+    }
+
+    /// Every operation in the OpenAPI document is routed, i.e. requests for it
+    /// don't fall through to the router's empty 404 or get a 405. The reverse
+    /// is checked by `openapi::tests::test_every_route_is_documented`.
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_openapi_routes_exist(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let base_url = spawn_server(
+            pool,
+            SignatureAlgorithm::ALL.to_vec(),
+            vec![voter_certificate_authority().certificate.clone()],
+        )?;
+        let http = reqwest::Client::new();
+
+        let openapi: serde_json::Value = http
+            .get(base_url.join("/api/openapi.json")?)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let paths = openapi["paths"].as_object().unwrap();
+        assert!(paths.contains_key("/api/objects"), "{paths:?}");
+
+        let path_param = regex::Regex::new(r"\{([a-z0-9_]+)\}")?;
+        for (path, path_item) in paths {
+            let path = path_param.replace_all(path, |captures: &regex::Captures| {
+                if captures[1].ends_with("_id") {
+                    Uuid::new_v4().to_string()
+                } else {
+                    captures[1].to_owned()
+                }
+            });
+            for method in path_item.as_object().unwrap().keys() {
+                let response = http
+                    .request(method.to_uppercase().parse()?, base_url.join(&path)?)
+                    .send()
+                    .await?;
+                let status = response.status();
+                let body = response.bytes().await?;
+                assert_ne!(
+                    status,
+                    reqwest::StatusCode::METHOD_NOT_ALLOWED,
+                    "{method} {path}"
+                );
+                assert!(
+                    status != reqwest::StatusCode::NOT_FOUND || !body.is_empty(),
+                    "{method} {path} is not routed"
+                );
+            }
+        }
+
+        Ok(())
+    }
 }
@@ -1,11 +1,33 @@
 //! Application configuration.
 
+use std::str::FromStr;
+
 use clap::Parser;
+use openssl::{
+    pkey::{PKey, Private},
+    x509::X509,
+};
+use types_rs::cacvote::{JurisdictionCode, SignatureAlgorithm};
+
+use crate::cac;
 
 const TEN_MB: usize = 10 * 1024 * 1024;
 
 pub const MAX_REQUEST_SIZE: usize = TEN_MB;
 
+/// The largest blob that may be uploaded. Blobs are uploaded in chunks of at
+/// most [`MAX_REQUEST_SIZE`].
+pub const MAX_BLOB_SIZE: u64 = 512 * 1024 * 1024;
+
+/// How long a voter has to answer a voter session challenge.
+pub const VOTER_SESSION_CHALLENGE_TTL: time::Duration = time::Duration::minutes(5);
+
+/// How long a voter session lasts.
+pub const VOTER_SESSION_TTL: time::Duration = time::Duration::minutes(15);
+
+pub const VOTER_SESSION_CHALLENGE_NONCE_LENGTH: usize = 32;
+pub const VOTER_SESSION_TOKEN_LENGTH: usize = 32;
+
 #[derive(Debug, Clone, Parser)]
 #[command(author, version, about)]
 pub struct Config {
@@ -20,4 +42,111 @@ pub struct Config {
     /// Log level.
     #[arg(long, env = "LOG_LEVEL", default_value = "info")]
     pub(crate) log_level: tracing::Level,
+
+    /// Base URL of an OpenTelemetry collector to export traces to over
+    /// OTLP/HTTP, e.g. `http://localhost:4318`. Without one, traces are only
+    /// logged.
+    #[arg(long, env = "OTEL_EXPORTER_OTLP_ENDPOINT")]
+    pub(crate) otlp_endpoint: Option<String>,
+
+    /// Signature algorithms accepted on new objects, e.g. `ES256,PS256`.
+    /// Objects without an explicit algorithm are checked against the algorithm
+    /// inferred from their signing certificate.
+    #[arg(
+        long,
+        env = "ALLOWED_SIGNATURE_ALGORITHMS",
+        value_delimiter = ',',
+        default_values_t = SignatureAlgorithm::ALL
+    )]
+    pub(crate) allowed_signature_algorithms: Vec<SignatureAlgorithm>,
+
+    /// Accept objects whose type this build doesn't know, e.g. from newer
+    /// clients, storing and relaying them as is. Only their signatures are
+    /// checked, and since their private fields aren't known they're served to
+    /// the public unredacted. Objects of known types in a newer payload
+    /// envelope or schema version are rejected regardless, since the checks
+    /// their type requires can't be made.
+    #[arg(long, env = "ALLOW_UNKNOWN_OBJECT_TYPES")]
+    pub(crate) allow_unknown_object_types: bool,
+
+    /// Secret used to commit to the private fields of objects shown to the
+    /// public (see [`RedactionPolicy`][types_rs::cacvote::RedactionPolicy]).
+    /// Changing it changes every published commitment.
+    #[arg(long, env = "REDACTION_KEY")]
+    pub(crate) redaction_key: String,
+
+    /// API keys that let jurisdiction officials read their jurisdiction's
+    /// objects in full, e.g. `st.dev-jurisdiction=secret,st.other=secret2`.
+    #[arg(long, env = "OFFICIAL_API_KEYS", value_delimiter = ',')]
+    pub(crate) official_api_keys: Vec<OfficialApiKey>,
+
+    /// Paths of the CA certificates, in PEM or DER format, that voters' Common
+    /// Access Card certificates must chain to in order to start a voter
+    /// session or to sign registration requests and cast ballots, e.g. the DoD
+    /// JITC email CA. Without any, neither is accepted.
+    #[arg(
+        long,
+        env = "CAC_CA_CERTIFICATES",
+        value_delimiter = ',',
+        value_parser = cac::load_ca_certificate
+    )]
+    pub(crate) cac_ca_certificates: Vec<X509>,
+
+    /// Paths of the CA certificates, in PEM or DER format, that election
+    /// officials' card certificates must chain to in order to count towards a
+    /// [`SignaturePolicy`][types_rs::cacvote::SignaturePolicy] restricted to
+    /// them, e.g. the VotingWorks root CA. Without any, objects with such a
+    /// policy, e.g. decrypted tallies, are not accepted.
+    #[arg(
+        long,
+        env = "VX_CA_CERTIFICATES",
+        value_delimiter = ',',
+        value_parser = cac::load_ca_certificate
+    )]
+    pub(crate) vx_ca_certificates: Vec<X509>,
+
+    /// Path of the private key, in PEM format, used to sign the tree heads of
+    /// jurisdiction snapshots (see
+    /// [`SnapshotTreeHead`][types_rs::cacvote::SnapshotTreeHead]). Without
+    /// one, tree heads are unsigned.
+    #[arg(long, env = "SNAPSHOT_SIGNING_KEY", value_parser = load_private_key)]
+    pub(crate) snapshot_signing_key: Option<PKey<Private>>,
+}
+
+/// Reads a private key from a file in PEM format.
+fn load_private_key(path: &str) -> Result<PKey<Private>, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Unable to read {path}: {e}"))?;
+    PKey::private_key_from_pem(&bytes)
+        .map_err(|e| format!("Unable to parse private key {path}: {e}"))
+}
+
+/// An API key granting full read access to one jurisdiction's objects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OfficialApiKey {
+    pub(crate) jurisdiction_code: JurisdictionCode,
+    pub(crate) key: String,
+}
+
+impl FromStr for OfficialApiKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((jurisdiction_code, key)) = s.split_once('=') else {
+            return Err(format!(
+                "Expected an API key of the form JURISDICTION=KEY, got: {s}"
+            ));
+        };
+
+        if key.is_empty() {
+            return Err(format!(
+                "Empty API key for jurisdiction {jurisdiction_code}"
+            ));
+        }
+
+        Ok(Self {
+            jurisdiction_code: JurisdictionCode::try_from(jurisdiction_code)
+                .map_err(|e| format!("Invalid jurisdiction code: {e}"))?,
+            key: key.to_owned(),
+        })
+    }
 }
@@ -14,8 +14,10 @@ use color_eyre::eyre::bail;
 use sqlx::{self, postgres::PgPoolOptions, Connection, PgPool};
 use tracing::Level;
 use types_rs::cacvote::{
-    self, BallotVerificationPayload, JournalEntry, JournalEntryAction, JurisdictionCode,
-    SignedBuffer, SignedObject,
+    self, BallotVerificationPayload, BlobHasher, BlobReference, BlobUpload, Cosignatures,
+    JournalEntry, JournalEntryAction, JurisdictionCode, ObjectMetadata, ObjectSearchPage,
+    ObjectSearchQuery, ObjectSearchResult, ObjectView, SignatureAlgorithm, SignedBuffer,
+    SignedObject, SignerCertificate, SnapshotEntry, SnapshotLeaf, SnapshotPage,
 };
 use uuid::Uuid;
 
@@ -33,17 +35,185 @@ pub async fn setup(config: &Config) -> color_eyre::Result<PgPool> {
         .connect(&config.database_url)
         .await?;
     sqlx::migrate!("db/migrations").run(&pool).await?;
+    index_signer_certificates(&mut *pool.acquire().await?).await?;
+    index_voter_ids(&mut *pool.acquire().await?, config).await?;
     Ok(pool)
 }
 
+/// The version of the latest migration in this build, i.e. the version the
+/// database is at once [`setup`] has run.
+pub(crate) fn latest_migration_version() -> Option<i64> {
+    sqlx::migrate!("db/migrations")
+        .iter()
+        .map(|migration| migration.version)
+        .max()
+}
+
+/// The version of the latest migration applied to the database.
+pub(crate) async fn get_applied_migration_version(
+    connection: &mut sqlx::PgConnection,
+) -> color_eyre::Result<Option<i64>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT MAX(version) AS version
+        FROM _sqlx_migrations
+        WHERE success
+        "#
+    )
+    .fetch_one(connection)
+    .await?
+    .version)
+}
+
+/// Fills in the signer attributes of certificates that were stored without
+/// them, i.e. those moved out of the `objects` table by migration.
+async fn index_signer_certificates(connection: &mut sqlx::PgConnection) -> color_eyre::Result<()> {
+    let records = sqlx::query!(
+        r#"
+        SELECT fingerprint, certificates
+        FROM certificates
+        WHERE not_after IS NULL
+        "#
+    )
+    .fetch_all(&mut *connection)
+    .await?;
+
+    for record in records {
+        let signer_certificate = match SignerCertificate::from_pem_stack(&record.certificates) {
+            Ok(signer_certificate) => signer_certificate,
+            Err(e) => {
+                tracing::warn!(
+                    "unable to parse certificates with fingerprint {}: {e}",
+                    record.fingerprint
+                );
+                continue;
+            }
+        };
+
+        sqlx::query!(
+            r#"
+            UPDATE certificates
+            SET jurisdiction = $2, card_type = $3, not_after = $4
+            WHERE fingerprint = $1
+            "#,
+            record.fingerprint,
+            signer_certificate.jurisdiction,
+            signer_certificate.card_type,
+            signer_certificate.not_after,
+        )
+        .execute(&mut *connection)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Fills in the voter IDs of voters' objects that were stored without them,
+/// i.e. those created before voter IDs were recorded. Objects whose
+/// certificates no longer verify, e.g. because they have expired, are left
+/// without one and so aren't listed for their voter.
+async fn index_voter_ids(
+    connection: &mut sqlx::PgConnection,
+    config: &Config,
+) -> color_eyre::Result<()> {
+    let objects = sqlx::query_as!(
+        cacvote::SignedObject,
+        r#"
+        SELECT
+            o.id,
+            o.election_id,
+            o.payload,
+            o.certificates,
+            o.signature,
+            o.signature_algorithm as "signature_algorithm: SignatureAlgorithm",
+            o.cosignatures as "cosignatures: Cosignatures"
+        FROM objects AS o
+        WHERE o.voter_id IS NULL
+          AND o.object_type IN ($1, $2)
+        "#,
+        cacvote::Payload::registration_request_object_type(),
+        cacvote::Payload::cast_ballot_object_type(),
+    )
+    .fetch_all(&mut *connection)
+    .await?;
+
+    let mut unindexed_count = 0;
+    for object in objects {
+        let common_access_card_id =
+            match crate::cac::verify_voter_object_signer(&object, &config.cac_ca_certificates) {
+                Ok(Some(common_access_card_id)) => common_access_card_id,
+                Ok(None) | Err(_) => {
+                    unindexed_count += 1;
+                    continue;
+                }
+            };
+
+        sqlx::query!(
+            r#"
+            UPDATE objects
+            SET voter_id = $2
+            WHERE id = $1
+            "#,
+            object.id,
+            crate::cac::voter_id(config.redaction_key.as_bytes(), &common_access_card_id)?,
+        )
+        .execute(&mut *connection)
+        .await?;
+    }
+
+    if unindexed_count > 0 {
+        tracing::warn!("unable to identify the voters of {unindexed_count} objects");
+    }
+
+    Ok(())
+}
+
+/// Indexes the signer of a stack of PEM-encoded certificates if it isn't
+/// already indexed, returning the signer's fingerprint. The stack itself is
+/// stored with each object it signed, since the same signer may present
+/// different chains over time.
+///
+/// The indexed attributes are read from the certificate as presented and the
+/// chain is not verified here, so they are only as trustworthy as the checks
+/// the caller made before storing the object.
+async fn store_signer_certificate(
+    connection: &mut sqlx::PgConnection,
+    certificates: &[u8],
+) -> color_eyre::Result<String> {
+    let signer_certificate = SignerCertificate::from_pem_stack(certificates)?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO certificates (fingerprint, certificates, jurisdiction, card_type, not_after)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (fingerprint) DO NOTHING
+        "#,
+        signer_certificate.fingerprint,
+        certificates,
+        signer_certificate.jurisdiction,
+        signer_certificate.card_type,
+        signer_certificate.not_after,
+    )
+    .execute(connection)
+    .await?;
+
+    Ok(signer_certificate.fingerprint)
+}
+
+/// Returned by [`create_object`] when another new version of the object it
+/// supersedes was stored first.
+#[derive(Debug, thiserror::Error)]
+#[error("Object {0} has already been superseded")]
+pub(crate) struct AlreadySuperseded(pub(crate) Uuid);
+
+/// Stores an object, whose signatures the caller must already have verified.
+/// `voter_id` identifies the voter who signed it for voters' objects, see
+/// [`crate::cac::voter_id`].
 pub async fn create_object(
     connection: &mut sqlx::PgConnection,
     object: &SignedObject,
+    voter_id: Option<&str>,
 ) -> color_eyre::Result<Uuid> {
-    if !object.verify()? {
-        bail!("Unable to verify signature/certificates")
-    }
-
     let Some(jurisdiction_code) = object.jurisdiction_code() else {
         tracing::error!(
             "no jurisdiction found in object: {:?} (try_to_inner={:?})",
@@ -53,14 +223,29 @@ pub async fn create_object(
         bail!("No jurisdiction found");
     };
 
-    let object_type = object.try_to_inner()?.object_type();
+    let payload = object.try_to_inner()?;
+    let object_type = payload.object_type();
+    let supersedes = object.supersedes()?;
+    let action = if supersedes.is_some() {
+        JournalEntryAction::Update
+    } else {
+        JournalEntryAction::Create
+    };
 
     let mut txn = connection.begin().await?;
 
+    let signer_fingerprint = match store_signer_certificate(&mut txn, &object.certificates).await {
+        Ok(signer_fingerprint) => signer_fingerprint,
+        Err(e) => {
+            txn.rollback().await?;
+            bail!("Error storing signer certificate: {e}");
+        }
+    };
+
     match sqlx::query!(
         r#"
-        INSERT INTO objects (id, election_id, jurisdiction, object_type, payload, certificates, signature)
-        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        INSERT INTO objects (id, election_id, jurisdiction, object_type, payload, certificates, signer_fingerprint, signature, signature_algorithm, cosignatures, supersedes_object_id, voter_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
         "#,
         &object.id,
         object.election_id,
@@ -68,7 +253,12 @@ pub async fn create_object(
         object_type,
         &object.payload,
         &object.certificates,
-        &object.signature
+        signer_fingerprint,
+        &object.signature,
+        object.signature_algorithm as Option<SignatureAlgorithm>,
+        &object.cosignatures as &Cosignatures,
+        supersedes,
+        voter_id,
     )
     .execute(&mut *txn)
     .await
@@ -76,22 +266,57 @@ pub async fn create_object(
         Ok(_) => {}
         Err(e) => {
             txn.rollback().await?;
+            if let (Some(superseded_object_id), sqlx::Error::Database(database_error)) =
+                (supersedes, &e)
+            {
+                if database_error.constraint() == Some("objects_supersedes_object_id_key") {
+                    return Err(AlreadySuperseded(superseded_object_id).into());
+                }
+            }
             bail!("Error creating object: {e}");
         }
     }
 
+    for cosignature in object.cosignatures.iter() {
+        let cosigner_fingerprint =
+            match store_signer_certificate(&mut txn, &cosignature.certificates).await {
+                Ok(cosigner_fingerprint) => cosigner_fingerprint,
+                Err(e) => {
+                    txn.rollback().await?;
+                    bail!("Error storing cosigner certificate: {e}");
+                }
+            };
+
+        if let Err(e) = sqlx::query!(
+            r#"
+            INSERT INTO object_cosigners (object_id, signer_fingerprint)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            object.id,
+            cosigner_fingerprint,
+        )
+        .execute(&mut *txn)
+        .await
+        {
+            txn.rollback().await?;
+            bail!("Error storing cosigner: {e}");
+        }
+    }
+
     tracing::debug!("Creating object with id {}", object.id);
 
     let journal_entry = match sqlx::query!(
         r#"
         INSERT INTO journal_entries (object_id, election_id, jurisdiction, object_type, action)
-        VALUES ($1, $2, $3, $4, 'create')
+        VALUES ($1, $2, $3, $4, $5)
         RETURNING id
         "#,
         object.id,
         object.election_id,
         jurisdiction_code.as_str(),
         object_type,
+        action.as_str(),
     )
     .fetch_one(&mut *txn)
     .await
@@ -112,6 +337,20 @@ pub async fn create_object(
     Ok(object.id)
 }
 
+pub(crate) async fn count_journal_entries(
+    connection: &mut sqlx::PgConnection,
+) -> color_eyre::Result<i64> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT COUNT(*) AS "count!"
+        FROM journal_entries
+        "#
+    )
+    .fetch_one(connection)
+    .await?
+    .count)
+}
+
 pub async fn get_journal_entries(
     connection: &mut sqlx::PgConnection,
     since_journal_entry_id: Option<Uuid>,
@@ -237,9 +476,16 @@ pub async fn get_object_by_id(
     let object = sqlx::query_as!(
         cacvote::SignedObject,
         r#"
-        SELECT id, election_id, payload, certificates, signature
-        FROM objects
-        WHERE id = $1
+        SELECT
+            o.id,
+            o.election_id,
+            o.payload,
+            o.certificates,
+            o.signature,
+            o.signature_algorithm as "signature_algorithm: SignatureAlgorithm",
+            o.cosignatures as "cosignatures: Cosignatures"
+        FROM objects AS o
+        WHERE o.id = $1
         "#,
         object_id
     )
@@ -261,36 +507,350 @@ pub async fn get_object_by_id(
     Ok(object)
 }
 
-pub(crate) async fn get_election_ids(
+/// Gets the latest version of the object with `object_id`, which may be the
+/// object itself. See [`cacvote::ObjectVersion`].
+pub(crate) async fn get_latest_object_version(
     connection: &mut sqlx::PgConnection,
-) -> color_eyre::Result<Vec<Uuid>> {
-    let object = sqlx::query!(
+    object_id: Uuid,
+) -> color_eyre::Result<Option<SignedObject>> {
+    let Some(record) = sqlx::query!(
+        r#"
+        WITH RECURSIVE versions AS (
+            SELECT id, 0 AS depth
+            FROM objects
+            WHERE id = $1
+            UNION ALL
+            SELECT o.id, v.depth + 1
+            FROM objects AS o
+            INNER JOIN versions AS v
+              ON o.supersedes_object_id = v.id
+        )
+        SELECT id AS "id!"
+        FROM versions
+        ORDER BY depth DESC
+        LIMIT 1
+        "#,
+        object_id
+    )
+    .fetch_optional(&mut *connection)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    get_object_by_id(connection, record.id).await
+}
+
+/// Gets every version of the object with `object_id`, both earlier and later,
+/// oldest first. Empty if there is no such object.
+pub(crate) async fn get_object_versions(
+    connection: &mut sqlx::PgConnection,
+    object_id: Uuid,
+) -> color_eyre::Result<Vec<cacvote::ObjectVersion>> {
+    Ok(sqlx::query_as!(
+        cacvote::ObjectVersion,
+        r#"
+        WITH RECURSIVE
+        earlier_versions AS (
+            SELECT id, supersedes_object_id
+            FROM objects
+            WHERE id = $1
+            UNION ALL
+            SELECT o.id, o.supersedes_object_id
+            FROM objects AS o
+            INNER JOIN earlier_versions AS v
+              ON o.id = v.supersedes_object_id
+        ),
+        later_versions AS (
+            SELECT id
+            FROM objects
+            WHERE id = $1
+            UNION ALL
+            SELECT o.id
+            FROM objects AS o
+            INNER JOIN later_versions AS v
+              ON o.supersedes_object_id = v.id
+        )
+        SELECT
+            o.id AS object_id,
+            o.supersedes_object_id AS supersedes,
+            o.created_at
+        FROM objects AS o
+        WHERE o.id IN (SELECT id FROM earlier_versions)
+           OR o.id IN (SELECT id FROM later_versions)
+        ORDER BY o.created_at, o.id
+        "#,
+        object_id
+    )
+    .fetch_all(connection)
+    .await?)
+}
+
+/// Gets the ID of the object that supersedes the one with `object_id`, if it
+/// has been superseded.
+pub(crate) async fn get_superseding_object_id(
+    connection: &mut sqlx::PgConnection,
+    object_id: Uuid,
+) -> color_eyre::Result<Option<Uuid>> {
+    Ok(sqlx::query!(
         r#"
         SELECT id
         FROM objects
-        WHERE object_type = $1
+        WHERE supersedes_object_id = $1
         "#,
-        cacvote::Payload::election_object_type(),
+        object_id
+    )
+    .fetch_optional(connection)
+    .await?
+    .map(|record| record.id))
+}
+
+pub(crate) async fn get_object_created_at(
+    connection: &mut sqlx::PgConnection,
+    object_id: Uuid,
+) -> color_eyre::Result<Option<time::OffsetDateTime>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT created_at
+        FROM objects
+        WHERE id = $1
+        "#,
+        object_id
+    )
+    .fetch_optional(connection)
+    .await?
+    .map(|record| record.created_at))
+}
+
+/// Gets the cast ballot whose signature has the SHA-256 digest
+/// `tracking_code`, see [`cacvote::tracking_code`].
+pub(crate) async fn get_cast_ballot_by_tracking_code(
+    connection: &mut sqlx::PgConnection,
+    tracking_code: &[u8],
+) -> color_eyre::Result<Option<SignedObject>> {
+    Ok(sqlx::query_as!(
+        cacvote::SignedObject,
+        r#"
+        SELECT
+            o.id,
+            o.election_id,
+            o.payload,
+            o.certificates,
+            o.signature,
+            o.signature_algorithm as "signature_algorithm: SignatureAlgorithm",
+            o.cosignatures as "cosignatures: Cosignatures"
+        FROM objects AS o
+        WHERE sha256(o.signature) = $1
+          AND o.object_type = $2
+        "#,
+        tracking_code,
+        cacvote::Payload::cast_ballot_object_type(),
+    )
+    .fetch_optional(connection)
+    .await?)
+}
+
+/// Gets when the first object of a type was created for an election at or
+/// after `created_after`.
+pub(crate) async fn get_first_object_created_at_by_election_and_type(
+    connection: &mut sqlx::PgConnection,
+    election_id: Uuid,
+    object_type: &str,
+    created_after: time::OffsetDateTime,
+) -> color_eyre::Result<Option<time::OffsetDateTime>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT MIN(created_at) AS created_at
+        FROM objects
+        WHERE election_id = $1
+          AND object_type = $2
+          AND created_at >= $3
+        "#,
+        election_id,
+        object_type,
+        created_after,
+    )
+    .fetch_one(connection)
+    .await?
+    .created_at)
+}
+
+/// Finds the objects matching `query`, returning at most `limit` objects along
+/// with whether there are more. Objects are returned in full, so redact them
+/// before showing them to anyone but their jurisdiction's officials. Returns
+/// `None` if the query continues from a cursor that isn't a stored object.
+pub(crate) async fn search_objects(
+    connection: &mut sqlx::PgConnection,
+    query: &ObjectSearchQuery,
+    limit: usize,
+) -> color_eyre::Result<Option<ObjectSearchPage>> {
+    // fetch one extra to know whether there's another page
+    let fetch_limit = i64::try_from(limit)? + 1;
+
+    let cursor = match query.cursor {
+        Some(cursor) => {
+            let Some(record) = sqlx::query!(
+                r#"
+                SELECT created_at
+                FROM objects
+                WHERE id = $1
+                "#,
+                cursor,
+            )
+            .fetch_optional(&mut *connection)
+            .await?
+            else {
+                return Ok(None);
+            };
+            Some((record.created_at, cursor))
+        }
+        None => None,
+    };
+
+    // the object columns are only selected when they're wanted, so that
+    // metadata-only searches share the same filters without the cost
+    let records = sqlx::query!(
+        r#"
+        SELECT
+            o.id,
+            o.election_id,
+            o.jurisdiction as "jurisdiction: JurisdictionCode",
+            o.object_type,
+            o.signer_fingerprint,
+            o.supersedes_object_id,
+            o.created_at,
+            CASE WHEN $11 THEN NULL ELSE o.payload END AS payload,
+            CASE WHEN $11 THEN NULL ELSE o.certificates END AS certificates,
+            CASE WHEN $11 THEN NULL ELSE o.signature END AS signature,
+            o.signature_algorithm as "signature_algorithm: SignatureAlgorithm",
+            CASE WHEN $11 THEN NULL ELSE o.cosignatures END AS "cosignatures: Cosignatures"
+        FROM objects AS o
+        WHERE ($1::varchar IS NULL OR o.jurisdiction = $1)
+          AND ($2::varchar IS NULL OR o.object_type = $2)
+          AND ($3::uuid IS NULL OR o.election_id = $3)
+          AND ($4::varchar IS NULL OR o.signer_fingerprint = $4)
+          AND ($5::timestamptz IS NULL OR o.created_at >= $5)
+          AND ($6::timestamptz IS NULL OR o.created_at < $6)
+          AND ($7::timestamptz IS NULL OR (o.created_at, o.id) > ($7, $8::uuid))
+          AND ($10 OR NOT EXISTS (SELECT 1 FROM objects AS s WHERE s.supersedes_object_id = o.id))
+        ORDER BY o.created_at, o.id
+        LIMIT $9
+        "#,
+        query
+            .jurisdiction_code
+            .as_ref()
+            .map(JurisdictionCode::as_str),
+        query.object_type,
+        query.election_id,
+        query.signer_fingerprint,
+        query.created_after,
+        query.created_before,
+        cursor.map(|(created_at, _)| created_at),
+        cursor.map(|(_, id)| id),
+        fetch_limit,
+        query.include_superseded,
+        query.metadata_only,
     )
     .fetch_all(connection)
     .await?;
 
-    Ok(object.into_iter().map(|object| object.id).collect())
+    let mut objects = records
+        .into_iter()
+        .map(|record| {
+            let object = match (
+                record.payload,
+                record.certificates,
+                record.signature,
+                record.cosignatures,
+            ) {
+                (Some(payload), Some(certificates), Some(signature), Some(cosignatures)) => {
+                    Some(ObjectView::Full(SignedObject {
+                        id: record.id,
+                        election_id: record.election_id,
+                        payload,
+                        certificates,
+                        signature,
+                        signature_algorithm: record.signature_algorithm,
+                        cosignatures,
+                    }))
+                }
+                _ => None,
+            };
+
+            ObjectSearchResult {
+                metadata: ObjectMetadata {
+                    id: record.id,
+                    election_id: record.election_id,
+                    jurisdiction_code: record.jurisdiction,
+                    object_type: record.object_type,
+                    signer_fingerprint: record.signer_fingerprint,
+                    supersedes: record.supersedes_object_id,
+                    created_at: record.created_at,
+                },
+                object,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let next_cursor = if objects.len() > limit {
+        objects.truncate(limit);
+        objects.last().map(|result| result.metadata.id)
+    } else {
+        None
+    };
+
+    Ok(Some(ObjectSearchPage {
+        objects,
+        next_cursor,
+    }))
 }
 
-pub(crate) async fn get_cast_ballot_ids_by_election(
+/// Gets the attributes of an indexed signer's certificate. These are read from
+/// the stored certificate rather than the index, since the common name isn't
+/// indexed.
+pub(crate) async fn get_signer_certificate(
     connection: &mut sqlx::PgConnection,
-    election_id: Uuid,
+    fingerprint: &str,
+) -> color_eyre::Result<Option<SignerCertificate>> {
+    let Some(record) = sqlx::query!(
+        r#"
+        SELECT certificates
+        FROM certificates
+        WHERE fingerprint = $1
+        "#,
+        fingerprint,
+    )
+    .fetch_optional(connection)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(SignerCertificate::from_pem_stack(
+        &record.certificates,
+    )?))
+}
+
+/// Gets the IDs of all objects signed or cosigned by the certificate with the
+/// given fingerprint, oldest first.
+pub(crate) async fn get_object_ids_by_signer(
+    connection: &mut sqlx::PgConnection,
+    fingerprint: &str,
 ) -> color_eyre::Result<Vec<Uuid>> {
     let records = sqlx::query!(
         r#"
-        SELECT id
-        FROM objects
-        WHERE object_type = $1
-          AND election_id = $2
+        SELECT o.id
+        FROM objects AS o
+        WHERE o.signer_fingerprint = $1
+           OR EXISTS (
+               SELECT 1
+               FROM object_cosigners AS oc
+               WHERE oc.object_id = o.id
+                 AND oc.signer_fingerprint = $1
+           )
+        ORDER BY o.created_at, o.id
         "#,
-        cacvote::Payload::cast_ballot_object_type(),
-        election_id
+        fingerprint,
     )
     .fetch_all(connection)
     .await?;
@@ -298,78 +858,601 @@ pub(crate) async fn get_cast_ballot_ids_by_election(
     Ok(records.into_iter().map(|record| record.id).collect())
 }
 
-pub(crate) async fn get_object_by_election_id_and_type(
-    conn: &mut sqlx::PgConnection,
-    election_id: Uuid,
+/// Gets the objects of a type signed by the voter with `voter_id`, with any
+/// of their CACs, oldest first.
+pub(crate) async fn get_objects_by_voter_and_type(
+    connection: &mut sqlx::PgConnection,
+    voter_id: &str,
     object_type: &str,
-) -> color_eyre::Result<Option<SignedObject>> {
+) -> color_eyre::Result<Vec<SignedObject>> {
     Ok(sqlx::query_as!(
         cacvote::SignedObject,
         r#"
-            SELECT id, election_id, payload, certificates, signature
-            FROM objects
-            WHERE election_id = $1
-              AND object_type = $2
-            "#,
-        election_id,
+        SELECT
+            o.id,
+            o.election_id,
+            o.payload,
+            o.certificates,
+            o.signature,
+            o.signature_algorithm as "signature_algorithm: SignatureAlgorithm",
+            o.cosignatures as "cosignatures: Cosignatures"
+        FROM objects AS o
+        WHERE o.voter_id = $1
+          AND o.object_type = $2
+        ORDER BY o.created_at, o.id
+        "#,
+        voter_id,
         object_type,
     )
-    .fetch_optional(conn)
+    .fetch_all(connection)
     .await?)
 }
 
-pub(crate) async fn get_machine_id_by_identifier(
-    conn: &mut sqlx::PgConnection,
-    identifier: &str,
-) -> color_eyre::Result<Option<Uuid>> {
-    Ok(sqlx::query!(
+/// Gets the registrations approving any of the given registration requests,
+/// oldest first.
+pub(crate) async fn get_registrations_by_registration_request_ids(
+    connection: &mut sqlx::PgConnection,
+    registration_request_ids: &[Uuid],
+) -> color_eyre::Result<Vec<SignedObject>> {
+    Ok(sqlx::query_as!(
+        cacvote::SignedObject,
         r#"
-        SELECT id
-        FROM machines
-        WHERE machine_identifier = $1
+        SELECT
+            o.id,
+            o.election_id,
+            o.payload,
+            o.certificates,
+            o.signature,
+            o.signature_algorithm as "signature_algorithm: SignatureAlgorithm",
+            o.cosignatures as "cosignatures: Cosignatures"
+        FROM objects AS o
+        WHERE o.object_type = $1
+          AND (convert_from(o.payload, 'UTF8')::jsonb ->> $2)::uuid = ANY($3)
+        ORDER BY o.created_at
         "#,
-        identifier,
+        cacvote::Payload::registration_object_type(),
+        cacvote::Registration::registration_request_object_id_field_name(),
+        registration_request_ids,
     )
-    .fetch_optional(conn)
-    .await?
-    .map(|record| record.id))
+    .fetch_all(connection)
+    .await?)
 }
 
-pub(crate) async fn create_scanned_mailing_label_code(
-    conn: &mut sqlx::PgConnection,
-    ballot_verification_payload: &[u8],
-) -> color_eyre::Result<Uuid> {
-    let mut txn = conn.begin().await?;
-    let original_payload = ballot_verification_payload;
-    let signed_buffer: SignedBuffer = tlv::from_slice(original_payload)?;
+/// A registration request or registration of a voter who has registration
+/// requests in more than one jurisdiction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CrossJurisdictionRegistrationRecord {
+    pub(crate) object_id: Uuid,
+    pub(crate) object_type: String,
+    pub(crate) jurisdiction_code: JurisdictionCode,
 
-    // TODO: do something with `signed_buffer.signature()`?
-    let ballot_verification_payload: BallotVerificationPayload =
-        tlv::from_slice(signed_buffer.buffer())?;
+    /// The voter who signed the registration request, see
+    /// [`crate::cac::voter_id`].
+    pub(crate) voter_id: String,
 
-    let Some(machine_id) =
-        get_machine_id_by_identifier(&mut txn, ballot_verification_payload.machine_id()).await?
-    else {
-        bail!(
-            "Machine with identifier {} not found",
-            ballot_verification_payload.machine_id()
-        );
-    };
+    /// The object's own ID for registration requests.
+    pub(crate) registration_request_object_id: Uuid,
 
-    let record = sqlx::query!(
+    pub(crate) election_object_id: Option<Uuid>,
+}
+
+/// Gets the registration requests and registrations of every voter who has
+/// registration requests in more than one jurisdiction, one of them
+/// `jurisdiction_code`, grouped by voter and oldest first. Voters are
+/// identified by the chain-validated CAC certificates that signed their
+/// registration requests, since payload CAC IDs may be encrypted.
+pub(crate) async fn get_cross_jurisdiction_registrations(
+    connection: &mut sqlx::PgConnection,
+    jurisdiction_code: &JurisdictionCode,
+) -> color_eyre::Result<Vec<CrossJurisdictionRegistrationRecord>> {
+    Ok(sqlx::query_as!(
+        CrossJurisdictionRegistrationRecord,
         r#"
-        INSERT INTO scanned_mailing_label_codes (
-            election_id,
-            machine_id,
-            common_access_card_id,
-            encrypted_ballot_signature_hash,
-            original_payload
+        WITH registration_requests AS (
+            SELECT
+                o.id,
+                o.jurisdiction,
+                o.created_at,
+                o.voter_id
+            FROM objects AS o
+            WHERE o.object_type = $1
+              AND o.voter_id IS NOT NULL
+        ),
+        duplicated_registration_requests AS (
+            SELECT *
+            FROM registration_requests
+            WHERE voter_id IN (
+                SELECT voter_id
+                FROM registration_requests
+                GROUP BY voter_id
+                HAVING count(DISTINCT jurisdiction) > 1
+                   AND bool_or(jurisdiction = $4)
+            )
+        ),
+        voter_objects AS (
+            SELECT
+                rr.id AS object_id,
+                $1 AS object_type,
+                rr.jurisdiction,
+                rr.voter_id,
+                rr.id AS registration_request_object_id,
+                NULL::uuid AS election_object_id,
+                rr.created_at
+            FROM duplicated_registration_requests AS rr
+            UNION ALL
+            SELECT
+                o.id,
+                o.object_type,
+                o.jurisdiction,
+                rr.voter_id,
+                rr.id,
+                o.election_id,
+                o.created_at
+            FROM objects AS o
+            INNER JOIN duplicated_registration_requests AS rr
+              ON rr.id = (convert_from(o.payload, 'UTF8')::jsonb ->> $3)::uuid
+            WHERE o.object_type = $2
         )
-        VALUES ($1, $2, $3, $4, $5)
-        RETURNING id
+        SELECT
+            object_id AS "object_id!",
+            object_type AS "object_type!",
+            jurisdiction AS "jurisdiction_code!: JurisdictionCode",
+            voter_id AS "voter_id!",
+            registration_request_object_id AS "registration_request_object_id!",
+            election_object_id
+        FROM voter_objects
+        ORDER BY voter_id, created_at, object_id
         "#,
-        ballot_verification_payload.election_object_id(),
-        machine_id,
+        cacvote::Payload::registration_request_object_type(),
+        cacvote::Payload::registration_object_type(),
+        cacvote::Registration::registration_request_object_id_field_name(),
+        jurisdiction_code.as_str(),
+    )
+    .fetch_all(connection)
+    .await?)
+}
+
+pub(crate) async fn get_election_ids(
+    connection: &mut sqlx::PgConnection,
+) -> color_eyre::Result<Vec<Uuid>> {
+    let object = sqlx::query!(
+        r#"
+        SELECT id
+        FROM objects
+        WHERE object_type = $1
+          AND supersedes_object_id IS NULL
+        ORDER BY created_at, id
+        "#,
+        cacvote::Payload::election_object_type(),
+    )
+    .fetch_all(connection)
+    .await?;
+
+    Ok(object.into_iter().map(|object| object.id).collect())
+}
+
+/// An election's latest version along with what has been posted for it.
+#[derive(Debug)]
+pub(crate) struct ElectionOverview {
+    /// The ID of the election's original object, which other objects refer
+    /// to.
+    pub(crate) election_id: Uuid,
+    pub(crate) election_object: SignedObject,
+
+    /// The number of ballots that count, i.e. that haven't been superseded.
+    pub(crate) cast_ballot_count: i64,
+    pub(crate) encrypted_tally_posted_at: Option<time::OffsetDateTime>,
+    pub(crate) decrypted_tally_posted_at: Option<time::OffsetDateTime>,
+    pub(crate) shuffled_ballots_posted_at: Option<time::OffsetDateTime>,
+}
+
+/// Gets the overview of every election, or only of the one with
+/// `election_id`, oldest first. Loads them all with a single query so that
+/// listing elections doesn't take a query per election.
+pub(crate) async fn get_election_overviews(
+    connection: &mut sqlx::PgConnection,
+    election_id: Option<Uuid>,
+) -> color_eyre::Result<Vec<ElectionOverview>> {
+    let records = sqlx::query!(
+        r#"
+        WITH RECURSIVE
+        versions AS (
+            SELECT id AS election_id, id, 0 AS depth
+            FROM objects
+            WHERE object_type = $1
+              AND supersedes_object_id IS NULL
+              AND ($2::uuid IS NULL OR id = $2)
+            UNION ALL
+            SELECT v.election_id, o.id, v.depth + 1
+            FROM objects AS o
+            INNER JOIN versions AS v
+              ON o.supersedes_object_id = v.id
+        ),
+        latest_versions AS (
+            SELECT DISTINCT ON (election_id) election_id, id
+            FROM versions
+            ORDER BY election_id, depth DESC
+        ),
+        activity AS (
+            SELECT
+                o.election_id,
+                MIN(o.created_at) FILTER (WHERE o.object_type = $3) AS encrypted_tally_posted_at,
+                MIN(o.created_at) FILTER (WHERE o.object_type = $4) AS decrypted_tally_posted_at,
+                MIN(o.created_at) FILTER (WHERE o.object_type = $5) AS shuffled_ballots_posted_at
+            FROM objects AS o
+            WHERE o.election_id IN (SELECT election_id FROM latest_versions)
+              AND NOT EXISTS (SELECT 1 FROM objects AS s WHERE s.supersedes_object_id = o.id)
+            GROUP BY o.election_id
+        ),
+        -- only each voter's last ballot counts
+        cast_ballot_counts AS (
+            SELECT election_id, COUNT(*) AS cast_ballot_count
+            FROM cast_ballot_supersessions
+            WHERE superseded_by_id IS NULL
+            GROUP BY election_id
+        )
+        SELECT
+            l.election_id AS "election_id!",
+            e.id,
+            e.election_id AS election_object_election_id,
+            e.payload,
+            e.certificates,
+            e.signature,
+            e.signature_algorithm as "signature_algorithm: SignatureAlgorithm",
+            e.cosignatures as "cosignatures: Cosignatures",
+            COALESCE(c.cast_ballot_count, 0) AS "cast_ballot_count!",
+            a.encrypted_tally_posted_at,
+            a.decrypted_tally_posted_at,
+            a.shuffled_ballots_posted_at
+        FROM latest_versions AS l
+        INNER JOIN objects AS original
+          ON original.id = l.election_id
+        INNER JOIN objects AS e
+          ON e.id = l.id
+        LEFT JOIN activity AS a
+          ON a.election_id = l.election_id
+        LEFT JOIN cast_ballot_counts AS c
+          ON c.election_id = l.election_id
+        ORDER BY original.created_at, l.election_id
+        "#,
+        cacvote::Payload::election_object_type(),
+        election_id,
+        cacvote::Payload::encrypted_election_tally_object_type(),
+        cacvote::Payload::decrypted_election_tally_object_type(),
+        cacvote::Payload::shuffled_encrypted_cast_ballots_object_type(),
+    )
+    .fetch_all(connection)
+    .await?;
+
+    Ok(records
+        .into_iter()
+        .map(|record| ElectionOverview {
+            election_id: record.election_id,
+            election_object: SignedObject {
+                id: record.id,
+                election_id: record.election_object_election_id,
+                payload: record.payload,
+                certificates: record.certificates,
+                signature: record.signature,
+                signature_algorithm: record.signature_algorithm,
+                cosignatures: record.cosignatures,
+            },
+            cast_ballot_count: record.cast_ballot_count,
+            encrypted_tally_posted_at: record.encrypted_tally_posted_at,
+            decrypted_tally_posted_at: record.decrypted_tally_posted_at,
+            shuffled_ballots_posted_at: record.shuffled_ballots_posted_at,
+        })
+        .collect())
+}
+
+/// Whether a cast ballot counts, see the `cast_ballot_supersessions` view.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CastBallotSupersession {
+    pub(crate) cast_ballot_id: Uuid,
+
+    /// The next ballot cast by the same voter in the same election, if any.
+    /// Only ballots that haven't been superseded count.
+    pub(crate) superseded_by_id: Option<Uuid>,
+    pub(crate) superseded_at: Option<time::OffsetDateTime>,
+}
+
+/// Gets every ballot cast in the election in the order they were recorded,
+/// along with whether each was superseded.
+pub(crate) async fn get_cast_ballot_supersessions_by_election(
+    connection: &mut sqlx::PgConnection,
+    election_id: Uuid,
+) -> color_eyre::Result<Vec<CastBallotSupersession>> {
+    Ok(sqlx::query_as!(
+        CastBallotSupersession,
+        r#"
+        SELECT
+            cast_ballot_id AS "cast_ballot_id!",
+            superseded_by_id,
+            superseded_at
+        FROM cast_ballot_supersessions
+        WHERE election_id = $1
+        ORDER BY recorded_at, cast_ballot_id
+        "#,
+        election_id
+    )
+    .fetch_all(connection)
+    .await?)
+}
+
+pub(crate) async fn get_cast_ballot_supersession(
+    connection: &mut sqlx::PgConnection,
+    cast_ballot_id: Uuid,
+) -> color_eyre::Result<Option<CastBallotSupersession>> {
+    Ok(sqlx::query_as!(
+        CastBallotSupersession,
+        r#"
+        SELECT
+            cast_ballot_id AS "cast_ballot_id!",
+            superseded_by_id,
+            superseded_at
+        FROM cast_ballot_supersessions
+        WHERE cast_ballot_id = $1
+        "#,
+        cast_ballot_id
+    )
+    .fetch_optional(connection)
+    .await?)
+}
+
+pub(crate) async fn get_object_by_election_id_and_type(
+    conn: &mut sqlx::PgConnection,
+    election_id: Uuid,
+    object_type: &str,
+) -> color_eyre::Result<Option<SignedObject>> {
+    Ok(sqlx::query_as!(
+        cacvote::SignedObject,
+        r#"
+            SELECT
+                o.id,
+                o.election_id,
+                o.payload,
+                o.certificates,
+                o.signature,
+                o.signature_algorithm as "signature_algorithm: SignatureAlgorithm",
+                o.cosignatures as "cosignatures: Cosignatures"
+            FROM objects AS o
+            WHERE o.election_id = $1
+              AND o.object_type = $2
+              AND NOT EXISTS (SELECT 1 FROM objects AS s WHERE s.supersedes_object_id = o.id)
+            ORDER BY o.created_at DESC
+            LIMIT 1
+            "#,
+        election_id,
+        object_type,
+    )
+    .fetch_optional(conn)
+    .await?)
+}
+
+/// Gets the current, i.e. not superseded, objects of a type in a
+/// jurisdiction, most recently created first.
+pub(crate) async fn get_objects_by_jurisdiction_and_type(
+    conn: &mut sqlx::PgConnection,
+    jurisdiction_code: &JurisdictionCode,
+    object_type: &str,
+) -> color_eyre::Result<Vec<SignedObject>> {
+    Ok(sqlx::query_as!(
+        cacvote::SignedObject,
+        r#"
+            SELECT
+                o.id,
+                o.election_id,
+                o.payload,
+                o.certificates,
+                o.signature,
+                o.signature_algorithm as "signature_algorithm: SignatureAlgorithm",
+                o.cosignatures as "cosignatures: Cosignatures"
+            FROM objects AS o
+            WHERE o.jurisdiction = $1
+              AND o.object_type = $2
+              AND NOT EXISTS (SELECT 1 FROM objects AS s WHERE s.supersedes_object_id = o.id)
+            ORDER BY o.created_at DESC, o.id
+            "#,
+        jurisdiction_code.as_str(),
+        object_type,
+    )
+    .fetch_all(conn)
+    .await?)
+}
+
+/// A machine, e.g. a CACVote Mark terminal, whose mailing labels the server
+/// accepts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Machine {
+    pub id: Uuid,
+    pub machine_identifier: String,
+
+    /// A stack of PEM-encoded X.509 certificates.
+    pub certificates: Vec<u8>,
+
+    pub created_at: time::OffsetDateTime,
+}
+
+pub async fn get_machines(conn: &mut sqlx::PgConnection) -> color_eyre::Result<Vec<Machine>> {
+    Ok(sqlx::query_as!(
+        Machine,
+        r#"
+        SELECT id, machine_identifier, certificates, created_at
+        FROM machines
+        ORDER BY machine_identifier, created_at
+        "#,
+    )
+    .fetch_all(conn)
+    .await?)
+}
+
+pub async fn create_machine(
+    conn: &mut sqlx::PgConnection,
+    machine_identifier: &str,
+    certificates: &[u8],
+) -> color_eyre::Result<Uuid> {
+    if get_machine_id_by_identifier(conn, machine_identifier)
+        .await?
+        .is_some()
+    {
+        bail!("Machine already exists: {machine_identifier}");
+    }
+
+    Ok(sqlx::query!(
+        r#"
+        INSERT INTO machines (machine_identifier, certificates)
+        VALUES ($1, $2)
+        RETURNING id
+        "#,
+        machine_identifier,
+        certificates,
+    )
+    .fetch_one(conn)
+    .await?
+    .id)
+}
+
+/// Deletes a machine, returning whether it existed. Machines whose mailing
+/// labels have been scanned can't be deleted.
+pub async fn delete_machine(
+    conn: &mut sqlx::PgConnection,
+    machine_identifier: &str,
+) -> color_eyre::Result<bool> {
+    let mut txn = conn.begin().await?;
+
+    // keep labels from being scanned while checking for them
+    let Some(record) = sqlx::query!(
+        r#"
+        SELECT
+            id,
+            (
+                SELECT count(*)
+                FROM scanned_mailing_label_codes
+                WHERE machine_id = machines.id
+            ) AS "scanned_mailing_label_count!"
+        FROM machines
+        WHERE machine_identifier = $1
+        FOR UPDATE
+        "#,
+        machine_identifier,
+    )
+    .fetch_optional(&mut *txn)
+    .await?
+    else {
+        return Ok(false);
+    };
+
+    if record.scanned_mailing_label_count > 0 {
+        bail!(
+            "Machine {machine_identifier} can't be removed because {} of its mailing labels have been scanned",
+            record.scanned_mailing_label_count
+        );
+    }
+
+    sqlx::query!(
+        r#"
+        DELETE FROM machines
+        WHERE id = $1
+        "#,
+        record.id,
+    )
+    .execute(&mut *txn)
+    .await?;
+
+    txn.commit().await?;
+    Ok(true)
+}
+
+pub(crate) async fn get_machine_id_by_identifier(
+    conn: &mut sqlx::PgConnection,
+    identifier: &str,
+) -> color_eyre::Result<Option<Uuid>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT id
+        FROM machines
+        WHERE machine_identifier = $1
+        "#,
+        identifier,
+    )
+    .fetch_optional(conn)
+    .await?
+    .map(|record| record.id))
+}
+
+pub(crate) async fn has_scanned_mailing_label_code(
+    conn: &mut sqlx::PgConnection,
+    encrypted_ballot_signature_hash: &[u8],
+) -> color_eyre::Result<bool> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1
+            FROM scanned_mailing_label_codes
+            WHERE encrypted_ballot_signature_hash = $1
+        ) AS "exists!"
+        "#,
+        encrypted_ballot_signature_hash,
+    )
+    .fetch_one(conn)
+    .await?
+    .exists)
+}
+
+/// Gets when a mailing label for the ballot with `encrypted_ballot_signature_hash`
+/// was first scanned, if ever.
+pub(crate) async fn get_mailing_label_scanned_at(
+    conn: &mut sqlx::PgConnection,
+    encrypted_ballot_signature_hash: &[u8],
+) -> color_eyre::Result<Option<time::OffsetDateTime>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT MIN(created_at) AS scanned_at
+        FROM scanned_mailing_label_codes
+        WHERE encrypted_ballot_signature_hash = $1
+        "#,
+        encrypted_ballot_signature_hash,
+    )
+    .fetch_one(conn)
+    .await?
+    .scanned_at)
+}
+
+pub(crate) async fn create_scanned_mailing_label_code(
+    conn: &mut sqlx::PgConnection,
+    ballot_verification_payload: &[u8],
+) -> color_eyre::Result<Uuid> {
+    let mut txn = conn.begin().await?;
+    let original_payload = ballot_verification_payload;
+    let signed_buffer: SignedBuffer = tlv::from_slice(original_payload)?;
+
+    // TODO: do something with `signed_buffer.signature()`?
+    let ballot_verification_payload: BallotVerificationPayload =
+        tlv::from_slice(signed_buffer.buffer())?;
+
+    let Some(machine_id) =
+        get_machine_id_by_identifier(&mut txn, ballot_verification_payload.machine_id()).await?
+    else {
+        bail!(
+            "Machine with identifier {} not found",
+            ballot_verification_payload.machine_id()
+        );
+    };
+
+    let record = sqlx::query!(
+        r#"
+        INSERT INTO scanned_mailing_label_codes (
+            election_id,
+            machine_id,
+            common_access_card_id,
+            encrypted_ballot_signature_hash,
+            original_payload
+        )
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id
+        "#,
+        ballot_verification_payload.election_object_id(),
+        machine_id,
         ballot_verification_payload.common_access_card_id(),
         ballot_verification_payload.encrypted_ballot_signature_hash(),
         original_payload,
@@ -381,3 +1464,651 @@ pub(crate) async fn create_scanned_mailing_label_code(
 
     Ok(record.id)
 }
+
+/// Adds a challenge for starting a voter session.
+pub(crate) async fn create_voter_session_challenge(
+    conn: &mut sqlx::PgConnection,
+    nonce: &[u8],
+    expires_at: time::OffsetDateTime,
+) -> color_eyre::Result<()> {
+    let mut txn = conn.begin().await?;
+
+    sqlx::query!(
+        r#"
+        DELETE FROM voter_session_challenges
+        WHERE expires_at <= current_timestamp
+        "#,
+    )
+    .execute(&mut *txn)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO voter_session_challenges (nonce, expires_at)
+        VALUES ($1, $2)
+        "#,
+        nonce,
+        expires_at,
+    )
+    .execute(&mut *txn)
+    .await?;
+
+    txn.commit().await?;
+    Ok(())
+}
+
+/// Uses up the challenge with `nonce`, returning whether it existed and had
+/// not expired. Each challenge can only be used once.
+pub(crate) async fn consume_voter_session_challenge(
+    conn: &mut sqlx::PgConnection,
+    nonce: &[u8],
+) -> color_eyre::Result<bool> {
+    Ok(sqlx::query!(
+        r#"
+        DELETE FROM voter_session_challenges
+        WHERE nonce = $1
+        RETURNING expires_at > current_timestamp AS "is_valid!"
+        "#,
+        nonce,
+    )
+    .fetch_optional(conn)
+    .await?
+    .is_some_and(|record| record.is_valid))
+}
+
+pub(crate) async fn create_voter_session(
+    conn: &mut sqlx::PgConnection,
+    token_hash: &[u8],
+    voter_id: &str,
+    expires_at: time::OffsetDateTime,
+) -> color_eyre::Result<Uuid> {
+    Ok(sqlx::query!(
+        r#"
+        INSERT INTO voter_sessions (token_hash, voter_id, expires_at)
+        VALUES ($1, $2, $3)
+        RETURNING id
+        "#,
+        token_hash,
+        voter_id,
+        expires_at,
+    )
+    .fetch_one(conn)
+    .await?
+    .id)
+}
+
+/// Gets the voter ID of the unexpired voter session whose token has the
+/// SHA-256 digest `token_hash`.
+pub(crate) async fn get_voter_session_voter_id(
+    conn: &mut sqlx::PgConnection,
+    token_hash: &[u8],
+) -> color_eyre::Result<Option<String>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT voter_id
+        FROM voter_sessions
+        WHERE token_hash = $1
+          AND expires_at > current_timestamp
+        "#,
+        token_hash,
+    )
+    .fetch_optional(conn)
+    .await?
+    .map(|record| record.voter_id))
+}
+
+/// Determines whether the blob `reference` refers to has been stored.
+pub(crate) async fn has_blob(
+    conn: &mut sqlx::PgConnection,
+    reference: &BlobReference,
+) -> color_eyre::Result<bool> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1
+            FROM blobs
+            WHERE sha256 = $1
+              AND octet_length(data)::bigint = $2
+        ) AS "exists!"
+        "#,
+        reference.sha256,
+        i64::try_from(reference.size)?,
+    )
+    .fetch_one(conn)
+    .await?
+    .exists)
+}
+
+pub(crate) async fn get_blob(
+    conn: &mut sqlx::PgConnection,
+    sha256: &str,
+) -> color_eyre::Result<Option<Vec<u8>>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT data
+        FROM blobs
+        WHERE sha256 = $1
+        "#,
+        sha256,
+    )
+    .fetch_optional(conn)
+    .await?
+    .map(|record| record.data))
+}
+
+/// Starts uploading the blob `reference` refers to on behalf of
+/// `jurisdiction_code`, or returns the upload it already started so that it
+/// can be resumed. An upload of the same digest with a different size can
+/// never complete, so it is replaced. If the blob is already stored, the
+/// upload starts out complete.
+pub(crate) async fn start_blob_upload(
+    conn: &mut sqlx::PgConnection,
+    jurisdiction_code: &JurisdictionCode,
+    reference: &BlobReference,
+) -> color_eyre::Result<BlobUpload> {
+    sqlx::query!(
+        r#"
+        DELETE FROM blob_uploads
+        WHERE jurisdiction = $1
+          AND sha256 = $2
+          AND size <> $3
+        "#,
+        jurisdiction_code.as_str(),
+        reference.sha256,
+        i64::try_from(reference.size)?,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO blob_uploads (jurisdiction, sha256, size, completed_at)
+        SELECT
+            $1,
+            $2::varchar,
+            $3::bigint,
+            CASE
+                WHEN EXISTS (
+                    SELECT 1
+                    FROM blobs
+                    WHERE sha256 = $2::varchar
+                      AND octet_length(data)::bigint = $3::bigint
+                ) THEN current_timestamp
+            END
+        ON CONFLICT (jurisdiction, sha256) DO NOTHING
+        "#,
+        jurisdiction_code.as_str(),
+        reference.sha256,
+        i64::try_from(reference.size)?,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let record = sqlx::query!(
+        r#"
+        SELECT id
+        FROM blob_uploads
+        WHERE jurisdiction = $1
+          AND sha256 = $2
+        "#,
+        jurisdiction_code.as_str(),
+        reference.sha256,
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    match get_blob_upload(conn, jurisdiction_code, record.id).await? {
+        Some(upload) => Ok(upload),
+        None => bail!("Blob upload {} disappeared", record.id),
+    }
+}
+
+/// Gets an upload started by `jurisdiction_code`.
+pub(crate) async fn get_blob_upload(
+    conn: &mut sqlx::PgConnection,
+    jurisdiction_code: &JurisdictionCode,
+    upload_id: Uuid,
+) -> color_eyre::Result<Option<BlobUpload>> {
+    let Some(record) = sqlx::query!(
+        r#"
+        SELECT
+            u.id,
+            u.sha256,
+            u.size,
+            CASE
+                WHEN u.completed_at IS NULL THEN coalesce(
+                    (
+                        SELECT sum(octet_length(c.data))
+                        FROM blob_upload_chunks c
+                        WHERE c.upload_id = u.id
+                    ),
+                    0
+                )::bigint
+                ELSE u.size
+            END AS "offset!"
+        FROM blob_uploads u
+        WHERE u.id = $1
+          AND u.jurisdiction = $2
+        "#,
+        upload_id,
+        jurisdiction_code.as_str(),
+    )
+    .fetch_optional(conn)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(BlobUpload {
+        id: record.id,
+        blob: BlobReference {
+            sha256: record.sha256,
+            size: u64::try_from(record.size)?,
+        },
+        offset: u64::try_from(record.offset)?,
+    }))
+}
+
+/// The result of [`append_blob_upload_chunk`].
+#[derive(Debug)]
+pub(crate) enum BlobUploadChunkOutcome {
+    /// The chunk was added. If it was the last one, the blob has been stored.
+    Appended(BlobUpload),
+
+    /// The chunk doesn't start at the upload's offset, or would make the blob
+    /// larger than its size.
+    Rejected(BlobUpload),
+
+    /// All chunks were received but don't match the blob's digest, so the
+    /// upload was discarded.
+    DigestMismatch,
+}
+
+/// Appends a chunk starting at `offset` to an upload started by
+/// `jurisdiction_code`, storing the blob once all of it has been received.
+/// Returns `None` if there is no such upload.
+pub(crate) async fn append_blob_upload_chunk(
+    conn: &mut sqlx::PgConnection,
+    jurisdiction_code: &JurisdictionCode,
+    upload_id: Uuid,
+    offset: u64,
+    chunk: &[u8],
+) -> color_eyre::Result<Option<BlobUploadChunkOutcome>> {
+    let mut txn = conn.begin().await?;
+
+    // serialize chunks for the same upload
+    if sqlx::query!(
+        r#"
+        SELECT id
+        FROM blob_uploads
+        WHERE id = $1
+          AND jurisdiction = $2
+        FOR UPDATE
+        "#,
+        upload_id,
+        jurisdiction_code.as_str(),
+    )
+    .fetch_optional(&mut *txn)
+    .await?
+    .is_none()
+    {
+        return Ok(None);
+    }
+
+    let Some(mut upload) = get_blob_upload(&mut txn, jurisdiction_code, upload_id).await? else {
+        return Ok(None);
+    };
+
+    if upload.is_complete()
+        || offset != upload.offset
+        || upload.offset + chunk.len() as u64 > upload.blob.size
+    {
+        return Ok(Some(BlobUploadChunkOutcome::Rejected(upload)));
+    }
+
+    sqlx::query!(
+        r#"
+        INSERT INTO blob_upload_chunks (upload_id, "offset", data)
+        VALUES ($1, $2, $3)
+        "#,
+        upload_id,
+        i64::try_from(offset)?,
+        chunk,
+    )
+    .execute(&mut *txn)
+    .await?;
+    upload.offset += chunk.len() as u64;
+
+    if upload.is_complete() {
+        // hash one chunk at a time rather than holding the whole blob, which
+        // is assembled by the database below
+        let mut hasher = BlobHasher::new();
+        let mut chunk_offset = 0;
+        while chunk_offset < upload.blob.size {
+            let record = sqlx::query!(
+                r#"
+                SELECT data
+                FROM blob_upload_chunks
+                WHERE upload_id = $1
+                  AND "offset" = $2
+                "#,
+                upload_id,
+                i64::try_from(chunk_offset)?,
+            )
+            .fetch_one(&mut *txn)
+            .await?;
+            hasher.update(&record.data);
+            chunk_offset += record.data.len() as u64;
+        }
+
+        if hasher.finish() != upload.blob {
+            sqlx::query!(
+                r#"
+                DELETE FROM blob_uploads
+                WHERE id = $1
+                "#,
+                upload_id,
+            )
+            .execute(&mut *txn)
+            .await?;
+            txn.commit().await?;
+            return Ok(Some(BlobUploadChunkOutcome::DigestMismatch));
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO blobs (sha256, data)
+            SELECT $2, string_agg(data, ''::bytea ORDER BY "offset")
+            FROM blob_upload_chunks
+            WHERE upload_id = $1
+            ON CONFLICT (sha256) DO NOTHING
+            "#,
+            upload_id,
+            upload.blob.sha256,
+        )
+        .execute(&mut *txn)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM blob_upload_chunks
+            WHERE upload_id = $1
+            "#,
+            upload_id,
+        )
+        .execute(&mut *txn)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE blob_uploads
+            SET completed_at = current_timestamp
+            WHERE id = $1
+            "#,
+            upload_id,
+        )
+        .execute(&mut *txn)
+        .await?;
+    }
+
+    txn.commit().await?;
+    Ok(Some(BlobUploadChunkOutcome::Appended(upload)))
+}
+
+/// Gets the ID of a jurisdiction's latest journal entry, which snapshots are
+/// taken as of by default.
+pub(crate) async fn get_latest_journal_entry_id(
+    conn: &mut sqlx::PgConnection,
+    jurisdiction_code: &JurisdictionCode,
+) -> color_eyre::Result<Option<Uuid>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT id
+        FROM journal_entries
+        WHERE jurisdiction = $1
+        ORDER BY created_at DESC, id DESC
+        LIMIT 1
+        "#,
+        jurisdiction_code.as_str(),
+    )
+    .fetch_optional(conn)
+    .await?
+    .map(|record| record.id))
+}
+
+/// Gets when the journal entry a snapshot is taken as of was created, or
+/// `None` if it isn't one of the jurisdiction's journal entries.
+async fn get_snapshot_cursor_created_at(
+    conn: &mut sqlx::PgConnection,
+    jurisdiction_code: &JurisdictionCode,
+    cursor: Uuid,
+) -> color_eyre::Result<Option<time::OffsetDateTime>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT created_at
+        FROM journal_entries
+        WHERE id = $1
+          AND jurisdiction = $2
+        "#,
+        cursor,
+        jurisdiction_code.as_str(),
+    )
+    .fetch_optional(conn)
+    .await?
+    .map(|record| record.created_at))
+}
+
+/// Gets the leaves of the snapshot of a jurisdiction's objects as of the
+/// journal entry `cursor`, see [`cacvote::SnapshotTreeHead`]. Returns `None`
+/// if `cursor` isn't one of the jurisdiction's journal entries.
+pub(crate) async fn get_snapshot_leaves(
+    conn: &mut sqlx::PgConnection,
+    jurisdiction_code: &JurisdictionCode,
+    cursor: Uuid,
+) -> color_eyre::Result<Option<Vec<SnapshotLeaf>>> {
+    let Some(cursor_created_at) =
+        get_snapshot_cursor_created_at(conn, jurisdiction_code, cursor).await?
+    else {
+        return Ok(None);
+    };
+
+    let records = sqlx::query!(
+        r#"
+        SELECT
+            j.id AS journal_entry_id,
+            j.election_id AS journal_entry_election_id,
+            j.object_type,
+            j.action AS "action: JournalEntryAction",
+            j.created_at,
+            o.id AS object_id,
+            o.election_id AS object_election_id,
+            sha256(o.payload) AS "payload_sha256!",
+            sha256(o.certificates) AS "certificates_sha256!",
+            sha256(o.signature) AS "signature_sha256!",
+            o.signature_algorithm AS "signature_algorithm: SignatureAlgorithm",
+            o.cosignatures AS "cosignatures: Cosignatures"
+        FROM journal_entries AS j
+        INNER JOIN objects AS o
+          ON o.id = j.object_id
+        WHERE j.jurisdiction = $1
+          AND j.created_at <= $2
+          AND (o.deleted_at IS NULL OR o.deleted_at > $2)
+        ORDER BY j.created_at, j.id
+        "#,
+        jurisdiction_code.as_str(),
+        cursor_created_at,
+    )
+    .fetch_all(conn)
+    .await?;
+
+    let digest = |bytes: Vec<u8>| -> color_eyre::Result<[u8; 32]> {
+        bytes
+            .try_into()
+            .map_err(|_| color_eyre::eyre::eyre!("Expected a SHA-256 digest"))
+    };
+
+    records
+        .into_iter()
+        .map(|record| {
+            Ok(SnapshotLeaf {
+                journal_entry_id: record.journal_entry_id,
+                journal_entry_election_id: record.journal_entry_election_id,
+                jurisdiction_code: jurisdiction_code.clone(),
+                object_type: record.object_type,
+                action: record.action,
+                created_at: record.created_at,
+                object_id: record.object_id,
+                object_election_id: record.object_election_id,
+                payload_sha256: digest(record.payload_sha256)?,
+                certificates_sha256: digest(record.certificates_sha256)?,
+                signature_sha256: digest(record.signature_sha256)?,
+                signature_algorithm: record.signature_algorithm,
+                cosignatures_sha256: SnapshotLeaf::cosignatures_sha256(&record.cosignatures),
+            })
+        })
+        .collect::<color_eyre::Result<Vec<_>>>()
+        .map(Some)
+}
+
+/// Gets a page of the entries of the snapshot of a jurisdiction's objects as
+/// of the journal entry `cursor`, in the same order as
+/// [`get_snapshot_leaves`]. Returns `None` if `cursor` isn't one of the
+/// jurisdiction's journal entries.
+pub(crate) async fn get_snapshot_entries(
+    conn: &mut sqlx::PgConnection,
+    jurisdiction_code: &JurisdictionCode,
+    cursor: Uuid,
+    offset: u64,
+    limit: usize,
+) -> color_eyre::Result<Option<SnapshotPage>> {
+    let Some(cursor_created_at) =
+        get_snapshot_cursor_created_at(conn, jurisdiction_code, cursor).await?
+    else {
+        return Ok(None);
+    };
+
+    // fetch one extra entry to tell whether there's another page
+    let records = sqlx::query!(
+        r#"
+        SELECT
+            j.id AS journal_entry_id,
+            j.election_id AS journal_entry_election_id,
+            j.object_type,
+            j.action AS "action: JournalEntryAction",
+            j.created_at,
+            o.id,
+            o.election_id,
+            o.payload,
+            o.certificates,
+            o.signature,
+            o.signature_algorithm AS "signature_algorithm: SignatureAlgorithm",
+            o.cosignatures AS "cosignatures: Cosignatures"
+        FROM journal_entries AS j
+        INNER JOIN objects AS o
+          ON o.id = j.object_id
+        WHERE j.jurisdiction = $1
+          AND j.created_at <= $2
+          AND (o.deleted_at IS NULL OR o.deleted_at > $2)
+        ORDER BY j.created_at, j.id
+        OFFSET $3
+        LIMIT $4
+        "#,
+        jurisdiction_code.as_str(),
+        cursor_created_at,
+        i64::try_from(offset)?,
+        i64::try_from(limit)? + 1,
+    )
+    .fetch_all(conn)
+    .await?;
+
+    let has_more = records.len() > limit;
+    let entries = records
+        .into_iter()
+        .take(limit)
+        .map(|record| SnapshotEntry {
+            journal_entry: JournalEntry {
+                id: record.journal_entry_id,
+                object_id: record.id,
+                election_id: record.journal_entry_election_id,
+                jurisdiction_code: jurisdiction_code.clone(),
+                object_type: record.object_type,
+                action: record.action,
+                created_at: record.created_at,
+            },
+            object: SignedObject {
+                id: record.id,
+                election_id: record.election_id,
+                payload: record.payload,
+                certificates: record.certificates,
+                signature: record.signature,
+                signature_algorithm: record.signature_algorithm,
+                cosignatures: record.cosignatures,
+            },
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Some(SnapshotPage {
+        next_offset: has_more.then_some(offset + entries.len() as u64),
+        entries,
+    }))
+}
+
+/// An object as stored, with the denormalized fields the audit checks against
+/// its payload.
+#[derive(Debug)]
+pub(crate) struct StoredObject {
+    pub(crate) object: SignedObject,
+    pub(crate) jurisdiction: String,
+    pub(crate) object_type: String,
+    pub(crate) supersedes_object_id: Option<Uuid>,
+    pub(crate) journal_entry_count: i64,
+}
+
+/// Gets every stored object, oldest first, for [`crate::audit`].
+pub(crate) async fn get_stored_objects(
+    conn: &mut sqlx::PgConnection,
+) -> color_eyre::Result<Vec<StoredObject>> {
+    let records = sqlx::query!(
+        r#"
+        SELECT
+            o.id,
+            o.election_id,
+            o.jurisdiction,
+            o.object_type,
+            o.payload,
+            o.certificates,
+            o.signature,
+            o.signature_algorithm AS "signature_algorithm: SignatureAlgorithm",
+            o.cosignatures AS "cosignatures: Cosignatures",
+            o.supersedes_object_id,
+            (
+                SELECT count(*)
+                FROM journal_entries AS j
+                WHERE j.object_id = o.id
+            ) AS "journal_entry_count!"
+        FROM objects AS o
+        ORDER BY o.created_at, o.id
+        "#,
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(records
+        .into_iter()
+        .map(|record| StoredObject {
+            object: SignedObject {
+                id: record.id,
+                election_id: record.election_id,
+                payload: record.payload,
+                certificates: record.certificates,
+                signature: record.signature,
+                signature_algorithm: record.signature_algorithm,
+                cosignatures: record.cosignatures,
+            },
+            jurisdiction: record.jurisdiction,
+            object_type: record.object_type,
+            supersedes_object_id: record.supersedes_object_id,
+            journal_entry_count: record.journal_entry_count,
+        })
+        .collect())
+}
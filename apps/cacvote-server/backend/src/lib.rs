@@ -1,6 +1,12 @@
 pub mod app;
+pub mod audit;
 pub mod bulletin_board;
+pub mod cac;
 pub mod client;
 pub mod config;
 pub mod db;
 pub mod log;
+pub mod metrics;
+pub mod negotiation;
+pub mod openapi;
+pub mod registration_conflicts;
@@ -6,16 +6,46 @@
 //!
 //! You may use the `RUST_LOG` environment variable to configure logging at
 //! runtime (see [`EnvFilter`][`tracing_subscriber::EnvFilter`]).
+//!
+//! When an OTLP endpoint is configured, spans are also exported to it as
+//! OpenTelemetry traces, and incoming requests continue the trace named by
+//! their W3C `traceparent` header, if any.
 
+use axum::http::Request;
+use opentelemetry::{global, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{propagation::TraceContextPropagator, runtime, trace, Resource};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
 use tracing_subscriber::{prelude::*, util::SubscriberInitExt};
 
 use crate::config::Config;
 
+const SERVICE_NAME: &str = "cacvote-server";
+
 /// Sets up logging for the application. Call this early in the process
 /// lifecycle to ensure logs are not silently ignored.
 pub fn setup(config: &Config) -> color_eyre::Result<()> {
     color_eyre::install()?;
     let stdout_log = tracing_subscriber::fmt::layer().pretty();
+    let otlp_trace = match &config.otlp_endpoint {
+        Some(endpoint) => {
+            global::set_text_map_propagator(TraceContextPropagator::new());
+            let tracer =
+                opentelemetry_otlp::new_pipeline()
+                    .tracing()
+                    .with_exporter(
+                        opentelemetry_otlp::new_exporter()
+                            .http()
+                            .with_endpoint(endpoint),
+                    )
+                    .with_trace_config(trace::config().with_resource(Resource::new([
+                        KeyValue::new("service.name", SERVICE_NAME),
+                    ])))
+                    .install_batch(runtime::Tokio)?;
+            Some(tracing_opentelemetry::layer().with_tracer(tracer))
+        }
+        None => None,
+    };
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::builder()
@@ -30,6 +60,66 @@ pub fn setup(config: &Config) -> color_eyre::Result<()> {
                 .from_env_lossy(),
         )
         .with(stdout_log)
+        .with(otlp_trace)
         .init();
     Ok(())
 }
+
+/// Exports any spans not yet exported. Call this before exiting.
+pub fn shutdown() {
+    global::shutdown_tracer_provider();
+}
+
+/// Makes the span for an HTTP request handled by [`TraceLayer`], continuing
+/// the trace of the client's request if it sent a `traceparent` header.
+///
+/// [`TraceLayer`]: tower_http::trace::TraceLayer
+pub fn make_request_span<B>(request: &Request<B>) -> tracing::Span {
+    let span = tracing::info_span!(
+        "request",
+        method = %request.method(),
+        uri = %request.uri(),
+        version = ?request.version(),
+    );
+    let parent_context = global::get_text_map_propagator(|propagator| {
+        propagator.extract(&opentelemetry_http::HeaderExtractor(request.headers()))
+    });
+    span.set_parent(parent_context);
+    span
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry::trace::{TraceContextExt, TracerProvider as _};
+    use opentelemetry_sdk::trace::TracerProvider;
+
+    use super::*;
+
+    #[test]
+    fn test_make_request_span_continues_trace() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let tracer_provider = TracerProvider::builder().build();
+        let tracer = tracer_provider.tracer("test");
+        let subscriber =
+            tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(tracer));
+
+        let request = Request::builder()
+            .uri("/api/objects")
+            .header(
+                "traceparent",
+                "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            )
+            .body(())
+            .unwrap();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = make_request_span(&request);
+            let context = span.context();
+            let span_context = context.span().span_context().clone();
+            assert_eq!(
+                span_context.trace_id().to_string(),
+                "4bf92f3577b34da6a3ce929d0e0e4736"
+            );
+        });
+    }
+}
@@ -0,0 +1,134 @@
+//! Prometheus metrics, served at `/metrics` in the text exposition format. All
+//! metric names are prefixed with `cacvote_server_`.
+
+use std::{sync::Arc, time::Instant};
+
+use axum::{
+    extract::{MatchedPath, State},
+    http::Request,
+    middleware::Next,
+    response::Response,
+};
+use prometheus::{
+    HistogramOpts, HistogramVec, IntCounterVec, IntGauge, Opts, Registry, TextEncoder,
+};
+
+/// Why a signature was rejected, the label of
+/// [`Metrics::signature_verification_failures`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SignatureVerificationFailure {
+    /// An object's signature doesn't verify against its certificates.
+    Object,
+
+    /// A voter's object isn't signed by the voter's Common Access Card.
+    VoterCertificate,
+
+    /// A voter session challenge isn't signed by a valid Common Access Card.
+    VoterSession,
+}
+
+impl SignatureVerificationFailure {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Object => "object",
+            Self::VoterCertificate => "voter_certificate",
+            Self::VoterSession => "voter_session",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Metrics {
+    registry: Registry,
+    http_request_duration: HistogramVec,
+    objects_created: IntCounterVec,
+    signature_verification_failures: IntCounterVec,
+    journal_entries: IntGauge,
+}
+
+impl Metrics {
+    pub fn new() -> prometheus::Result<Self> {
+        let registry = Registry::new_custom(Some("cacvote_server".to_owned()), None)?;
+
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "Time taken to respond to HTTP requests, by route",
+            ),
+            &["method", "route", "status"],
+        )?;
+        let objects_created = IntCounterVec::new(
+            Opts::new("objects_created_total", "Objects created"),
+            &["object_type", "jurisdiction"],
+        )?;
+        let signature_verification_failures = IntCounterVec::new(
+            Opts::new(
+                "signature_verification_failures_total",
+                "Requests rejected because a signature or certificate did not verify",
+            ),
+            &["kind"],
+        )?;
+        let journal_entries = IntGauge::new("journal_entries", "Entries in the journal")?;
+
+        registry.register(Box::new(http_request_duration.clone()))?;
+        registry.register(Box::new(objects_created.clone()))?;
+        registry.register(Box::new(signature_verification_failures.clone()))?;
+        registry.register(Box::new(journal_entries.clone()))?;
+
+        Ok(Self {
+            registry,
+            http_request_duration,
+            objects_created,
+            signature_verification_failures,
+            journal_entries,
+        })
+    }
+
+    pub(crate) fn record_object_created(&self, object_type: &str, jurisdiction: &str) {
+        self.objects_created
+            .with_label_values(&[object_type, jurisdiction])
+            .inc();
+    }
+
+    pub(crate) fn record_signature_verification_failure(
+        &self,
+        failure: SignatureVerificationFailure,
+    ) {
+        self.signature_verification_failures
+            .with_label_values(&[failure.as_str()])
+            .inc();
+    }
+
+    /// Encodes every metric in the text exposition format, with the journal
+    /// size, which is read from the database when scraped, set to
+    /// `journal_entries`.
+    pub(crate) fn encode(&self, journal_entries: i64) -> prometheus::Result<String> {
+        self.journal_entries.set(journal_entries);
+        TextEncoder::new().encode_to_string(&self.registry.gather())
+    }
+}
+
+/// Records the time taken to respond to each request by its route, e.g.
+/// `/api/objects/:object_id`, rather than its path, so that the number of
+/// series stays bounded. Must be added with [`axum::Router::route_layer`] so
+/// that the route is known.
+pub(crate) async fn track_request_duration<B>(
+    State(metrics): State<Arc<Metrics>>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let started_at = Instant::now();
+    let method = request.method().clone();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| "unknown".to_owned(), |path| path.as_str().to_owned());
+
+    let response = next.run(request).await;
+
+    metrics
+        .http_request_duration
+        .with_label_values(&[method.as_str(), &route, response.status().as_str()])
+        .observe(started_at.elapsed().as_secs_f64());
+    response
+}
@@ -0,0 +1,132 @@
+//! Content negotiation for API bodies. Request bodies are decoded according to
+//! their `Content-Type` and `Content-Encoding`, and responses are encoded as
+//! asked for by the `Accept` header, JSON being the default either way (see
+//! [`BodyEncoding`]). Response compression is handled by a layer in
+//! [`app::setup`][crate::app::setup].
+
+use std::convert::Infallible;
+
+use axum::{
+    async_trait,
+    body::{Bytes, HttpBody},
+    extract::{FromRequest, FromRequestParts},
+    http::{header, request::Parts, HeaderMap, HeaderValue, Request, StatusCode},
+    response::{IntoResponse, Response},
+    BoxError, Json,
+};
+use cacvote_server_client::encoding::{self, BodyEncoding, ContentEncoding};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::json;
+
+use crate::config::MAX_REQUEST_SIZE;
+
+/// A request body decoded according to its `Content-Type`, decompressing it
+/// first if it has a `Content-Encoding`.
+#[derive(Debug)]
+pub(crate) struct Negotiated<T>(pub(crate) T);
+
+#[async_trait]
+impl<T, S, B> FromRequest<S, B> for Negotiated<T>
+where
+    T: DeserializeOwned,
+    B: HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request<B>, state: &S) -> Result<Self, Response> {
+        let headers = req.headers();
+        let body_encoding =
+            BodyEncoding::from_content_type(header_str(headers, header::CONTENT_TYPE))
+                .map_err(rejection)?;
+        let content_encoding =
+            ContentEncoding::from_header(header_str(headers, header::CONTENT_ENCODING))
+                .map_err(rejection)?;
+
+        let mut body = Bytes::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?
+            .to_vec();
+
+        if let Some(content_encoding) = content_encoding {
+            body = content_encoding
+                .decompress(&body, MAX_REQUEST_SIZE)
+                .map_err(rejection)?;
+        }
+
+        body_encoding.decode(&body).map(Self).map_err(rejection)
+    }
+}
+
+/// The body encoding a client asked for in its `Accept` header.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Accept(BodyEncoding);
+
+impl Accept {
+    /// Responds with `value` in the encoding the client asked for.
+    pub(crate) const fn respond<T>(self, value: T) -> Encoded<T> {
+        Encoded(self.0, value)
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Accept
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Infallible> {
+        Ok(Self(BodyEncoding::from_accept(header_str(
+            &parts.headers,
+            header::ACCEPT,
+        ))))
+    }
+}
+
+/// A response body in a negotiated encoding, see [`Accept::respond`].
+#[derive(Debug)]
+pub(crate) struct Encoded<T>(BodyEncoding, T);
+
+impl<T: Serialize> IntoResponse for Encoded<T> {
+    fn into_response(self) -> Response {
+        let Self(body_encoding, value) = self;
+
+        match body_encoding.encode(&value) {
+            Ok(body) => (
+                [(
+                    header::CONTENT_TYPE,
+                    HeaderValue::from_static(body_encoding.mime_type()),
+                )],
+                body,
+            )
+                .into_response(),
+            Err(error) => {
+                tracing::error!("Unable to encode response: {error}");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({ "error": error.to_string() })),
+                )
+                    .into_response()
+            }
+        }
+    }
+}
+
+fn header_str(headers: &HeaderMap, name: header::HeaderName) -> Option<&str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+fn rejection(error: encoding::Error) -> Response {
+    let status = match error {
+        encoding::Error::UnsupportedMediaType(_)
+        | encoding::Error::UnsupportedContentEncoding(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        encoding::Error::TooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+        _ => StatusCode::BAD_REQUEST,
+    };
+    // the client's mistake, not the server's
+    tracing::warn!("Rejecting request body: {status} {error}");
+    (status, Json(json!({ "error": error.to_string() }))).into_response()
+}
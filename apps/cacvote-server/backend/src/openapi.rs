@@ -0,0 +1,143 @@
+//! The OpenAPI document describing the HTTP API, served at
+//! `/api/openapi.json`. It is generated from the `#[utoipa::path]` attribute
+//! of each route handler in [`app`] and the schemas derived for the
+//! [`types_rs::cacvote`] types they use.
+//!
+//! Request and response bodies are described as JSON, but may also be CBOR or
+//! MessagePack (see [`negotiation`][crate::negotiation]).
+
+use serde::Serialize;
+use types_rs::cacvote::Payload;
+use utoipa::{
+    openapi::{
+        schema::{KnownFormat, SchemaFormat, Type},
+        security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+        ObjectBuilder, RefOr, Schema,
+    },
+    Modify, OpenApi, PartialSchema, ToSchema,
+};
+use uuid::Uuid;
+
+use crate::app;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "CACVote Server",
+        description = "Stores and relays signed CACVote objects between jurisdictions and voters."
+    ),
+    paths(
+        app::get_openapi,
+        app::get_metrics,
+        app::get_status,
+        app::get_readiness,
+        app::search_objects,
+        app::create_object,
+        app::get_object_by_id,
+        app::get_object_versions,
+        app::get_journal_entries,
+        app::get_snapshot_tree_head,
+        app::get_snapshot_entries,
+        app::start_blob_upload,
+        app::get_blob_upload,
+        app::append_blob_upload_chunk,
+        app::get_blob,
+        app::get_signer_certificate,
+        app::list_object_ids_by_signer,
+        app::scanned_create_mailing_label_code,
+        app::get_jurisdiction_encryption_key,
+        app::get_ballot_tracking_status,
+        app::list_registration_conflicts,
+        app::create_voter_session_challenge,
+        app::create_voter_session,
+        app::list_voter_registrations,
+        app::list_voter_elections,
+        app::list_voter_cast_ballots,
+        app::list_elections,
+        app::list_cast_ballots_by_election,
+        app::get_cast_ballot_by_id,
+        app::get_encrypted_tally_by_election,
+        app::get_decrypted_tally_by_election,
+        app::list_shuffled_ballots_by_election,
+        app::bulletin_board_elections,
+        app::bulletin_board_election,
+        app::bulletin_board_results,
+        app::bulletin_board_ballot,
+    ),
+    // the payload of a signed object is the base64 encoding of one of these
+    components(schemas(Payload)),
+    modifiers(&SecuritySchemes),
+)]
+pub struct ApiDoc;
+
+/// The body of every error response.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+/// The body of a response to creating something identified by a UUID.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedResponse {
+    pub id: Uuid,
+}
+
+/// An `application/octet-stream` body, e.g. a blob.
+#[derive(Debug)]
+pub struct Binary;
+
+impl PartialSchema for Binary {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .schema_type(Type::String)
+            .format(Some(SchemaFormat::KnownFormat(KnownFormat::Binary)))
+            .into()
+    }
+}
+
+impl ToSchema for Binary {}
+
+/// Registers the bearer tokens that route handlers' `security` refers to.
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "officialApiKey",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some(
+                        "An API key identifying a jurisdiction official, who may read the \
+                         jurisdiction's objects without redaction",
+                    ))
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "voterSession",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some(
+                        "A voter session token, see `POST /api/voter-sessions`",
+                    ))
+                    .build(),
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Changes to the API show up as changes to this snapshot. That every
+    /// documented operation is routed is checked against a running server in
+    /// `client::tests::test_openapi_routes_exist`.
+    #[test]
+    fn test_openapi_document() {
+        insta::assert_snapshot!(ApiDoc::openapi().to_pretty_json().unwrap());
+    }
+}
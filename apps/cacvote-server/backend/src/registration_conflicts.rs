@@ -0,0 +1,382 @@
+//! Detection of voters registering in more than one jurisdiction. Each
+//! jurisdiction only syncs its own objects, so the server is the only place
+//! these duplicates can be seen.
+
+use std::collections::{HashMap, HashSet};
+
+use time::Date;
+use types_rs::cacvote::{
+    self, JurisdictionCode, Payload, RegistrationConflict, RegistrationConflictKind,
+    RegistrationConflictObject,
+};
+use uuid::Uuid;
+
+use crate::db::{self, CrossJurisdictionRegistrationRecord};
+
+/// Commitments to voter IDs use this in place of a field name so that conflict
+/// IDs can't be matched against the commitments in redacted objects.
+const CONFLICT_ID_LABEL: &str = "registrationConflict";
+
+/// Loads the registration conflicts (see [`RegistrationConflict`]) involving
+/// `jurisdiction_code`. Conflict IDs are keyed with `key` so that they don't
+/// reveal voters.
+pub(crate) async fn load_registration_conflicts(
+    conn: &mut sqlx::PgConnection,
+    jurisdiction_code: &JurisdictionCode,
+    key: &[u8],
+) -> color_eyre::Result<Vec<RegistrationConflict>> {
+    let records = db::get_cross_jurisdiction_registrations(conn, jurisdiction_code).await?;
+
+    let mut election_dates = HashMap::new();
+    for election_object_id in records
+        .iter()
+        .filter_map(|record| record.election_object_id)
+        .collect::<HashSet<_>>()
+    {
+        if let Some(object) = db::get_latest_object_version(conn, election_object_id).await? {
+            if let Payload::Election(election) = object.try_to_inner()? {
+                election_dates.insert(
+                    election_object_id,
+                    election.election_definition.election.date,
+                );
+            }
+        }
+    }
+
+    find_registration_conflicts(records, &election_dates, jurisdiction_code, key)
+}
+
+/// How two registration requests or registrations of the same voter in
+/// different jurisdictions overlap, if they do.
+///
+/// Registrations overlap when their elections are held on the same date, and
+/// may overlap when the date of either is unknown. Registration requests that
+/// haven't been approved yet overlap everything.
+fn overlap(
+    record: &CrossJurisdictionRegistrationRecord,
+    other: &CrossJurisdictionRegistrationRecord,
+    election_dates: &HashMap<Uuid, Date>,
+) -> Option<RegistrationConflictKind> {
+    let election_date = |record: &CrossJurisdictionRegistrationRecord| {
+        if record.object_type == Payload::registration_request_object_type() {
+            return Ok(None);
+        }
+
+        record
+            .election_object_id
+            .and_then(|election_object_id| election_dates.get(&election_object_id))
+            .map(Some)
+            .ok_or(())
+    };
+
+    match (election_date(record), election_date(other)) {
+        (Ok(Some(date)), Ok(Some(other_date))) => {
+            (date == other_date).then_some(RegistrationConflictKind::Overlapping)
+        }
+        (Ok(None), _) | (_, Ok(None)) => Some(RegistrationConflictKind::Overlapping),
+        (Err(()), _) | (_, Err(())) => Some(RegistrationConflictKind::UnknownElectionDate),
+    }
+}
+
+/// Finds the conflicts involving `jurisdiction_code` among `records`, which
+/// must be grouped by voter.
+fn find_registration_conflicts(
+    records: Vec<CrossJurisdictionRegistrationRecord>,
+    election_dates: &HashMap<Uuid, Date>,
+    jurisdiction_code: &JurisdictionCode,
+    key: &[u8],
+) -> color_eyre::Result<Vec<RegistrationConflict>> {
+    let mut conflicts = Vec::new();
+
+    for voter_records in records.chunk_by(|a, b| a.voter_id == b.voter_id) {
+        let approved_registration_request_ids = voter_records
+            .iter()
+            .filter(|record| record.object_type == Payload::registration_object_type())
+            .map(|record| record.registration_request_object_id)
+            .collect::<HashSet<_>>();
+
+        // approved requests are represented by their registrations
+        let candidates = voter_records
+            .iter()
+            .filter(|record| {
+                record.object_type == Payload::registration_object_type()
+                    || !approved_registration_request_ids.contains(&record.object_id)
+            })
+            .collect::<Vec<_>>();
+
+        for kind in [
+            RegistrationConflictKind::Overlapping,
+            RegistrationConflictKind::UnknownElectionDate,
+        ] {
+            let conflicting = candidates
+                .iter()
+                .filter(|record| {
+                    candidates.iter().any(|other| {
+                        other.jurisdiction_code != record.jurisdiction_code
+                            && (record.jurisdiction_code == *jurisdiction_code
+                                || other.jurisdiction_code == *jurisdiction_code)
+                            && overlap(record, other, election_dates) == Some(kind)
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            let Some(first) = conflicting.first() else {
+                continue;
+            };
+
+            let mut jurisdiction_codes = conflicting
+                .iter()
+                .map(|record| record.jurisdiction_code.clone())
+                .collect::<Vec<_>>();
+            jurisdiction_codes.sort();
+            jurisdiction_codes.dedup();
+
+            conflicts.push(RegistrationConflict {
+                id: cacvote::commitment(key, CONFLICT_ID_LABEL, &first.voter_id.as_str().into())?,
+                kind,
+                jurisdiction_codes,
+                objects: conflicting
+                    .iter()
+                    .map(|record| RegistrationConflictObject {
+                        object_id: record.object_id,
+                        object_type: record.object_type.clone(),
+                        jurisdiction_code: record.jurisdiction_code.clone(),
+                        election_object_id: record.election_object_id,
+                    })
+                    .collect(),
+            });
+        }
+    }
+
+    Ok(conflicts)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+
+    fn registration_request(
+        voter_id: &str,
+        jurisdiction_code: &str,
+    ) -> CrossJurisdictionRegistrationRecord {
+        let object_id = Uuid::new_v4();
+        CrossJurisdictionRegistrationRecord {
+            object_id,
+            object_type: Payload::registration_request_object_type().to_owned(),
+            jurisdiction_code: JurisdictionCode::try_from(jurisdiction_code).unwrap(),
+            voter_id: voter_id.to_owned(),
+            registration_request_object_id: object_id,
+            election_object_id: None,
+        }
+    }
+
+    fn registration(
+        registration_request: &CrossJurisdictionRegistrationRecord,
+        election_object_id: Uuid,
+    ) -> CrossJurisdictionRegistrationRecord {
+        CrossJurisdictionRegistrationRecord {
+            object_id: Uuid::new_v4(),
+            object_type: Payload::registration_object_type().to_owned(),
+            jurisdiction_code: registration_request.jurisdiction_code.clone(),
+            voter_id: registration_request.voter_id.clone(),
+            registration_request_object_id: registration_request.object_id,
+            election_object_id: Some(election_object_id),
+        }
+    }
+
+    fn conflicting_object_ids(
+        records: Vec<CrossJurisdictionRegistrationRecord>,
+        election_dates: &HashMap<Uuid, Date>,
+        jurisdiction_code: &str,
+    ) -> Vec<(RegistrationConflictKind, Vec<Uuid>)> {
+        find_registration_conflicts(
+            records,
+            election_dates,
+            &JurisdictionCode::try_from(jurisdiction_code).unwrap(),
+            b"key",
+        )
+        .unwrap()
+        .into_iter()
+        .map(|conflict| {
+            (
+                conflict.kind,
+                conflict
+                    .objects
+                    .into_iter()
+                    .map(|object| object.object_id)
+                    .collect(),
+            )
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_find_registration_conflicts() {
+        use RegistrationConflictKind::{Overlapping, UnknownElectionDate};
+
+        let [november_election, other_november_election, march_election] =
+            [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        let election_dates = HashMap::from([
+            (november_election, date!(2024 - 11 - 05)),
+            (other_november_election, date!(2024 - 11 - 05)),
+            (march_election, date!(2024 - 03 - 05)),
+        ]);
+
+        // pending requests overlap each other
+        let a = registration_request("1234567890", "st.a");
+        let b = registration_request("1234567890", "st.b");
+        assert_eq!(
+            conflicting_object_ids(vec![a.clone(), b.clone()], &election_dates, "st.a"),
+            vec![(Overlapping, vec![a.object_id, b.object_id])]
+        );
+
+        // and registrations in other jurisdictions
+        let a_registration = registration(&a, march_election);
+        assert_eq!(
+            conflicting_object_ids(
+                vec![a.clone(), b.clone(), a_registration.clone()],
+                &election_dates,
+                "st.a"
+            ),
+            vec![(Overlapping, vec![b.object_id, a_registration.object_id])]
+        );
+
+        // registrations overlap if their elections are on the same date
+        let b_registration = registration(&b, november_election);
+        assert_eq!(
+            conflicting_object_ids(
+                vec![
+                    a.clone(),
+                    b.clone(),
+                    a_registration.clone(),
+                    b_registration.clone()
+                ],
+                &election_dates,
+                "st.a"
+            ),
+            vec![]
+        );
+        let other_a_registration = registration(&a, other_november_election);
+        assert_eq!(
+            conflicting_object_ids(
+                vec![
+                    a.clone(),
+                    b.clone(),
+                    a_registration,
+                    b_registration.clone(),
+                    other_a_registration.clone()
+                ],
+                &election_dates,
+                "st.a"
+            ),
+            vec![(
+                Overlapping,
+                vec![b_registration.object_id, other_a_registration.object_id]
+            )]
+        );
+
+        // and may overlap if either date is unknown, which is reported apart
+        let unknown_registration = registration(&a, Uuid::new_v4());
+        assert_eq!(
+            conflicting_object_ids(
+                vec![
+                    a.clone(),
+                    b.clone(),
+                    b_registration.clone(),
+                    unknown_registration.clone()
+                ],
+                &election_dates,
+                "st.a"
+            ),
+            vec![(
+                UnknownElectionDate,
+                vec![b_registration.object_id, unknown_registration.object_id]
+            )]
+        );
+
+        // the same jurisdiction twice is not a conflict
+        let c = registration_request("1111111111", "st.a");
+        let d = registration_request("1111111111", "st.a");
+        assert_eq!(
+            conflicting_object_ids(vec![c, d], &election_dates, "st.a"),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_conflicts_not_involving_jurisdiction() {
+        let [november_election, other_november_election] = [Uuid::new_v4(), Uuid::new_v4()];
+        let election_dates = HashMap::from([
+            (november_election, date!(2024 - 11 - 05)),
+            (other_november_election, date!(2024 - 11 - 05)),
+        ]);
+
+        // st.b and st.c overlap, and st.a's election date is unknown
+        let a = registration_request("1234567890", "st.a");
+        let b = registration_request("1234567890", "st.b");
+        let c = registration_request("1234567890", "st.c");
+        let records = vec![
+            a.clone(),
+            b.clone(),
+            c.clone(),
+            registration(&a, Uuid::new_v4()),
+            registration(&b, november_election),
+            registration(&c, other_november_election),
+        ];
+        assert_eq!(
+            conflicting_object_ids(records.clone(), &election_dates, "st.b"),
+            vec![
+                (
+                    RegistrationConflictKind::Overlapping,
+                    vec![records[4].object_id, records[5].object_id]
+                ),
+                (
+                    RegistrationConflictKind::UnknownElectionDate,
+                    vec![records[3].object_id, records[4].object_id]
+                )
+            ]
+        );
+        assert_eq!(
+            conflicting_object_ids(records.clone(), &election_dates, "st.a"),
+            vec![(
+                RegistrationConflictKind::UnknownElectionDate,
+                vec![
+                    records[3].object_id,
+                    records[4].object_id,
+                    records[5].object_id
+                ]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_conflict_ids() {
+        let records = vec![
+            registration_request("1111111111", "st.a"),
+            registration_request("1111111111", "st.b"),
+            registration_request("2222222222", "st.a"),
+            registration_request("2222222222", "st.b"),
+        ];
+        let conflicts = find_registration_conflicts(
+            records,
+            &HashMap::new(),
+            &JurisdictionCode::try_from("st.a").unwrap(),
+            b"key",
+        )
+        .unwrap();
+        let [first, second] = conflicts.as_slice() else {
+            panic!("expected two conflicts, got: {conflicts:?}");
+        };
+        assert_ne!(first.id, second.id);
+        assert!(!first.id.contains("1111111111"));
+        assert_eq!(
+            first.jurisdiction_codes,
+            vec![
+                JurisdictionCode::try_from("st.a").unwrap(),
+                JurisdictionCode::try_from("st.b").unwrap()
+            ]
+        );
+    }
+}
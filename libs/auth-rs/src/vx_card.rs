@@ -208,6 +208,13 @@ impl VxCard {
         ))
     }
 
+    /// Reads the certificate of the VxAdmin that programmed the card, which
+    /// issued the card's VxAdmin certificate.
+    pub fn vx_admin_cert_authority_cert(&self) -> Result<X509, CardReaderError> {
+        self.select_applet()?;
+        self.retrieve_cert(VX_ADMIN_CERT_AUTHORITY_CERT.object_id())
+    }
+
     #[tracing::instrument(level = "debug", skip(self, public_key, pin))]
     fn verify_card_private_key(
         &self,
@@ -0,0 +1,287 @@
+//! Body encodings shared by the CACVote server and its clients. JSON is the
+//! default; CBOR and MessagePack carry binary fields such as
+//! [`SignedObject::payload`][types_rs::cacvote::SignedObject::payload] as raw
+//! bytes rather than base64, and bodies in any encoding may be compressed.
+
+use std::io::{Read, Write};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("unsupported media type: {0}")]
+    UnsupportedMediaType(String),
+
+    #[error("unsupported content encoding: {0}")]
+    UnsupportedContentEncoding(String),
+
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("cbor error: {0}")]
+    Cbor(String),
+
+    #[error("msgpack encode error: {0}")]
+    MessagePackEncode(#[from] rmp_serde::encode::Error),
+
+    #[error("msgpack decode error: {0}")]
+    MessagePackDecode(#[from] rmp_serde::decode::Error),
+
+    #[error("decompressed body is larger than {0} bytes")]
+    TooLarge(usize),
+
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// The encoding of a request or response body, as given by its
+/// `Content-Type` header.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BodyEncoding {
+    #[default]
+    Json,
+    Cbor,
+    MessagePack,
+}
+
+impl BodyEncoding {
+    pub const ALL: [Self; 3] = [Self::Json, Self::Cbor, Self::MessagePack];
+
+    #[must_use]
+    pub const fn mime_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Cbor => "application/cbor",
+            Self::MessagePack => "application/msgpack",
+        }
+    }
+
+    /// Determines the encoding of a body from its `Content-Type`. A missing
+    /// content type is treated as JSON.
+    pub fn from_content_type(content_type: Option<&str>) -> Result<Self, Error> {
+        let Some(content_type) = content_type else {
+            return Ok(Self::Json);
+        };
+
+        let mime_type = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+
+        match mime_type.as_str() {
+            "application/json" => Ok(Self::Json),
+            "application/cbor" => Ok(Self::Cbor),
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => {
+                Ok(Self::MessagePack)
+            }
+            _ => Err(Error::UnsupportedMediaType(content_type.to_owned())),
+        }
+    }
+
+    /// Picks the most preferred supported encoding from an `Accept` header,
+    /// falling back to JSON if there is no header or nothing else matches.
+    #[must_use]
+    pub fn from_accept(accept: Option<&str>) -> Self {
+        let Some(accept) = accept else {
+            return Self::Json;
+        };
+
+        let mut best: Option<(Self, f32)> = None;
+
+        for media_range in accept.split(',') {
+            let mut parts = media_range.split(';');
+            let mime_type = parts.next().unwrap_or_default().trim();
+            let quality = parts
+                .filter_map(|parameter| parameter.trim().strip_prefix("q="))
+                .find_map(|quality| quality.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            let encoding = match mime_type {
+                "*/*" | "application/*" => Self::Json,
+                _ => match Self::from_content_type(Some(mime_type)) {
+                    Ok(encoding) => encoding,
+                    Err(_) => continue,
+                },
+            };
+
+            if quality > 0.0 && best.is_none_or(|(_, best_quality)| quality > best_quality) {
+                best = Some((encoding, quality));
+            }
+        }
+
+        best.map_or(Self::Json, |(encoding, _)| encoding)
+    }
+
+    pub fn encode(self, value: &impl Serialize) -> Result<Vec<u8>, Error> {
+        match self {
+            Self::Json => Ok(serde_json::to_vec(value)?),
+            Self::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(value, &mut bytes)
+                    .map_err(|err| Error::Cbor(err.to_string()))?;
+                Ok(bytes)
+            }
+            // structs are encoded as maps rather than arrays so that optional
+            // fields may be left out
+            Self::MessagePack => Ok(rmp_serde::to_vec_named(value)?),
+        }
+    }
+
+    pub fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T, Error> {
+        match self {
+            Self::Json => Ok(serde_json::from_slice(bytes)?),
+            Self::Cbor => ciborium::from_reader(bytes).map_err(|err| Error::Cbor(err.to_string())),
+            Self::MessagePack => Ok(rmp_serde::from_slice(bytes)?),
+        }
+    }
+}
+
+/// The compression of a body, as given by its `Content-Encoding` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentEncoding {
+    Gzip,
+    Zstd,
+}
+
+impl ContentEncoding {
+    /// The value of an `Accept-Encoding` header accepting every supported
+    /// compression, most preferred first.
+    pub const ACCEPT_ENCODING: &'static str = "zstd, gzip";
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    /// Determines the compression of a body from its `Content-Encoding`.
+    /// Returns `None` for an uncompressed body.
+    pub fn from_header(content_encoding: Option<&str>) -> Result<Option<Self>, Error> {
+        match content_encoding.map(str::trim) {
+            None | Some("" | "identity") => Ok(None),
+            Some(content_encoding) if content_encoding.eq_ignore_ascii_case("gzip") => {
+                Ok(Some(Self::Gzip))
+            }
+            Some(content_encoding) if content_encoding.eq_ignore_ascii_case("zstd") => {
+                Ok(Some(Self::Zstd))
+            }
+            Some(content_encoding) => Err(Error::UnsupportedContentEncoding(
+                content_encoding.to_owned(),
+            )),
+        }
+    }
+
+    pub fn compress(self, bytes: &[u8]) -> Result<Vec<u8>, Error> {
+        match self {
+            Self::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(bytes)?;
+                Ok(encoder.finish()?)
+            }
+            Self::Zstd => Ok(zstd::encode_all(bytes, 0)?),
+        }
+    }
+
+    /// Decompresses `bytes`, failing if the result is larger than `max_size`
+    /// so that a small compressed body can't exhaust memory.
+    pub fn decompress(self, bytes: &[u8], max_size: usize) -> Result<Vec<u8>, Error> {
+        let decoder: Box<dyn Read + '_> = match self {
+            Self::Gzip => Box::new(flate2::read::GzDecoder::new(bytes)),
+            Self::Zstd => Box::new(zstd::stream::read::Decoder::new(bytes)?),
+        };
+
+        let mut decompressed = Vec::new();
+        decoder
+            .take(
+                u64::try_from(max_size)
+                    .unwrap_or(u64::MAX)
+                    .saturating_add(1),
+            )
+            .read_to_end(&mut decompressed)?;
+
+        if decompressed.len() > max_size {
+            return Err(Error::TooLarge(max_size));
+        }
+
+        Ok(decompressed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_content_type() {
+        assert_eq!(
+            BodyEncoding::from_content_type(None).unwrap(),
+            BodyEncoding::Json
+        );
+        assert_eq!(
+            BodyEncoding::from_content_type(Some("application/json; charset=utf-8")).unwrap(),
+            BodyEncoding::Json
+        );
+        assert_eq!(
+            BodyEncoding::from_content_type(Some("application/cbor")).unwrap(),
+            BodyEncoding::Cbor
+        );
+        assert_eq!(
+            BodyEncoding::from_content_type(Some("application/x-msgpack")).unwrap(),
+            BodyEncoding::MessagePack
+        );
+        assert!(BodyEncoding::from_content_type(Some("text/plain")).is_err());
+    }
+
+    #[test]
+    fn test_from_accept() {
+        assert_eq!(BodyEncoding::from_accept(None), BodyEncoding::Json);
+        assert_eq!(BodyEncoding::from_accept(Some("*/*")), BodyEncoding::Json);
+        assert_eq!(
+            BodyEncoding::from_accept(Some("application/cbor")),
+            BodyEncoding::Cbor
+        );
+        assert_eq!(
+            BodyEncoding::from_accept(Some(
+                "application/json;q=0.5, application/msgpack, text/html"
+            )),
+            BodyEncoding::MessagePack
+        );
+        assert_eq!(
+            BodyEncoding::from_accept(Some("application/cbor;q=0, text/html")),
+            BodyEncoding::Json
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let value = serde_json::json!({ "a": [1, 2, 3], "b": "text" });
+
+        for body_encoding in BodyEncoding::ALL {
+            let encoded = body_encoding.encode(&value).unwrap();
+
+            for content_encoding in [ContentEncoding::Gzip, ContentEncoding::Zstd] {
+                let compressed = content_encoding.compress(&encoded).unwrap();
+                assert_eq!(
+                    content_encoding
+                        .decompress(&compressed, encoded.len())
+                        .unwrap(),
+                    encoded
+                );
+                assert!(matches!(
+                    content_encoding.decompress(&compressed, encoded.len() - 1),
+                    Err(Error::TooLarge(_))
+                ));
+            }
+
+            assert_eq!(
+                body_encoding.decode::<serde_json::Value>(&encoded).unwrap(),
+                value
+            );
+        }
+    }
+}
@@ -1,9 +1,31 @@
-use reqwest::{Response, Url};
-use types_rs::cacvote::{JournalEntry, JurisdictionCode, SignedObject};
+use opentelemetry::global;
+use opentelemetry_http::HeaderInjector;
+use reqwest::{header, header::HeaderMap, Method, RequestBuilder, Response, Url};
+use serde::de::DeserializeOwned;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use types_rs::cacvote::{
+    BallotTrackingStatus, BlobReference, BlobUpload, CreateVoterSessionRequest, JournalEntry,
+    JurisdictionCode, ObjectSearchPage, ObjectSearchQuery, ObjectVersion, ObjectView,
+    RegistrationConflict, SignedObject, SignerCertificate, SnapshotEntry, SnapshotPage,
+    SnapshotQuery, SnapshotTreeHead, VoterRegistration, VoterSession, VoterSessionChallenge,
+};
 use uuid::Uuid;
 
+pub mod encoding;
+
+use encoding::{BodyEncoding, ContentEncoding};
+
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
+/// The size of the chunks blobs are uploaded in, well under the server's
+/// request size limit.
+pub const BLOB_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+/// The largest response body the client will decompress, so that a small
+/// compressed response can't exhaust memory. Blobs are limited to their
+/// referenced size instead.
+pub const MAX_RESPONSE_SIZE: usize = 256 * 1024 * 1024;
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("reqwest error: {0}")]
@@ -17,12 +39,27 @@ pub enum Error {
 
     #[error("uuid error: {0}")]
     Uuid(#[from] uuid::Error),
+
+    #[error("encoding error: {0}")]
+    Encoding(#[from] encoding::Error),
+
+    #[error("blob upload {upload_id} was rejected at offset {offset}")]
+    BlobUploadRejected { upload_id: Uuid, offset: u64 },
+
+    #[error("blob does not match its digest {0}")]
+    BlobMismatch(String),
+
+    #[error("snapshot entries do not match tree head with root hash {0}")]
+    SnapshotMismatch(String),
 }
 
 /// A client for the CACVote server.
 #[derive(Debug)]
 pub struct Client {
     base_url: Url,
+    bearer_token: Option<String>,
+    body_encoding: BodyEncoding,
+    request_compression: Option<ContentEncoding>,
 }
 
 impl Client {
@@ -36,7 +73,60 @@ impl Client {
     /// let client = Client::new(base_url);
     /// ```
     pub const fn new(base_url: Url) -> Self {
-        Self { base_url }
+        Self {
+            base_url,
+            bearer_token: None,
+            body_encoding: BodyEncoding::Json,
+            request_compression: None,
+        }
+    }
+
+    /// Authenticate requests with a jurisdiction official's API key, which
+    /// allows reading that jurisdiction's objects without redaction.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cacvote_server_client::Client;
+    /// let client = Client::localhost().with_api_key("secret");
+    /// ```
+    #[must_use]
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.bearer_token = Some(api_key.into());
+        self
+    }
+
+    /// Authenticate requests as a voter, which allows reading the voter's own
+    /// registrations, elections and cast ballots. See
+    /// [`Client::create_voter_session`].
+    #[must_use]
+    pub fn with_voter_session(mut self, voter_session: &VoterSession) -> Self {
+        self.bearer_token = Some(voter_session.token.clone());
+        self
+    }
+
+    /// Encode request bodies with `body_encoding` and ask for responses in it.
+    /// JSON is the default; CBOR and MessagePack are more compact since they
+    /// don't base64-encode binary fields.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cacvote_server_client::{encoding::BodyEncoding, Client};
+    /// let client = Client::localhost().with_body_encoding(BodyEncoding::Cbor);
+    /// ```
+    #[must_use]
+    pub const fn with_body_encoding(mut self, body_encoding: BodyEncoding) -> Self {
+        self.body_encoding = body_encoding;
+        self
+    }
+
+    /// Compress request bodies with `content_encoding`. Responses are always
+    /// accepted compressed.
+    #[must_use]
+    pub const fn with_request_compression(mut self, content_encoding: ContentEncoding) -> Self {
+        self.request_compression = Some(content_encoding);
+        self
     }
 
     /// Create a new client to connect to the server running on localhost.
@@ -57,11 +147,15 @@ impl Client {
 
     /// Create an object on the server.
     pub async fn create_object(&self, signed_object: SignedObject) -> Result<Uuid> {
-        let response = self.post_json("/api/objects", &signed_object).await?;
-        Ok(Uuid::try_parse(&response.text().await?)?)
+        let response = self.post("/api/objects", &signed_object).await?;
+        Ok(Uuid::try_parse_ascii(
+            &read_body(response, MAX_RESPONSE_SIZE).await?,
+        )?)
     }
 
-    /// Get an object by its ID.
+    /// Get an object by its ID. Objects with private fields are redacted
+    /// unless this client has an API key for the object's jurisdiction (see
+    /// [`Client::with_api_key`]).
     ///
     /// # Example
     ///
@@ -69,20 +163,306 @@ impl Client {
     /// # use cacvote_server_client::Client;
     /// # async {
     /// # let client = Client::localhost();
-    /// let object_id = client
+    /// let object = client
     ///     .get_object_by_id("00000000-0000-0000-0000-000000000000".parse().unwrap())
     ///     .await
     ///     .unwrap();
     /// # };
     /// ```
-    pub async fn get_object_by_id(&self, object_id: Uuid) -> Result<Option<SignedObject>> {
+    pub async fn get_object_by_id(&self, object_id: Uuid) -> Result<Option<ObjectView>> {
         let response = self.get(&format!("/api/objects/{object_id}")).await?;
 
         if response.status() == reqwest::StatusCode::NOT_FOUND {
             return Ok(None);
         }
 
-        Ok(Some(response.error_for_status()?.json().await?))
+        Ok(Some(decode(response.error_for_status()?).await?))
+    }
+
+    /// Get every version of an object, oldest first, whichever version's ID is
+    /// given. Returns `None` if there is no such object.
+    pub async fn get_object_versions(&self, object_id: Uuid) -> Result<Option<Vec<ObjectVersion>>> {
+        let response = self
+            .get(&format!("/api/objects/{object_id}/versions"))
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        Ok(Some(decode(response.error_for_status()?).await?))
+    }
+
+    /// Upload `data` as a blob in chunks of [`BLOB_CHUNK_SIZE`], returning the
+    /// reference to put in payloads. An upload that was interrupted is resumed
+    /// where it left off, and data the server already has isn't sent again.
+    /// Requires an official API key (see [`Client::with_api_key`]).
+    pub async fn upload_blob(&self, data: &[u8]) -> Result<BlobReference> {
+        let reference = BlobReference::for_data(data);
+        let mut upload: BlobUpload =
+            decode(self.post("/api/blobs/uploads", &reference).await?).await?;
+
+        while !upload.is_complete() {
+            let start = usize::try_from(upload.offset)
+                .unwrap_or(usize::MAX)
+                .min(data.len());
+            let end = start.saturating_add(BLOB_CHUNK_SIZE).min(data.len());
+            let url = self
+                .base_url
+                .join(&format!("/api/blobs/uploads/{}", upload.id))?;
+            let response = self
+                .request(Method::PATCH, url)
+                .header(BlobUpload::OFFSET_HEADER, upload.offset)
+                .header(header::CONTENT_TYPE, "application/octet-stream")
+                .body(data[start..end].to_vec())
+                .send()
+                .await?;
+
+            // another client of the same jurisdiction is uploading the same
+            // blob, so carry on from wherever it got to
+            if response.status() == reqwest::StatusCode::CONFLICT {
+                let current: BlobUpload = decode(response).await?;
+                if current.offset == upload.offset {
+                    return Err(Error::BlobUploadRejected {
+                        upload_id: upload.id,
+                        offset: upload.offset,
+                    });
+                }
+                upload = current;
+                continue;
+            }
+
+            upload = decode(response.error_for_status()?).await?;
+        }
+
+        Ok(reference)
+    }
+
+    /// Get the blob `reference` refers to, checking that it matches the
+    /// reference. Returns `None` if the server doesn't have it.
+    pub async fn get_blob(&self, reference: &BlobReference) -> Result<Option<Vec<u8>>> {
+        let response = self
+            .get(&format!("/api/blobs/{}", reference.sha256))
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let max_size = usize::try_from(reference.size).unwrap_or(usize::MAX);
+        let data = read_body(response.error_for_status()?, max_size).await?;
+        if !reference.matches(&data) {
+            return Err(Error::BlobMismatch(reference.sha256.clone()));
+        }
+
+        Ok(Some(data))
+    }
+
+    /// Search for objects matching `query`. Results are paginated, so pass the
+    /// returned page's `next_cursor` as the query's `cursor` to get the next
+    /// page.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cacvote_server_client::Client;
+    /// # use types_rs::cacvote::{JurisdictionCode, ObjectSearchQuery};
+    /// # async {
+    /// # let client = Client::localhost();
+    /// // get the metadata of every election in a jurisdiction
+    /// let mut query = ObjectSearchQuery {
+    ///     jurisdiction_code: Some(JurisdictionCode::try_from("st.dev-jurisdiction").unwrap()),
+    ///     object_type: Some("Election".to_owned()),
+    ///     metadata_only: true,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// loop {
+    ///     let page = client.search_objects(&query).await.unwrap();
+    ///     // do something with `page.objects`
+    ///
+    ///     match page.next_cursor {
+    ///         Some(cursor) => query.cursor = Some(cursor),
+    ///         None => break,
+    ///     }
+    /// }
+    /// # };
+    /// ```
+    pub async fn search_objects(&self, query: &ObjectSearchQuery) -> Result<ObjectSearchPage> {
+        let url = self.base_url.join("/api/objects")?;
+        let response = self.request(Method::GET, url).query(query).send().await?;
+        decode(response.error_for_status()?).await
+    }
+
+    /// Get the attributes of a signer's certificate by its fingerprint, i.e.
+    /// the hex-encoded SHA-256 digest of the certificate.
+    pub async fn get_signer_certificate(
+        &self,
+        fingerprint: &str,
+    ) -> Result<Option<SignerCertificate>> {
+        let response = self
+            .get(&format!("/api/certificates/{fingerprint}"))
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        Ok(Some(decode(response.error_for_status()?).await?))
+    }
+
+    /// Get the IDs of all objects signed by the signer whose certificate has
+    /// the given fingerprint, oldest first.
+    pub async fn get_object_ids_by_signer(&self, fingerprint: &str) -> Result<Vec<Uuid>> {
+        let response = self
+            .get(&format!("/api/certificates/{fingerprint}/objects"))
+            .await?;
+        decode(response.error_for_status()?).await
+    }
+
+    /// Get the latest encryption key published by a jurisdiction, if any. The
+    /// key object's signature should be checked before the key is used.
+    pub async fn get_jurisdiction_encryption_key(
+        &self,
+        jurisdiction_code: &JurisdictionCode,
+    ) -> Result<Option<SignedObject>> {
+        let response = self
+            .get(&format!(
+                "/api/jurisdictions/{jurisdiction_code}/encryption-key"
+            ))
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        Ok(Some(decode(response.error_for_status()?).await?))
+    }
+
+    /// Get the status history of the cast ballot with a tracking code, see
+    /// [`types_rs::cacvote::tracking_code`]. Returns `None` if there is no
+    /// such ballot.
+    pub async fn get_ballot_tracking_status(
+        &self,
+        tracking_code: &str,
+    ) -> Result<Option<BallotTrackingStatus>> {
+        let response = self
+            .get(&format!("/api/ballot-tracking/{tracking_code}"))
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        Ok(Some(decode(response.error_for_status()?).await?))
+    }
+
+    /// Get the registration conflicts involving this client's jurisdiction.
+    /// Requires an official API key.
+    pub async fn get_registration_conflicts(&self) -> Result<Vec<RegistrationConflict>> {
+        let response = self.get("/api/registration-conflicts").await?;
+        decode(response.error_for_status()?).await
+    }
+
+    /// Get a challenge to sign with a voter's CAC in order to start a voter
+    /// session.
+    pub async fn create_voter_session_challenge(&self) -> Result<VoterSessionChallenge> {
+        let response = self
+            .post("/api/voter-sessions/challenges", &serde_json::json!({}))
+            .await?;
+        decode(response).await
+    }
+
+    /// Start a voter session by answering a challenge from
+    /// [`Client::create_voter_session_challenge`]. Use the session with
+    /// [`Client::with_voter_session`].
+    pub async fn create_voter_session(
+        &self,
+        request: &CreateVoterSessionRequest,
+    ) -> Result<VoterSession> {
+        let response = self.post("/api/voter-sessions", request).await?;
+        decode(response).await
+    }
+
+    /// Get the registration requests of the voter whose session this client
+    /// uses, each with its registration if approved.
+    pub async fn get_voter_registrations(&self) -> Result<Vec<VoterRegistration>> {
+        let response = self.get("/api/voter/registrations").await?;
+        decode(response.error_for_status()?).await
+    }
+
+    /// Get the elections the voter whose session this client uses is
+    /// registered for.
+    pub async fn get_voter_elections(&self) -> Result<Vec<SignedObject>> {
+        let response = self.get("/api/voter/elections").await?;
+        decode(response.error_for_status()?).await
+    }
+
+    /// Get the ballots cast by the voter whose session this client uses.
+    pub async fn get_voter_cast_ballots(&self) -> Result<Vec<SignedObject>> {
+        let response = self.get("/api/voter/cast-ballots").await?;
+        decode(response.error_for_status()?).await
+    }
+
+    /// Get the tree head of the snapshot of a jurisdiction's objects as of the
+    /// journal entry `cursor`, or as of the jurisdiction's latest journal entry
+    /// if `cursor` is `None`. Returns `None` if there is no such journal entry.
+    pub async fn get_snapshot_tree_head(
+        &self,
+        jurisdiction_code: &JurisdictionCode,
+        cursor: Option<Uuid>,
+    ) -> Result<Option<SnapshotTreeHead>> {
+        let query = SnapshotQuery {
+            jurisdiction_code: jurisdiction_code.clone(),
+            cursor,
+            offset: None,
+            limit: None,
+        };
+        let url = self.base_url.join("/api/snapshot")?;
+        let response = self.request(Method::GET, url).query(&query).send().await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        Ok(Some(decode(response.error_for_status()?).await?))
+    }
+
+    /// Get a page of the entries of a snapshot. Requires an official API key
+    /// for the snapshot's jurisdiction.
+    pub async fn get_snapshot_entries(&self, query: &SnapshotQuery) -> Result<SnapshotPage> {
+        let url = self.base_url.join("/api/snapshot/entries")?;
+        let response = self.request(Method::GET, url).query(query).send().await?;
+        decode(response.error_for_status()?).await
+    }
+
+    /// Get every entry of the snapshot with `tree_head`, page by page, checking
+    /// that together they match the tree head. The tree head's signature is
+    /// not checked.
+    pub async fn get_snapshot(&self, tree_head: &SnapshotTreeHead) -> Result<Vec<SnapshotEntry>> {
+        let mut query = SnapshotQuery {
+            jurisdiction_code: tree_head.jurisdiction_code.clone(),
+            cursor: Some(tree_head.cursor),
+            offset: None,
+            limit: Some(SnapshotQuery::MAX_LIMIT),
+        };
+        let mut entries = Vec::new();
+
+        loop {
+            let page = self.get_snapshot_entries(&query).await?;
+            entries.extend(page.entries);
+
+            match page.next_offset {
+                Some(next_offset) => query.offset = Some(next_offset),
+                None => break,
+            }
+        }
+
+        if !tree_head.matches_entries(&entries) {
+            return Err(Error::SnapshotMismatch(tree_head.root_hash.clone()));
+        }
+
+        Ok(entries)
     }
 
     /// Get journal entries from the server.
@@ -127,12 +507,8 @@ impl Client {
 
         let url =
             Url::parse_with_params(self.base_url.join("/api/journal-entries")?.as_str(), params)?;
-        Ok(self
-            .get(url.as_str())
-            .await?
-            .error_for_status()?
-            .json::<Vec<JournalEntry>>()
-            .await?)
+        let response = self.get(url.as_str()).await?;
+        decode(response.error_for_status()?).await
     }
 
     async fn get(&self, path: &str) -> Result<Response> {
@@ -141,16 +517,72 @@ impl Client {
         } else {
             self.base_url.join(path)?
         };
-        Ok(reqwest::get(url).await?)
+        Ok(self.request(Method::GET, url).send().await?)
     }
 
-    async fn post_json(&self, path: &str, body: &impl serde::Serialize) -> Result<Response> {
+    async fn post(&self, path: &str, body: &impl serde::Serialize) -> Result<Response> {
         let url = self.base_url.join(path)?;
-        Ok(reqwest::Client::new()
-            .post(url)
-            .json(body)
-            .send()
-            .await?
-            .error_for_status()?)
+        let mut body = self.body_encoding.encode(body)?;
+        let mut request = self
+            .request(Method::POST, url)
+            .header(header::CONTENT_TYPE, self.body_encoding.mime_type());
+
+        if let Some(content_encoding) = self.request_compression {
+            body = content_encoding.compress(&body)?;
+            request = request.header(header::CONTENT_ENCODING, content_encoding.name());
+        }
+
+        Ok(request.body(body).send().await?.error_for_status()?)
+    }
+
+    /// Starts a request, passing along the current trace context, if any, so
+    /// that the server's spans join the caller's trace.
+    fn request(&self, method: Method, url: Url) -> RequestBuilder {
+        let mut trace_headers = HeaderMap::new();
+        global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(
+                &tracing::Span::current().context(),
+                &mut HeaderInjector(&mut trace_headers),
+            );
+        });
+
+        let request = reqwest::Client::new()
+            .request(method, url)
+            .header(header::ACCEPT, self.body_encoding.mime_type())
+            .header(header::ACCEPT_ENCODING, ContentEncoding::ACCEPT_ENCODING)
+            .headers(trace_headers);
+
+        match &self.bearer_token {
+            Some(bearer_token) => request.bearer_auth(bearer_token),
+            None => request,
+        }
+    }
+}
+
+/// Decodes a response body according to its `Content-Type`.
+async fn decode<T: DeserializeOwned>(response: Response) -> Result<T> {
+    let body_encoding = BodyEncoding::from_content_type(
+        response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok()),
+    )?;
+    Ok(body_encoding.decode(&read_body(response, MAX_RESPONSE_SIZE).await?)?)
+}
+
+/// Reads a response body, decompressing it according to its
+/// `Content-Encoding` to at most `max_size` bytes.
+async fn read_body(response: Response, max_size: usize) -> Result<Vec<u8>> {
+    let content_encoding = ContentEncoding::from_header(
+        response
+            .headers()
+            .get(header::CONTENT_ENCODING)
+            .and_then(|value| value.to_str().ok()),
+    )?;
+
+    let body = response.bytes().await?;
+    match content_encoding {
+        Some(content_encoding) => Ok(content_encoding.decompress(&body, max_size)?),
+        None => Ok(body.to_vec()),
     }
 }
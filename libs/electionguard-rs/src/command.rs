@@ -1,4 +1,4 @@
-use std::{io, process::Command};
+use std::{io, path::Path, process::Command};
 
 #[tracing::instrument]
 pub fn run_electionguard_command(command: &mut Command) -> io::Result<()> {
@@ -33,3 +33,23 @@ pub fn run_electionguard_command(command: &mut Command) -> io::Result<()> {
             }
         })
 }
+
+/// Checks that the Java ElectionGuard CLI can be run, i.e. that `java` is
+/// installed and can load the CLI from `classpath`, by asking one of its
+/// commands for its usage rather than running it.
+pub fn check_electionguard_cli(classpath: &Path) -> io::Result<()> {
+    if !classpath.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("ElectionGuard classpath not found: {}", classpath.display()),
+        ));
+    }
+
+    run_electionguard_command(
+        Command::new("java")
+            .arg("-classpath")
+            .arg(classpath)
+            .arg("org.cryptobiotic.eg.cli.RunCreateElectionConfig")
+            .arg("--help"),
+    )
+}
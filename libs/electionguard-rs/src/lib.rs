@@ -6,3 +6,5 @@ pub mod manifest;
 pub mod mixnet;
 pub mod tally;
 mod zip;
+
+pub use command::check_electionguard_cli;
@@ -0,0 +1,116 @@
+//! Lets a voter follow their ballot through the stages of an election using a
+//! tracking code, without revealing who they are or how they voted.
+//!
+//! A ballot's tracking code is the hex-encoded SHA-256 digest of the cast
+//! ballot object's signature, which is also the encrypted ballot signature hash
+//! in the [`BallotVerificationPayload`][super::BallotVerificationPayload]
+//! printed on the voter's mailing label.
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+#[cfg(feature = "utoipa")]
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// The length of a decoded tracking code, i.e. of a SHA-256 digest.
+pub const TRACKING_CODE_LENGTH: usize = 32;
+
+/// Gets the tracking code for a cast ballot object with `signature`.
+#[must_use]
+pub fn tracking_code(signature: &[u8]) -> String {
+    hex::encode(hmac_sha256::Hash::hash(signature))
+}
+
+/// Decodes a tracking code, ignoring case. Returns `None` if it is not a
+/// hex-encoded SHA-256 digest.
+#[must_use]
+pub fn decode_tracking_code(tracking_code: &str) -> Option<[u8; TRACKING_CODE_LENGTH]> {
+    let mut digest = [0; TRACKING_CODE_LENGTH];
+    hex::decode_to_slice(tracking_code, &mut digest).ok()?;
+    Some(digest)
+}
+
+/// A stage a ballot has reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum BallotTrackingStage {
+    /// The jurisdiction approved the voter's registration.
+    RegistrationApproved,
+
+    /// The cast ballot was recorded on the bulletin board.
+    BallotRecorded,
+
+    /// The jurisdiction received the voter's mailing label and scanned it.
+    MailingLabelScanned,
+
+    /// The jurisdiction posted shuffled ballots for the election after the
+    /// ballot was recorded and while it was still the voter's last ballot.
+    /// The shuffle's inputs aren't published, so this does not show that the
+    /// ballot was among them, only that it could have been.
+    ShufflePosted,
+
+    /// The voter cast another ballot in the same election, which replaced
+    /// this one. Only a voter's last ballot is counted.
+    Superseded,
+}
+
+/// When a ballot reached a stage.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct BallotTrackingEvent {
+    pub stage: BallotTrackingStage,
+
+    #[serde(with = "time::serde::iso8601")]
+    pub occurred_at: OffsetDateTime,
+}
+
+/// The status history of the ballot with a tracking code. Holds only what is
+/// already public about the ballot, never the voter or their selections.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct BallotTrackingStatus {
+    pub tracking_code: String,
+    pub election_object_id: Uuid,
+    pub cast_ballot_object_id: Uuid,
+
+    /// The stages the ballot has reached, oldest first.
+    pub events: Vec<BallotTrackingEvent>,
+}
+
+impl BallotTrackingStatus {
+    /// Determines whether the ballot has reached `stage`.
+    #[must_use]
+    pub fn has_reached(&self, stage: BallotTrackingStage) -> bool {
+        self.events.iter().any(|event| event.stage == stage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracking_code_round_trip() {
+        let code = tracking_code(b"signature");
+        assert_eq!(code.len(), TRACKING_CODE_LENGTH * 2);
+        assert_eq!(
+            decode_tracking_code(&code),
+            Some(hmac_sha256::Hash::hash(b"signature"))
+        );
+        assert_eq!(
+            decode_tracking_code(&code.to_ascii_uppercase()),
+            decode_tracking_code(&code)
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid_tracking_code() {
+        assert_eq!(decode_tracking_code(""), None);
+        assert_eq!(decode_tracking_code("not hex"), None);
+        assert_eq!(decode_tracking_code(&"ab".repeat(31)), None);
+        assert_eq!(decode_tracking_code(&"ab".repeat(33)), None);
+    }
+}
@@ -0,0 +1,122 @@
+//! Serialization for binary fields. Human-readable formats such as JSON carry
+//! them as standard base64 strings, while binary formats such as CBOR and
+//! MessagePack carry the bytes as-is.
+
+use std::fmt;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{de, Deserializer, Serializer};
+
+/// Use with `#[serde(with = "Base64Standard")]` on `Vec<u8>` fields.
+pub struct Base64Standard;
+
+impl Base64Standard {
+    pub fn serialize<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&STANDARD.encode(bytes))
+        } else {
+            serializer.serialize_bytes(bytes)
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // Fields inside flattened or internally tagged types are buffered by
+        // serde before they get here, which loses track of whether the format
+        // is human-readable, so either representation is accepted.
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(BytesVisitor)
+        } else {
+            deserializer.deserialize_bytes(BytesVisitor)
+        }
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> de::Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a base64 string or a byte array")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        STANDARD.decode(v).map_err(de::Error::custom)
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(v)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Blob {
+        #[serde(with = "Base64Standard")]
+        data: Vec<u8>,
+    }
+
+    #[test]
+    fn test_json_uses_base64() {
+        let blob = Blob {
+            data: b"hello".to_vec(),
+        };
+        let json = serde_json::to_string(&blob).unwrap();
+        assert_eq!(json, r#"{"data":"aGVsbG8="}"#);
+        assert_eq!(serde_json::from_str::<Blob>(&json).unwrap(), blob);
+    }
+
+    #[test]
+    fn test_binary_formats_use_bytes() {
+        let blob = Blob {
+            data: b"hello".to_vec(),
+        };
+
+        let mut cbor = Vec::new();
+        ciborium::into_writer(&blob, &mut cbor).unwrap();
+        assert!(cbor.windows(5).any(|window| window == b"hello"));
+        assert_eq!(
+            ciborium::from_reader::<Blob, _>(cbor.as_slice()).unwrap(),
+            blob
+        );
+
+        let msgpack = rmp_serde::to_vec_named(&blob).unwrap();
+        assert!(msgpack.windows(5).any(|window| window == b"hello"));
+        assert_eq!(rmp_serde::from_slice::<Blob>(&msgpack).unwrap(), blob);
+    }
+}
@@ -0,0 +1,188 @@
+//! Types for blobs, large binary artifacts stored apart from the objects that
+//! use them. A payload refers to a blob by its SHA-256 digest, so the object's
+//! signature covers the blob's content without the blob being embedded in it.
+//!
+//! Blobs are uploaded in chunks: an upload is started for a digest and size,
+//! then chunks are appended at the upload's offset until it is complete. An
+//! interrupted upload is resumed by starting it again, which picks up at the
+//! offset reached so far.
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "utoipa")]
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::Base64Standard;
+
+/// A reference to a blob by its content.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+pub struct BlobReference {
+    /// The lowercase hex-encoded SHA-256 digest of the blob.
+    pub sha256: String,
+
+    /// The size of the blob in bytes.
+    pub size: u64,
+}
+
+impl BlobReference {
+    #[must_use]
+    pub fn for_data(data: &[u8]) -> Self {
+        let mut hasher = BlobHasher::new();
+        hasher.update(data);
+        hasher.finish()
+    }
+
+    /// Determines whether `data` is the blob this refers to.
+    #[must_use]
+    pub fn matches(&self, data: &[u8]) -> bool {
+        *self == Self::for_data(data)
+    }
+
+    /// Determines whether `sha256` is a well-formed digest, i.e. 64 lowercase
+    /// hex digits.
+    #[must_use]
+    pub fn is_valid_sha256(sha256: &str) -> bool {
+        sha256.len() == 64
+            && sha256
+                .bytes()
+                .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+    }
+}
+
+/// Computes the [`BlobReference`] for a blob a piece at a time, so that the
+/// whole blob needn't be held in memory.
+#[derive(Default)]
+pub struct BlobHasher {
+    hash: hmac_sha256::Hash,
+    size: u64,
+}
+
+impl BlobHasher {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `data`, the next piece of the blob.
+    pub fn update(&mut self, data: &[u8]) {
+        self.hash.update(data);
+        self.size += data.len() as u64;
+    }
+
+    #[must_use]
+    pub fn finish(self) -> BlobReference {
+        BlobReference {
+            sha256: hex::encode(self.hash.finalize()),
+            size: self.size,
+        }
+    }
+}
+
+/// Binary data in a payload, either embedded as base64 or stored as a blob.
+/// Embedded data serializes exactly as a plain base64 field did, so payloads
+/// signed before blobs existed keep their canonical encoding.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(untagged)]
+pub enum BlobContent {
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = Byte))]
+    Inline(#[serde(with = "Base64Standard")] Vec<u8>),
+    Blob(BlobReference),
+}
+
+impl BlobContent {
+    /// The embedded data, or `None` if it is stored as a blob.
+    #[must_use]
+    pub fn inline(&self) -> Option<&[u8]> {
+        match self {
+            Self::Inline(data) => Some(data),
+            Self::Blob(_) => None,
+        }
+    }
+
+    #[must_use]
+    pub fn blob_reference(&self) -> Option<&BlobReference> {
+        match self {
+            Self::Inline(_) => None,
+            Self::Blob(reference) => Some(reference),
+        }
+    }
+}
+
+impl From<Vec<u8>> for BlobContent {
+    fn from(data: Vec<u8>) -> Self {
+        Self::Inline(data)
+    }
+}
+
+/// The progress of a chunked blob upload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct BlobUpload {
+    pub id: Uuid,
+
+    #[serde(flatten)]
+    pub blob: BlobReference,
+
+    /// The number of bytes received so far, where the next chunk must start.
+    pub offset: u64,
+}
+
+impl BlobUpload {
+    /// The request header giving the offset a chunk starts at.
+    pub const OFFSET_HEADER: &'static str = "Upload-Offset";
+
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.offset == self.blob.size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blob_reference() {
+        let reference = BlobReference::for_data(b"hello");
+        assert_eq!(
+            reference.sha256,
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        assert_eq!(reference.size, 5);
+        assert!(reference.matches(b"hello"));
+        assert!(!reference.matches(b"hello!"));
+        assert!(BlobReference::is_valid_sha256(&reference.sha256));
+        assert!(!BlobReference::is_valid_sha256("2CF24DBA"));
+    }
+
+    #[test]
+    fn test_blob_hasher() {
+        let mut hasher = BlobHasher::new();
+        hasher.update(b"he");
+        hasher.update(b"");
+        hasher.update(b"llo");
+        assert_eq!(hasher.finish(), BlobReference::for_data(b"hello"));
+    }
+
+    #[test]
+    fn test_blob_content_serialization() {
+        // inline data is a plain base64 string, as before blobs existed
+        let inline = BlobContent::from(b"hello".to_vec());
+        assert_eq!(serde_json::to_string(&inline).unwrap(), r#""aGVsbG8=""#);
+
+        let blob = BlobContent::Blob(BlobReference::for_data(b"hello"));
+        assert_eq!(
+            serde_json::to_string(&blob).unwrap(),
+            r#"{"sha256":"2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824","size":5}"#
+        );
+
+        for content in [inline, blob] {
+            let round_trip: BlobContent =
+                serde_json::from_str(&serde_json::to_string(&content).unwrap()).unwrap();
+            assert_eq!(round_trip, content);
+        }
+    }
+}
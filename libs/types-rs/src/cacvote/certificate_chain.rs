@@ -0,0 +1,51 @@
+//! Verification of signers' certificate stacks against trusted CAs.
+
+use color_eyre::eyre::bail;
+use openssl::{
+    stack::Stack,
+    x509::{store::X509StoreBuilder, X509StoreContext, X509},
+};
+
+/// Verifies that the first certificate of a PEM-encoded stack is currently
+/// valid and chains to one of `ca_certificates`, using the rest of the stack
+/// as intermediates. Returns the first certificate.
+pub fn verify_certificate_chain(
+    certificates: &[u8],
+    ca_certificates: &[X509],
+) -> color_eyre::Result<X509> {
+    let mut certificates = X509::stack_from_pem(certificates)?.into_iter();
+    let Some(leaf_certificate) = certificates.next() else {
+        bail!("No certificates given");
+    };
+
+    let mut store = X509StoreBuilder::new()?;
+    for ca_certificate in ca_certificates {
+        store.add_cert(ca_certificate.clone())?;
+    }
+    let store = store.build();
+
+    let mut intermediates = Stack::new()?;
+    for certificate in certificates {
+        intermediates.push(certificate)?;
+    }
+
+    let mut context = X509StoreContext::new()?;
+    let verified = context.init(&store, &leaf_certificate, &intermediates, |context| {
+        if context.verify_cert()? {
+            Ok(Ok(()))
+        } else {
+            Ok(Err(context.error()))
+        }
+    })?;
+
+    // verification can leave errors on the thread's OpenSSL error queue even
+    // when it succeeds, which would confuse later OpenSSL calls, e.g. parsing
+    // PEM stacks
+    let _ = openssl::error::ErrorStack::get();
+
+    if let Err(error) = verified {
+        bail!("Certificate verification failed: {error}");
+    }
+
+    Ok(leaf_certificate)
+}
@@ -0,0 +1,149 @@
+//! Additional signatures over a [`SignedObject`][super::SignedObject]'s
+//! payload, used when more than one official must approve an object (see
+//! [`SignaturePolicy`][super::SignaturePolicy]).
+
+use std::ops::Deref;
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "utoipa")]
+use utoipa::ToSchema;
+
+use super::{Base64Standard, SignatureAlgorithm};
+
+/// An independent signature over the same payload as the object's primary
+/// signature, made by a different signer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct Cosignature {
+    /// The certificate stack of the signer, PEM encoded.
+    #[serde(with = "Base64Standard")]
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = Byte))]
+    pub certificates: Vec<u8>,
+
+    /// The signature of the object's payload (see
+    /// [`SignatureAlgorithm::signing_message`]).
+    #[serde(with = "Base64Standard")]
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = Byte))]
+    pub signature: Vec<u8>,
+
+    /// The algorithm used to produce `signature`.
+    pub signature_algorithm: SignatureAlgorithm,
+}
+
+#[cfg(feature = "openssl")]
+impl Cosignature {
+    /// Signs `payload`, which must be the payload of the object being
+    /// cosigned, with `private_key` using the preferred algorithm for the
+    /// key's type.
+    pub fn sign(
+        payload: &[u8],
+        certificates: Vec<openssl::x509::X509>,
+        private_key: &openssl::pkey::PKeyRef<openssl::pkey::Private>,
+    ) -> color_eyre::Result<Self> {
+        let Some(signature_algorithm) = SignatureAlgorithm::default_for_key(private_key) else {
+            color_eyre::eyre::bail!("Unsupported private key type: {:?}", private_key.id());
+        };
+
+        let signature =
+            signature_algorithm.sign(private_key, &signature_algorithm.signing_message(payload))?;
+        let certificates = certificates
+            .into_iter()
+            .map(|cert| cert.to_pem())
+            .collect::<Result<Vec<_>, _>>()?
+            .concat();
+
+        Ok(Self {
+            certificates,
+            signature,
+            signature_algorithm,
+        })
+    }
+
+    pub fn to_x509(&self) -> Result<Vec<openssl::x509::X509>, openssl::error::ErrorStack> {
+        openssl::x509::X509::stack_from_pem(&self.certificates)
+    }
+
+    /// Verifies this signature over `payload` using the first certificate in
+    /// the stack.
+    pub fn verify(&self, payload: &[u8]) -> Result<bool, openssl::error::ErrorStack> {
+        let public_key = match self.to_x509()?.first() {
+            Some(x509) => x509.public_key()?,
+            None => return Ok(false),
+        };
+
+        self.signature_algorithm.verify(
+            &public_key,
+            &self.signature_algorithm.signing_message(payload),
+            &self.signature,
+        )
+    }
+}
+
+/// The cosignatures of an object, in the order they were added.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(transparent)]
+pub struct Cosignatures(Vec<Cosignature>);
+
+impl Cosignatures {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn push(&mut self, cosignature: Cosignature) {
+        self.0.push(cosignature);
+    }
+}
+
+impl Deref for Cosignatures {
+    type Target = [Cosignature];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Vec<Cosignature>> for Cosignatures {
+    fn from(cosignatures: Vec<Cosignature>) -> Self {
+        Self(cosignatures)
+    }
+}
+
+impl<'a> IntoIterator for &'a Cosignatures {
+    type Item = &'a Cosignature;
+    type IntoIter = std::slice::Iter<'a, Cosignature>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for Cosignatures {
+    fn decode(value: sqlx::postgres::PgValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        let sqlx::types::Json(cosignatures) =
+            <sqlx::types::Json<Vec<Cosignature>> as sqlx::Decode<'r, sqlx::Postgres>>::decode(
+                value,
+            )?;
+        Ok(Self(cosignatures))
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl<'q> sqlx::Encode<'q, sqlx::Postgres> for Cosignatures {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <sqlx::types::Json<&Vec<Cosignature>> as sqlx::Encode<'q, sqlx::Postgres>>::encode_by_ref(
+            &sqlx::types::Json(&self.0),
+            buf,
+        )
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl sqlx::Type<sqlx::Postgres> for Cosignatures {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        sqlx::postgres::PgTypeInfo::with_name("jsonb")
+    }
+}
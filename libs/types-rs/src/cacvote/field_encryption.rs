@@ -0,0 +1,384 @@
+//! Encryption of the private payload fields of voter objects (see
+//! [`RedactionPolicy`]) to a [`JurisdictionEncryptionKey`], so that the server
+//! stores only ciphertext. Fields are encrypted before signing, so signatures
+//! cover the ciphertext.
+//!
+//! An encrypted value is a string of the form
+//! `hpke:<key id>:<base64>:<binding>`, where the base64 data is the HPKE
+//! encapsulated key followed by the ciphertext and the binding is
+//! [`field_binding`] of the plaintext. Values that don't start with `hpke:`
+//! are plaintext, e.g. from objects created before the jurisdiction published
+//! a key.
+//!
+//! The binding lets the server check an encrypted field against the signer's
+//! certificate without decrypting it, and the jurisdiction checks it against
+//! the plaintext when decrypting, so a voter can't encrypt someone else's CAC
+//! ID under their own signature. Values encrypted before bindings were added
+//! have none.
+
+#[cfg(feature = "openssl")]
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+#[cfg(feature = "openssl")]
+use super::{hpke, JurisdictionEncryptionKey, JurisdictionScoped};
+use super::{Payload, RedactionPolicy};
+
+/// The prefix of every encrypted field value.
+pub const ENCRYPTED_FIELD_PREFIX: &str = "hpke:";
+
+/// Separates field bindings from other SHA-256 digests.
+const FIELD_BINDING_PREFIX: &[u8] = b"cacvote field binding v1\0";
+
+/// The HPKE `info` for field encryption. The object type, jurisdiction and
+/// field name go in the associated data so that a ciphertext can't be moved
+/// to another field or object type.
+#[cfg(feature = "openssl")]
+const HPKE_INFO: &[u8] = b"cacvote field encryption v1";
+
+/// Determines whether a payload field value is encrypted.
+#[must_use]
+pub fn is_encrypted_field_value(value: &str) -> bool {
+    value.starts_with(ENCRYPTED_FIELD_PREFIX)
+}
+
+/// Gets the binding of an encrypted value, if it has one.
+#[must_use]
+pub fn encrypted_field_binding(value: &str) -> Option<&str> {
+    let (_key_id, data) = value
+        .strip_prefix(ENCRYPTED_FIELD_PREFIX)?
+        .split_once(':')?;
+    Some(data.split_once(':')?.1)
+}
+
+/// Commits to the plaintext of a private field, see the [module
+/// docs][self]. Values are compared the way the server compares plaintext
+/// fields with the signer's certificate, i.e. trimmed and ignoring ASCII case.
+/// Not keyed, since the server already knows the values it is checked against.
+#[must_use]
+pub fn field_binding(
+    object_type: &str,
+    jurisdiction_code: &str,
+    field_name: &str,
+    plaintext: &str,
+) -> String {
+    let mut hash = hmac_sha256::Hash::new();
+    hash.update(FIELD_BINDING_PREFIX);
+    hash.update([object_type, jurisdiction_code, field_name].join("\0"));
+    hash.update([0]);
+    hash.update(plaintext.trim().to_ascii_uppercase());
+    hex::encode(hash.finalize())
+}
+
+/// Hex-encoded first 8 bytes of the SHA-256 digest of a public key.
+pub(super) fn key_id(public_key: &[u8]) -> String {
+    hex::encode(&hmac_sha256::Hash::hash(public_key)[..8])
+}
+
+#[cfg(feature = "openssl")]
+fn associated_data(object_type: &str, jurisdiction_code: &str, field_name: &str) -> Vec<u8> {
+    [object_type, jurisdiction_code, field_name]
+        .join("\0")
+        .into_bytes()
+}
+
+impl Payload {
+    /// Determines whether any of this payload's private fields is stored in
+    /// plaintext.
+    pub fn has_plaintext_private_fields(&self) -> Result<bool, serde_json::Error> {
+        let payload = serde_json::to_value(self)?;
+        Ok(RedactionPolicy::for_object_type(self.object_type())
+            .private_fields
+            .iter()
+            .filter_map(|field_name| payload.get(field_name)?.as_str())
+            .any(|value| !is_encrypted_field_value(value)))
+    }
+
+    /// Replaces each private string field with its value `f` returns.
+    #[cfg(feature = "openssl")]
+    fn map_private_fields(
+        &self,
+        mut f: impl FnMut(&str, &str) -> color_eyre::Result<String>,
+    ) -> color_eyre::Result<Self> {
+        let mut payload = serde_json::to_value(self)?;
+
+        if let Some(fields) = payload.as_object_mut() {
+            for field_name in RedactionPolicy::for_object_type(self.object_type()).private_fields {
+                if let Some(serde_json::Value::String(value)) = fields.get_mut(*field_name) {
+                    *value = f(field_name, value)?;
+                }
+            }
+        }
+
+        Ok(serde_json::from_value(payload)?)
+    }
+
+    /// Encrypts this payload's plaintext private fields to `key`, which must
+    /// belong to the payload's jurisdiction.
+    #[cfg(feature = "openssl")]
+    pub fn encrypt_private_fields(
+        &self,
+        key: &JurisdictionEncryptionKey,
+    ) -> color_eyre::Result<Self> {
+        let jurisdiction_code = self.jurisdiction_code();
+        if key.jurisdiction_code != jurisdiction_code {
+            color_eyre::eyre::bail!(
+                "Encryption key is for jurisdiction {}, not {jurisdiction_code}",
+                key.jurisdiction_code
+            );
+        }
+
+        let key_id = key.key_id();
+        self.map_private_fields(|field_name, value| {
+            if is_encrypted_field_value(value) {
+                return Ok(value.to_owned());
+            }
+
+            let (enc, ciphertext) = hpke::seal(
+                &key.public_key,
+                HPKE_INFO,
+                &associated_data(self.object_type(), jurisdiction_code.as_str(), field_name),
+                value.as_bytes(),
+            )?;
+            Ok(format!(
+                "{ENCRYPTED_FIELD_PREFIX}{key_id}:{}:{}",
+                STANDARD.encode([enc, ciphertext].concat()),
+                field_binding(
+                    self.object_type(),
+                    jurisdiction_code.as_str(),
+                    field_name,
+                    value
+                )
+            ))
+        })
+    }
+
+    /// Decrypts this payload's encrypted private fields, checking each against
+    /// its binding if it has one. Fields encrypted to a key not in `key_pairs`
+    /// are left encrypted.
+    #[cfg(feature = "openssl")]
+    pub fn decrypt_private_fields(&self, key_pairs: &[hpke::KeyPair]) -> color_eyre::Result<Self> {
+        let jurisdiction_code = self.jurisdiction_code();
+
+        self.map_private_fields(|field_name, value| {
+            let Some(encrypted) = value.strip_prefix(ENCRYPTED_FIELD_PREFIX) else {
+                return Ok(value.to_owned());
+            };
+
+            let Some((value_key_id, data)) = encrypted.split_once(':') else {
+                color_eyre::eyre::bail!("Malformed encrypted value in field {field_name}");
+            };
+            let (data, binding) = match data.split_once(':') {
+                Some((data, binding)) => (data, Some(binding)),
+                None => (data, None),
+            };
+
+            let Some(key_pair) = key_pairs
+                .iter()
+                .find(|key_pair| key_id(&key_pair.public_key) == value_key_id)
+            else {
+                return Ok(value.to_owned());
+            };
+
+            let data = STANDARD.decode(data)?;
+            if data.len() < hpke::ENCAPSULATED_KEY_LENGTH {
+                color_eyre::eyre::bail!("Malformed encrypted value in field {field_name}");
+            }
+            let (enc, ciphertext) = data.split_at(hpke::ENCAPSULATED_KEY_LENGTH);
+
+            let plaintext = hpke::open(
+                &key_pair.private_key,
+                enc,
+                HPKE_INFO,
+                &associated_data(self.object_type(), jurisdiction_code.as_str(), field_name),
+                ciphertext,
+            )?;
+            let plaintext = String::from_utf8(plaintext)?;
+
+            if let Some(binding) = binding {
+                if binding
+                    != field_binding(
+                        self.object_type(),
+                        jurisdiction_code.as_str(),
+                        field_name,
+                        &plaintext,
+                    )
+                {
+                    color_eyre::eyre::bail!(
+                        "Encrypted value in field {field_name} does not match its binding"
+                    );
+                }
+            }
+
+            Ok(plaintext)
+        })
+    }
+}
+
+#[cfg(all(test, feature = "openssl"))]
+mod tests {
+    use super::*;
+    use crate::cacvote::{JurisdictionCode, RegistrationRequest};
+
+    fn registration_request_payload(jurisdiction_code: &str) -> Payload {
+        Payload::RegistrationRequest(RegistrationRequest {
+            common_access_card_id: "1234567890".to_owned(),
+            jurisdiction_code: JurisdictionCode::try_from(jurisdiction_code).unwrap(),
+            given_name: "John".to_owned(),
+            family_name: "Doe".to_owned(),
+        })
+    }
+
+    fn encryption_key(
+        jurisdiction_code: &str,
+        key_pair: &hpke::KeyPair,
+    ) -> JurisdictionEncryptionKey {
+        JurisdictionEncryptionKey {
+            jurisdiction_code: JurisdictionCode::try_from(jurisdiction_code).unwrap(),
+            public_key: key_pair.public_key.clone(),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let key_pair = hpke::KeyPair::generate().unwrap();
+        let payload = registration_request_payload("st.dev-jurisdiction");
+        assert!(payload.has_plaintext_private_fields().unwrap());
+
+        let encrypted = payload
+            .encrypt_private_fields(&encryption_key("st.dev-jurisdiction", &key_pair))
+            .unwrap();
+        assert!(!encrypted.has_plaintext_private_fields().unwrap());
+
+        let Payload::RegistrationRequest(ref encrypted_request) = encrypted else {
+            panic!("wrong payload type: {encrypted:?}");
+        };
+        for (field_name, value, plaintext) in [
+            (
+                "commonAccessCardId",
+                &encrypted_request.common_access_card_id,
+                "1234567890",
+            ),
+            ("givenName", &encrypted_request.given_name, "JOHN"),
+            ("familyName", &encrypted_request.family_name, " doe"),
+        ] {
+            assert!(is_encrypted_field_value(value), "{value}");
+            assert_eq!(
+                encrypted_field_binding(value),
+                Some(
+                    field_binding(
+                        "RegistrationRequest",
+                        "st.dev-jurisdiction",
+                        field_name,
+                        plaintext
+                    )
+                    .as_str()
+                ),
+                "{field_name}"
+            );
+        }
+        assert_eq!(
+            encrypted_request.jurisdiction_code.as_str(),
+            "st.dev-jurisdiction"
+        );
+
+        let decrypted = encrypted.decrypt_private_fields(&[key_pair]).unwrap();
+        assert_eq!(
+            decrypted.to_canonical_json().unwrap(),
+            payload.to_canonical_json().unwrap()
+        );
+
+        // without the key, fields stay encrypted
+        let undecrypted = encrypted
+            .decrypt_private_fields(&[hpke::KeyPair::generate().unwrap()])
+            .unwrap();
+        assert_eq!(
+            undecrypted.to_canonical_json().unwrap(),
+            encrypted.to_canonical_json().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_wrong_jurisdiction() {
+        let key_pair = hpke::KeyPair::generate().unwrap();
+        assert!(registration_request_payload("st.dev-jurisdiction")
+            .encrypt_private_fields(&encryption_key("st.other-jurisdiction", &key_pair))
+            .is_err());
+    }
+
+    #[test]
+    fn test_ciphertext_is_bound_to_field() {
+        let key_pair = hpke::KeyPair::generate().unwrap();
+        let Payload::RegistrationRequest(mut request) =
+            registration_request_payload("st.dev-jurisdiction")
+                .encrypt_private_fields(&encryption_key("st.dev-jurisdiction", &key_pair))
+                .unwrap()
+        else {
+            unreachable!()
+        };
+
+        // moving a ciphertext to another field makes it fail to decrypt
+        request.given_name = request.family_name.clone();
+        assert!(Payload::RegistrationRequest(request)
+            .decrypt_private_fields(&[key_pair])
+            .is_err());
+    }
+
+    #[test]
+    fn test_field_binding() {
+        // must match the `fieldBinding` test in CACVote Mark
+        assert_eq!(
+            field_binding(
+                "RegistrationRequest",
+                "st.test-jurisdiction",
+                "givenName",
+                " john "
+            ),
+            "e2fbd1b7b0502093323419887f8ce83288b1699fd14400c05dc96fe28866b351"
+        );
+    }
+
+    #[test]
+    fn test_ciphertext_is_checked_against_binding() {
+        let key_pair = hpke::KeyPair::generate().unwrap();
+        let key = encryption_key("st.dev-jurisdiction", &key_pair);
+        let Payload::RegistrationRequest(mut request) =
+            registration_request_payload("st.dev-jurisdiction")
+                .encrypt_private_fields(&key)
+                .unwrap()
+        else {
+            unreachable!()
+        };
+
+        // a binding for someone else's CAC ID
+        let (ciphertext, _) = request.common_access_card_id.rsplit_once(':').unwrap();
+        let ciphertext = ciphertext.to_owned();
+        let other_binding = field_binding(
+            "RegistrationRequest",
+            "st.dev-jurisdiction",
+            "commonAccessCardId",
+            "0987654321",
+        );
+        request.common_access_card_id = format!("{ciphertext}:{other_binding}");
+        assert_eq!(
+            encrypted_field_binding(&request.common_access_card_id),
+            Some(other_binding.as_str())
+        );
+        assert!(Payload::RegistrationRequest(request.clone())
+            .decrypt_private_fields(std::slice::from_ref(&key_pair))
+            .is_err());
+
+        // values encrypted before bindings were added have none
+        request.common_access_card_id = ciphertext;
+        assert_eq!(
+            encrypted_field_binding(&request.common_access_card_id),
+            None
+        );
+        let Payload::RegistrationRequest(decrypted) = Payload::RegistrationRequest(request)
+            .decrypt_private_fields(&[key_pair])
+            .unwrap()
+        else {
+            unreachable!()
+        };
+        assert_eq!(decrypted.common_access_card_id, "1234567890");
+    }
+}
@@ -0,0 +1,288 @@
+//! Single-shot HPKE ([RFC 9180](https://www.rfc-editor.org/rfc/rfc9180)) in
+//! base mode with the DHKEM(X25519, HKDF-SHA256), HKDF-SHA256, AES-128-GCM
+//! cipher suite. This is the only suite we use, so it is all that's
+//! implemented.
+
+use openssl::{
+    derive::Deriver,
+    error::ErrorStack,
+    pkey::{Id, PKey, Private},
+    symm::{decrypt_aead, encrypt_aead, Cipher},
+};
+
+/// The length of an encapsulated key, i.e. an X25519 public key.
+pub const ENCAPSULATED_KEY_LENGTH: usize = 32;
+
+const KEM_ID: u16 = 0x0020;
+const KDF_ID: u16 = 0x0001;
+const AEAD_ID: u16 = 0x0001;
+const AEAD_KEY_LENGTH: usize = 16;
+const AEAD_NONCE_LENGTH: usize = 12;
+const AEAD_TAG_LENGTH: usize = 16;
+const MODE_BASE: u8 = 0x00;
+
+/// An X25519 key pair, as raw 32-byte keys.
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeyPair {
+    pub private_key: Vec<u8>,
+    pub public_key: Vec<u8>,
+}
+
+impl std::fmt::Debug for KeyPair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyPair")
+            .field("public_key", &hex::encode(&self.public_key))
+            .finish_non_exhaustive()
+    }
+}
+
+impl KeyPair {
+    pub fn generate() -> Result<Self, ErrorStack> {
+        Self::from_pkey(&PKey::generate_x25519()?)
+    }
+
+    pub fn from_private_key(private_key: &[u8]) -> Result<Self, ErrorStack> {
+        Self::from_pkey(&PKey::private_key_from_raw_bytes(private_key, Id::X25519)?)
+    }
+
+    fn from_pkey(pkey: &PKey<Private>) -> Result<Self, ErrorStack> {
+        Ok(Self {
+            private_key: pkey.raw_private_key()?,
+            public_key: pkey.raw_public_key()?,
+        })
+    }
+}
+
+/// Encrypts `plaintext` to the holder of the private key for `public_key`.
+/// Returns the encapsulated key and the ciphertext, both of which are needed
+/// to decrypt.
+pub fn seal(
+    public_key: &[u8],
+    info: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>), ErrorStack> {
+    seal_with_ephemeral_key(&KeyPair::generate()?, public_key, info, aad, plaintext)
+}
+
+fn seal_with_ephemeral_key(
+    ephemeral_key_pair: &KeyPair,
+    public_key: &[u8],
+    info: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>), ErrorStack> {
+    let dh = diffie_hellman(&ephemeral_key_pair.private_key, public_key)?;
+    let enc = ephemeral_key_pair.public_key.clone();
+    let shared_secret = extract_and_expand(&dh, &[enc.as_slice(), public_key].concat());
+    let (key, nonce) = key_schedule(&shared_secret, info);
+
+    let mut tag = [0; AEAD_TAG_LENGTH];
+    let mut ciphertext = encrypt_aead(
+        Cipher::aes_128_gcm(),
+        &key,
+        Some(&nonce),
+        aad,
+        plaintext,
+        &mut tag,
+    )?;
+    ciphertext.extend_from_slice(&tag);
+
+    Ok((enc, ciphertext))
+}
+
+/// Decrypts a ciphertext produced by [`seal`] with the same `info` and `aad`.
+/// Fails if the ciphertext was not for this key or was tampered with.
+pub fn open(
+    private_key: &[u8],
+    enc: &[u8],
+    info: &[u8],
+    aad: &[u8],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, ErrorStack> {
+    let key_pair = KeyPair::from_private_key(private_key)?;
+    let dh = diffie_hellman(private_key, enc)?;
+    let shared_secret = extract_and_expand(&dh, &[enc, key_pair.public_key.as_slice()].concat());
+    let (key, nonce) = key_schedule(&shared_secret, info);
+
+    // a ciphertext too short to hold a tag fails to decrypt like any other
+    // invalid ciphertext
+    let (ciphertext, tag) = ciphertext.split_at(ciphertext.len().saturating_sub(AEAD_TAG_LENGTH));
+    decrypt_aead(
+        Cipher::aes_128_gcm(),
+        &key,
+        Some(&nonce),
+        aad,
+        ciphertext,
+        tag,
+    )
+}
+
+fn diffie_hellman(private_key: &[u8], public_key: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+    let private_key = PKey::private_key_from_raw_bytes(private_key, Id::X25519)?;
+    let public_key = PKey::public_key_from_raw_bytes(public_key, Id::X25519)?;
+    let mut deriver = Deriver::new(&private_key)?;
+    deriver.set_peer(&public_key)?;
+    deriver.derive_to_vec()
+}
+
+fn kem_suite_id() -> Vec<u8> {
+    [b"KEM".as_slice(), &KEM_ID.to_be_bytes()].concat()
+}
+
+fn hpke_suite_id() -> Vec<u8> {
+    [
+        b"HPKE".as_slice(),
+        &KEM_ID.to_be_bytes(),
+        &KDF_ID.to_be_bytes(),
+        &AEAD_ID.to_be_bytes(),
+    ]
+    .concat()
+}
+
+fn labeled_extract(suite_id: &[u8], salt: &[u8], label: &[u8], ikm: &[u8]) -> [u8; 32] {
+    hmac_sha256::HKDF::extract(salt, [b"HPKE-v1".as_slice(), suite_id, label, ikm].concat())
+}
+
+fn labeled_expand(suite_id: &[u8], prk: &[u8], label: &[u8], info: &[u8], out: &mut [u8]) {
+    let length = u16::try_from(out.len()).expect("HPKE output lengths fit in two bytes");
+    let labeled_info = [
+        length.to_be_bytes().as_slice(),
+        b"HPKE-v1",
+        suite_id,
+        label,
+        info,
+    ]
+    .concat();
+    hmac_sha256::HKDF::expand(out, prk, labeled_info);
+}
+
+fn extract_and_expand(dh: &[u8], kem_context: &[u8]) -> [u8; 32] {
+    let suite_id = kem_suite_id();
+    let eae_prk = labeled_extract(&suite_id, b"", b"eae_prk", dh);
+    let mut shared_secret = [0; 32];
+    labeled_expand(
+        &suite_id,
+        &eae_prk,
+        b"shared_secret",
+        kem_context,
+        &mut shared_secret,
+    );
+    shared_secret
+}
+
+fn key_schedule(
+    shared_secret: &[u8],
+    info: &[u8],
+) -> ([u8; AEAD_KEY_LENGTH], [u8; AEAD_NONCE_LENGTH]) {
+    let suite_id = hpke_suite_id();
+    let psk_id_hash = labeled_extract(&suite_id, b"", b"psk_id_hash", b"");
+    let info_hash = labeled_extract(&suite_id, b"", b"info_hash", info);
+    let key_schedule_context = [[MODE_BASE].as_slice(), &psk_id_hash, &info_hash].concat();
+    let secret = labeled_extract(&suite_id, shared_secret, b"secret", b"");
+
+    let mut key = [0; AEAD_KEY_LENGTH];
+    labeled_expand(&suite_id, &secret, b"key", &key_schedule_context, &mut key);
+    let mut base_nonce = [0; AEAD_NONCE_LENGTH];
+    labeled_expand(
+        &suite_id,
+        &secret,
+        b"base_nonce",
+        &key_schedule_context,
+        &mut base_nonce,
+    );
+
+    (key, base_nonce)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_hex(s: &str) -> Vec<u8> {
+        hex::decode(s).unwrap()
+    }
+
+    /// RFC 9180, Appendix A.1.1, sequence number 0.
+    #[test]
+    fn test_rfc_9180_test_vector() {
+        let ephemeral_key_pair = KeyPair::from_private_key(&from_hex(
+            "52c4a758a802cd8b936eceea314432798d5baf2d7e9235dc084ab1b9cfa2f736",
+        ))
+        .unwrap();
+        let recipient_key_pair = KeyPair::from_private_key(&from_hex(
+            "4612c550263fc8ad58375df3f557aac531d26850903e55a9f23f21d8534e8ac8",
+        ))
+        .unwrap();
+        assert_eq!(
+            recipient_key_pair.public_key,
+            from_hex("3948cfe0ad1ddb695d780e59077195da6c56506b027329794ab02bca80815c4d")
+        );
+
+        let info = from_hex("4f6465206f6e2061204772656369616e2055726e");
+        let aad = from_hex("436f756e742d30");
+        let plaintext = from_hex("4265617574792069732074727574682c20747275746820626561757479");
+
+        let (enc, ciphertext) = seal_with_ephemeral_key(
+            &ephemeral_key_pair,
+            &recipient_key_pair.public_key,
+            &info,
+            &aad,
+            &plaintext,
+        )
+        .unwrap();
+        assert_eq!(
+            enc,
+            from_hex("37fda3567bdbd628e88668c3c8d7e97d1d1253b6d4ea6d44c150f741f1bf4431")
+        );
+        assert_eq!(
+            ciphertext,
+            from_hex(
+                "f938558b5d72f1a23810b4be2ab4f84331acc02fc97babc53a52ae8218a355a96d8770ac83d07bea87e13c512a"
+            )
+        );
+
+        assert_eq!(
+            open(
+                &recipient_key_pair.private_key,
+                &enc,
+                &info,
+                &aad,
+                &ciphertext
+            )
+            .unwrap(),
+            plaintext
+        );
+    }
+
+    #[test]
+    fn test_open_failures() {
+        let key_pair = KeyPair::generate().unwrap();
+        let other_key_pair = KeyPair::generate().unwrap();
+        let (enc, ciphertext) = seal(&key_pair.public_key, b"info", b"aad", b"secret").unwrap();
+
+        assert_eq!(
+            open(&key_pair.private_key, &enc, b"info", b"aad", &ciphertext).unwrap(),
+            b"secret"
+        );
+        assert!(open(
+            &other_key_pair.private_key,
+            &enc,
+            b"info",
+            b"aad",
+            &ciphertext
+        )
+        .is_err());
+        assert!(open(&key_pair.private_key, &enc, b"other", b"aad", &ciphertext).is_err());
+        assert!(open(&key_pair.private_key, &enc, b"info", b"other", &ciphertext).is_err());
+        assert!(open(
+            &key_pair.private_key,
+            &enc,
+            b"info",
+            b"aad",
+            &ciphertext[1..]
+        )
+        .is_err());
+        assert!(open(&key_pair.private_key, &enc, b"info", b"aad", b"").is_err());
+    }
+}
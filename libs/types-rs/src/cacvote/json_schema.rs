@@ -0,0 +1,360 @@
+//! JSON Schemas for the payloads of signed objects and the presenters the JX
+//! terminal sends its frontend, derived from the same [`ToSchema`]
+//! implementations as the OpenAPI documents. The generated document is checked
+//! in at `libs/types-rs/schemas/cacvote.schema.json` so that the TypeScript
+//! side can be checked against it without building any Rust.
+
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+use utoipa::{
+    openapi::{RefOr, Schema},
+    ToSchema,
+};
+
+use super::{
+    CastBallotPresenter, DecryptedElectionTallyPresenter, ElectionPresenter,
+    EncryptedElectionTallyPresenter, Payload, PendingObjectPresenter, RegistrationPresenter,
+    RegistrationRequestPresenter, SessionData, ShuffledEncryptedCastBallotsPresenter,
+};
+
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Builds a JSON Schema document whose root is [`Payload`]. Every type it
+/// refers to, and every presenter, is defined under `$defs`, so e.g. a
+/// [`SessionData`] can be validated against `#/$defs/SessionData`.
+#[must_use]
+pub fn json_schema() -> Value {
+    let mut schemas = Vec::new();
+    add_schema::<Payload>(&mut schemas);
+    add_schema::<SessionData>(&mut schemas);
+    add_schema::<ElectionPresenter>(&mut schemas);
+    add_schema::<RegistrationRequestPresenter>(&mut schemas);
+    add_schema::<RegistrationPresenter>(&mut schemas);
+    add_schema::<CastBallotPresenter>(&mut schemas);
+    add_schema::<EncryptedElectionTallyPresenter>(&mut schemas);
+    add_schema::<DecryptedElectionTallyPresenter>(&mut schemas);
+    add_schema::<ShuffledEncryptedCastBallotsPresenter>(&mut schemas);
+    add_schema::<PendingObjectPresenter>(&mut schemas);
+
+    // sorted so that the checked in document only changes with the types
+    let defs = schemas
+        .into_iter()
+        .map(|(name, schema)| {
+            let mut schema = serde_json::to_value(schema).expect("schemas are always serializable");
+            rewrite_refs(&mut schema);
+            (name, schema)
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    json!({
+        "$schema": JSON_SCHEMA_DIALECT,
+        "title": "CACVote payload",
+        "$ref": "#/$defs/Payload",
+        "$defs": defs,
+    })
+}
+
+fn add_schema<T: ToSchema>(schemas: &mut Vec<(String, RefOr<Schema>)>) {
+    schemas.push((T::name().into_owned(), T::schema()));
+    T::schemas(schemas);
+}
+
+/// Points references at `$defs` rather than at the OpenAPI components they're
+/// generated for.
+fn rewrite_refs(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                match value {
+                    Value::String(reference) if key == "$ref" => {
+                        if let Some(name) = reference.strip_prefix("#/components/schemas/") {
+                            *reference = format!("#/$defs/{name}");
+                        }
+                    }
+                    _ => rewrite_refs(value),
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(rewrite_refs),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use pretty_assertions::assert_eq;
+    use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+    use super::*;
+    use crate::cacvote::{
+        CastBallot, DecryptedElectionTally, EncryptedElectionTally, Registration,
+        RegistrationRequest, ShuffledEncryptedCastBallots,
+    };
+
+    /// A payload of every object type, shared with
+    /// `apps/cacvote-mark/backend/src/cacvote-server/types.test.ts`, which
+    /// checks that CACVote Mark encodes those it knows about the same way.
+    const VECTORS: &str = include_str!("../../tests/fixtures/payload-vectors.json");
+
+    /// Presenters as the JX terminal sends them to its frontend, shared with
+    /// `apps/cacvote-jx-terminal/frontend/src/cacvote-server/session_data.test.ts`.
+    const PRESENTER_VECTORS: &str = include_str!("../../tests/fixtures/presenter-vectors.json");
+
+    #[derive(Debug, Deserialize)]
+    struct Vector {
+        description: String,
+        payload: Value,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct PresenterVector {
+        description: String,
+        schema: String,
+        value: Value,
+    }
+
+    fn schema_path() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("schemas/cacvote.schema.json")
+    }
+
+    fn validator() -> jsonschema::JSONSchema {
+        jsonschema::JSONSchema::options()
+            .with_draft(jsonschema::Draft::Draft202012)
+            .compile(&json_schema())
+            .expect("the schema is valid")
+    }
+
+    /// Validates against one of the types under `$defs` rather than [`Payload`].
+    fn validator_for(name: &str) -> jsonschema::JSONSchema {
+        let mut schema = json_schema();
+        schema["$ref"] = json!(format!("#/$defs/{name}"));
+        jsonschema::JSONSchema::options()
+            .with_draft(jsonschema::Draft::Draft202012)
+            .compile(&schema)
+            .expect("the schema is valid")
+    }
+
+    fn round_trip<T: DeserializeOwned + Serialize>(value: &Value) -> Result<Value, String> {
+        let decoded: T = serde_json::from_value(value.clone()).map_err(|e| e.to_string())?;
+        Ok(serde_json::to_value(decoded).unwrap())
+    }
+
+    fn validation_errors(validator: &jsonschema::JSONSchema, value: &Value) -> Vec<String> {
+        match validator.validate(value) {
+            Ok(()) => vec![],
+            Err(errors) => errors.map(|error| error.to_string()).collect(),
+        }
+    }
+
+    /// Run with `UPDATE_SCHEMAS=1` to update the checked in schema.
+    #[test]
+    fn test_checked_in_schema_is_up_to_date() {
+        let generated = format!(
+            "{}\n",
+            serde_json::to_string_pretty(&json_schema()).unwrap()
+        );
+
+        if std::env::var_os("UPDATE_SCHEMAS").is_some() {
+            std::fs::create_dir_all(schema_path().parent().unwrap()).unwrap();
+            std::fs::write(schema_path(), &generated).unwrap();
+        }
+
+        let checked_in = std::fs::read_to_string(schema_path()).unwrap_or_default();
+        assert!(
+            checked_in == generated,
+            "{} is out of date, run the tests with UPDATE_SCHEMAS=1 to update it",
+            schema_path().display()
+        );
+    }
+
+    #[test]
+    fn test_every_reference_is_defined() {
+        let schema = json_schema();
+        let defs = schema["$defs"].as_object().unwrap();
+
+        fn references(value: &Value, found: &mut Vec<String>) {
+            match value {
+                Value::Object(object) => {
+                    for (key, value) in object {
+                        match value {
+                            Value::String(reference) if key == "$ref" => {
+                                found.push(reference.clone());
+                            }
+                            _ => references(value, found),
+                        }
+                    }
+                }
+                Value::Array(values) => values.iter().for_each(|value| references(value, found)),
+                _ => {}
+            }
+        }
+
+        let mut found = Vec::new();
+        references(&schema, &mut found);
+        for reference in found {
+            let name = reference
+                .strip_prefix("#/$defs/")
+                .unwrap_or_else(|| panic!("unexpected reference {reference}"));
+            assert!(defs.contains_key(name), "{reference} is not defined");
+        }
+    }
+
+    /// The hand-written `*_field_name` functions, used to query payloads in
+    /// SQL, name properties of the schema derived from the `serde` attributes.
+    #[test]
+    fn test_field_names_match_schema() {
+        let schema = json_schema();
+        let field_names: [(&str, &[&str]); 6] = [
+            (
+                "RegistrationRequest",
+                &[
+                    RegistrationRequest::common_access_card_id_field_name(),
+                    RegistrationRequest::jurisdiction_code_field_name(),
+                    RegistrationRequest::given_name_field_name(),
+                    RegistrationRequest::family_name_field_name(),
+                ],
+            ),
+            (
+                "Registration",
+                &[
+                    Registration::common_access_card_id_field_name(),
+                    Registration::jurisdiction_code_field_name(),
+                    Registration::registration_request_object_id_field_name(),
+                    Registration::election_object_id_field_name(),
+                    Registration::ballot_style_id_field_name(),
+                    Registration::precinct_id_field_name(),
+                ],
+            ),
+            (
+                "CastBallot",
+                &[
+                    CastBallot::registration_request_object_id_field_name(),
+                    CastBallot::registration_object_id_field_name(),
+                    CastBallot::election_object_id_field_name(),
+                ],
+            ),
+            (
+                "EncryptedElectionTally",
+                &[EncryptedElectionTally::election_object_id_field_name()],
+            ),
+            (
+                "DecryptedElectionTally",
+                &[DecryptedElectionTally::election_object_id_field_name()],
+            ),
+            (
+                "ShuffledEncryptedCastBallots",
+                &[ShuffledEncryptedCastBallots::election_object_id_field_name()],
+            ),
+        ];
+
+        for (type_name, field_names) in field_names {
+            let properties = schema["$defs"][type_name]["properties"]
+                .as_object()
+                .unwrap_or_else(|| panic!("{type_name} has no properties"));
+            for field_name in field_names {
+                assert!(
+                    properties.contains_key(*field_name),
+                    "{type_name} has no {field_name} property"
+                );
+            }
+        }
+    }
+
+    /// Payloads encoded by the TypeScript implementation are valid, decode,
+    /// and encode again to the same values, less any fields the Rust types
+    /// don't have.
+    #[test]
+    fn test_vectors() {
+        let validator = validator();
+        let vectors: Vec<Vector> = serde_json::from_str(VECTORS).unwrap();
+        assert!(!vectors.is_empty());
+
+        for Vector {
+            description,
+            payload,
+        } in vectors
+        {
+            assert_eq!(
+                validation_errors(&validator, &payload),
+                Vec::<String>::new(),
+                "{description}"
+            );
+
+            let decoded: Payload = serde_json::from_value(payload.clone())
+                .unwrap_or_else(|e| panic!("{description}: {e}"));
+            let encoded = serde_json::to_value(&decoded).unwrap();
+            assert_eq!(
+                validation_errors(&validator, &encoded),
+                Vec::<String>::new(),
+                "{description}"
+            );
+            for (key, value) in encoded.as_object().unwrap() {
+                assert_eq!(Some(value), payload.get(key), "{description}: {key}");
+            }
+        }
+    }
+
+    /// Every object type has a vector, so that each is checked against the
+    /// TypeScript implementation if it has one.
+    #[test]
+    fn test_vectors_cover_every_object_type() {
+        let vectors: Vec<Vector> = serde_json::from_str(VECTORS).unwrap();
+        let mut object_types = vectors
+            .iter()
+            .map(|vector| vector.payload["objectType"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        object_types.sort_unstable();
+        object_types.dedup();
+
+        let mut expected = Payload::OBJECT_TYPES.to_vec();
+        expected.sort_unstable();
+        assert_eq!(object_types, expected);
+    }
+
+    /// Presenters as the JX terminal's frontend expects them are valid, decode,
+    /// and encode again to exactly the same values.
+    #[test]
+    fn test_presenter_vectors() {
+        let vectors: Vec<PresenterVector> = serde_json::from_str(PRESENTER_VECTORS).unwrap();
+        assert!(!vectors.is_empty());
+
+        for PresenterVector {
+            description,
+            schema,
+            value,
+        } in vectors
+        {
+            assert_eq!(
+                validation_errors(&validator_for(&schema), &value),
+                Vec::<String>::new(),
+                "{description}"
+            );
+
+            let encoded = match schema.as_str() {
+                "SessionData" => round_trip::<SessionData>(&value),
+                "ElectionPresenter" => round_trip::<ElectionPresenter>(&value),
+                "RegistrationRequestPresenter" => {
+                    round_trip::<RegistrationRequestPresenter>(&value)
+                }
+                "RegistrationPresenter" => round_trip::<RegistrationPresenter>(&value),
+                "CastBallotPresenter" => round_trip::<CastBallotPresenter>(&value),
+                "PendingObjectPresenter" => round_trip::<PendingObjectPresenter>(&value),
+                _ => panic!("{description}: unexpected schema {schema}"),
+            }
+            .unwrap_or_else(|e| panic!("{description}: {e}"));
+            assert_eq!(encoded, value, "{description}");
+        }
+    }
+
+    #[test]
+    fn test_rejects_unknown_object_type() {
+        let validator = validator();
+        assert!(!validator.is_valid(&json!({
+            "objectType": "Ballot",
+            "jurisdictionCode": "st.dev-jurisdiction",
+        })));
+    }
+}
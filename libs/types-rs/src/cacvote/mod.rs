@@ -3,10 +3,11 @@ use std::num::NonZeroUsize;
 use std::ops::Deref;
 use std::str::FromStr;
 
-use base64_serde::base64_serde_type;
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use tlv_derive::{Decode, Encode};
+#[cfg(feature = "utoipa")]
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::election::BallotStyleId;
@@ -14,9 +15,64 @@ use crate::election::ElectionDefinition;
 use crate::election::ElectionHash;
 use crate::election::PrecinctId;
 
-base64_serde_type!(Base64Standard, base64::engine::general_purpose::STANDARD);
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+mod ballot_tracking;
+mod base64_standard;
+mod blob;
+#[cfg(feature = "openssl")]
+mod certificate_chain;
+mod cosignature;
+mod field_encryption;
+#[cfg(feature = "openssl")]
+pub mod hpke;
+#[cfg(feature = "utoipa")]
+pub mod json_schema;
+mod object_search;
+mod object_version;
+mod readiness;
+mod redaction;
+mod registration_conflict;
+mod schema_version;
+mod signature;
+mod signature_policy;
+mod signer_certificate;
+mod snapshot;
+mod voter_session;
+
+pub use ballot_tracking::{
+    decode_tracking_code, tracking_code, BallotTrackingEvent, BallotTrackingStage,
+    BallotTrackingStatus, TRACKING_CODE_LENGTH,
+};
+use base64_standard::Base64Standard;
+pub use blob::{BlobContent, BlobHasher, BlobReference, BlobUpload};
+#[cfg(feature = "openssl")]
+pub use certificate_chain::verify_certificate_chain;
+pub use cosignature::{Cosignature, Cosignatures};
+pub use field_encryption::{
+    encrypted_field_binding, field_binding, is_encrypted_field_value, ENCRYPTED_FIELD_PREFIX,
+};
+pub use object_search::{ObjectMetadata, ObjectSearchPage, ObjectSearchQuery, ObjectSearchResult};
+pub use object_version::{ObjectVersion, SUPERSEDES_FIELD_NAME};
+pub use readiness::{ReadinessCheck, ReadinessReport};
+pub use redaction::{commitment, ObjectView, RedactedObject, RedactionPolicy};
+pub use registration_conflict::{
+    RegistrationConflict, RegistrationConflictKind, RegistrationConflictObject,
+};
+pub use schema_version::{
+    current_schema_version, INITIAL_SCHEMA_VERSION, SCHEMA_VERSION_FIELD_NAME,
+};
+pub use signature::SignatureAlgorithm;
+pub use signature_policy::{SignaturePolicy, SignaturePolicyStatus, ELECTION_MANAGER_CARD_TYPE};
+pub use signer_certificate::SignerCertificate;
+pub use snapshot::{
+    merkle_tree_root, SnapshotEntry, SnapshotLeaf, SnapshotPage, SnapshotQuery, SnapshotTreeHead,
+    TreeHeadSignature,
+};
+pub use voter_session::{
+    CreateVoterSessionRequest, VoterRegistration, VoterSession, VoterSessionChallenge,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[repr(transparent)]
 pub struct JurisdictionCode(String);
 
@@ -88,6 +144,7 @@ impl sqlx::Type<sqlx::Postgres> for JurisdictionCode {
 }
 
 #[derive(Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SignedObject {
     pub id: Uuid,
@@ -96,32 +153,127 @@ pub struct SignedObject {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub election_id: Option<Uuid>,
 
-    /// Data to be signed. Must be JSON decodable as [`Payload`][crate::cacvote::Payload].
+    /// Data to be signed. Must be the canonical JSON encoding (see
+    /// [`canonical_json`][crate::canonical_json]) of a
+    /// [`Payload`][crate::cacvote::Payload], except for legacy objects (see
+    /// [`SignedObject::is_legacy`]).
     #[serde(with = "Base64Standard")]
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = Byte))]
     pub payload: Vec<u8>,
 
     /// A stack of PEM-encoded X.509 certificates.
     #[serde(with = "Base64Standard")]
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = Byte))]
     pub certificates: Vec<u8>,
 
-    /// The signature of the payload.
+    /// The signature of the payload (see
+    /// [`SignatureAlgorithm::signing_message`]), or for legacy objects of the
+    /// payload itself.
     #[serde(with = "Base64Standard")]
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = Byte))]
     pub signature: Vec<u8>,
+
+    /// The algorithm used to produce `signature`. Objects signed before
+    /// algorithm identifiers were introduced don't have one, in which case the
+    /// algorithm is inferred from the signing certificate's key (see
+    /// [`SignatureAlgorithm::infer_legacy`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature_algorithm: Option<SignatureAlgorithm>,
+
+    /// Signatures over `payload` by signers other than the primary signer,
+    /// for objects whose [`SignaturePolicy`] requires more than one.
+    #[serde(default, skip_serializing_if = "Cosignatures::is_empty")]
+    pub cosignatures: Cosignatures,
 }
 
 impl SignedObject {
+    /// Signs `payload` with `private_key` using the preferred algorithm for
+    /// the key's type (see [`SignatureAlgorithm::default_for_key`]).
     #[cfg(feature = "openssl")]
     pub fn from_payload(
         payload: &Payload,
         certificates: Vec<openssl::x509::X509>,
         private_key: &openssl::pkey::PKeyRef<openssl::pkey::Private>,
     ) -> color_eyre::Result<Self> {
-        let election_id = payload.election_id();
-        let mut signer =
-            openssl::sign::Signer::new(openssl::hash::MessageDigest::sha256(), private_key)?;
-        let payload = serde_json::to_vec(payload)?;
-        signer.update(&payload)?;
-        let signature = signer.sign_to_vec()?;
+        let Some(signature_algorithm) = SignatureAlgorithm::default_for_key(private_key) else {
+            color_eyre::eyre::bail!("Unsupported private key type: {:?}", private_key.id());
+        };
+
+        Self::from_payload_with_algorithm(payload, certificates, private_key, signature_algorithm)
+    }
+
+    #[cfg(feature = "openssl")]
+    pub fn from_payload_with_algorithm(
+        payload: &Payload,
+        certificates: Vec<openssl::x509::X509>,
+        private_key: &openssl::pkey::PKeyRef<openssl::pkey::Private>,
+        signature_algorithm: SignatureAlgorithm,
+    ) -> color_eyre::Result<Self> {
+        Self::sign(
+            payload.election_id(),
+            payload.to_canonical_json()?,
+            certificates,
+            private_key,
+            signature_algorithm,
+        )
+    }
+
+    /// Signs `payload` as a new version of the object with ID `supersedes`
+    /// (see [`SignedObject::supersedes`]), using the preferred algorithm for
+    /// the key's type.
+    #[cfg(feature = "openssl")]
+    pub fn from_payload_superseding(
+        payload: &Payload,
+        supersedes: Uuid,
+        certificates: Vec<openssl::x509::X509>,
+        private_key: &openssl::pkey::PKeyRef<openssl::pkey::Private>,
+    ) -> color_eyre::Result<Self> {
+        let Some(signature_algorithm) = SignatureAlgorithm::default_for_key(private_key) else {
+            color_eyre::eyre::bail!("Unsupported private key type: {:?}", private_key.id());
+        };
+
+        Self::from_payload_superseding_with_algorithm(
+            payload,
+            supersedes,
+            certificates,
+            private_key,
+            signature_algorithm,
+        )
+    }
+
+    #[cfg(feature = "openssl")]
+    pub fn from_payload_superseding_with_algorithm(
+        payload: &Payload,
+        supersedes: Uuid,
+        certificates: Vec<openssl::x509::X509>,
+        private_key: &openssl::pkey::PKeyRef<openssl::pkey::Private>,
+        signature_algorithm: SignatureAlgorithm,
+    ) -> color_eyre::Result<Self> {
+        Self::sign(
+            payload.election_id(),
+            payload.to_canonical_json_superseding(supersedes)?,
+            certificates,
+            private_key,
+            signature_algorithm,
+        )
+    }
+
+    #[cfg(feature = "openssl")]
+    fn sign(
+        election_id: Option<Uuid>,
+        payload: Vec<u8>,
+        certificates: Vec<openssl::x509::X509>,
+        private_key: &openssl::pkey::PKeyRef<openssl::pkey::Private>,
+        signature_algorithm: SignatureAlgorithm,
+    ) -> color_eyre::Result<Self> {
+        if !signature_algorithm.is_compatible_with(private_key) {
+            color_eyre::eyre::bail!(
+                "Signature algorithm {signature_algorithm} is not compatible with the private key"
+            );
+        }
+
+        let signature = signature_algorithm
+            .sign(private_key, &signature_algorithm.signing_message(&payload))?;
 
         let certificates = certificates
             .iter()
@@ -135,6 +287,8 @@ impl SignedObject {
             payload,
             certificates,
             signature,
+            signature_algorithm: Some(signature_algorithm),
+            cosignatures: Cosignatures::default(),
         })
     }
 
@@ -142,22 +296,124 @@ impl SignedObject {
         serde_json::from_slice(&self.payload)
     }
 
+    /// The ID of the object this is a new version of, if any. See
+    /// [`ObjectVersion`].
+    pub fn supersedes(&self) -> Result<Option<Uuid>, serde_json::Error> {
+        serde_json::from_slice::<object_version::PayloadHeader>(&self.payload)
+            .map(|header| header.supersedes)
+    }
+
     #[cfg(feature = "openssl")]
     pub fn to_x509(&self) -> Result<Vec<openssl::x509::X509>, openssl::error::ErrorStack> {
         openssl::x509::X509::stack_from_pem(&self.certificates)
     }
 
+    /// Determines whether the payload is encoded canonically, i.e. whether
+    /// another implementation re-encoding the same payload would produce the
+    /// exact bytes that were signed.
+    #[must_use]
+    pub fn has_canonical_payload(&self) -> bool {
+        crate::canonical_json::is_canonical(&self.payload)
+    }
+
+    /// The algorithm `signature` was made with, either as declared by the
+    /// object or, for objects that predate algorithm identifiers, as inferred
+    /// from the signing certificate.
+    #[cfg(feature = "openssl")]
+    pub fn effective_signature_algorithm(&self) -> Option<SignatureAlgorithm> {
+        self.signature_algorithm.or_else(|| {
+            let public_key = self.to_x509().ok()?.first()?.public_key().ok()?;
+            SignatureAlgorithm::infer_legacy(&public_key)
+        })
+    }
+
+    /// Determines whether this object was signed before payloads were
+    /// canonically encoded, i.e. whether it lacks a signature algorithm
+    /// identifier. The stored bytes of such objects are what was signed, in
+    /// whatever encoding their signer used.
+    #[must_use]
+    pub fn is_legacy(&self) -> bool {
+        self.signature_algorithm.is_none()
+    }
+
+    /// Verifies the signature of the payload using the first certificate in
+    /// the stack, along with every cosignature. Payloads that are not
+    /// canonically encoded only verify for legacy objects (see
+    /// [`SignedObject::is_legacy`]).
     #[cfg(feature = "openssl")]
     pub fn verify(&self) -> Result<bool, openssl::error::ErrorStack> {
+        if !self.is_legacy() && !self.has_canonical_payload() {
+            return Ok(false);
+        }
+
         let public_key = match self.to_x509()?.first() {
             Some(x509) => x509.public_key()?,
             None => return Ok(false),
         };
 
-        let mut verifier =
-            openssl::sign::Verifier::new(openssl::hash::MessageDigest::sha256(), &public_key)?;
-        verifier.update(&self.payload)?;
-        verifier.verify(&self.signature)
+        let Some(signature_algorithm) = self.effective_signature_algorithm() else {
+            return Ok(false);
+        };
+
+        // only declared algorithms are bound into the signature
+        let signed_data = match self.signature_algorithm {
+            Some(signature_algorithm) => signature_algorithm.signing_message(&self.payload),
+            None => self.payload.clone(),
+        };
+
+        if !signature_algorithm.verify(&public_key, &signed_data, &self.signature)? {
+            return Ok(false);
+        }
+
+        for cosignature in &self.cosignatures {
+            if !cosignature.verify(&self.payload)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Adds a signature by another signer. The cosignature must be over this
+    /// object's payload.
+    pub fn add_cosignature(&mut self, cosignature: Cosignature) {
+        self.cosignatures.push(cosignature);
+    }
+
+    /// The certificate of each signer, primary signer first. Signatures whose
+    /// certificates can't be parsed are skipped.
+    #[cfg(feature = "openssl")]
+    pub fn signing_certificates(&self) -> Vec<openssl::x509::X509> {
+        std::iter::once(self.to_x509())
+            .chain(self.cosignatures.iter().map(Cosignature::to_x509))
+            .filter_map(|certificates| certificates.ok()?.into_iter().next())
+            .collect()
+    }
+
+    /// Checks this object against the [`SignaturePolicy`] for its type, see
+    /// [`SignaturePolicy::evaluate`].
+    #[cfg(feature = "openssl")]
+    pub fn signature_policy_status(&self) -> Result<SignaturePolicyStatus, serde_json::Error> {
+        let policy = SignaturePolicy::for_object_type(self.try_to_inner()?.object_type());
+        Ok(policy.evaluate(self))
+    }
+
+    /// Checks this object against the [`SignaturePolicy`] for its type,
+    /// counting only signers whose certificates chain to one of
+    /// `ca_certificates`, see [`SignaturePolicy::evaluate_trusted`].
+    #[cfg(feature = "openssl")]
+    pub fn trusted_signature_policy_status(
+        &self,
+        ca_certificates: &[openssl::x509::X509],
+    ) -> Result<SignaturePolicyStatus, serde_json::Error> {
+        let policy = SignaturePolicy::for_object_type(self.try_to_inner()?.object_type());
+        Ok(policy.evaluate_trusted(self, ca_certificates))
+    }
+
+    /// The attributes of the primary signer's certificate.
+    #[cfg(feature = "openssl")]
+    pub fn signer_certificate(&self) -> color_eyre::Result<SignerCertificate> {
+        SignerCertificate::from_pem_stack(&self.certificates)
     }
 
     #[must_use]
@@ -181,26 +437,46 @@ impl SignedObject {
     #[cfg(feature = "openssl")]
     #[must_use]
     pub fn jurisdiction_code_from_certificates(&self) -> Option<JurisdictionCode> {
-        /// Format: {state-2-letter-abbreviation}.{county-or-town} (e.g. ms.warren or ca.los-angeles)
-        const VX_CUSTOM_CERT_FIELD_JURISDICTION: &str = "1.3.6.1.4.1.59817.2";
+        certificate_field(
+            self.to_x509().ok()?.first()?,
+            VX_CUSTOM_CERT_FIELD_JURISDICTION,
+        )?
+        .try_into()
+        .ok()
+    }
+}
 
-        self.to_x509()
-            .ok()?
-            .first()?
+/// Format: {state-2-letter-abbreviation}.{county-or-town} (e.g. ms.warren or ca.los-angeles)
+#[cfg(feature = "openssl")]
+const VX_CUSTOM_CERT_FIELD_JURISDICTION: &str = "1.3.6.1.4.1.59817.2";
+
+/// One of: system-administrator, election-manager, poll-worker, poll-worker-with-pin
+#[cfg(feature = "openssl")]
+const VX_CUSTOM_CERT_FIELD_CARD_TYPE: &str = "1.3.6.1.4.1.59817.3";
+
+/// Reads a custom VotingWorks field from a certificate's subject.
+#[cfg(feature = "openssl")]
+fn certificate_field(certificate: &openssl::x509::X509Ref, field_name: &str) -> Option<String> {
+    Some(
+        certificate
             .subject_name()
             .entries()
-            .find(|entry| entry.object().to_string() == VX_CUSTOM_CERT_FIELD_JURISDICTION)?
+            .find(|entry| entry.object().to_string() == field_name)?
             .data()
             .as_utf8()
             .ok()?
-            .to_string()
-            .try_into()
-            .ok()
-    }
+            .to_string(),
+    )
 }
 
+/// The payload of a signed object, tagged by its `objectType`.
+// With `remote = "Self"`, the derived `Serialize` and `Deserialize`
+// implementations are inherent functions, wrapped by the trait implementations
+// below so that payloads this build can't interpret become `Payload::Unknown`
+// rather than errors.
 #[derive(Debug, Deserialize, Serialize)]
-#[serde(rename_all = "PascalCase", tag = "objectType")]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(remote = "Self", rename_all = "PascalCase", tag = "objectType")]
 pub enum Payload {
     RegistrationRequest(RegistrationRequest),
     Registration(Registration),
@@ -209,10 +485,106 @@ pub enum Payload {
     EncryptedElectionTally(EncryptedElectionTally),
     DecryptedElectionTally(DecryptedElectionTally),
     ShuffledEncryptedCastBallots(ShuffledEncryptedCastBallots),
+    JurisdictionEncryptionKey(JurisdictionEncryptionKey),
+
+    /// A payload of an object type this build doesn't know about, or in an
+    /// envelope version newer than [`PAYLOAD_ENVELOPE_VERSION`] or a schema
+    /// version newer than [`current_schema_version`]. It's kept as
+    /// is so that it can still be stored and relayed, and encodes back to the
+    /// same JSON.
+    #[serde(skip)]
+    Unknown {
+        object_type: String,
+
+        /// Every payload is scoped to a jurisdiction, so this is required even
+        /// of payloads that are otherwise unknown.
+        jurisdiction_code: JurisdictionCode,
+
+        raw: serde_json::Value,
+    },
+}
+
+/// The version of the fields every payload has regardless of its type, i.e.
+/// `objectType`, `jurisdictionCode` and `supersedes`. Payloads in version 1
+/// omit the `envelopeVersion` field. A payload in a newer version is decoded
+/// as [`Payload::Unknown`], whatever its object type, since its fields may not
+/// mean what this build expects.
+pub const PAYLOAD_ENVELOPE_VERSION: u32 = 1;
+
+/// The payload field holding its envelope version, see
+/// [`PAYLOAD_ENVELOPE_VERSION`].
+pub const ENVELOPE_VERSION_FIELD_NAME: &str = "envelopeVersion";
+
+impl Serialize for Payload {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Unknown { raw, .. } => raw.serialize(serializer),
+            _ => match current_schema_version(self.object_type()) {
+                // the derived implementation
+                INITIAL_SCHEMA_VERSION => Self::serialize(self, serializer),
+                schema_version => {
+                    use serde::ser::Error;
+
+                    let mut value = Self::serialize(self, serde_json::value::Serializer)
+                        .map_err(S::Error::custom)?;
+                    if let serde_json::Value::Object(fields) = &mut value {
+                        fields.insert(SCHEMA_VERSION_FIELD_NAME.to_owned(), schema_version.into());
+                    }
+                    value.serialize(serializer)
+                }
+            },
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Payload {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let mut raw = serde_json::Value::deserialize(deserializer)?;
+        let header: object_version::PayloadHeader =
+            serde_json::from_value(raw.clone()).map_err(D::Error::custom)?;
+
+        let schema_version = header.schema_version.unwrap_or(INITIAL_SCHEMA_VERSION);
+        if header.envelope_version.unwrap_or(1) > PAYLOAD_ENVELOPE_VERSION
+            || !Self::OBJECT_TYPES.contains(&header.object_type.as_str())
+            || schema_version > current_schema_version(&header.object_type)
+        {
+            let Some(jurisdiction_code) = header.jurisdiction_code else {
+                return Err(D::Error::missing_field("jurisdictionCode"));
+            };
+            return Ok(Self::Unknown {
+                object_type: header.object_type,
+                jurisdiction_code,
+                raw,
+            });
+        }
+
+        if let serde_json::Value::Object(fields) = &mut raw {
+            schema_version::upgrade(&header.object_type, schema_version, fields)
+                .map_err(D::Error::custom)?;
+        }
+
+        // the derived implementation
+        Self::deserialize(raw).map_err(D::Error::custom)
+    }
 }
 
 impl Payload {
-    pub fn object_type(&self) -> &'static str {
+    /// Every object type this build knows about, i.e. every variant but
+    /// [`Payload::Unknown`].
+    pub const OBJECT_TYPES: &'static [&'static str] = &[
+        "RegistrationRequest",
+        "Registration",
+        "Election",
+        "CastBallot",
+        "EncryptedElectionTally",
+        "DecryptedElectionTally",
+        "ShuffledEncryptedCastBallots",
+        "JurisdictionEncryptionKey",
+    ];
+
+    pub fn object_type(&self) -> &str {
         match self {
             Self::RegistrationRequest(_) => Self::registration_request_object_type(),
             Self::Registration(_) => Self::registration_object_type(),
@@ -223,7 +595,38 @@ impl Payload {
             Self::ShuffledEncryptedCastBallots(_) => {
                 Self::shuffled_encrypted_cast_ballots_object_type()
             }
+            Self::JurisdictionEncryptionKey(_) => Self::jurisdiction_encryption_key_object_type(),
+            Self::Unknown { object_type, .. } => object_type,
+        }
+    }
+
+    /// Whether this is a payload this build can't interpret, see
+    /// [`Payload::Unknown`].
+    #[must_use]
+    pub const fn is_unknown(&self) -> bool {
+        matches!(self, Self::Unknown { .. })
+    }
+
+    /// Encodes the payload as canonical JSON. These are the bytes that get
+    /// signed and stored in [`SignedObject::payload`].
+    pub fn to_canonical_json(&self) -> Result<Vec<u8>, serde_json::Error> {
+        crate::canonical_json::to_vec(self)
+    }
+
+    /// Encodes the payload as canonical JSON for a new version of the object
+    /// with ID `supersedes`, see [`SignedObject::supersedes`].
+    pub fn to_canonical_json_superseding(
+        &self,
+        supersedes: Uuid,
+    ) -> Result<Vec<u8>, serde_json::Error> {
+        let mut value = serde_json::to_value(self)?;
+        if let serde_json::Value::Object(fields) = &mut value {
+            fields.insert(
+                SUPERSEDES_FIELD_NAME.to_owned(),
+                serde_json::to_value(supersedes)?,
+            );
         }
+        crate::canonical_json::to_vec(&value)
     }
 
     pub fn election_id(&self) -> Option<Uuid> {
@@ -235,6 +638,37 @@ impl Payload {
             Self::EncryptedElectionTally(tally) => Some(tally.election_object_id),
             Self::DecryptedElectionTally(tally) => Some(tally.election_object_id),
             Self::ShuffledEncryptedCastBallots(ballots) => Some(ballots.election_object_id),
+            Self::JurisdictionEncryptionKey(_) => None,
+            // newer object types are expected to name their election the same
+            // way as the known ones
+            Self::Unknown { raw, .. } => raw
+                .get(CastBallot::election_object_id_field_name())
+                .and_then(|value| value.as_str())
+                .and_then(|value| value.parse().ok()),
+        }
+    }
+
+    /// The blobs this payload refers to, which must be stored before an
+    /// object with this payload is.
+    pub fn blob_references(&self) -> Vec<&BlobReference> {
+        match self {
+            Self::Election(election) => election
+                .electionguard_election_metadata_blob
+                .blob_reference()
+                .into_iter()
+                .collect(),
+            Self::ShuffledEncryptedCastBallots(ballots) => ballots
+                .electionguard_shuffled_ballots
+                .blob_reference()
+                .into_iter()
+                .collect(),
+            Self::RegistrationRequest(_)
+            | Self::Registration(_)
+            | Self::CastBallot(_)
+            | Self::EncryptedElectionTally(_)
+            | Self::DecryptedElectionTally(_)
+            | Self::JurisdictionEncryptionKey(_)
+            | Self::Unknown { .. } => vec![],
         }
     }
 
@@ -279,6 +713,12 @@ impl Payload {
         // `Payload` enum.
         "ShuffledEncryptedCastBallots"
     }
+
+    pub fn jurisdiction_encryption_key_object_type() -> &'static str {
+        // This must match the naming rules of the `serde` attribute in the
+        // `Payload` enum.
+        "JurisdictionEncryptionKey"
+    }
 }
 
 impl JurisdictionScoped for Payload {
@@ -291,11 +731,16 @@ impl JurisdictionScoped for Payload {
             Self::EncryptedElectionTally(tally) => tally.jurisdiction_code(),
             Self::DecryptedElectionTally(tally) => tally.jurisdiction_code(),
             Self::ShuffledEncryptedCastBallots(ballots) => ballots.jurisdiction_code(),
+            Self::JurisdictionEncryptionKey(key) => key.jurisdiction_code(),
+            Self::Unknown {
+                jurisdiction_code, ..
+            } => jurisdiction_code.clone(),
         }
     }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct JournalEntry {
     pub id: Uuid,
@@ -312,6 +757,9 @@ pub struct JournalEntry {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum JournalEntryAction {
     Create,
+
+    /// A new version of an object was created, see [`ObjectVersion`].
+    Update,
     Delete,
     Unknown(String),
 }
@@ -320,6 +768,7 @@ impl JournalEntryAction {
     pub fn as_str(&self) -> &str {
         match self {
             JournalEntryAction::Create => "create",
+            JournalEntryAction::Update => "update",
             JournalEntryAction::Delete => "delete",
             JournalEntryAction::Unknown(s) => s.as_str(),
         }
@@ -330,6 +779,7 @@ impl From<&str> for JournalEntryAction {
     fn from(s: &str) -> Self {
         match s {
             "create" => JournalEntryAction::Create,
+            "update" => JournalEntryAction::Update,
             "delete" => JournalEntryAction::Delete,
             _ => JournalEntryAction::Unknown(s.to_owned()),
         }
@@ -372,6 +822,21 @@ impl Serialize for JournalEntryAction {
     }
 }
 
+/// Known actions are listed, but others may be added, so clients must accept
+/// any string.
+#[cfg(feature = "utoipa")]
+impl utoipa::PartialSchema for JournalEntryAction {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::Schema> {
+        utoipa::openapi::ObjectBuilder::new()
+            .schema_type(utoipa::openapi::schema::Type::String)
+            .examples(["create", "update", "delete"])
+            .into()
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl ToSchema for JournalEntryAction {}
+
 #[cfg(feature = "sqlx")]
 impl<'r> sqlx::Decode<'r, sqlx::Postgres> for JournalEntryAction
 where
@@ -400,6 +865,7 @@ impl sqlx::Type<sqlx::Postgres> for JournalEntryAction {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 pub enum SmartcardStatus {
     #[default]
     NoReader,
@@ -408,6 +874,7 @@ pub enum SmartcardStatus {
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[serde(rename_all = "camelCase", tag = "type")]
 pub enum VerificationStatus {
     #[serde(rename_all = "camelCase")]
@@ -429,6 +896,7 @@ trait JurisdictionScoped {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct RegistrationRequest {
     pub common_access_card_id: String,
@@ -474,6 +942,7 @@ impl RegistrationRequest {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Registration {
     pub common_access_card_id: String,
@@ -529,6 +998,7 @@ impl Registration {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CreateElectionRequest {
     pub jurisdiction_code: JurisdictionCode,
@@ -537,14 +1007,13 @@ pub struct CreateElectionRequest {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Election {
     pub jurisdiction_code: JurisdictionCode,
     pub election_definition: ElectionDefinition,
     pub mailing_address: String,
-
-    #[serde(with = "Base64Standard")]
-    pub electionguard_election_metadata_blob: Vec<u8>,
+    pub electionguard_election_metadata_blob: BlobContent,
 }
 
 impl JurisdictionScoped for Election {
@@ -562,6 +1031,7 @@ impl Deref for Election {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CastBallot {
     pub common_access_card_id: String,
@@ -573,6 +1043,12 @@ pub struct CastBallot {
 }
 
 impl CastBallot {
+    pub fn common_access_card_id_field_name() -> &'static str {
+        // This must match the naming rules of the `serde` attribute in the
+        // `CastBallot` struct.
+        "commonAccessCardId"
+    }
+
     pub fn registration_request_object_id_field_name() -> &'static str {
         // This must match the naming rules of the `serde` attribute in the
         // `CastBallot` struct.
@@ -599,6 +1075,7 @@ impl JurisdictionScoped for CastBallot {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CastBallotPresenter {
     cast_ballot: CastBallot,
@@ -659,11 +1136,13 @@ impl Deref for CastBallotPresenter {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct EncryptedElectionTally {
     pub jurisdiction_code: JurisdictionCode,
     pub election_object_id: Uuid,
     #[serde(with = "Base64Standard")]
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = Byte))]
     pub electionguard_encrypted_tally: Vec<u8>,
 }
 
@@ -682,12 +1161,14 @@ impl JurisdictionScoped for EncryptedElectionTally {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct EncryptedElectionTallyPresenter {
     pub encrypted_election_tally: EncryptedElectionTally,
     #[serde(with = "time::serde::iso8601")]
     pub created_at: OffsetDateTime,
     #[serde(
+        default,
         with = "time::serde::iso8601::option",
         skip_serializing_if = "Option::is_none"
     )]
@@ -695,11 +1176,13 @@ pub struct EncryptedElectionTallyPresenter {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DecryptedElectionTally {
     pub jurisdiction_code: JurisdictionCode,
     pub election_object_id: Uuid,
     #[serde(with = "Base64Standard")]
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = Byte))]
     pub electionguard_decrypted_tally: Vec<u8>,
 }
 
@@ -712,12 +1195,14 @@ impl DecryptedElectionTally {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DecryptedElectionTallyPresenter {
     pub decrypted_election_tally: DecryptedElectionTally,
     #[serde(with = "time::serde::iso8601")]
     pub created_at: OffsetDateTime,
     #[serde(
+        default,
         with = "time::serde::iso8601::option",
         skip_serializing_if = "Option::is_none"
     )]
@@ -731,12 +1216,12 @@ impl JurisdictionScoped for DecryptedElectionTally {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ShuffledEncryptedCastBallots {
     pub jurisdiction_code: JurisdictionCode,
     pub election_object_id: Uuid,
-    #[serde(with = "Base64Standard")]
-    pub electionguard_shuffled_ballots: Vec<u8>,
+    pub electionguard_shuffled_ballots: BlobContent,
 }
 
 impl JurisdictionScoped for ShuffledEncryptedCastBallots {
@@ -753,7 +1238,36 @@ impl ShuffledEncryptedCastBallots {
     }
 }
 
+/// A public key that voter PII in a jurisdiction's objects is encrypted to,
+/// see [`Payload::encrypt_private_fields`]. The latest key published for a
+/// jurisdiction is the one to use.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct JurisdictionEncryptionKey {
+    pub jurisdiction_code: JurisdictionCode,
+
+    /// A raw X25519 public key.
+    #[serde(with = "Base64Standard")]
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = Byte))]
+    pub public_key: Vec<u8>,
+}
+
+impl JurisdictionScoped for JurisdictionEncryptionKey {
+    fn jurisdiction_code(&self) -> JurisdictionCode {
+        self.jurisdiction_code.clone()
+    }
+}
+
+impl JurisdictionEncryptionKey {
+    /// A short identifier for the key, included in each value encrypted to it.
+    pub fn key_id(&self) -> String {
+        field_encryption::key_id(&self.public_key)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ShuffledEncryptedCastBallotsPresenter {
     #[serde(flatten)]
@@ -761,13 +1275,60 @@ pub struct ShuffledEncryptedCastBallotsPresenter {
     #[serde(with = "time::serde::iso8601")]
     pub created_at: OffsetDateTime,
     #[serde(
+        default,
         with = "time::serde::iso8601::option",
         skip_serializing_if = "Option::is_none"
     )]
     pub synced_at: Option<OffsetDateTime>,
 }
 
+/// An object that is waiting on signatures from more signers before it can be
+/// posted, e.g. a decrypted tally awaiting a second election manager.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct PendingObjectPresenter {
+    pub id: Uuid,
+    object_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    election_id: Option<Uuid>,
+    signature_policy_status: SignaturePolicyStatus,
+    #[serde(with = "time::serde::iso8601")]
+    created_at: OffsetDateTime,
+}
+
+impl PendingObjectPresenter {
+    pub const fn new(
+        id: Uuid,
+        object_type: String,
+        election_id: Option<Uuid>,
+        signature_policy_status: SignaturePolicyStatus,
+        created_at: OffsetDateTime,
+    ) -> Self {
+        Self {
+            id,
+            object_type,
+            election_id,
+            signature_policy_status,
+            created_at,
+        }
+    }
+
+    pub fn object_type(&self) -> &str {
+        &self.object_type
+    }
+
+    pub fn election_id(&self) -> Option<Uuid> {
+        self.election_id
+    }
+
+    pub fn signature_policy_status(&self) -> SignaturePolicyStatus {
+        self.signature_policy_status
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[serde(rename_all = "camelCase", tag = "type")]
 pub enum SessionData {
     #[serde(rename_all = "camelCase")]
@@ -777,6 +1338,7 @@ pub enum SessionData {
         pending_registration_requests: Vec<RegistrationRequestPresenter>,
         registrations: Vec<RegistrationPresenter>,
         cast_ballots: Vec<CastBallotPresenter>,
+        pending_objects: Vec<PendingObjectPresenter>,
     },
     #[serde(rename_all = "camelCase")]
     Unauthenticated { has_smartcard: bool },
@@ -791,6 +1353,7 @@ impl Default for SessionData {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CreateRegistrationRequest {
     pub election_id: Uuid,
@@ -800,6 +1363,7 @@ pub struct CreateRegistrationRequest {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ElectionPresenter {
     pub id: Uuid,
@@ -839,6 +1403,7 @@ impl Deref for ElectionPresenter {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct RegistrationRequestPresenter {
     pub id: Uuid,
@@ -877,6 +1442,7 @@ impl RegistrationRequestPresenter {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct RegistrationPresenter {
     pub id: Uuid,
@@ -948,7 +1514,9 @@ impl RegistrationPresenter {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
 pub struct MixEncryptedBallotsRequest {
+    #[cfg_attr(feature = "utoipa", schema(value_type = usize, minimum = 1))]
     pub phases: NonZeroUsize,
 }
 
@@ -1049,6 +1617,280 @@ impl SignedBuffer {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    fn registration_request_payload() -> Payload {
+        Payload::RegistrationRequest(RegistrationRequest {
+            common_access_card_id: "1234567890".to_owned(),
+            jurisdiction_code: JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
+            given_name: "John".to_owned(),
+            family_name: "Doe".to_owned(),
+        })
+    }
+
+    #[test]
+    fn test_payload_canonical_json() {
+        // must match the "RegistrationRequest payload" entry in
+        // `tests/fixtures/canonical-json-vectors.json`
+        assert_eq!(
+            String::from_utf8(registration_request_payload().to_canonical_json().unwrap()).unwrap(),
+            r#"{"commonAccessCardId":"1234567890","familyName":"Doe","givenName":"John","jurisdictionCode":"st.dev-jurisdiction","objectType":"RegistrationRequest"}"#
+        );
+    }
+
+    #[test]
+    fn test_unknown_object_type_round_trip() {
+        let json =
+            r#"{"ballotCount":3,"jurisdictionCode":"st.dev-jurisdiction","objectType":"PollBook"}"#;
+        let payload: Payload = serde_json::from_str(json).unwrap();
+        assert!(payload.is_unknown());
+        assert_eq!(payload.object_type(), "PollBook");
+        assert_eq!(payload.jurisdiction_code().as_str(), "st.dev-jurisdiction");
+        assert_eq!(payload.election_id(), None);
+        assert!(payload.blob_references().is_empty());
+        assert_eq!(
+            String::from_utf8(payload.to_canonical_json().unwrap()).unwrap(),
+            json
+        );
+
+        let election_id = Uuid::new_v4();
+        let payload: Payload = serde_json::from_value(serde_json::json!({
+            "electionObjectId": election_id,
+            "jurisdictionCode": "st.dev-jurisdiction",
+            "objectType": "PollBook",
+        }))
+        .unwrap();
+        assert_eq!(payload.election_id(), Some(election_id));
+    }
+
+    #[test]
+    fn test_newer_envelope_version_is_unknown() {
+        let mut value = serde_json::to_value(registration_request_payload()).unwrap();
+        value[ENVELOPE_VERSION_FIELD_NAME] = (PAYLOAD_ENVELOPE_VERSION + 1).into();
+
+        let payload: Payload = serde_json::from_value(value.clone()).unwrap();
+        assert!(payload.is_unknown());
+        assert_eq!(payload.object_type(), "RegistrationRequest");
+        assert_eq!(serde_json::to_value(&payload).unwrap(), value);
+
+        value[ENVELOPE_VERSION_FIELD_NAME] = PAYLOAD_ENVELOPE_VERSION.into();
+        let payload: Payload = serde_json::from_value(value).unwrap();
+        assert!(matches!(payload, Payload::RegistrationRequest(_)));
+    }
+
+    #[test]
+    fn test_schema_versions() {
+        let mut value = serde_json::to_value(registration_request_payload()).unwrap();
+        assert_eq!(value.get(SCHEMA_VERSION_FIELD_NAME), None);
+
+        // payloads from a newer build are kept as is
+        value[SCHEMA_VERSION_FIELD_NAME] =
+            (current_schema_version("RegistrationRequest") + 1).into();
+        let payload: Payload = serde_json::from_value(value.clone()).unwrap();
+        assert!(payload.is_unknown());
+        assert_eq!(serde_json::to_value(&payload).unwrap(), value);
+
+        value[SCHEMA_VERSION_FIELD_NAME] = INITIAL_SCHEMA_VERSION.into();
+        let payload: Payload = serde_json::from_value(value.clone()).unwrap();
+        assert!(matches!(payload, Payload::RegistrationRequest(_)));
+
+        value[SCHEMA_VERSION_FIELD_NAME] = 0.into();
+        serde_json::from_value::<Payload>(value).unwrap_err();
+    }
+
+    #[test]
+    fn test_unknown_payload_requires_jurisdiction_code() {
+        assert!(serde_json::from_str::<Payload>(r#"{"objectType":"PollBook"}"#).is_err());
+        assert!(
+            serde_json::from_str::<Payload>(r#"{"jurisdictionCode":"st.dev-jurisdiction"}"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_malformed_known_payload_is_not_unknown() {
+        let error = serde_json::from_str::<Payload>(
+            r#"{"jurisdictionCode":"st.dev-jurisdiction","objectType":"RegistrationRequest"}"#,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("commonAccessCardId"), "{error}");
+    }
+
+    #[cfg(feature = "openssl")]
+    #[test]
+    fn test_signed_object_requires_canonical_payload() {
+        use openssl::{pkey::PKey, x509::X509};
+
+        let private_key = PKey::private_key_from_pem(include_bytes!(
+            "../../../auth/certs/dev/vx-admin-private-key.pem"
+        ))
+        .unwrap();
+        let certificate = X509::from_pem(include_bytes!(
+            "../../../auth/certs/dev/vx-admin-cert-authority-cert.pem"
+        ))
+        .unwrap();
+
+        let payload = registration_request_payload();
+        let signed_object =
+            SignedObject::from_payload(&payload, vec![certificate.clone()], &private_key).unwrap();
+        assert!(signed_object.has_canonical_payload());
+        assert!(signed_object.verify().unwrap());
+
+        // a valid signature over a non-canonical encoding is rejected
+        let payload = serde_json::to_vec(&payload).unwrap();
+        let signature_algorithm = SignatureAlgorithm::EcdsaP256Sha256;
+        let signed_object = SignedObject {
+            id: Uuid::new_v4(),
+            election_id: None,
+            signature: signature_algorithm
+                .sign(&private_key, &signature_algorithm.signing_message(&payload))
+                .unwrap(),
+            payload,
+            certificates: certificate.to_pem().unwrap(),
+            signature_algorithm: Some(SignatureAlgorithm::EcdsaP256Sha256),
+            cosignatures: Cosignatures::default(),
+        };
+        assert!(!signed_object.has_canonical_payload());
+        assert!(!signed_object.verify().unwrap());
+    }
+
+    #[cfg(feature = "openssl")]
+    #[test]
+    fn test_legacy_signed_object_verifies() {
+        // signed by the dev VxAdmin key before payloads were canonically
+        // encoded, with the `objectType` tag first
+        let signed_object: SignedObject = serde_json::from_str(include_str!(
+            "../../tests/fixtures/pre-canonical-signed-object.json"
+        ))
+        .unwrap();
+        assert!(signed_object.is_legacy());
+        assert!(!signed_object.has_canonical_payload());
+        assert!(signed_object.verify().unwrap());
+        assert_eq!(
+            signed_object
+                .try_to_inner()
+                .unwrap()
+                .to_canonical_json()
+                .unwrap(),
+            registration_request_payload().to_canonical_json().unwrap()
+        );
+
+        // the stored bytes are what was signed
+        let mut tampered = signed_object;
+        tampered.payload = registration_request_payload().to_canonical_json().unwrap();
+        assert!(!tampered.verify().unwrap());
+    }
+
+    #[cfg(feature = "openssl")]
+    #[test]
+    fn test_signed_object_signature_algorithm() {
+        use openssl::{pkey::PKey, x509::X509};
+
+        let private_key = PKey::private_key_from_pem(include_bytes!(
+            "../../../auth/certs/dev/vx-admin-private-key.pem"
+        ))
+        .unwrap();
+        let certificate = X509::from_pem(include_bytes!(
+            "../../../auth/certs/dev/vx-admin-cert-authority-cert.pem"
+        ))
+        .unwrap();
+
+        let mut signed_object = SignedObject::from_payload(
+            &registration_request_payload(),
+            vec![certificate.clone()],
+            &private_key,
+        )
+        .unwrap();
+        assert_eq!(
+            signed_object.signature_algorithm,
+            Some(SignatureAlgorithm::EcdsaP256Sha256)
+        );
+
+        // the algorithm is part of the envelope
+        let json = serde_json::to_value(&signed_object).unwrap();
+        assert_eq!(json["signatureAlgorithm"], "ES256");
+
+        // the algorithm is bound into the signature, so it can't be changed
+        // or dropped, even though legacy objects infer the same algorithm from
+        // the certificate
+        signed_object.signature_algorithm = Some(SignatureAlgorithm::EcdsaP384Sha384);
+        assert!(!signed_object.verify().unwrap());
+        signed_object.signature_algorithm = None;
+        assert_eq!(
+            signed_object.effective_signature_algorithm(),
+            Some(SignatureAlgorithm::EcdsaP256Sha256)
+        );
+        assert!(!signed_object.verify().unwrap());
+
+        // the key can't produce signatures for an incompatible algorithm
+        let Err(error) = SignedObject::from_payload_with_algorithm(
+            &registration_request_payload(),
+            vec![certificate],
+            &private_key,
+            SignatureAlgorithm::RsaPssSha256,
+        ) else {
+            panic!("signed with an incompatible algorithm");
+        };
+        assert_eq!(
+            error.to_string(),
+            "Signature algorithm PS256 is not compatible with the private key"
+        );
+    }
+
+    #[cfg(feature = "openssl")]
+    #[test]
+    fn test_signed_object_supersedes() {
+        use openssl::{pkey::PKey, x509::X509};
+
+        let private_key = PKey::private_key_from_pem(include_bytes!(
+            "../../../auth/certs/dev/vx-admin-private-key.pem"
+        ))
+        .unwrap();
+        let certificate = X509::from_pem(include_bytes!(
+            "../../../auth/certs/dev/vx-admin-cert-authority-cert.pem"
+        ))
+        .unwrap();
+
+        let original = SignedObject::from_payload(
+            &registration_request_payload(),
+            vec![certificate.clone()],
+            &private_key,
+        )
+        .unwrap();
+        assert_eq!(original.supersedes().unwrap(), None);
+
+        let update = SignedObject::from_payload_superseding(
+            &registration_request_payload(),
+            original.id,
+            vec![certificate.clone()],
+            &private_key,
+        )
+        .unwrap();
+        assert_eq!(update.supersedes().unwrap(), Some(original.id));
+        assert!(update.verify().unwrap());
+
+        let update = SignedObject::from_payload_superseding_with_algorithm(
+            &registration_request_payload(),
+            original.id,
+            vec![certificate],
+            &private_key,
+            SignatureAlgorithm::EcdsaP256Sha256,
+        )
+        .unwrap();
+        assert_eq!(update.supersedes().unwrap(), Some(original.id));
+        assert_eq!(
+            update.signature_algorithm,
+            Some(SignatureAlgorithm::EcdsaP256Sha256)
+        );
+        assert!(update.verify().unwrap());
+
+        // the payload itself still reads as usual
+        assert_eq!(
+            update.try_to_inner().unwrap().object_type(),
+            Payload::registration_request_object_type()
+        );
+    }
+
     #[test]
     fn test_ballot_verification_payload() {
         let machine_id = "machine-id".to_owned();
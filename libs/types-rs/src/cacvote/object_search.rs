@@ -0,0 +1,126 @@
+//! Types for searching the objects stored on the server.
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+#[cfg(feature = "utoipa")]
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use super::{JurisdictionCode, ObjectView};
+
+/// Filters and pagination for an object search. Every filter is optional and
+/// all given filters must match. Results are ordered by creation time. Query
+/// parameters are camelCase, like the rest of the API.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(IntoParams))]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "utoipa", into_params(parameter_in = Query, rename_all = "camelCase"))]
+pub struct ObjectSearchQuery {
+    #[serde(
+        rename = "jurisdiction",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub jurisdiction_code: Option<JurisdictionCode>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub object_type: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub election_id: Option<Uuid>,
+
+    /// The fingerprint of the primary signer's certificate, see
+    /// [`SignerCertificate`][super::SignerCertificate].
+    #[serde(rename = "signer", default, skip_serializing_if = "Option::is_none")]
+    pub signer_fingerprint: Option<String>,
+
+    /// Only include objects created at or after this time.
+    #[serde(
+        default,
+        with = "time::serde::iso8601::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub created_after: Option<OffsetDateTime>,
+
+    /// Only include objects created before this time.
+    #[serde(
+        default,
+        with = "time::serde::iso8601::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub created_before: Option<OffsetDateTime>,
+
+    /// Continue a previous search after this object, i.e. the
+    /// [`ObjectSearchPage::next_cursor`] of the previous page. Searches with
+    /// a cursor that isn't a stored object are rejected.
+    #[serde(rename = "after", default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<Uuid>,
+
+    /// The maximum number of objects to return, up to
+    /// [`ObjectSearchQuery::MAX_LIMIT`]. Defaults to
+    /// [`ObjectSearchQuery::DEFAULT_LIMIT`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+
+    /// Return only the metadata of each object, leaving out the signed object
+    /// itself.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub metadata_only: bool,
+
+    /// Include versions of objects that have been superseded by a later one
+    /// (see [`ObjectVersion`][super::ObjectVersion]). By default only the
+    /// latest version of each object is returned.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub include_superseded: bool,
+}
+
+impl ObjectSearchQuery {
+    pub const DEFAULT_LIMIT: usize = 100;
+    pub const MAX_LIMIT: usize = 1000;
+}
+
+/// The searchable attributes of a stored object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectMetadata {
+    pub id: Uuid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub election_id: Option<Uuid>,
+    pub jurisdiction_code: JurisdictionCode,
+    pub object_type: String,
+    pub signer_fingerprint: String,
+
+    /// The previous version of the object, see
+    /// [`ObjectVersion`][super::ObjectVersion].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supersedes: Option<Uuid>,
+
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectSearchResult {
+    #[serde(flatten)]
+    pub metadata: ObjectMetadata,
+
+    /// The object itself, unless only metadata was requested. Private fields
+    /// are redacted unless the reader may see them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub object: Option<ObjectView>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectSearchPage {
+    pub objects: Vec<ObjectSearchResult>,
+
+    /// Set if there are more results, in which case it should be passed as
+    /// [`ObjectSearchQuery::cursor`] to get the next page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<Uuid>,
+}
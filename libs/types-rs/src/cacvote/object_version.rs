@@ -0,0 +1,55 @@
+//! Types for the version history of objects. Signed objects can't be changed,
+//! so an object is corrected by signing a new version whose payload names the
+//! object it supersedes (see [`SignedObject::supersedes`][super::SignedObject::supersedes]).
+//!
+//! Each version can be superseded only once, so an object's versions form a
+//! single chain. Other objects refer to the original version's ID, and the
+//! latest version is the one in effect.
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+#[cfg(feature = "utoipa")]
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::JurisdictionCode;
+
+/// The payload field naming the object that a new version supersedes. It sits
+/// alongside `objectType` so that it is covered by the signature.
+pub const SUPERSEDES_FIELD_NAME: &str = "supersedes";
+
+/// One version of an object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectVersion {
+    pub object_id: Uuid,
+
+    /// The previous version, `None` for the original.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supersedes: Option<Uuid>,
+
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+}
+
+/// The fields every payload may have regardless of its type, see
+/// [`PAYLOAD_ENVELOPE_VERSION`][super::PAYLOAD_ENVELOPE_VERSION] and
+/// [`schema_version`][super::schema_version].
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct PayloadHeader {
+    pub(super) object_type: String,
+
+    #[serde(default)]
+    pub(super) jurisdiction_code: Option<JurisdictionCode>,
+
+    #[serde(default)]
+    pub(super) supersedes: Option<Uuid>,
+
+    #[serde(default)]
+    pub(super) envelope_version: Option<u32>,
+
+    #[serde(default)]
+    pub(super) schema_version: Option<u32>,
+}
@@ -0,0 +1,50 @@
+//! Types for readiness checks, which report whether a backend can serve
+//! requests, as opposed to `/api/status`, which only reports that it is up.
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "utoipa")]
+use utoipa::ToSchema;
+
+/// The result of checking each of a backend's dependencies.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ReadinessReport {
+    /// Whether every check passed.
+    pub ready: bool,
+    pub checks: Vec<ReadinessCheck>,
+}
+
+impl ReadinessReport {
+    #[must_use]
+    pub fn new(checks: Vec<ReadinessCheck>) -> Self {
+        Self {
+            ready: checks.iter().all(|check| check.ok),
+            checks,
+        }
+    }
+}
+
+/// The result of checking one dependency, e.g. the database.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ReadinessCheck {
+    pub name: String,
+    pub ok: bool,
+
+    /// Why the check failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ReadinessCheck {
+    #[must_use]
+    pub fn new(name: impl Into<String>, result: Result<(), String>) -> Self {
+        Self {
+            name: name.into(),
+            ok: result.is_ok(),
+            error: result.err(),
+        }
+    }
+}
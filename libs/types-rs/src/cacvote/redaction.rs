@@ -0,0 +1,384 @@
+//! Privacy-preserving projections of objects for public readers. Fields that
+//! identify a voter are replaced with keyed commitments, while everything else,
+//! including ElectionGuard ciphertexts and their proofs, is left as-is.
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "utoipa")]
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{Base64Standard, Cosignatures, Payload, SignatureAlgorithm, SignedObject};
+
+/// The parts of an object type that must not be shown to the public.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RedactionPolicy {
+    /// Top-level payload field names, as serialized.
+    pub private_fields: &'static [&'static str],
+
+    /// Whether the signer's certificates are private, i.e. objects of this
+    /// type are signed by voters, whose CAC certificates name them.
+    pub private_signer: bool,
+}
+
+impl RedactionPolicy {
+    /// The policy for objects that contain nothing private.
+    pub const PUBLIC: Self = Self {
+        private_fields: &[],
+        private_signer: false,
+    };
+
+    /// Gets the policy for objects of the given type.
+    pub fn for_object_type(object_type: &str) -> Self {
+        // These must match the naming rules of the `serde` attributes on the
+        // payload structs.
+        if object_type == Payload::registration_request_object_type() {
+            Self {
+                private_fields: &["commonAccessCardId", "givenName", "familyName"],
+                private_signer: true,
+            }
+        } else if object_type == Payload::registration_object_type() {
+            Self {
+                private_fields: &["commonAccessCardId"],
+                private_signer: false,
+            }
+        } else if object_type == Payload::cast_ballot_object_type() {
+            Self {
+                private_fields: &["commonAccessCardId"],
+                private_signer: true,
+            }
+        } else {
+            Self::PUBLIC
+        }
+    }
+
+    #[must_use]
+    pub fn is_public(&self) -> bool {
+        self.private_fields.is_empty() && !self.private_signer
+    }
+}
+
+/// A [`SignedObject`] with its private payload fields replaced by
+/// commitments (see [`commitment`]), and its certificates replaced by a
+/// commitment too if they identify a voter.
+///
+/// The signature can't be checked against the redacted payload, so the digest
+/// of the signed payload is included: anyone holding the full object, such as
+/// a jurisdiction official or the voter who signed it, can confirm that it is
+/// the object shown here and then verify its signature as usual.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct RedactedObject {
+    pub id: Uuid,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub election_id: Option<Uuid>,
+
+    /// The payload as JSON, with each of `redacted_fields` replaced by its
+    /// hex-encoded commitment.
+    pub payload: serde_json::Value,
+
+    pub redacted_fields: Vec<String>,
+
+    /// SHA-256 digest of the signed payload bytes.
+    #[serde(with = "Base64Standard")]
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = Byte))]
+    pub payload_digest: Vec<u8>,
+
+    /// The signer's certificates, or empty if they're private (see
+    /// [`RedactionPolicy::private_signer`]).
+    #[serde(
+        default,
+        with = "Base64Standard",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = Byte))]
+    pub certificates: Vec<u8>,
+
+    /// The hex-encoded commitment to the signer's certificates in place of
+    /// private ones, which links objects signed with the same certificates
+    /// without revealing whose they are.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub certificates_commitment: Option<String>,
+
+    #[serde(with = "Base64Standard")]
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = Byte))]
+    pub signature: Vec<u8>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature_algorithm: Option<SignatureAlgorithm>,
+
+    #[serde(default, skip_serializing_if = "Cosignatures::is_empty")]
+    pub cosignatures: Cosignatures,
+}
+
+impl RedactedObject {
+    /// Determines whether this is a redaction of `object`.
+    #[must_use]
+    pub fn is_redaction_of(&self, object: &SignedObject) -> bool {
+        self.id == object.id
+            && self.payload_digest == hmac_sha256::Hash::hash(&object.payload)
+            && self.signature == object.signature
+    }
+}
+
+/// An object as shown to a particular reader: in full if they may see all of
+/// it, redacted otherwise.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(untagged)]
+pub enum ObjectView {
+    Full(SignedObject),
+    Redacted(RedactedObject),
+}
+
+impl ObjectView {
+    pub fn id(&self) -> Uuid {
+        match self {
+            Self::Full(object) => object.id,
+            Self::Redacted(object) => object.id,
+        }
+    }
+
+    /// The full object, if this reader was allowed to see it.
+    pub fn into_signed_object(self) -> Option<SignedObject> {
+        match self {
+            Self::Full(object) => Some(object),
+            Self::Redacted(_) => None,
+        }
+    }
+}
+
+/// Commits to the value of a payload field. Commitments are keyed so that
+/// values with little entropy, like CAC IDs, can't be recovered by guessing,
+/// but are otherwise deterministic so that the same voter's objects can be
+/// linked to each other without revealing who the voter is.
+pub fn commitment(
+    key: &[u8],
+    field_name: &str,
+    value: &serde_json::Value,
+) -> Result<String, serde_json::Error> {
+    let mut mac = hmac_sha256::HMAC::new(key);
+    mac.update(field_name);
+    mac.update([0]);
+    mac.update(crate::canonical_json::to_vec(value)?);
+    Ok(hex::encode(mac.finalize()))
+}
+
+/// Commits to a signer's certificates like [`commitment`] does to payload
+/// fields.
+fn certificates_commitment(key: &[u8], certificates: &[u8]) -> String {
+    let mut mac = hmac_sha256::HMAC::new(key);
+    mac.update("certificates");
+    mac.update([0]);
+    mac.update(certificates);
+    hex::encode(mac.finalize())
+}
+
+impl SignedObject {
+    /// Applies `policy` to this object, committing to private fields with
+    /// `key`.
+    pub fn redact(
+        &self,
+        policy: &RedactionPolicy,
+        key: &[u8],
+    ) -> Result<RedactedObject, serde_json::Error> {
+        let mut payload: serde_json::Value = serde_json::from_slice(&self.payload)?;
+        let mut redacted_fields = Vec::new();
+
+        if let Some(fields) = payload.as_object_mut() {
+            for field_name in policy.private_fields {
+                if let Some(value) = fields.get_mut(*field_name) {
+                    *value = serde_json::Value::String(commitment(key, field_name, value)?);
+                    redacted_fields.push((*field_name).to_owned());
+                }
+            }
+        }
+
+        Ok(RedactedObject {
+            id: self.id,
+            election_id: self.election_id,
+            payload,
+            redacted_fields,
+            payload_digest: hmac_sha256::Hash::hash(&self.payload).to_vec(),
+            certificates: if policy.private_signer {
+                Vec::new()
+            } else {
+                self.certificates.clone()
+            },
+            certificates_commitment: policy
+                .private_signer
+                .then(|| certificates_commitment(key, &self.certificates)),
+            signature: self.signature.clone(),
+            signature_algorithm: self.signature_algorithm,
+            cosignatures: self.cosignatures.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::Engine;
+
+    use super::*;
+    use crate::cacvote::{JurisdictionCode, RegistrationRequest};
+
+    fn registration_request_object() -> SignedObject {
+        SignedObject {
+            id: Uuid::from_u128(1),
+            election_id: None,
+            payload: Payload::RegistrationRequest(RegistrationRequest {
+                common_access_card_id: "1234567890".to_owned(),
+                jurisdiction_code: JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
+                given_name: "John".to_owned(),
+                family_name: "Doe".to_owned(),
+            })
+            .to_canonical_json()
+            .unwrap(),
+            certificates: b"-----BEGIN CERTIFICATE----- CN=DOE.JOHN.EDWARD.1234567890".to_vec(),
+            signature: b"signature".to_vec(),
+            signature_algorithm: None,
+            cosignatures: Cosignatures::default(),
+        }
+    }
+
+    #[test]
+    fn test_redact() {
+        let object = registration_request_object();
+        let policy = RedactionPolicy::for_object_type(Payload::registration_request_object_type());
+        let redacted = object.redact(&policy, b"key").unwrap();
+
+        let payload = serde_json::to_string(&redacted.payload).unwrap();
+        for private_value in ["1234567890", "John", "Doe"] {
+            assert!(!payload.contains(private_value), "{payload}");
+        }
+        assert_eq!(redacted.payload["jurisdictionCode"], "st.dev-jurisdiction");
+        assert_eq!(redacted.payload["objectType"], "RegistrationRequest");
+        assert_eq!(
+            redacted.redacted_fields,
+            vec!["commonAccessCardId", "givenName", "familyName"]
+        );
+        assert_eq!(
+            redacted.payload["commonAccessCardId"],
+            commitment(b"key", "commonAccessCardId", &"1234567890".into()).unwrap()
+        );
+        assert!(redacted.is_redaction_of(&object));
+
+        // commitments depend on the key and the field
+        assert_ne!(
+            commitment(b"key", "givenName", &"John".into()).unwrap(),
+            commitment(b"other key", "givenName", &"John".into()).unwrap()
+        );
+        assert_ne!(
+            commitment(b"key", "givenName", &"Doe".into()).unwrap(),
+            commitment(b"key", "familyName", &"Doe".into()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_redact_voter_certificates() {
+        let object = registration_request_object();
+        let policy = RedactionPolicy::for_object_type(Payload::registration_request_object_type());
+        let redacted = object.redact(&policy, b"key").unwrap();
+
+        // neither the common name nor the CAC ID in it is shown, even encoded
+        let json = serde_json::to_string(&redacted).unwrap();
+        let encoded_certificates =
+            base64::engine::general_purpose::STANDARD.encode(&object.certificates);
+        for private_value in [
+            "DOE.JOHN.EDWARD.1234567890",
+            "1234567890",
+            &encoded_certificates,
+        ] {
+            assert!(!json.contains(private_value), "{json}");
+        }
+        assert!(redacted.certificates.is_empty());
+
+        // the same certificates always have the same commitment
+        let mut other_object = registration_request_object();
+        other_object.id = Uuid::from_u128(2);
+        assert_eq!(
+            other_object
+                .redact(&policy, b"key")
+                .unwrap()
+                .certificates_commitment,
+            redacted.certificates_commitment
+        );
+        assert!(redacted.certificates_commitment.is_some());
+
+        // election managers' certificates aren't private
+        let policy = RedactionPolicy::for_object_type(Payload::registration_object_type());
+        let redacted = object.redact(&policy, b"key").unwrap();
+        assert_eq!(redacted.certificates, object.certificates);
+        assert_eq!(redacted.certificates_commitment, None);
+    }
+
+    #[test]
+    fn test_object_view_round_trip() {
+        let object = registration_request_object();
+        let redacted = object
+            .redact(
+                &RedactionPolicy::for_object_type(Payload::registration_request_object_type()),
+                b"key",
+            )
+            .unwrap();
+
+        let full_view: ObjectView =
+            serde_json::from_str(&serde_json::to_string(&ObjectView::Full(object)).unwrap())
+                .unwrap();
+        assert!(matches!(full_view, ObjectView::Full(_)));
+
+        let redacted_view: ObjectView = serde_json::from_str(
+            &serde_json::to_string(&ObjectView::Redacted(redacted.clone())).unwrap(),
+        )
+        .unwrap();
+        assert!(matches!(
+            redacted_view,
+            ObjectView::Redacted(ref round_trip) if *round_trip == redacted
+        ));
+        assert!(redacted_view.into_signed_object().is_none());
+    }
+
+    #[test]
+    fn test_object_view_binary_round_trip() {
+        let object = registration_request_object();
+        let redacted = object
+            .redact(
+                &RedactionPolicy::for_object_type(Payload::registration_request_object_type()),
+                b"key",
+            )
+            .unwrap();
+        let views = [ObjectView::Full(object), ObjectView::Redacted(redacted)];
+
+        let mut cbor = Vec::new();
+        ciborium::into_writer(&views, &mut cbor).unwrap();
+        let cbor_views: [ObjectView; 2] = ciborium::from_reader(cbor.as_slice()).unwrap();
+
+        let msgpack_views: [ObjectView; 2] =
+            rmp_serde::from_slice(&rmp_serde::to_vec_named(&views).unwrap()).unwrap();
+
+        for round_trip in [cbor_views, msgpack_views] {
+            let [ObjectView::Full(full), ObjectView::Redacted(redacted)] = round_trip else {
+                panic!("unexpected views: {round_trip:?}");
+            };
+            let [ObjectView::Full(object), ObjectView::Redacted(expected)] = &views else {
+                unreachable!();
+            };
+            assert_eq!(full.id, object.id);
+            assert_eq!(full.payload, object.payload);
+            assert_eq!(full.certificates, object.certificates);
+            assert_eq!(full.signature, object.signature);
+            assert_eq!(&redacted, expected);
+        }
+    }
+
+    #[test]
+    fn test_public_object_types() {
+        assert!(RedactionPolicy::for_object_type(Payload::election_object_type()).is_public());
+        assert!(
+            RedactionPolicy::for_object_type(Payload::decrypted_election_tally_object_type())
+                .is_public()
+        );
+        assert!(!RedactionPolicy::for_object_type(Payload::cast_ballot_object_type()).is_public());
+    }
+}
@@ -0,0 +1,64 @@
+//! Types for duplicate registrations of the same voter in more than one
+//! jurisdiction. Each jurisdiction only sees its own objects, so only the
+//! server can spot these.
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "utoipa")]
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::JurisdictionCode;
+
+/// A voter with registration requests or registrations in more than one
+/// jurisdiction for elections that overlap, i.e. that are held on the same
+/// date. A pending registration request overlaps every election, since it
+/// could be approved for any of them. Registrations whose election dates are
+/// unknown are reported separately, see [`RegistrationConflictKind`].
+///
+/// Identifies the voter only by an opaque ID, never by their CAC ID or name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrationConflict {
+    /// Stable for the same voter, so conflicts can be followed over time. A
+    /// voter has at most one conflict of each kind, which share this ID.
+    pub id: String,
+
+    pub kind: RegistrationConflictKind,
+
+    /// The jurisdictions involved, sorted.
+    pub jurisdiction_codes: Vec<JurisdictionCode>,
+
+    /// The conflicting objects, oldest first.
+    pub objects: Vec<RegistrationConflictObject>,
+}
+
+/// Whether the objects of a [`RegistrationConflict`] are known to overlap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum RegistrationConflictKind {
+    /// The objects are for elections held on the same date, or include a
+    /// pending registration request.
+    Overlapping,
+
+    /// The objects are registrations for elections whose dates aren't known,
+    /// e.g. because an election object is missing, so may or may not overlap.
+    UnknownElectionDate,
+}
+
+/// A registration request or registration that is part of a
+/// [`RegistrationConflict`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrationConflictObject {
+    pub object_id: Uuid,
+    pub object_type: String,
+    pub jurisdiction_code: JurisdictionCode,
+
+    /// The election a registration is for. Missing for pending registration
+    /// requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub election_object_id: Option<Uuid>,
+}
@@ -0,0 +1,242 @@
+//! Versions of the schema of each payload type. Signed payloads can't be
+//! re-signed when a type changes, so a payload names the version of its
+//! type's schema it was encoded with, and older versions are upgraded to the
+//! current one as they are decoded. Only the decoded [`Payload`][super::Payload]
+//! changes; the signed bytes in [`SignedObject::payload`][super::SignedObject::payload]
+//! are kept as they are.
+//!
+//! Every type starts at version 1, which is implied when the version field is
+//! missing so that payloads signed before versions existed are version 1.
+//! Adding an [`Upgrade`] to [`UPGRADES`] makes its object type's current
+//! version the next one, which new payloads of that type are then encoded
+//! with.
+
+use serde_json::{Map, Value};
+
+/// The payload field holding the version of its type's schema.
+pub const SCHEMA_VERSION_FIELD_NAME: &str = "schemaVersion";
+
+/// The version of a payload's schema when it has no version field.
+pub const INITIAL_SCHEMA_VERSION: u32 = 1;
+
+/// Rewrites the fields of a payload of `object_type` at `from_version` into
+/// those of the next version.
+pub(super) struct Upgrade {
+    pub(super) object_type: &'static str,
+    pub(super) from_version: u32,
+    pub(super) upgrade: fn(&mut Map<String, Value>) -> Result<(), String>,
+}
+
+/// Every upgrade, oldest first.
+pub(super) const UPGRADES: &[Upgrade] = &[];
+
+/// The version of `object_type`'s schema that this build encodes payloads
+/// with.
+#[must_use]
+pub fn current_schema_version(object_type: &str) -> u32 {
+    current_version(UPGRADES, object_type)
+}
+
+fn current_version(upgrades: &[Upgrade], object_type: &str) -> u32 {
+    upgrades
+        .iter()
+        .filter(|upgrade| upgrade.object_type == object_type)
+        .fold(INITIAL_SCHEMA_VERSION, |version, upgrade| {
+            version.max(upgrade.from_version + 1)
+        })
+}
+
+/// Upgrades `fields`, those of a payload of `object_type` at `version`, to the
+/// current version, leaving out the version field.
+pub(super) fn upgrade(
+    object_type: &str,
+    version: u32,
+    fields: &mut Map<String, Value>,
+) -> Result<(), String> {
+    upgrade_with(UPGRADES, object_type, version, fields)
+}
+
+fn upgrade_with(
+    upgrades: &[Upgrade],
+    object_type: &str,
+    version: u32,
+    fields: &mut Map<String, Value>,
+) -> Result<(), String> {
+    fields.remove(SCHEMA_VERSION_FIELD_NAME);
+
+    let mut version = version;
+    while version < current_version(upgrades, object_type) {
+        let Some(next) = upgrades
+            .iter()
+            .find(|upgrade| upgrade.object_type == object_type && upgrade.from_version == version)
+        else {
+            return Err(format!(
+                "No upgrade for {object_type} from schema version {version}"
+            ));
+        };
+        (next.upgrade)(fields).map_err(|e| {
+            format!("Unable to upgrade {object_type} from schema version {version}: {e}")
+        })?;
+        version += 1;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::*;
+    use crate::cacvote::Payload;
+
+    /// Payloads as they were signed by earlier builds, which must keep
+    /// decoding. Add to it, but don't change it, when a payload type changes,
+    /// noting where each new payload came from.
+    const CORPUS: &str = include_str!("../../tests/fixtures/payload-corpus.json");
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct CorpusEntry {
+        description: String,
+
+        /// Where the payload came from, e.g. captured from a client or written
+        /// by hand to match an encoding.
+        provenance: String,
+
+        object_type: String,
+
+        /// The signed bytes.
+        payload: String,
+
+        /// The payload as this build encodes it once decoded.
+        decoded: Value,
+    }
+
+    fn fields(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(fields) => fields,
+            _ => panic!("not an object: {value}"),
+        }
+    }
+
+    /// e.g. splitting a name into given and family names, then renaming the
+    /// family name
+    const TEST_UPGRADES: &[Upgrade] = &[
+        Upgrade {
+            object_type: "Voter",
+            from_version: 1,
+            upgrade: |fields| {
+                let Some(Value::String(name)) = fields.remove("name") else {
+                    return Err("missing name".to_owned());
+                };
+                let (given_name, family_name) = name.split_once(' ').unwrap_or((&name, ""));
+                fields.insert("givenName".to_owned(), given_name.into());
+                fields.insert("familyName".to_owned(), family_name.into());
+                Ok(())
+            },
+        },
+        Upgrade {
+            object_type: "Voter",
+            from_version: 2,
+            upgrade: |fields| {
+                let family_name = fields.remove("familyName").unwrap_or_default();
+                fields.insert("surname".to_owned(), family_name);
+                Ok(())
+            },
+        },
+    ];
+
+    #[test]
+    fn test_corpus() {
+        let corpus: Vec<CorpusEntry> = serde_json::from_str(CORPUS).unwrap();
+        assert!(!corpus.is_empty());
+
+        for CorpusEntry {
+            description,
+            provenance,
+            object_type,
+            payload,
+            decoded,
+        } in corpus
+        {
+            assert!(!provenance.is_empty(), "{description}");
+
+            // as they would have been signed
+            let value: Value = serde_json::from_str(&payload).unwrap();
+            assert_eq!(
+                String::from_utf8(crate::canonical_json::to_vec(&value).unwrap()).unwrap(),
+                payload,
+                "{description}"
+            );
+
+            let decoded_payload: Payload = serde_json::from_slice(payload.as_bytes())
+                .unwrap_or_else(|e| panic!("{description}: {e}"));
+            assert!(!decoded_payload.is_unknown(), "{description}");
+            assert_eq!(decoded_payload.object_type(), object_type, "{description}");
+            assert_eq!(
+                serde_json::to_value(&decoded_payload).unwrap(),
+                decoded,
+                "{description}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_current_version() {
+        assert_eq!(current_version(TEST_UPGRADES, "Voter"), 3);
+        assert_eq!(
+            current_version(TEST_UPGRADES, "Ballot"),
+            INITIAL_SCHEMA_VERSION
+        );
+    }
+
+    #[test]
+    fn test_upgrades_each_version() {
+        let mut v1 = fields(json!({ "objectType": "Voter", "name": "John Doe" }));
+        upgrade_with(TEST_UPGRADES, "Voter", 1, &mut v1).unwrap();
+        assert_eq!(
+            Value::Object(v1),
+            json!({ "objectType": "Voter", "givenName": "John", "surname": "Doe" })
+        );
+
+        let mut v2 = fields(json!({
+            "objectType": "Voter",
+            "schemaVersion": 2,
+            "givenName": "John",
+            "familyName": "Doe",
+        }));
+        upgrade_with(TEST_UPGRADES, "Voter", 2, &mut v2).unwrap();
+        assert_eq!(
+            Value::Object(v2),
+            json!({ "objectType": "Voter", "givenName": "John", "surname": "Doe" })
+        );
+
+        let mut v3 = fields(json!({
+            "objectType": "Voter",
+            "schemaVersion": 3,
+            "givenName": "John",
+            "surname": "Doe",
+        }));
+        upgrade_with(TEST_UPGRADES, "Voter", 3, &mut v3).unwrap();
+        assert_eq!(
+            Value::Object(v3),
+            json!({ "objectType": "Voter", "givenName": "John", "surname": "Doe" })
+        );
+    }
+
+    #[test]
+    fn test_upgrade_errors() {
+        let mut fields = fields(json!({ "objectType": "Voter" }));
+        assert_eq!(
+            upgrade_with(TEST_UPGRADES, "Voter", 1, &mut fields),
+            Err("Unable to upgrade Voter from schema version 1: missing name".to_owned())
+        );
+        assert_eq!(
+            upgrade_with(TEST_UPGRADES, "Voter", 0, &mut fields),
+            Err("No upgrade for Voter from schema version 0".to_owned())
+        );
+    }
+}
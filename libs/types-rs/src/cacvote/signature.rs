@@ -0,0 +1,344 @@
+//! Signature algorithms supported by [`SignedObject`][super::SignedObject].
+//!
+//! Algorithms are identified by their [JOSE names] so that the identifiers are
+//! already familiar to other implementations.
+//!
+//! [JOSE names]: https://www.iana.org/assignments/jose/jose.xhtml#web-signature-encryption-algorithms
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "utoipa")]
+use utoipa::ToSchema;
+
+/// Separates object signatures from signatures over anything else a key might
+/// sign, e.g. voter session challenges.
+const OBJECT_SIGNING_PREFIX: &[u8] = b"cacvote signed object\0";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+pub enum SignatureAlgorithm {
+    /// ECDSA using P-256 and SHA-256. Used by VotingWorks smartcards.
+    #[serde(rename = "ES256")]
+    EcdsaP256Sha256,
+
+    /// ECDSA using P-384 and SHA-384.
+    #[serde(rename = "ES384")]
+    EcdsaP384Sha384,
+
+    /// RSASSA-PKCS1-v1_5 using SHA-256. Used by Common Access Cards.
+    #[serde(rename = "RS256")]
+    RsaPkcs1Sha256,
+
+    /// RSASSA-PSS using SHA-256 and MGF1 with SHA-256.
+    #[serde(rename = "PS256")]
+    RsaPssSha256,
+
+    /// RSASSA-PSS using SHA-384 and MGF1 with SHA-384.
+    #[serde(rename = "PS384")]
+    RsaPssSha384,
+
+    /// EdDSA using Ed25519.
+    #[serde(rename = "EdDSA")]
+    Ed25519,
+}
+
+impl SignatureAlgorithm {
+    pub const ALL: [Self; 6] = [
+        Self::EcdsaP256Sha256,
+        Self::EcdsaP384Sha384,
+        Self::RsaPkcs1Sha256,
+        Self::RsaPssSha256,
+        Self::RsaPssSha384,
+        Self::Ed25519,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        // This must match the naming rules of the `serde` attributes above.
+        match self {
+            Self::EcdsaP256Sha256 => "ES256",
+            Self::EcdsaP384Sha384 => "ES384",
+            Self::RsaPkcs1Sha256 => "RS256",
+            Self::RsaPssSha256 => "PS256",
+            Self::RsaPssSha384 => "PS384",
+            Self::Ed25519 => "EdDSA",
+        }
+    }
+
+    /// The data signed with this algorithm for an object or cosignature with
+    /// `payload`. The algorithm identifier sits outside the payload, so it's
+    /// bound into the signature here to keep it from being swapped for another
+    /// one, e.g. to get past a server's list of allowed algorithms.
+    #[must_use]
+    pub fn signing_message(&self, payload: &[u8]) -> Vec<u8> {
+        [
+            OBJECT_SIGNING_PREFIX,
+            self.as_str().as_bytes(),
+            b"\0",
+            payload,
+        ]
+        .concat()
+    }
+}
+
+impl fmt::Display for SignatureAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SignatureAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.as_str() == s)
+            .ok_or_else(|| format!("Unknown signature algorithm: {s}"))
+    }
+}
+
+#[cfg(feature = "openssl")]
+mod crypto {
+    use openssl::{
+        ec::EcKey,
+        error::ErrorStack,
+        hash::MessageDigest,
+        nid::Nid,
+        pkey::{HasPublic, Id, PKeyRef, Private, Public},
+        rsa::Padding,
+        sign::{RsaPssSaltlen, Signer, Verifier},
+    };
+
+    use super::SignatureAlgorithm;
+
+    impl SignatureAlgorithm {
+        /// The algorithm that was implied by a key before objects carried an
+        /// explicit algorithm identifier: SHA-256 with whatever the key is.
+        pub fn infer_legacy<T: HasPublic>(key: &PKeyRef<T>) -> Option<Self> {
+            match key.id() {
+                Id::EC if ec_curve(key) == Some(Nid::X9_62_PRIME256V1) => {
+                    Some(Self::EcdsaP256Sha256)
+                }
+                Id::RSA => Some(Self::RsaPkcs1Sha256),
+                _ => None,
+            }
+        }
+
+        /// The preferred algorithm for signing with `key`.
+        pub fn default_for_key<T: HasPublic>(key: &PKeyRef<T>) -> Option<Self> {
+            match key.id() {
+                Id::EC => match ec_curve(key)? {
+                    Nid::X9_62_PRIME256V1 => Some(Self::EcdsaP256Sha256),
+                    Nid::SECP384R1 => Some(Self::EcdsaP384Sha384),
+                    _ => None,
+                },
+                Id::RSA => Some(Self::RsaPssSha256),
+                Id::ED25519 => Some(Self::Ed25519),
+                _ => None,
+            }
+        }
+
+        /// Determines whether `key` is of the type this algorithm requires.
+        pub fn is_compatible_with<T: HasPublic>(&self, key: &PKeyRef<T>) -> bool {
+            match self {
+                Self::EcdsaP256Sha256 => {
+                    key.id() == Id::EC && ec_curve(key) == Some(Nid::X9_62_PRIME256V1)
+                }
+                Self::EcdsaP384Sha384 => {
+                    key.id() == Id::EC && ec_curve(key) == Some(Nid::SECP384R1)
+                }
+                Self::RsaPkcs1Sha256 | Self::RsaPssSha256 | Self::RsaPssSha384 => {
+                    key.id() == Id::RSA
+                }
+                Self::Ed25519 => key.id() == Id::ED25519,
+            }
+        }
+
+        fn message_digest(&self) -> Option<MessageDigest> {
+            match self {
+                Self::EcdsaP256Sha256 | Self::RsaPkcs1Sha256 | Self::RsaPssSha256 => {
+                    Some(MessageDigest::sha256())
+                }
+                Self::EcdsaP384Sha384 | Self::RsaPssSha384 => Some(MessageDigest::sha384()),
+                // Ed25519 hashes internally
+                Self::Ed25519 => None,
+            }
+        }
+
+        fn is_pss(&self) -> bool {
+            matches!(self, Self::RsaPssSha256 | Self::RsaPssSha384)
+        }
+
+        /// Signs `data` with `key` using this algorithm.
+        pub fn sign(&self, key: &PKeyRef<Private>, data: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+            match self.message_digest() {
+                Some(digest) => {
+                    let mut signer = Signer::new(digest, key)?;
+                    if self.is_pss() {
+                        signer.set_rsa_padding(Padding::PKCS1_PSS)?;
+                        signer.set_rsa_pss_saltlen(RsaPssSaltlen::DIGEST_LENGTH)?;
+                        signer.set_rsa_mgf1_md(digest)?;
+                    }
+                    signer.update(data)?;
+                    signer.sign_to_vec()
+                }
+                None => Signer::new_without_digest(key)?.sign_oneshot_to_vec(data),
+            }
+        }
+
+        /// Verifies that `signature` is a signature of `data` made by the
+        /// private counterpart of `key` using this algorithm. Keys of the wrong
+        /// type never verify.
+        pub fn verify(
+            &self,
+            key: &PKeyRef<Public>,
+            data: &[u8],
+            signature: &[u8],
+        ) -> Result<bool, ErrorStack> {
+            if !self.is_compatible_with(key) {
+                return Ok(false);
+            }
+
+            match self.message_digest() {
+                Some(digest) => {
+                    let mut verifier = Verifier::new(digest, key)?;
+                    if self.is_pss() {
+                        verifier.set_rsa_padding(Padding::PKCS1_PSS)?;
+                        verifier.set_rsa_pss_saltlen(RsaPssSaltlen::DIGEST_LENGTH)?;
+                        verifier.set_rsa_mgf1_md(digest)?;
+                    }
+                    verifier.update(data)?;
+                    verifier.verify(signature)
+                }
+                None => Verifier::new_without_digest(key)?.verify_oneshot(signature, data),
+            }
+        }
+    }
+
+    fn ec_curve<T: HasPublic>(key: &PKeyRef<T>) -> Option<Nid> {
+        let ec_key: EcKey<T> = key.ec_key().ok()?;
+        ec_key.group().curve_name()
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl<'r> sqlx::Decode<'r, sqlx::Postgres> for SignatureAlgorithm
+where
+    sqlx::types::Json<Self>: sqlx::Decode<'r, sqlx::Postgres>,
+{
+    fn decode(value: sqlx::postgres::PgValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        Ok(value.as_str()?.parse()?)
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl<'q> sqlx::Encode<'q, sqlx::Postgres> for SignatureAlgorithm
+where
+    Vec<u8>: sqlx::Encode<'q, sqlx::Postgres>,
+{
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        <&str as sqlx::Encode<'q, sqlx::Postgres>>::encode_by_ref(&self.as_str(), buf)
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl sqlx::Type<sqlx::Postgres> for SignatureAlgorithm {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        sqlx::postgres::PgTypeInfo::with_name("varchar")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for algorithm in SignatureAlgorithm::ALL {
+            assert_eq!(algorithm.as_str().parse(), Ok(algorithm));
+            assert_eq!(
+                serde_json::to_string(&algorithm).unwrap(),
+                format!("\"{algorithm}\"")
+            );
+        }
+        "HS256".parse::<SignatureAlgorithm>().unwrap_err();
+    }
+
+    #[cfg(feature = "openssl")]
+    #[test]
+    fn test_sign_and_verify() {
+        use openssl::{
+            ec::{EcGroup, EcKey},
+            nid::Nid,
+            pkey::{PKey, Private, Public},
+            rsa::Rsa,
+        };
+
+        fn public(key: &PKey<Private>) -> PKey<Public> {
+            PKey::public_key_from_der(&key.public_key_to_der().unwrap()).unwrap()
+        }
+
+        let p256 = PKey::from_ec_key(
+            EcKey::generate(&EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap()).unwrap(),
+        )
+        .unwrap();
+        let p384 = PKey::from_ec_key(
+            EcKey::generate(&EcGroup::from_curve_name(Nid::SECP384R1).unwrap()).unwrap(),
+        )
+        .unwrap();
+        let rsa = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let ed25519 = PKey::generate_ed25519().unwrap();
+
+        for (algorithm, key) in [
+            (SignatureAlgorithm::EcdsaP256Sha256, &p256),
+            (SignatureAlgorithm::EcdsaP384Sha384, &p384),
+            (SignatureAlgorithm::RsaPkcs1Sha256, &rsa),
+            (SignatureAlgorithm::RsaPssSha256, &rsa),
+            (SignatureAlgorithm::RsaPssSha384, &rsa),
+            (SignatureAlgorithm::Ed25519, &ed25519),
+        ] {
+            let signature = algorithm.sign(key, b"hello").unwrap();
+            assert!(algorithm
+                .verify(&public(key), b"hello", &signature)
+                .unwrap());
+            assert!(!algorithm.verify(&public(key), b"bye", &signature).unwrap());
+            // a key of the wrong type never verifies
+            let wrong_key = if algorithm == SignatureAlgorithm::Ed25519 {
+                &p256
+            } else {
+                &ed25519
+            };
+            assert!(!algorithm
+                .verify(&public(wrong_key), b"hello", &signature)
+                .unwrap());
+        }
+
+        // PSS signatures are not PKCS#1 v1.5 signatures
+        let signature = SignatureAlgorithm::RsaPssSha256
+            .sign(&rsa, b"hello")
+            .unwrap();
+        assert!(!SignatureAlgorithm::RsaPkcs1Sha256
+            .verify(&public(&rsa), b"hello", &signature)
+            .unwrap_or(false));
+
+        assert_eq!(
+            SignatureAlgorithm::infer_legacy(&p256),
+            Some(SignatureAlgorithm::EcdsaP256Sha256)
+        );
+        assert_eq!(
+            SignatureAlgorithm::infer_legacy(&rsa),
+            Some(SignatureAlgorithm::RsaPkcs1Sha256)
+        );
+        assert_eq!(SignatureAlgorithm::infer_legacy(&p384), None);
+        assert_eq!(
+            SignatureAlgorithm::default_for_key(&p384),
+            Some(SignatureAlgorithm::EcdsaP384Sha384)
+        );
+        assert_eq!(
+            SignatureAlgorithm::default_for_key(&ed25519),
+            Some(SignatureAlgorithm::Ed25519)
+        );
+    }
+}
@@ -0,0 +1,242 @@
+//! Per-object-type rules for who must sign an object before it is accepted.
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "utoipa")]
+use utoipa::ToSchema;
+
+use super::Payload;
+
+/// The card type of election manager cards, as found in the card type field
+/// of the card's certificate.
+pub const ELECTION_MANAGER_CARD_TYPE: &str = "election-manager";
+
+/// Describes the signatures an object of a particular type must carry. Each
+/// signer is counted once no matter how many times they signed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignaturePolicy {
+    /// The number of distinct eligible signers required.
+    pub required_signers: usize,
+
+    /// If set, only signers whose certificate has this card type are eligible,
+    /// e.g. [`ELECTION_MANAGER_CARD_TYPE`].
+    pub card_type: Option<&'static str>,
+
+    /// If set, only signers whose certificate is for the object's jurisdiction
+    /// are eligible.
+    pub same_jurisdiction: bool,
+}
+
+impl SignaturePolicy {
+    /// The policy for objects that don't need more than one approval.
+    pub const SINGLE_SIGNER: Self = Self {
+        required_signers: 1,
+        card_type: None,
+        same_jurisdiction: false,
+    };
+
+    /// Gets the policy for objects of the given type.
+    pub fn for_object_type(object_type: &str) -> Self {
+        if object_type == Payload::decrypted_election_tally_object_type() {
+            // publishing results requires two-person integrity
+            Self {
+                required_signers: 2,
+                card_type: Some(ELECTION_MANAGER_CARD_TYPE),
+                same_jurisdiction: true,
+            }
+        } else if object_type == Payload::jurisdiction_encryption_key_object_type() {
+            // only the jurisdiction may choose who can read its voters' data
+            Self {
+                required_signers: 1,
+                card_type: Some(ELECTION_MANAGER_CARD_TYPE),
+                same_jurisdiction: true,
+            }
+        } else {
+            Self::SINGLE_SIGNER
+        }
+    }
+}
+
+/// The result of checking an object against its [`SignaturePolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct SignaturePolicyStatus {
+    pub signers_required: usize,
+    pub signers_collected: usize,
+}
+
+impl SignaturePolicyStatus {
+    #[must_use]
+    pub fn is_satisfied(&self) -> bool {
+        self.signers_collected >= self.signers_required
+    }
+}
+
+#[cfg(feature = "openssl")]
+mod evaluation {
+    use openssl::x509::{X509Ref, X509};
+
+    use super::{SignaturePolicy, SignaturePolicyStatus};
+    use crate::cacvote::{
+        certificate_field, verify_certificate_chain, JurisdictionCode, SignedObject,
+        VX_CUSTOM_CERT_FIELD_CARD_TYPE, VX_CUSTOM_CERT_FIELD_JURISDICTION,
+    };
+
+    impl SignaturePolicy {
+        /// Determines whether the policy relies on the attributes of signers'
+        /// certificates, which only mean something for certificates issued by
+        /// a trusted CA.
+        #[must_use]
+        pub fn restricts_signers(&self) -> bool {
+            self.card_type.is_some() || self.same_jurisdiction
+        }
+
+        /// Determines whether the holder of `certificate` may sign for an
+        /// object belonging to `jurisdiction_code`.
+        pub fn is_eligible_signer(
+            &self,
+            certificate: &X509Ref,
+            jurisdiction_code: Option<&JurisdictionCode>,
+        ) -> bool {
+            if let Some(card_type) = self.card_type {
+                if certificate_field(certificate, VX_CUSTOM_CERT_FIELD_CARD_TYPE).as_deref()
+                    != Some(card_type)
+                {
+                    return false;
+                }
+            }
+
+            if self.same_jurisdiction {
+                let Some(jurisdiction_code) = jurisdiction_code else {
+                    return false;
+                };
+
+                if certificate_field(certificate, VX_CUSTOM_CERT_FIELD_JURISDICTION).as_deref()
+                    != Some(jurisdiction_code.as_str())
+                {
+                    return false;
+                }
+            }
+
+            true
+        }
+
+        /// Counts the distinct eligible signers of `object`, taking their
+        /// certificates at face value. Only suitable when the signers were
+        /// authenticated some other way, e.g. by the card reader they signed
+        /// with; otherwise see [`SignaturePolicy::evaluate_trusted`]. This does
+        /// not check the signatures themselves, see [`SignedObject::verify`].
+        pub fn evaluate(&self, object: &SignedObject) -> SignaturePolicyStatus {
+            self.count_signers(object, object.signing_certificates())
+        }
+
+        /// Counts the distinct eligible signers of `object` whose certificates
+        /// chain to one of `ca_certificates` (see [`verify_certificate_chain`]).
+        /// Policies that don't restrict who may sign count every signer, as
+        /// [`SignaturePolicy::evaluate`] does.
+        pub fn evaluate_trusted(
+            &self,
+            object: &SignedObject,
+            ca_certificates: &[X509],
+        ) -> SignaturePolicyStatus {
+            if !self.restricts_signers() {
+                return self.evaluate(object);
+            }
+
+            let certificates = std::iter::once(object.certificates.as_slice())
+                .chain(
+                    object
+                        .cosignatures
+                        .iter()
+                        .map(|cosignature| cosignature.certificates.as_slice()),
+                )
+                .filter_map(|certificates| {
+                    verify_certificate_chain(certificates, ca_certificates).ok()
+                })
+                .collect();
+            self.count_signers(object, certificates)
+        }
+
+        fn count_signers(
+            &self,
+            object: &SignedObject,
+            certificates: Vec<X509>,
+        ) -> SignaturePolicyStatus {
+            let jurisdiction_code = object.jurisdiction_code();
+            let mut signers = Vec::new();
+
+            for certificate in certificates {
+                if !self.is_eligible_signer(&certificate, jurisdiction_code.as_ref()) {
+                    continue;
+                }
+
+                // the same card signing twice is still only one signer
+                let Ok(public_key) = certificate
+                    .public_key()
+                    .and_then(|key| key.public_key_to_der())
+                else {
+                    continue;
+                };
+
+                if !signers.contains(&public_key) {
+                    signers.push(public_key);
+                }
+            }
+
+            SignaturePolicyStatus {
+                signers_required: self.required_signers,
+                signers_collected: signers.len(),
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "openssl"))]
+mod tests {
+    use openssl::x509::X509;
+
+    use super::*;
+    use crate::cacvote::JurisdictionCode;
+
+    #[test]
+    fn test_for_object_type() {
+        assert_eq!(
+            SignaturePolicy::for_object_type(Payload::registration_object_type()),
+            SignaturePolicy::SINGLE_SIGNER
+        );
+        assert_eq!(
+            SignaturePolicy::for_object_type(Payload::decrypted_election_tally_object_type())
+                .required_signers,
+            2
+        );
+    }
+
+    #[test]
+    fn test_is_eligible_signer() {
+        let election_manager_certificate = X509::from_der(include_bytes!(
+            "../../../auth/certs/test/set-1/election-manager/card-vx-admin-cert.der"
+        ))
+        .unwrap();
+        let system_administrator_certificate = X509::from_der(include_bytes!(
+            "../../../auth/certs/test/set-1/system-administrator/card-vx-admin-cert.der"
+        ))
+        .unwrap();
+        let jurisdiction_code = JurisdictionCode::try_from("jurisdiction").unwrap();
+        let other_jurisdiction_code = JurisdictionCode::try_from("st.other").unwrap();
+
+        let policy =
+            SignaturePolicy::for_object_type(Payload::decrypted_election_tally_object_type());
+        assert!(policy.is_eligible_signer(&election_manager_certificate, Some(&jurisdiction_code)));
+        assert!(!policy.is_eligible_signer(
+            &election_manager_certificate,
+            Some(&other_jurisdiction_code)
+        ));
+        assert!(!policy.is_eligible_signer(&election_manager_certificate, None));
+        assert!(
+            !policy.is_eligible_signer(&system_administrator_certificate, Some(&jurisdiction_code))
+        );
+
+        assert!(SignaturePolicy::SINGLE_SIGNER
+            .is_eligible_signer(&system_administrator_certificate, None));
+    }
+}
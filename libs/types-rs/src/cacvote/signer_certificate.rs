@@ -0,0 +1,106 @@
+//! Searchable attributes of the certificate that signed an object. Objects are
+//! stored pointing at their signer's certificate by fingerprint rather than
+//! each carrying a copy of the certificate stack.
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+#[cfg(feature = "utoipa")]
+use utoipa::ToSchema;
+
+/// The attributes of a signer's certificate, i.e. the first certificate in a
+/// [`SignedObject`][super::SignedObject]'s certificate stack.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct SignerCertificate {
+    /// Hex-encoded SHA-256 digest of the DER encoding of the certificate.
+    pub fingerprint: String,
+
+    /// The subject's common name, e.g. `SURNAME.GIVEN.MIDDLE.1234567890` for
+    /// Common Access Cards.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub common_name: Option<String>,
+
+    /// The VotingWorks jurisdiction field, if present.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jurisdiction: Option<String>,
+
+    /// The VotingWorks card type field, if present, e.g.
+    /// [`ELECTION_MANAGER_CARD_TYPE`][super::ELECTION_MANAGER_CARD_TYPE].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub card_type: Option<String>,
+
+    #[serde(with = "time::serde::iso8601")]
+    pub not_after: OffsetDateTime,
+}
+
+#[cfg(feature = "openssl")]
+impl SignerCertificate {
+    /// Reads the attributes of the first certificate in a stack of
+    /// PEM-encoded certificates.
+    pub fn from_pem_stack(certificates: &[u8]) -> color_eyre::Result<Self> {
+        let Some(certificate) = openssl::x509::X509::stack_from_pem(certificates)?
+            .into_iter()
+            .next()
+        else {
+            color_eyre::eyre::bail!("No certificates found");
+        };
+
+        Ok(Self::from_x509(&certificate)?)
+    }
+
+    pub fn from_x509(
+        certificate: &openssl::x509::X509Ref,
+    ) -> Result<Self, openssl::error::ErrorStack> {
+        let fingerprint = hex::encode(certificate.digest(openssl::hash::MessageDigest::sha256())?);
+        let common_name = certificate
+            .subject_name()
+            .entries_by_nid(openssl::nid::Nid::COMMONNAME)
+            .next()
+            .and_then(|entry| Some(entry.data().as_utf8().ok()?.to_string()));
+        let not_after = openssl::asn1::Asn1Time::from_unix(0)?.diff(certificate.not_after())?;
+
+        Ok(Self {
+            fingerprint,
+            common_name,
+            jurisdiction: super::certificate_field(
+                certificate,
+                super::VX_CUSTOM_CERT_FIELD_JURISDICTION,
+            ),
+            card_type: super::certificate_field(certificate, super::VX_CUSTOM_CERT_FIELD_CARD_TYPE),
+            not_after: OffsetDateTime::UNIX_EPOCH
+                + time::Duration::days(not_after.days.into())
+                + time::Duration::seconds(not_after.secs.into()),
+        })
+    }
+}
+
+#[cfg(all(test, feature = "openssl"))]
+mod tests {
+    use super::*;
+    use crate::cacvote::ELECTION_MANAGER_CARD_TYPE;
+
+    #[test]
+    fn test_from_pem_stack() {
+        let certificate = openssl::x509::X509::from_der(include_bytes!(
+            "../../../auth/certs/test/set-1/election-manager/card-vx-admin-cert.der"
+        ))
+        .unwrap();
+        let signer_certificate =
+            SignerCertificate::from_pem_stack(&certificate.to_pem().unwrap()).unwrap();
+
+        assert_eq!(
+            signer_certificate,
+            SignerCertificate {
+                fingerprint: "bf03603517bc36f1a14fc950d29dfdbb967ee537d63a07b4d354d45a4873ae46"
+                    .to_owned(),
+                common_name: None,
+                jurisdiction: Some("jurisdiction".to_owned()),
+                card_type: Some(ELECTION_MANAGER_CARD_TYPE.to_owned()),
+                not_after: time::macros::datetime!(2124-04-05 18:10:01 UTC),
+            }
+        );
+
+        assert!(SignerCertificate::from_pem_stack(b"").is_err());
+    }
+}
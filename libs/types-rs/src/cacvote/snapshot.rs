@@ -0,0 +1,411 @@
+//! Types for snapshots of a jurisdiction's objects, which let a new JX terminal
+//! load every live object at once instead of replaying the whole journal.
+//!
+//! A snapshot is taken as of a journal entry, the cursor, and holds every
+//! object of the jurisdiction journaled at or before it that hadn't been
+//! deleted by then, in journal order. Its entries form the leaves of a Merkle
+//! tree built as in [RFC 6962], whose root is published in a
+//! [`SnapshotTreeHead`] optionally signed by the server. Because the snapshot
+//! as of a cursor never changes, its entries can be fetched in pages and
+//! checked against the tree head once all have been fetched.
+//!
+//! [RFC 6962]: https://www.rfc-editor.org/rfc/rfc6962#section-2.1
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "utoipa")]
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use super::{
+    Base64Standard, Cosignatures, JournalEntry, JournalEntryAction, JurisdictionCode,
+    SignatureAlgorithm, SignedObject,
+};
+
+/// Selects a snapshot and, when fetching its entries, a page of them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(IntoParams))]
+#[cfg_attr(feature = "utoipa", into_params(parameter_in = Query))]
+pub struct SnapshotQuery {
+    #[serde(rename = "jurisdiction")]
+    pub jurisdiction_code: JurisdictionCode,
+
+    /// The journal entry the snapshot is taken as of. Defaults to the
+    /// jurisdiction's latest journal entry when getting a tree head, and is
+    /// required when getting entries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<Uuid>,
+
+    /// The number of entries to skip, i.e. the
+    /// [`SnapshotPage::next_offset`] of the previous page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+
+    /// The maximum number of entries to return, up to
+    /// [`SnapshotQuery::MAX_LIMIT`]. Defaults to
+    /// [`SnapshotQuery::DEFAULT_LIMIT`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+impl SnapshotQuery {
+    pub const DEFAULT_LIMIT: usize = 100;
+    pub const MAX_LIMIT: usize = 1000;
+}
+
+/// A live object in a snapshot, with the journal entry that recorded it.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotEntry {
+    pub journal_entry: JournalEntry,
+    pub object: SignedObject,
+}
+
+impl SnapshotEntry {
+    #[must_use]
+    pub fn leaf(&self) -> SnapshotLeaf {
+        SnapshotLeaf {
+            journal_entry_id: self.journal_entry.id,
+            journal_entry_election_id: self.journal_entry.election_id,
+            jurisdiction_code: self.journal_entry.jurisdiction_code.clone(),
+            object_type: self.journal_entry.object_type.clone(),
+            action: self.journal_entry.action.clone(),
+            created_at: self.journal_entry.created_at,
+            object_id: self.object.id,
+            object_election_id: self.object.election_id,
+            payload_sha256: hmac_sha256::Hash::hash(&self.object.payload),
+            certificates_sha256: hmac_sha256::Hash::hash(&self.object.certificates),
+            signature_sha256: hmac_sha256::Hash::hash(&self.object.signature),
+            signature_algorithm: self.object.signature_algorithm,
+            cosignatures_sha256: SnapshotLeaf::cosignatures_sha256(&self.object.cosignatures),
+        }
+    }
+}
+
+/// What a snapshot's Merkle tree commits to for each entry: every field of the
+/// journal entry and the object, with the object's larger fields as digests
+/// so that the server can compute the tree without loading every object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotLeaf {
+    pub journal_entry_id: Uuid,
+    pub journal_entry_election_id: Option<Uuid>,
+    pub jurisdiction_code: JurisdictionCode,
+    pub object_type: String,
+    pub action: JournalEntryAction,
+    pub created_at: time::OffsetDateTime,
+    pub object_id: Uuid,
+    pub object_election_id: Option<Uuid>,
+    pub payload_sha256: [u8; 32],
+    pub certificates_sha256: [u8; 32],
+    pub signature_sha256: [u8; 32],
+    pub signature_algorithm: Option<SignatureAlgorithm>,
+    pub cosignatures_sha256: [u8; 32],
+}
+
+impl SnapshotLeaf {
+    /// The digest of an object's cosignatures as they are serialized.
+    #[must_use]
+    pub fn cosignatures_sha256(cosignatures: &Cosignatures) -> [u8; 32] {
+        let json = serde_json::to_vec(cosignatures).expect("cosignatures serialize to JSON");
+        hmac_sha256::Hash::hash(&json)
+    }
+
+    /// The leaf's hash in the Merkle tree, i.e. `SHA-256(0x00 || data)`.
+    /// Strings are prefixed with their length and optional values with
+    /// whether they're present, so that no two leaves share the same data.
+    #[must_use]
+    pub fn hash(&self) -> [u8; 32] {
+        fn update_str(hash: &mut hmac_sha256::Hash, value: &str) {
+            hash.update((value.len() as u64).to_be_bytes());
+            hash.update(value);
+        }
+
+        fn update_optional_uuid(hash: &mut hmac_sha256::Hash, value: Option<Uuid>) {
+            match value {
+                Some(value) => {
+                    hash.update([0x01]);
+                    hash.update(value.as_bytes());
+                }
+                None => hash.update([0x00]),
+            }
+        }
+
+        let mut hash = hmac_sha256::Hash::new();
+        hash.update([0x00]);
+        hash.update(self.journal_entry_id.as_bytes());
+        update_optional_uuid(&mut hash, self.journal_entry_election_id);
+        update_str(&mut hash, self.jurisdiction_code.as_str());
+        update_str(&mut hash, &self.object_type);
+        update_str(&mut hash, self.action.as_str());
+        hash.update(self.created_at.unix_timestamp_nanos().to_be_bytes());
+        hash.update(self.object_id.as_bytes());
+        update_optional_uuid(&mut hash, self.object_election_id);
+        hash.update(self.payload_sha256);
+        hash.update(self.certificates_sha256);
+        hash.update(self.signature_sha256);
+        update_str(
+            &mut hash,
+            self.signature_algorithm
+                .as_ref()
+                .map_or("", SignatureAlgorithm::as_str),
+        );
+        hash.update(self.cosignatures_sha256);
+        hash.finalize()
+    }
+}
+
+/// Computes the root of the Merkle tree with the given leaf hashes, in order.
+#[must_use]
+pub fn merkle_tree_root(leaf_hashes: &[[u8; 32]]) -> [u8; 32] {
+    match leaf_hashes {
+        [] => hmac_sha256::Hash::hash(&[]),
+        [leaf_hash] => *leaf_hash,
+        _ => {
+            // the left subtree holds the largest power of two of leaves that
+            // is smaller than the whole tree
+            let split = 1 << (leaf_hashes.len() - 1).ilog2();
+
+            let mut hash = hmac_sha256::Hash::new();
+            hash.update([0x01]);
+            hash.update(merkle_tree_root(&leaf_hashes[..split]));
+            hash.update(merkle_tree_root(&leaf_hashes[split..]));
+            hash.finalize()
+        }
+    }
+}
+
+/// The root of a snapshot's Merkle tree, identifying exactly which entries the
+/// snapshot holds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotTreeHead {
+    pub jurisdiction_code: JurisdictionCode,
+
+    /// The journal entry the snapshot was taken as of.
+    pub cursor: Uuid,
+
+    /// The number of entries in the snapshot.
+    pub tree_size: u64,
+
+    /// The hex-encoded root hash of the snapshot's Merkle tree.
+    pub root_hash: String,
+
+    /// The server's signature of the tree head, if it has a signing key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<TreeHeadSignature>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TreeHeadSignature {
+    pub algorithm: SignatureAlgorithm,
+
+    #[serde(with = "Base64Standard")]
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = Byte))]
+    pub signature: Vec<u8>,
+}
+
+impl SnapshotTreeHead {
+    /// Builds the unsigned tree head for a snapshot with the given leaves.
+    #[must_use]
+    pub fn new(jurisdiction_code: JurisdictionCode, cursor: Uuid, leaves: &[SnapshotLeaf]) -> Self {
+        let leaf_hashes = leaves.iter().map(SnapshotLeaf::hash).collect::<Vec<_>>();
+
+        Self {
+            jurisdiction_code,
+            cursor,
+            tree_size: leaves.len() as u64,
+            root_hash: hex::encode(merkle_tree_root(&leaf_hashes)),
+            signature: None,
+        }
+    }
+
+    /// The data covered by the signature: every field but the signature
+    /// itself, one per line.
+    #[must_use]
+    pub fn signed_data(&self) -> Vec<u8> {
+        format!(
+            "cacvote-snapshot-tree-head\n{}\n{}\n{}\n{}\n",
+            self.jurisdiction_code, self.cursor, self.tree_size, self.root_hash
+        )
+        .into_bytes()
+    }
+
+    /// Determines whether `entries` are exactly the entries of this snapshot,
+    /// in order.
+    #[must_use]
+    pub fn matches_entries(&self, entries: &[SnapshotEntry]) -> bool {
+        // the leaves only include the object's ID once
+        if entries
+            .iter()
+            .any(|entry| entry.journal_entry.object_id != entry.object.id)
+        {
+            return false;
+        }
+
+        let leaves = entries.iter().map(SnapshotEntry::leaf).collect::<Vec<_>>();
+        let expected = Self::new(self.jurisdiction_code.clone(), self.cursor, &leaves);
+        expected.tree_size == self.tree_size && expected.root_hash == self.root_hash
+    }
+}
+
+#[cfg(feature = "openssl")]
+impl SnapshotTreeHead {
+    /// Signs the tree head with `private_key` using the preferred algorithm
+    /// for the key's type.
+    pub fn sign(
+        &mut self,
+        private_key: &openssl::pkey::PKeyRef<openssl::pkey::Private>,
+    ) -> color_eyre::Result<()> {
+        let Some(algorithm) = SignatureAlgorithm::default_for_key(private_key) else {
+            color_eyre::eyre::bail!("Unsupported private key type: {:?}", private_key.id());
+        };
+
+        self.signature = Some(TreeHeadSignature {
+            algorithm,
+            signature: algorithm.sign(private_key, &self.signed_data())?,
+        });
+        Ok(())
+    }
+
+    /// Determines whether the tree head is signed by `public_key`.
+    pub fn verify_signature(
+        &self,
+        public_key: &openssl::pkey::PKeyRef<openssl::pkey::Public>,
+    ) -> Result<bool, openssl::error::ErrorStack> {
+        match &self.signature {
+            Some(signature) => {
+                signature
+                    .algorithm
+                    .verify(public_key, &self.signed_data(), &signature.signature)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+/// A page of a snapshot's entries.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotPage {
+    pub entries: Vec<SnapshotEntry>,
+
+    /// Set if there are more entries, in which case it should be passed as
+    /// [`SnapshotQuery::offset`] to get the next page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(n: u8) -> SnapshotLeaf {
+        SnapshotLeaf {
+            journal_entry_id: Uuid::from_bytes([n; 16]),
+            journal_entry_election_id: None,
+            jurisdiction_code: JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
+            object_type: "Registration".to_owned(),
+            action: JournalEntryAction::Create,
+            created_at: time::OffsetDateTime::from_unix_timestamp(i64::from(n)).unwrap(),
+            object_id: Uuid::from_bytes([n + 1; 16]),
+            object_election_id: None,
+            payload_sha256: [n; 32],
+            certificates_sha256: [n; 32],
+            signature_sha256: [n; 32],
+            signature_algorithm: None,
+            cosignatures_sha256: [n; 32],
+        }
+    }
+
+    fn node_hash(left: [u8; 32], right: [u8; 32]) -> [u8; 32] {
+        let mut hash = hmac_sha256::Hash::new();
+        hash.update([0x01]);
+        hash.update(left);
+        hash.update(right);
+        hash.finalize()
+    }
+
+    #[test]
+    fn test_merkle_tree_root() {
+        let [a, b, c, d, e] = [0, 1, 2, 3, 4].map(|n| leaf(n).hash());
+
+        assert_eq!(merkle_tree_root(&[]), hmac_sha256::Hash::hash(&[]));
+        assert_eq!(merkle_tree_root(&[a]), a);
+        assert_eq!(merkle_tree_root(&[a, b]), node_hash(a, b));
+        assert_eq!(merkle_tree_root(&[a, b, c]), node_hash(node_hash(a, b), c));
+        assert_eq!(
+            merkle_tree_root(&[a, b, c, d, e]),
+            node_hash(node_hash(node_hash(a, b), node_hash(c, d)), e)
+        );
+    }
+
+    #[test]
+    fn test_tree_head_depends_on_leaves() {
+        let jurisdiction_code = JurisdictionCode::try_from("st.dev-jurisdiction").unwrap();
+        let cursor = Uuid::new_v4();
+        let tree_head =
+            SnapshotTreeHead::new(jurisdiction_code.clone(), cursor, &[leaf(0), leaf(1)]);
+        assert_eq!(tree_head.tree_size, 2);
+
+        // order matters
+        assert_ne!(
+            SnapshotTreeHead::new(jurisdiction_code.clone(), cursor, &[leaf(1), leaf(0)]).root_hash,
+            tree_head.root_hash
+        );
+
+        let changes: [fn(&mut SnapshotLeaf); 9] = [
+            |leaf| leaf.payload_sha256 = [0xff; 32],
+            |leaf| leaf.action = JournalEntryAction::Update,
+            |leaf| leaf.created_at += time::Duration::microseconds(1),
+            |leaf| leaf.journal_entry_election_id = Some(Uuid::nil()),
+            |leaf| leaf.object_election_id = Some(Uuid::nil()),
+            |leaf| leaf.object_type = "Election".to_owned(),
+            |leaf| leaf.signature_algorithm = Some(SignatureAlgorithm::EcdsaP256Sha256),
+            |leaf| leaf.cosignatures_sha256 = [0xff; 32],
+            |leaf| {
+                leaf.jurisdiction_code = JurisdictionCode::try_from("st.other").unwrap();
+            },
+        ];
+        for change in changes {
+            let mut changed = leaf(1);
+            change(&mut changed);
+            assert_ne!(
+                SnapshotTreeHead::new(jurisdiction_code.clone(), cursor, &[leaf(0), changed])
+                    .root_hash,
+                tree_head.root_hash
+            );
+        }
+    }
+
+    #[cfg(feature = "openssl")]
+    #[test]
+    fn test_tree_head_signature() {
+        use openssl::{
+            ec::{EcGroup, EcKey},
+            nid::Nid,
+            pkey::PKey,
+        };
+
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let private_key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        let public_key =
+            PKey::public_key_from_pem(&private_key.public_key_to_pem().unwrap()).unwrap();
+
+        let mut tree_head = SnapshotTreeHead::new(
+            JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
+            Uuid::new_v4(),
+            &[leaf(0)],
+        );
+        assert!(!tree_head.verify_signature(&public_key).unwrap());
+
+        tree_head.sign(&private_key).unwrap();
+        assert!(tree_head.verify_signature(&public_key).unwrap());
+
+        tree_head.tree_size += 1;
+        assert!(!tree_head.verify_signature(&public_key).unwrap());
+    }
+}
@@ -0,0 +1,84 @@
+//! Types for voter sessions on the server. A voter device proves that it holds
+//! a Common Access Card by signing a one-time challenge with it, and in return
+//! gets a short-lived token for reading that voter's own objects.
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+#[cfg(feature = "utoipa")]
+use utoipa::ToSchema;
+
+use super::{Base64Standard, SignatureAlgorithm, SignedObject};
+
+/// Separates challenge signatures from signatures over anything else a CAC
+/// might sign, e.g. object payloads.
+const CHALLENGE_SIGNING_PREFIX: &[u8] = b"cacvote voter session challenge\0";
+
+/// A one-time challenge to be signed by the voter's CAC.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct VoterSessionChallenge {
+    #[serde(with = "Base64Standard")]
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = Byte))]
+    pub nonce: Vec<u8>,
+
+    #[serde(with = "time::serde::iso8601")]
+    pub expires_at: OffsetDateTime,
+}
+
+impl VoterSessionChallenge {
+    /// The data the voter's CAC signs to answer a challenge with `nonce`.
+    #[must_use]
+    pub fn signing_message(nonce: &[u8]) -> Vec<u8> {
+        [CHALLENGE_SIGNING_PREFIX, nonce].concat()
+    }
+}
+
+/// An answer to a [`VoterSessionChallenge`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CreateVoterSessionRequest {
+    #[serde(with = "Base64Standard")]
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = Byte))]
+    pub nonce: Vec<u8>,
+
+    /// The PEM-encoded certificate stack of the voter's CAC, CAC certificate
+    /// first, followed by any intermediate certificates.
+    #[serde(with = "Base64Standard")]
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = Byte))]
+    pub certificates: Vec<u8>,
+
+    /// The signature of [`VoterSessionChallenge::signing_message`].
+    #[serde(with = "Base64Standard")]
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = Byte))]
+    pub signature: Vec<u8>,
+
+    /// The algorithm of `signature`. If missing, it is inferred from the CAC
+    /// certificate as for objects.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature_algorithm: Option<SignatureAlgorithm>,
+}
+
+/// A session for reading a voter's own objects, sent as a bearer token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct VoterSession {
+    pub token: String,
+
+    #[serde(with = "time::serde::iso8601")]
+    pub expires_at: OffsetDateTime,
+}
+
+/// One of a voter's registration requests along with the registration that
+/// approved it, if any.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct VoterRegistration {
+    pub registration_request: SignedObject,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registration: Option<SignedObject>,
+}
@@ -0,0 +1,240 @@
+//! Canonical JSON encoding as defined by [RFC 8785] (JSON Canonicalization
+//! Scheme, or JCS).
+//!
+//! Signatures over JSON are only portable if every implementation produces
+//! the same bytes for the same value. JCS pins down the details that plain
+//! `serde_json::to_vec` leaves to the serializer: object members are sorted by
+//! the UTF-16 code units of their names, there is no insignificant whitespace,
+//! strings use the minimal escaping of ECMAScript's `JSON.stringify`, and
+//! numbers are formatted as ECMAScript's `Number.prototype.toString` would
+//! format the equivalent IEEE-754 double.
+//!
+//! [RFC 8785]: https://www.rfc-editor.org/rfc/rfc8785
+
+use serde::{ser::Error as _, Serialize};
+use serde_json::{Number, Value};
+
+/// The largest integer magnitude that can be represented exactly by an
+/// IEEE-754 double, i.e. `Number.MAX_SAFE_INTEGER` in ECMAScript.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Serializes `value` as canonical JSON bytes.
+///
+/// # Errors
+///
+/// Fails if `value` cannot be represented as JSON, or if it contains an
+/// integer outside of the range that an IEEE-754 double can represent exactly.
+pub fn to_vec<T>(value: &T) -> Result<Vec<u8>, serde_json::Error>
+where
+    T: ?Sized + Serialize,
+{
+    let value = serde_json::to_value(value)?;
+    let mut out = Vec::new();
+    write_value(&mut out, &value)?;
+    Ok(out)
+}
+
+/// Serializes `value` as a canonical JSON string.
+///
+/// # Errors
+///
+/// See [`to_vec`].
+pub fn to_string<T>(value: &T) -> Result<String, serde_json::Error>
+where
+    T: ?Sized + Serialize,
+{
+    let bytes = to_vec(value)?;
+    Ok(String::from_utf8(bytes).expect("canonical JSON is always valid UTF-8"))
+}
+
+/// Determines whether `bytes` is the canonical JSON encoding of the value it
+/// contains.
+pub fn is_canonical(bytes: &[u8]) -> bool {
+    match serde_json::from_slice::<Value>(bytes) {
+        Ok(value) => to_vec(&value).is_ok_and(|canonical| canonical == bytes),
+        Err(_) => false,
+    }
+}
+
+fn write_value(out: &mut Vec<u8>, value: &Value) -> Result<(), serde_json::Error> {
+    match value {
+        Value::Null | Value::Bool(_) | Value::String(_) => serde_json::to_writer(out, value)?,
+        Value::Number(number) => out.extend_from_slice(format_number(number)?.as_bytes()),
+        Value::Array(values) => {
+            out.push(b'[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write_value(out, value)?;
+            }
+            out.push(b']');
+        }
+        Value::Object(map) => {
+            let mut entries = map.iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+            out.push(b'{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                serde_json::to_writer(&mut *out, key)?;
+                out.push(b':');
+                write_value(out, value)?;
+            }
+            out.push(b'}');
+        }
+    }
+
+    Ok(())
+}
+
+fn format_number(number: &Number) -> Result<String, serde_json::Error> {
+    if let Some(n) = number.as_u64() {
+        if n > MAX_SAFE_INTEGER {
+            return Err(serde_json::Error::custom(format!(
+                "integer {n} cannot be represented exactly in canonical JSON"
+            )));
+        }
+        return Ok(n.to_string());
+    }
+
+    if let Some(n) = number.as_i64() {
+        if n.unsigned_abs() > MAX_SAFE_INTEGER {
+            return Err(serde_json::Error::custom(format!(
+                "integer {n} cannot be represented exactly in canonical JSON"
+            )));
+        }
+        return Ok(n.to_string());
+    }
+
+    match number.as_f64() {
+        Some(n) if n.is_finite() => Ok(format_f64(n)),
+        _ => Err(serde_json::Error::custom(format!(
+            "number {number} cannot be represented in canonical JSON"
+        ))),
+    }
+}
+
+/// Formats a finite double the way ECMAScript's `Number.prototype.toString`
+/// does (ECMA-262, section 6.1.6.1.20).
+fn format_f64(n: f64) -> String {
+    if n == 0.0 {
+        // covers negative zero, which ECMAScript prints as "0"
+        return "0".to_owned();
+    }
+
+    if n.is_sign_negative() {
+        return format!("-{}", format_f64(-n));
+    }
+
+    // Rust's `{:e}` produces the shortest digit string that round-trips, which
+    // is exactly what ECMAScript requires, e.g. "1.2345e-7".
+    let scientific = format!("{n:e}");
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("scientific notation always has an exponent");
+    let digits = mantissa.replace('.', "");
+    let exponent: i32 = exponent.parse().expect("exponent is always an integer");
+
+    // ECMAScript describes the value as `0.{digits} × 10^n`.
+    let k = digits.len() as i32;
+    let n = exponent + 1;
+
+    if k <= n && n <= 21 {
+        format!("{digits}{}", "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        let (integer, fraction) = digits.split_at(n as usize);
+        format!("{integer}.{fraction}")
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{digits}", "0".repeat(-n as usize))
+    } else {
+        let sign = if n - 1 < 0 { '-' } else { '+' };
+        let (first, rest) = digits.split_at(1);
+        let exponent = (n - 1).abs();
+        if rest.is_empty() {
+            format!("{first}e{sign}{exponent}")
+        } else {
+            format!("{first}.{rest}e{sign}{exponent}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde::Deserialize;
+
+    use super::*;
+
+    /// Test vectors shared with the TypeScript implementation.
+    const VECTORS: &str = include_str!("../tests/fixtures/canonical-json-vectors.json");
+
+    #[derive(Debug, Deserialize)]
+    struct Vector {
+        description: String,
+        input: Value,
+        canonical: String,
+    }
+
+    #[test]
+    fn test_vectors() {
+        let vectors: Vec<Vector> = serde_json::from_str(VECTORS).unwrap();
+        assert!(!vectors.is_empty());
+
+        for vector in vectors {
+            assert_eq!(
+                to_string(&vector.input).unwrap(),
+                vector.canonical,
+                "{}",
+                vector.description
+            );
+            assert!(
+                is_canonical(vector.canonical.as_bytes()),
+                "{}",
+                vector.description
+            );
+        }
+    }
+
+    #[test]
+    fn test_numbers() {
+        for (n, expected) in [
+            (0.0, "0"),
+            (-0.0, "0"),
+            (1.0, "1"),
+            (-1.5, "-1.5"),
+            (0.1, "0.1"),
+            (1e-6, "0.000001"),
+            (1e-7, "1e-7"),
+            (1.5e-7, "1.5e-7"),
+            (1e20, "100000000000000000000"),
+            (1e21, "1e+21"),
+            (1.2345e25, "1.2345e+25"),
+            (123.456, "123.456"),
+            (f64::MAX, "1.7976931348623157e+308"),
+            (f64::MIN_POSITIVE, "2.2250738585072014e-308"),
+            (5e-324, "5e-324"),
+        ] {
+            assert_eq!(format_f64(n), expected, "formatting {n:?}");
+        }
+    }
+
+    #[test]
+    fn test_unsafe_integers() {
+        to_vec(&(MAX_SAFE_INTEGER + 1)).unwrap_err();
+        to_vec(&-((MAX_SAFE_INTEGER + 1) as i64)).unwrap_err();
+        to_vec(&MAX_SAFE_INTEGER).unwrap();
+        to_vec(&-(MAX_SAFE_INTEGER as i64)).unwrap();
+    }
+
+    #[test]
+    fn test_is_canonical() {
+        assert!(is_canonical(br#"{"a":1,"b":[true,null]}"#));
+        assert!(!is_canonical(br#"{"b":1,"a":2}"#));
+        assert!(!is_canonical(br#"{"a": 1}"#));
+        assert!(!is_canonical(br#"{"a":1.0}"#));
+        assert!(!is_canonical(b"not json"));
+    }
+}
@@ -6,6 +6,14 @@ use std::{fmt::Display, str::FromStr};
 use time::macros::format_description;
 
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "utoipa")]
+use utoipa::{
+    openapi::{
+        schema::{self, KnownFormat, ObjectBuilder, SchemaFormat},
+        RefOr, Schema,
+    },
+    PartialSchema, ToSchema,
+};
 
 use crate::{ballot_card::BallotSide, geometry::GridUnit, util::idtype};
 
@@ -109,6 +117,22 @@ impl<'de> Deserialize<'de> for ElectionDefinition {
     }
 }
 
+/// Election definitions are sent as the base64 encoding of the election JSON
+/// they were parsed from, which is what their hash covers.
+#[cfg(feature = "utoipa")]
+impl PartialSchema for ElectionDefinition {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .schema_type(schema::Type::String)
+            .format(Some(SchemaFormat::KnownFormat(KnownFormat::Byte)))
+            .description(Some("Base64-encoded election definition JSON"))
+            .into()
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl ToSchema for ElectionDefinition {}
+
 #[derive(Debug, Clone, PartialEq)]
 #[repr(transparent)]
 pub struct ElectionHash(String);
@@ -201,6 +225,22 @@ impl Serialize for ElectionHash {
     }
 }
 
+#[cfg(feature = "utoipa")]
+impl PartialSchema for ElectionHash {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .schema_type(schema::Type::String)
+            .pattern(Some(format!(
+                "^[0-9a-f]{{{}}}$",
+                Self::EXPECTED_STRING_LENGTH
+            )))
+            .into()
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl ToSchema for ElectionHash {}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct PartialElectionHash(String);
 
@@ -1,6 +1,7 @@
 pub mod auth;
 pub mod ballot_card;
 pub mod cacvote;
+pub mod canonical_json;
 pub mod cdf;
 pub mod election;
 pub mod geometry;